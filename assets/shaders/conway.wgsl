@@ -1,5 +1,78 @@
+#import "shaders/edges.wgsl"::fold_edges
+
 @group(0) @binding(0) var texture: texture_storage_2d<rgba8unorm, read_write>;
 
+struct ConwayUniforms {
+    density: f32,
+    seed: u32,
+    table_states: u32,
+    // 0 for Moore, 1 for von Neumann.
+    table_neighborhood: u32,
+    // The chance of a cell following `blend_table` instead, and a seed for
+    // picking which do.
+    blend_amount: f32,
+    blend_seed: u32,
+    portal_count: u32,
+    // 1 when cells need energy to live, spending `energy_cost` a generation
+    // while empty cells regain `energy_regen`. See `EnergySettings`.
+    energy_enabled: u32,
+    energy_cost: f32,
+    energy_regen: f32,
+    // Cells die once they've lived this many generations, unless it's 0.
+    // See `Lifespans`.
+    max_age: u32,
+    // What's past the board's edges: 0 for dead cells, 1 to wrap around and
+    // 2 to mirror. See `Edges`.
+    edges: u32,
+    // Bit n is set when a dead cell with n live neighbours is born, or a
+    // live one survives, and how many states there are, counting the decay
+    // states between live and dead. See `RuleString`.
+    birth: u32,
+    survival: u32,
+    rule_states: u32,
+    // With a Larger than Life rule, everything within `radius` counts, in a
+    // square (`neighborhood` 0) or a diamond (1), the cell itself too when
+    // `middle` is 1, and birth and survival take counts in these ranges.
+    radius: u32,
+    neighborhood: u32,
+    middle: u32,
+    birth_min: u32,
+    birth_max: u32,
+    survival_min: u32,
+    survival_max: u32,
+    // The chance of a cell taking its state in the target picture instead.
+    // See `TargetBias`.
+    target_bias: f32,
+    // Each portal's two rectangles, as (x, y, width, height), for up to
+    // `MAX_PORTALS` portals. See `Portal`.
+    portals: array<vec4<i32>, 16>,
+}
+
+@group(0) @binding(1) var<uniform> uniforms: ConwayUniforms;
+
+// The next state for every neighbourhood, four 8-bit states to a word. See `rule_table.rs`.
+@group(0) @binding(2) var<storage, read> rule_table: array<u32>;
+
+// Another table blended in. See `RuleBlend`.
+@group(0) @binding(5) var<storage, read> blend_table: array<u32>;
+
+// Each cell's current, in cells per generation. See `FlowField`.
+@group(0) @binding(6) var flow: texture_2d<f32>;
+
+// The board before this update. Updates read from it so they don't see
+// neighbours that have already been written.
+@group(0) @binding(3) var previous: texture_2d<f32>;
+
+// Per-cell flags. See `BoardMasks`.
+@group(0) @binding(4) var masks: texture_2d<u32>;
+const WALL: u32 = 1u;
+const FROZEN: u32 = 2u;
+const TARGET: u32 = 4u;
+
+fn has_mask(location: vec2<i32>, mask: u32) -> bool {
+    return (textureLoad(masks, location, 0).x & mask) != 0u;
+}
+
 fn hash(value: u32) -> u32 {
     var state = value;
     state = state ^ 2747636419u;
@@ -22,13 +95,140 @@ fn init(
     @builtin(num_workgroups) num_workgroups: vec3<u32>,
 ) {
     let location = vec2<u32>(global_id.xy);
-    let alive = randomFloat(location.x * num_workgroups.x + location.y) > 0.9;
-    let color = vec4f(f32(alive));
+    let alive = randomFloat(hash(uniforms.seed) + location.x * num_workgroups.x + location.y) < uniforms.density
+        && !has_mask(vec2<i32>(location), WALL);
+    // Every cell starts with full energy.
+    let color = vec4f(f32(alive), 1.0, f32(alive), f32(alive));
     textureStore(texture, location, color);
 }
 
+// Whether a cell is pulled toward the target picture this update. Picked
+// apart from the `RuleBlend` cells, so the two don't line up.
+fn follows_target(location: vec2<i32>) -> bool {
+    let cell_seed = hash(u32(location.x) * 7919u + u32(location.y)) ^ hash(uniforms.blend_seed ^ 1640531527u);
+    return randomFloat(cell_seed) < uniforms.target_bias;
+}
+
+// Where to read `target` from, round the other side or reflected back if
+// the edges wrap around or mirror.
+fn fold_board_edges(target: vec2<i32>) -> vec2<i32> {
+    return fold_edges(target, vec2<i32>(textureDimensions(previous)), uniforms.edges);
+}
+
+// Whether a folded location reads as dead: a wall, or still off the board.
+fn reads_dead(location: vec2<i32>) -> bool {
+    let size = vec2<i32>(textureDimensions(previous));
+    return any(location < vec2<i32>(0)) || any(location >= size) || has_mask(location, WALL);
+}
+
+// Reads the previous board, with walls dead, and everything beyond the edges
+// too unless they wrap around or mirror.
+fn load_previous(target: vec2<i32>) -> vec4f {
+    let location = fold_board_edges(target);
+    if (reads_dead(location)) {
+        return vec4f(0.0);
+    }
+    return textureLoad(previous, location, 0);
+}
+
+fn in_rect(location: vec2<i32>, rect: vec4<i32>) -> bool {
+    return all(location >= rect.xy) && all(location < rect.xy + rect.zw);
+}
+
+// Where `target` is relative to `from`, but in `to`, wrapping around at the edges.
+fn wrap_into(target: vec2<i32>, from: vec4<i32>, to: vec4<i32>) -> vec2<i32> {
+    let relative = target - from.xy;
+    return to.xy + (relative % from.zw + from.zw) % from.zw;
+}
+
+// Reads a neighbour of `location`. Looking out of one of a portal's
+// rectangles looks into the other one instead.
+fn load_neighbor(location: vec2<i32>, offset: vec2<i32>) -> vec4f {
+    let target = location + offset;
+    for (var i = 0u; i < uniforms.portal_count; i++) {
+        let a = uniforms.portals[2u * i];
+        let b = uniforms.portals[2u * i + 1u];
+        if (in_rect(location, a) && !in_rect(target, a)) {
+            return load_previous(wrap_into(target, a, b));
+        }
+        if (in_rect(location, b) && !in_rect(target, b)) {
+            return load_previous(wrap_into(target, b, a));
+        }
+    }
+    return load_previous(target);
+}
+
+// Energy lives in the green channel. Returns whether the cell can live with
+// the energy left in its spot, and how much there will be next generation.
+fn spend_energy(location: vec2<i32>, alive: bool) -> vec2f {
+    if (uniforms.energy_enabled == 0u) {
+        return vec2f(f32(alive), 1.0);
+    }
+    let energy = textureLoad(previous, location, 0).y;
+    if (alive && energy >= uniforms.energy_cost) {
+        return vec2f(1.0, energy - uniforms.energy_cost);
+    }
+    return vec2f(0.0, min(energy + uniforms.energy_regen, 1.0));
+}
+
+// Ages live in the blue channel, as 1 - age / 255 so fresh cells are white.
+// Returns how old a cell will be if it survives this generation, or 0 if
+// it's being born.
+fn previous_age(location: vec2<i32>) -> u32 {
+    let cell = textureLoad(previous, location, 0);
+    return select(0u, u32(round((1.0 - cell.z) * 255.0)) + 1u, cell.x > 0.0);
+}
+
+// Whether a cell that would live this long is past its lifespan.
+fn too_old(age: u32) -> bool {
+    return uniforms.max_age != 0u && age >= uniforms.max_age;
+}
+
+// Writes a cell's state, as a gray level, along with its energy and age.
+fn store_cell(location: vec2<i32>, level: f32, energy: f32, age: u32) {
+    let age_level = select(1.0 - f32(min(age, 255u)) / 255.0, level, uniforms.max_age == 0u);
+    textureStore(texture, location, vec4f(level, energy, age_level, level));
+}
+
 fn is_alive(location: vec2<i32>, offset_x: i32, offset_y: i32) -> i32 {
-    return i32(textureLoad(texture, location + vec2<i32>(offset_x, offset_y)).x);
+    return i32(load_neighbor(location, vec2<i32>(offset_x, offset_y)).x);
+}
+
+// Whether a cell is alive next generation under a Larger than Life rule.
+fn larger_than_life(location: vec2<i32>, alive: bool) -> bool {
+    let radius = i32(uniforms.radius);
+    var count = select(0u, 1u, alive && uniforms.middle == 1u);
+    for (var y = -radius; y <= radius; y++) {
+        for (var x = -radius; x <= radius; x++) {
+            let inside = uniforms.neighborhood == 0u || abs(x) + abs(y) <= radius;
+            if (inside && (x != 0 || y != 0)) {
+                count += u32(is_alive(location, x, y));
+            }
+        }
+    }
+    if (alive) {
+        return count >= uniforms.survival_min && count <= uniforms.survival_max;
+    }
+    return count >= uniforms.birth_min && count <= uniforms.birth_max;
+}
+
+// Moves every cell along the flow field. Each cell pulls in whatever is
+// upstream of it, so cells are never written twice; fractional velocities
+// round up or down at random, the same way across the board, so a steady
+// current carries patterns along whole.
+@compute
+@workgroup_size(8, 8)
+fn advect(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+) {
+    let location = vec2<i32>(global_id.xy);
+    let velocity = textureLoad(flow, location, 0).xy;
+    let offset = vec2<i32>(floor(velocity + randomFloat(hash(uniforms.blend_seed) ^ 2246822519u)));
+    var cell = load_previous(location - offset);
+    if (has_mask(location, FROZEN) || has_mask(location, WALL)) {
+        cell = textureLoad(previous, location, 0);
+    }
+    textureStore(texture, location, cell);
 }
 
 @compute
@@ -38,7 +238,7 @@ fn update(
 ) {
     let location = vec2<i32>(global_id.xy);
 
-    // Set alive if there are 2 or 3 alive neighbors.
+    // Born or surviving with the `RuleString`'s neighbour counts.
     let alive_neighbors: i32 =
         is_alive(location, -1, -1) +
         is_alive(location, -1, 0) +
@@ -49,19 +249,105 @@ fn update(
         is_alive(location, 1, 0) +
         is_alive(location, 1, 1);
 
-    var alive: bool;
-    if (alive_neighbors == 3) {
-        alive = true;
-    } else if (alive_neighbors == 2) {
-        alive = is_alive(location, 0, 0) == 1;
-    } else {
-        alive = false;
+    let counts = select(uniforms.birth, uniforms.survival, is_alive(location, 0, 0) == 1);
+    var alive = ((counts >> u32(alive_neighbors)) & 1u) != 0u;
+    if (uniforms.radius > 0u) {
+        alive = larger_than_life(location, is_alive(location, 0, 0) == 1);
     }
-
-    let color = vec4f(f32(alive));
-    textureStore(texture, location, color);
+    // Dying cells, anywhere between live and dead, can't be born.
+    let level = load_previous(location).x;
+    alive = alive && (level == 0.0 || level == 1.0);
+    let age = previous_age(location);
+    alive = alive && !too_old(age);
+    if (follows_target(location)) {
+        alive = has_mask(location, TARGET);
+    }
+    var cell = spend_energy(location, alive);
+    // With decay states, anything alive or dying that isn't alive next goes
+    // a state dimmer instead of dying outright.
+    if (uniforms.rule_states > 2u && level > 0.0 && cell.x == 0.0) {
+        let steps = f32(uniforms.rule_states - 1u);
+        cell.x = (round(level * steps) - 1.0) / steps;
+    }
+    var next_age = select(0u, age, cell.x > 0.0);
+    if (has_mask(location, FROZEN)) {
+        cell = vec2f(level, textureLoad(previous, location, 0).y);
+        next_age = max(age, 1u) - 1u;
+    }
+    if (has_mask(location, WALL)) {
+        cell.x = 0.0;
+    }
+    store_cell(location, cell.x, cell.y, next_age);
 }
  
+// Multi-state cells store their state as a gray level.
+fn cell_state(location: vec2<i32>, offset: vec2<i32>) -> u32 {
+    let level = load_neighbor(location, offset).x;
+    return u32(round(level * f32(uniforms.table_states - 1u)));
+}
+
+@compute
+@workgroup_size(8, 8)
+fn update_table(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+) {
+    let location = vec2<i32>(global_id.xy);
+
+    // The centre, then its neighbours clockwise from north (y points down).
+    var offsets = array<vec2<i32>, 9>(
+        vec2<i32>(0, 0),
+        vec2<i32>(0, -1),
+        vec2<i32>(1, -1),
+        vec2<i32>(1, 0),
+        vec2<i32>(1, 1),
+        vec2<i32>(0, 1),
+        vec2<i32>(-1, 1),
+        vec2<i32>(-1, 0),
+        vec2<i32>(-1, -1),
+    );
+    let von_neumann = uniforms.table_neighborhood == 1u;
+    let count = select(9u, 5u, von_neumann);
+
+    // Read the states as the digits of a little-endian base `table_states` number.
+    var index = 0u;
+    var place = 1u;
+    for (var i = 0u; i < count; i++) {
+        // Von Neumann neighbourhoods skip the diagonals.
+        let j = select(i, max(2u * i, 1u) - 1u, von_neumann);
+        index += cell_state(location, offsets[j]) * place;
+        place *= uniforms.table_states;
+    }
+
+    var next = (rule_table[index / 4u] >> ((index % 4u) * 8u)) & 255u;
+    let cell_seed = hash(u32(location.x) * 7919u + u32(location.y)) ^ hash(uniforms.blend_seed);
+    if (randomFloat(cell_seed) < uniforms.blend_amount) {
+        next = (blend_table[index / 4u] >> ((index % 4u) * 8u)) & 255u;
+    }
+    if (follows_target(location)) {
+        next = select(0u, uniforms.table_states - 1u, has_mask(location, TARGET));
+    }
+    // Any live state ages, and needs energy.
+    let age = previous_age(location);
+    if (too_old(age)) {
+        next = 0u;
+    }
+    let fed = spend_energy(location, next != 0u);
+    if (fed.x == 0.0) {
+        next = 0u;
+    }
+    var energy = fed.y;
+    var next_age = select(0u, age, next != 0u);
+    if (has_mask(location, FROZEN)) {
+        next = cell_state(location, vec2<i32>(0, 0));
+        energy = textureLoad(previous, location, 0).y;
+        next_age = max(age, 1u) - 1u;
+    }
+    if (has_mask(location, WALL)) {
+        next = 0u;
+    }
+    store_cell(location, f32(next) / f32(uniforms.table_states - 1u), energy, next_age);
+}
+
 struct VSOutput {
   @builtin(position) position: vec4f,
 };
@@ -75,4 +361,260 @@ fn set_cells_vs(@location(0) position: vec2f) -> VSOutput {
  
 @fragment fn set_cells_fs(vsOut: VSOutput) -> @location(0) vec4f {
   return vec4f(1.0); // Black
-}
\ No newline at end of file
+}
+
+@fragment fn clear_cells_fs(vsOut: VSOutput) -> @location(0) vec4f {
+  return vec4f(0.0, 0.0, 0.0, 1.0);
+}
+
+// Rows of cells to write, each packed as (x | y << 16, length | alive << 31).
+// See `CellRuns`.
+@group(1) @binding(0) var<storage, read> runs: array<vec2<u32>>;
+
+// Writes a run per invocation, the same as `set_cells_fs` or `clear_cells_fs`
+// would have cell by cell.
+@compute
+@workgroup_size(64)
+fn write_runs(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+    if (invocation_id.x >= arrayLength(&runs)) {
+        return;
+    }
+    let run = runs[invocation_id.x];
+    let start = vec2<i32>(i32(run.x & 0xffffu), i32(run.x >> 16u));
+    let length = i32(run.y & 0x7fffffffu);
+    let value = select(vec4f(0.0, 0.0, 0.0, 1.0), vec4f(1.0), (run.y >> 31u) == 1u);
+    for (var x = 0; x < length; x++) {
+        textureStore(texture, start + vec2<i32>(x, 0), value);
+    }
+}
+
+// Rectangles to write, each packed as (x | y << 16, width | height << 16,
+// alive, 0). See `CellRects`.
+@group(1) @binding(1) var<storage, read> rects: array<vec4<u32>>;
+
+// Each layer of workgroups writes a rect, as far as it reaches. Filling
+// leaves walls empty, the same as `SetCells`.
+@compute
+@workgroup_size(8, 8)
+fn write_rects(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+    let rect = rects[invocation_id.z];
+    let size = vec2<u32>(rect.y & 0xffffu, rect.y >> 16u);
+    if (any(invocation_id.xy >= size)) {
+        return;
+    }
+    let location = vec2<i32>(vec2<u32>(rect.x & 0xffffu, rect.x >> 16u) + invocation_id.xy);
+    let alive = rect.z == 1u;
+    if (alive && has_mask(location, WALL)) {
+        return;
+    }
+    textureStore(texture, location, select(vec4f(0.0, 0.0, 0.0, 1.0), vec4f(1.0), alive));
+}
+
+// The `BoardTransform` to apply: 0 to rotate, 1 to flip horizontally, 2 to
+// flip vertically, or 3 to shift by (y, z), wrapping around if w is 1.
+@group(1) @binding(2) var<storage, read> board_transform: array<vec4<i32>>;
+
+// Reads every cell from where the transform takes it from, in the copy of
+// the board in `previous`, energy and age along with it. As in
+// `BoardTransform::source`, cells from off the board come in empty, and ones
+// landing on walls are lost.
+@compute
+@workgroup_size(8, 8)
+fn transform(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+    let size = vec2<i32>(textureDimensions(previous));
+    let location = vec2<i32>(invocation_id.xy);
+    let transform = board_transform[0];
+    var source = location;
+    switch transform.x {
+        case 0: {
+            let offset = location * 2 + 1 - size;
+            source = (vec2<i32>(offset.y, -offset.x) + size - 1) / 2;
+        }
+        case 1: {
+            source = vec2<i32>(size.x - 1 - location.x, location.y);
+        }
+        case 2: {
+            source = vec2<i32>(location.x, size.y - 1 - location.y);
+        }
+        default: {
+            source = location - transform.yz;
+            if (transform.w == 1) {
+                source = (source % size + size) % size;
+            }
+        }
+    }
+    var value = vec4f(0.0, 0.0, 0.0, 1.0);
+    if (all(source >= vec2<i32>(0)) && all(source < size) && !has_mask(location, WALL)) {
+        value = textureLoad(previous, source, 0);
+    }
+    textureStore(texture, location, value);
+}
+
+// Lenia's states before this step, and where it writes the next ones, at
+// full precision. See `SimulationKind`.
+@group(1) @binding(3) var lenia_previous: texture_2d<f32>;
+@group(1) @binding(4) var lenia_state: texture_storage_2d<r32float, write>;
+
+// The kernel's weights over the square `radius` cells out each way, row by
+// row, summing to 1. See `LeniaSettings`.
+@group(1) @binding(5) var<storage, read> lenia_kernel: array<f32>;
+
+struct LeniaUniforms {
+    radius: u32,
+    // Where the growth function peaks, and how wide it is.
+    mu: f32,
+    sigma: f32,
+    // How much of the growth a cell takes each step.
+    dt: f32,
+}
+
+@group(1) @binding(6) var<uniform> lenia: LeniaUniforms;
+
+// A cell's state before this step. The board holds it to 8 bits, so where
+// the board's any further off than that, it's been edited since, and the
+// edit wins.
+fn lenia_level(target: vec2<i32>) -> f32 {
+    let location = fold_board_edges(target);
+    if (reads_dead(location)) {
+        return 0.0;
+    }
+    let level = textureLoad(previous, location, 0).x;
+    let state = textureLoad(lenia_previous, location, 0).x;
+    return select(state, level, abs(round(state * 255.0) / 255.0 - level) > 0.5 / 255.0);
+}
+
+// A step of Lenia: each cell's neighbourhood weighted by the kernel, and the
+// cell grown or shrunk by how near that is to `mu`. Portals, flow, rules and
+// lifespans don't come into it, but walls and frozen cells still do.
+@compute
+@workgroup_size(8, 8)
+fn update_lenia(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+) {
+    let location = vec2<i32>(global_id.xy);
+    let radius = i32(lenia.radius);
+    let side = 2 * radius + 1;
+    var potential = 0.0;
+    for (var y = -radius; y <= radius; y++) {
+        for (var x = -radius; x <= radius; x++) {
+            let weight = lenia_kernel[u32((y + radius) * side + x + radius)];
+            if (weight > 0.0) {
+                potential += weight * lenia_level(location + vec2<i32>(x, y));
+            }
+        }
+    }
+    let spread = potential - lenia.mu;
+    let growth = 2.0 * exp(-spread * spread / (2.0 * lenia.sigma * lenia.sigma)) - 1.0;
+    let level = lenia_level(location);
+    var state = clamp(level + lenia.dt * growth, 0.0, 1.0);
+    if (has_mask(location, FROZEN)) {
+        state = level;
+    }
+    if (has_mask(location, WALL)) {
+        state = 0.0;
+    }
+    textureStore(lenia_state, location, vec4f(state, 0.0, 0.0, 1.0));
+    store_cell(location, state, 1.0, 0u);
+}
+
+// The elementary automaton's rule number, and the row it writes this step.
+// See `ElementaryRule`.
+struct ElementaryUniforms {
+    rule: u32,
+    row: u32,
+}
+@group(1) @binding(7) var<uniform> elementary: ElementaryUniforms;
+
+// A generation of an elementary automaton: one row, from the row above, or
+// the bottom one for the top row, read through the edges like any other
+// update. Walls stay dead, and frozen cells as they were.
+@compute
+@workgroup_size(64)
+fn update_elementary(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+) {
+    let size = vec2<i32>(textureDimensions(previous));
+    let row = i32(elementary.row);
+    let location = vec2<i32>(i32(global_id.x), row);
+    if (location.x >= size.x) {
+        return;
+    }
+    let above = vec2<i32>(location.x, (row + size.y - 1) % size.y);
+    var pattern = 0u;
+    for (var x = -1; x <= 1; x++) {
+        pattern = pattern << 1u | u32(is_alive(above, x, 0));
+    }
+    var level = f32((elementary.rule >> pattern) & 1u);
+    if (has_mask(location, FROZEN)) {
+        level = textureLoad(previous, location, 0).x;
+    }
+    if (has_mask(location, WALL)) {
+        level = 0.0;
+    }
+    store_cell(location, level, 1.0, 0u);
+}
+
+// Regions per side of the `DensityGrid`, one for each cell of a workgroup.
+const DENSITY_GRID_SIZE: u32 = 8u;
+
+// What `reduce` adds the board up into: the living cells in each region of
+// the `DensityGrid`, counting those at or above `alive_level`, and the box
+// around them for `LiveBounds`. See `update_living_cells`, which starts the
+// mins off at the most a `u32` holds so an empty board's come out crossed.
+struct Reduction {
+    alive_level: f32,
+    density: array<atomic<u32>, 64>,
+    min_x: atomic<u32>,
+    min_y: atomic<u32>,
+    max_x: atomic<u32>,
+    max_y: atomic<u32>,
+}
+@group(1) @binding(8) var<storage, read_write> reduction: Reduction;
+
+// A workgroup's own counts, so each region takes one atomic add on the
+// buffer per workgroup rather than one per living cell.
+var<workgroup> density_tally: array<atomic<u32>, 64>;
+// And its own box, as the min x, min y, max x and max y, likewise.
+var<workgroup> bounds_tally: array<atomic<u32>, 4>;
+
+const NO_CELL: u32 = 0xffffffffu;
+
+// Counts the living cells in the copy of the board in `previous`, region by
+// region, and finds the box around them.
+@compute
+@workgroup_size(8, 8)
+fn reduce(
+    @builtin(global_invocation_id) invocation_id: vec3<u32>,
+    @builtin(local_invocation_index) local_index: u32,
+) {
+    let size = vec2<u32>(textureDimensions(previous));
+    let location = invocation_id.xy;
+    // Workgroup memory starts out zeroed, which suits the maxes but not the
+    // mins.
+    if (local_index == 0u) {
+        atomicStore(&bounds_tally[0], NO_CELL);
+        atomicStore(&bounds_tally[1], NO_CELL);
+    }
+    workgroupBarrier();
+    let level = round(textureLoad(previous, vec2<i32>(location), 0).x * 255.0);
+    if (level >= reduction.alive_level) {
+        let region = location.y * DENSITY_GRID_SIZE / size.y * DENSITY_GRID_SIZE
+            + location.x * DENSITY_GRID_SIZE / size.x;
+        atomicAdd(&density_tally[region], 1u);
+        atomicMin(&bounds_tally[0], location.x);
+        atomicMin(&bounds_tally[1], location.y);
+        atomicMax(&bounds_tally[2], location.x);
+        atomicMax(&bounds_tally[3], location.y);
+    }
+    workgroupBarrier();
+    let count = atomicLoad(&density_tally[local_index]);
+    if (count > 0u) {
+        atomicAdd(&reduction.density[local_index], count);
+    }
+    if (local_index == 0u && atomicLoad(&bounds_tally[0]) != NO_CELL) {
+        atomicMin(&reduction.min_x, atomicLoad(&bounds_tally[0]));
+        atomicMin(&reduction.min_y, atomicLoad(&bounds_tally[1]));
+        atomicMax(&reduction.max_x, atomicLoad(&bounds_tally[2]));
+        atomicMax(&reduction.max_y, atomicLoad(&bounds_tally[3]));
+    }
+}
@@ -0,0 +1,108 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+#import "shaders/edges.wgsl"::fold_edges
+
+// Draws the board averaging `samples` x `samples` cells across each pixel's
+// footprint, rather than taking the one nearest its centre, and optionally
+// decoding its levels from sRGB. With `lod` on, pixels covering more than a
+// cell each way read the matching level of `mips` instead. With `fate` on,
+// live cells are colored by whether they survive the next generation, and
+// with `decay_states`, dying cells by how far along they are.
+struct DisplaySettings {
+    // The board sprite's color, which the board is multiplied by.
+    tint: vec4<f32>,
+    samples: u32,
+    srgb: u32,
+    lod: u32,
+    fate: u32,
+    // Bit n is set when a live cell with n live neighbours survives.
+    survival: u32,
+    // The rule's state count, dead and live included, while dying cells are
+    // colored, or 0.
+    decay_states: u32,
+    // What's past the edges, as the update shaders see it. See `Edges`.
+    edges: u32,
+}
+
+@group(1) @binding(0) var<uniform> settings: DisplaySettings;
+@group(1) @binding(1) var board: texture_2d<f32>;
+@group(1) @binding(2) var mips: texture_2d<f32>;
+
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+    let low = color / 12.92;
+    let high = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(high, low, color <= vec3<f32>(0.04045));
+}
+
+fn decode(rgb: vec3<f32>) -> vec3<f32> {
+    return select(rgb, srgb_to_linear(rgb), settings.srgb != 0u);
+}
+
+// Green for a live cell that survives, blue if it dies with fewer neighbours
+// than it could survive with, and red if it dies with more. Past the edges,
+// neighbours are found the way the update shaders find them.
+fn fate(cell: vec2<i32>, size: vec2<i32>) -> vec3<f32> {
+    var neighbors = 0u;
+    for (var dy = -1; dy <= 1; dy++) {
+        for (var dx = -1; dx <= 1; dx++) {
+            let neighbor = fold_edges(cell + vec2<i32>(dx, dy), size, settings.edges);
+            let inside = all(neighbor >= vec2<i32>(0)) && all(neighbor < size);
+            if ((dx != 0 || dy != 0) && inside && textureLoad(board, neighbor, 0).x == 1.0) {
+                neighbors += 1u;
+            }
+        }
+    }
+    if ((settings.survival & (1u << neighbors)) != 0u) {
+        return vec3<f32>(0.2, 0.9, 0.3);
+    }
+    let lonely = neighbors < countTrailingZeros(settings.survival);
+    return select(vec3<f32>(1.0, 0.3, 0.2), vec3<f32>(0.3, 0.5, 1.0), lonely);
+}
+
+// Orange just after a cell dies, through to purple in its last state before
+// it's dead.
+fn decay(level: f32) -> vec3<f32> {
+    let steps = f32(settings.decay_states - 1u);
+    let t = round(level * steps) / (steps - 1.0);
+    return mix(vec3<f32>(0.35, 0.1, 0.55), vec3<f32>(1.0, 0.55, 0.15), t);
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let size = vec2<i32>(textureDimensions(board));
+    let position = in.uv * vec2<f32>(size);
+    // How many cells across this pixel covers.
+    let footprint = fwidth(position);
+    let n = f32(settings.samples);
+
+    let widest = max(footprint.x, footprint.y);
+    if (settings.lod != 0u && widest > 1.0) {
+        // The most detailed level with no more than a texel per pixel.
+        let level = min(u32(floor(log2(widest))), textureNumLevels(mips) - 1u);
+        let level_size = vec2<i32>(textureDimensions(mips, level));
+        let texel = clamp(vec2<i32>(floor(in.uv * vec2<f32>(level_size))), vec2<i32>(0), level_size - 1);
+        let sample = textureLoad(mips, texel, i32(level));
+        return vec4<f32>(decode(sample.rgb) * settings.tint.rgb, sample.a * settings.tint.a);
+    }
+
+    // Dead cells are transparent, so weight colors by alpha.
+    var color = vec3<f32>(0.0);
+    var alpha = 0.0;
+    for (var i = 0u; i < settings.samples; i++) {
+        for (var j = 0u; j < settings.samples; j++) {
+            let offset = (vec2<f32>(f32(i), f32(j)) + 0.5) / n - 0.5;
+            let cell = clamp(vec2<i32>(floor(position + offset * footprint)), vec2<i32>(0), size - 1);
+            let sample = textureLoad(board, cell, 0);
+            var rgb = select(decode(sample.rgb), fate(cell, size), settings.fate != 0u && sample.x > 0.5);
+            var a = sample.a;
+            // Dying cells are drawn solid, in their decay color.
+            if (settings.decay_states > 2u && sample.x > 0.0 && sample.x < 1.0) {
+                rgb = decay(sample.x);
+                a = 1.0;
+            }
+            color += rgb * a;
+            alpha += a;
+        }
+    }
+    let rgb = select(vec3<f32>(0.0), color / alpha, alpha > 0.0);
+    return vec4<f32>(rgb * settings.tint.rgb, alpha / (n * n) * settings.tint.a);
+}
@@ -0,0 +1,16 @@
+// What's past the board's edges, shared by the update shaders and the display
+// so the two agree. See `Edges`.
+
+// Where to read `target` from on a `size` board, round the other side or
+// reflected back if `edges` wrap around (1) or mirror (2). With dead edges
+// (0) it's left where it is, so it can still be off the board.
+fn fold_edges(target: vec2<i32>, size: vec2<i32>, edges: u32) -> vec2<i32> {
+    var location = target;
+    if (edges == 1u) {
+        location = (location % size + size) % size;
+    } else if (edges == 2u) {
+        location = select(location, -location - 1, location < vec2<i32>(0));
+        location = select(location, size * 2 - location - 1, location >= size);
+    }
+    return location;
+}
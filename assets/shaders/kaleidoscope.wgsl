@@ -0,0 +1,36 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+// Folds the board into `folds`-fold radial symmetry around its centre,
+// mirroring the wedge just counterclockwise of the positive x axis.
+struct KaleidoscopeSettings {
+    background: vec4<f32>,
+    // The board sprite's color, which the board is multiplied by.
+    tint: vec4<f32>,
+    folds: f32,
+    // Width over height, so the wedges aren't squashed.
+    aspect: f32,
+}
+
+@group(1) @binding(0) var<uniform> settings: KaleidoscopeSettings;
+@group(1) @binding(1) var board: texture_2d<f32>;
+@group(1) @binding(2) var board_sampler: sampler;
+
+const TAU: f32 = 6.28318530718;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let p = (in.uv - 0.5) * vec2<f32>(settings.aspect, 1.0);
+    let wedge = TAU / settings.folds;
+    var angle = atan2(p.y, p.x);
+    angle = angle - floor(angle / wedge) * wedge;
+    // Every other wedge is a mirror image, so the seams line up.
+    angle = min(angle, wedge - angle);
+    let folded = length(p) * vec2<f32>(cos(angle), sin(angle)) / vec2<f32>(settings.aspect, 1.0) + 0.5;
+
+    // The board is drawn with dead cells transparent, so put them over the
+    // background, and leave whatever folds in from past the edges empty.
+    let cell = textureSample(board, board_sampler, folded);
+    let on_board = all(folded >= vec2<f32>(0.0)) && all(folded <= vec2<f32>(1.0));
+    let alpha = select(0.0, cell.a, on_board);
+    return vec4<f32>(mix(settings.background.rgb, cell.rgb * settings.tint.rgb, alpha), 1.0);
+}
@@ -0,0 +1,29 @@
+// Builds a level of the board's mip chain from the one above it. Colors are
+// averaged over the live part of each 2 x 2 block, weighting by alpha, and
+// alpha is the block's average, so it ends up as the density of live cells.
+// Odd rows and columns at the edge are folded into their neighbours.
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var destination: texture_storage_2d<rgba8unorm, write>;
+
+@compute
+@workgroup_size(8, 8)
+fn downsample(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+) {
+    let location = vec2<i32>(global_id.xy);
+    if (any(location >= vec2<i32>(textureDimensions(destination)))) {
+        return;
+    }
+    let source_size = vec2<i32>(textureDimensions(source));
+    var color = vec3<f32>(0.0);
+    var alpha = 0.0;
+    for (var i = 0; i < 4; i++) {
+        let cell = min(location * 2 + vec2<i32>(i % 2, i / 2), source_size - 1);
+        let sample = textureLoad(source, cell, 0);
+        color += sample.rgb * sample.a;
+        alpha += sample.a;
+    }
+    let rgb = select(vec3<f32>(0.0), color / alpha, alpha > 0.0);
+    textureStore(destination, location, vec4<f32>(rgb, alpha / 4.0));
+}
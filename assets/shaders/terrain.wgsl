@@ -0,0 +1,43 @@
+#import bevy_pbr::mesh_functions::{get_model_matrix, mesh_position_local_to_clip}
+
+// Draws the board as terrain, raising each vertex of a flat plane by the cell
+// under it.
+@group(1) @binding(0) var<uniform> height_scale: f32;
+@group(1) @binding(1) var board: texture_2d<f32>;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+    @location(0) position: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) height: f32,
+};
+
+// Live cells stand 1 high. While lifespans are on blue is 1 - age / 255, so
+// older cells stand up to twice that; otherwise it's the same as red.
+fn cell_height(uv: vec2<f32>) -> f32 {
+    let size = vec2<f32>(textureDimensions(board));
+    let cell = textureLoad(board, vec2<i32>(min(uv * size, size - 1.0)), 0);
+    return cell.x * (2.0 - cell.z);
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let height = cell_height(vertex.uv);
+    let position = vertex.position + vec3<f32>(0.0, height * height_scale, 0.0);
+    var out: VertexOutput;
+    out.clip_position = mesh_position_local_to_clip(get_model_matrix(vertex.instance_index), vec4<f32>(position, 1.0));
+    out.height = height;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // From the valley floor up to the oldest peaks.
+    let low = vec3<f32>(0.05, 0.1, 0.2);
+    let high = vec3<f32>(0.9, 1.0, 0.8);
+    return vec4<f32>(mix(low, high, clamp(in.height / 2.0, 0.0, 1.0)), 1.0);
+}
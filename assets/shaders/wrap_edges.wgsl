@@ -0,0 +1,29 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+// Draws the board's opposite edges in the margin around it, fading out away
+// from the border. The board itself covers the middle.
+struct WrapEdgesSettings {
+    // The board sprite's color, which the board is multiplied by.
+    tint: vec4<f32>,
+    // How far the margin reaches past each edge, as a fraction of the board.
+    margin: vec2<f32>,
+    // Opacity right at the border.
+    strength: f32,
+}
+
+@group(1) @binding(0) var<uniform> settings: WrapEdgesSettings;
+@group(1) @binding(1) var board: texture_2d<f32>;
+@group(1) @binding(2) var board_sampler: sampler;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let position = in.uv * (1.0 + 2.0 * settings.margin) - settings.margin;
+    if (all(position >= vec2<f32>(0.0)) && all(position <= vec2<f32>(1.0))) {
+        discard;
+    }
+    // How far out this is, from 0 at the border to 1 at the margin's edge.
+    let past = max(max(-position, position - 1.0), vec2<f32>(0.0)) / settings.margin;
+    let fade = 1.0 - max(past.x, past.y);
+    let cell = textureSample(board, board_sampler, fract(position));
+    return vec4<f32>(cell.rgb * settings.tint.rgb, cell.a * settings.strength * fade);
+}
@@ -0,0 +1,229 @@
+// An A/B test of the GPU board against the CPU one (`abtest N` in the console,
+// `abtest off` to stop and put the results away): the board is reseeded from
+// a seed drawn from `SimRng`, the CPU board is seeded with the same soup from
+// it, and both run the same B/S rule for N generations, the CPU board trailing
+// the GPU in a background task that catches up to each board read back and
+// compares them. The inspector shows the seed, how many cells differ, when
+// they first did, and how many generations a second each side manages.
+//
+// The CPU board doesn't know about walls, portals, wrapping, energy and the
+// rest, or edits made during the run, so any of those shows up as divergence.
+
+use std::time::Instant;
+
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use futures_lite::future;
+use rand::Rng;
+
+use crate::conway::{
+    ActiveRuleTable, BoardSnapshot, Generation, GenerationAdvanced, RuleString, SeedSettings, SimulationState,
+};
+use crate::cpu::CpuBoard;
+use crate::event_log::LogEvent;
+use crate::inspector::Inspector;
+use crate::life_rule::LifeRule;
+use crate::rng::SimRng;
+
+pub struct AbTestPlugin;
+
+const SECTION: &str = "A/B test";
+
+#[derive(Event, Clone, Copy, Debug)]
+pub enum AbTestCommand {
+    Start { generations: u64 },
+    Stop,
+}
+
+// The CPU board caught up to a GPU one.
+struct Caught {
+    board: CpuBoard,
+    generation: u64,
+    differing: usize,
+    seconds: f32,
+}
+
+struct Run {
+    rule: LifeRule,
+    generations: u64,
+    // What both boards were seeded from.
+    seed: u32,
+    // The generation both started from, once the GPU's been seeded.
+    start: Option<u64>,
+    // The CPU board and its generation, unless a task has them.
+    board: Option<CpuBoard>,
+    generation: u64,
+    // The generation of the last board read back from the GPU.
+    gpu_generation: u64,
+    catching_up: Option<Task<Caught>>,
+    // Seconds the GPU has been running for, and the CPU stepping for.
+    gpu_seconds: f32,
+    cpu_seconds: f32,
+    differing: usize,
+    first_divergence: Option<u64>,
+    finished: bool,
+}
+
+impl Run {
+    fn report(&self) -> String {
+        let start = self.start.unwrap_or(0);
+        let (stepped, gpu_stepped) = (self.generation - start, self.gpu_generation - start);
+        let shown = stepped.min(self.generations);
+        let progress = format!("generation {} of {}", shown, self.generations);
+        let mut lines = vec![format!("{} from seed {}: {}", self.rule, self.seed, progress)];
+        lines.push(match self.first_divergence {
+            Some(generation) => format!("{} cells differ, first at generation {}", self.differing, generation - start),
+            None => "no divergence".to_string(),
+        });
+        if stepped > 0 {
+            let gpu = gpu_stepped as f32 / self.gpu_seconds.max(f32::EPSILON);
+            let cpu = stepped as f32 / self.cpu_seconds.max(f32::EPSILON);
+            lines.push(format!("GPU {:.0} gen/s, CPU {:.0} gen/s ({:.1}x)", gpu, cpu, gpu / cpu.max(f32::EPSILON)));
+        }
+        lines.join("\n")
+    }
+}
+
+#[derive(Resource, Default)]
+struct AbTest(Option<Run>);
+
+#[allow(clippy::too_many_arguments)]
+fn handle_commands(
+    mut ab_commands: EventReader<AbTestCommand>,
+    mut ab_test: ResMut<AbTest>,
+    rule_table: Res<ActiveRuleTable>,
+    rule_string: Res<RuleString>,
+    mut seed_settings: ResMut<SeedSettings>,
+    mut rng: ResMut<SimRng>,
+    mut inspector: ResMut<Inspector>,
+    mut log: EventWriter<LogEvent>,
+) {
+    for command in ab_commands.read() {
+        inspector.remove(SECTION);
+        ab_test.0 = None;
+        let AbTestCommand::Start { generations } = *command else {
+            continue;
+        };
+        let name = rule_table.name(&rule_string);
+        let rule = match LifeRule::parse(&name).and_then(LifeRule::for_cpu) {
+            Ok(rule) => rule,
+            Err(_) => {
+                log.send(LogEvent(format!("The CPU board can't run {}, only two-state B/S rules", name)));
+                continue;
+            }
+        };
+        let seed = rng.rng.gen();
+        seed_settings.seed = seed;
+        seed_settings.reseed();
+        ab_test.0 = Some(Run {
+            rule,
+            generations: generations.max(1),
+            seed,
+            start: None,
+            board: None,
+            generation: 0,
+            gpu_generation: 0,
+            catching_up: None,
+            gpu_seconds: 0.0,
+            cpu_seconds: 0.0,
+            differing: 0,
+            first_divergence: None,
+            finished: false,
+        });
+    }
+}
+
+fn catch_up(mut board: CpuBoard, from: u64, to: u64, rule: LifeRule, gpu_cells: Vec<u8>) -> Caught {
+    let started = Instant::now();
+    for _ in from..to {
+        board.step_with(&rule);
+    }
+    let seconds = started.elapsed().as_secs_f32();
+    let differing = board.cells.iter().zip(&gpu_cells).filter(|(cpu, gpu)| (**cpu == 255) != (**gpu == 255)).count();
+    Caught { board, generation: to, differing, seconds }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_test(
+    time: Res<Time>,
+    mut generations: EventReader<GenerationAdvanced>,
+    simulation_state: Res<SimulationState>,
+    generation: Res<Generation>,
+    seed_settings: Res<SeedSettings>,
+    board: Res<BoardSnapshot>,
+    mut ab_test: ResMut<AbTest>,
+    mut inspector: ResMut<Inspector>,
+    mut log: EventWriter<LogEvent>,
+) {
+    let advanced = generations.read().last();
+    let Some(run) = ab_test.0.as_mut().filter(|run| !run.finished) else {
+        return;
+    };
+    let Some(start) = run.start else {
+        // Both start from generation 0 of the seed's soup, once the GPU's had it.
+        if generation.is_seeded_for(&seed_settings) {
+            let size = board.size();
+            let mut cpu_board = CpuBoard::new(size.x, size.y);
+            cpu_board.seed_like_gpu(seed_settings.density, run.seed);
+            run.start = Some(0);
+            run.generation = 0;
+            run.gpu_generation = 0;
+            run.board = Some(cpu_board);
+            inspector.set(SECTION, run.report());
+        }
+        return;
+    };
+    let end = start + run.generations;
+    if run.gpu_generation < end && !simulation_state.paused {
+        run.gpu_seconds += time.delta_seconds();
+    }
+    if let Some(advanced) = advanced {
+        if advanced.generation < run.gpu_generation {
+            log.send(LogEvent("Stopped the A/B test, the board went back a generation".to_string()));
+            run.finished = true;
+            return;
+        }
+        run.gpu_generation = advanced.generation;
+    }
+    // Boards read back while the CPU's catching up aren't compared.
+    if let Some(task) = &mut run.catching_up {
+        let Some(caught) = future::block_on(future::poll_once(task)) else {
+            return;
+        };
+        run.catching_up = None;
+        run.board = Some(caught.board);
+        run.generation = caught.generation;
+        run.cpu_seconds += caught.seconds;
+        run.differing = caught.differing;
+        if caught.differing > 0 && run.first_divergence.is_none() {
+            run.first_divergence = Some(caught.generation);
+        }
+        if run.generation >= end {
+            run.finished = true;
+            log.send(LogEvent(format!("A/B test done: {}", run.report().replace('\n', ", "))));
+        }
+        inspector.set(SECTION, run.report());
+        if run.finished {
+            return;
+        }
+    }
+    let Some(advanced) = advanced.filter(|advanced| advanced.generation > run.generation) else {
+        return;
+    };
+    let Some(cpu_board) = run.board.take() else {
+        return;
+    };
+    let (from, to, rule, gpu_cells) = (run.generation, advanced.generation, run.rule, board.cells.clone());
+    let task = AsyncComputeTaskPool::get().spawn(async move { catch_up(cpu_board, from, to, rule, gpu_cells) });
+    run.catching_up = Some(task);
+}
+
+impl Plugin for AbTestPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<AbTestCommand>()
+        .init_resource::<AbTest>()
+        .add_systems(Update, (handle_commands, run_test).chain())
+        ;
+    }
+}
@@ -0,0 +1,264 @@
+// Achievements for milestones on the board, unlocked from the generation and
+// stabilization events and persisted to disk as tab-separated lines. Each one
+// pops up a toast when it's unlocked, and A shows the whole list.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use crate::census;
+use crate::conway::{BoardSnapshot, GenerationAdvanced};
+use crate::event_log::LogEvent;
+use crate::leaderboard;
+use crate::stabilization::Stabilized;
+
+pub struct AchievementsPlugin;
+
+pub const ACHIEVEMENTS_PATH: &str = "achievements.tsv";
+const CROWD_POPULATION: u64 = 100_000;
+const MARATHON_GENERATIONS: u64 = 1_000_000;
+const LONG_PERIOD: u64 = 15;
+// Telling a gun apart takes a census, so only take one this often, and only
+// on boards sparse enough for it to be quick.
+const CENSUS_GENERATIONS: u64 = 60;
+const MAX_CENSUS_POPULATION: u64 = 5_000;
+// Censuses in a row the number of gliders has to go up in to count as a gun.
+const GUN_CENSUSES: u32 = 4;
+const TOAST_SECONDS: f32 = 4.0;
+const FONT_SIZE: f32 = 28.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Achievement {
+    GliderGun,
+    Crowd,
+    Marathon,
+    LongOscillator,
+}
+
+impl Achievement {
+    pub const ALL: [Self; 4] = [Self::GliderGun, Self::Crowd, Self::Marathon, Self::LongOscillator];
+
+    /// The name it's saved under.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::GliderGun => "glider-gun",
+            Self::Crowd => "population-100k",
+            Self::Marathon => "generation-1m",
+            Self::LongOscillator => "period-15",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::GliderGun => "Gunsmith",
+            Self::Crowd => "Standing room only",
+            Self::Marathon => "Marathon",
+            Self::LongOscillator => "Slow pulse",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::GliderGun => "Build a glider gun",
+            Self::Crowd => "Have 100,000 cells alive at once",
+            Self::Marathon => "Keep a board alive for 1,000,000 generations",
+            Self::LongOscillator => "Settle into a cycle with a period of 15 or more",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|achievement| achievement.id() == id)
+    }
+}
+
+/// Sent when an achievement is unlocked for the first time.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct AchievementUnlocked(pub Achievement);
+
+/// The achievements unlocked so far, with the dates they were unlocked on.
+#[derive(Resource, Default)]
+pub struct Achievements {
+    pub unlocked: Vec<(Achievement, String)>,
+}
+
+impl Achievements {
+    /// Loads the achievements, skipping any malformed lines. A missing file is none unlocked.
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let contents = fs::read_to_string(path).unwrap_or_default();
+        let unlocked = contents
+            .lines()
+            .filter_map(|line| {
+                let (id, date) = line.split_once('\t')?;
+                Some((Achievement::from_id(id)?, date.to_string()))
+            })
+            .collect();
+        Self { unlocked }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents = self.unlocked
+            .iter()
+            .map(|(achievement, date)| format!("{}\t{}\n", achievement.id(), date))
+            .collect::<String>();
+        fs::write(path, contents)
+    }
+
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.iter().any(|(unlocked, _)| *unlocked == achievement)
+    }
+}
+
+// Gliders counted by the last census, to spot a gun firing them.
+#[derive(Default)]
+struct GunWatch {
+    generation: u64,
+    gliders: usize,
+    // Censuses in a row with more gliders than the last.
+    rising: u32,
+}
+
+#[derive(Resource, Default)]
+struct Toasts(VecDeque<(Achievement, Timer)>);
+
+#[derive(Component)]
+struct ToastText;
+
+#[derive(Component)]
+struct AchievementsPage;
+
+fn setup(mut commands: Commands) {
+    let style = |top| Style { position_type: PositionType::Absolute, top, left: Val::Percent(30.0), ..default() };
+    commands.spawn((
+        ToastText,
+        TextBundle::from_section("", TextStyle { font_size: FONT_SIZE, color: Color::GOLD, ..default() })
+            .with_style(style(Val::Px(40.0))),
+    ));
+    commands.spawn((
+        AchievementsPage,
+        TextBundle::from_section("", TextStyle { font_size: FONT_SIZE, color: Color::WHITE, ..default() })
+            .with_style(style(Val::Percent(25.0)))
+            .with_background_color(Color::rgba(0.0, 0.0, 0.0, 0.8)),
+    )).insert(Visibility::Hidden);
+}
+
+fn check_achievements(
+    mut generations: EventReader<GenerationAdvanced>,
+    mut stabilized: EventReader<Stabilized>,
+    board: Res<BoardSnapshot>,
+    achievements: Res<Achievements>,
+    mut unlocked: EventWriter<AchievementUnlocked>,
+    mut gun_watch: Local<GunWatch>,
+) {
+    let mut earned = vec![];
+    if stabilized.read().any(|stabilized| stabilized.period >= LONG_PERIOD) {
+        earned.push(Achievement::LongOscillator);
+    }
+    if let Some(advanced) = generations.read().last() {
+        if advanced.population >= CROWD_POPULATION {
+            earned.push(Achievement::Crowd);
+        }
+        if advanced.generation >= MARATHON_GENERATIONS && advanced.population > 0 {
+            earned.push(Achievement::Marathon);
+        }
+        // The board was reseeded.
+        if advanced.generation < gun_watch.generation {
+            *gun_watch = GunWatch::default();
+        }
+        let due = advanced.generation >= gun_watch.generation + CENSUS_GENERATIONS
+            && advanced.population <= MAX_CENSUS_POPULATION;
+        if due && !achievements.is_unlocked(Achievement::GliderGun) {
+            let objects = census::recognize(&board.alive_cells());
+            let gliders = objects.iter().filter(|object| object.name == Some("glider")).count();
+            let rising = if gliders > gun_watch.gliders { gun_watch.rising + 1 } else { 0 };
+            *gun_watch = GunWatch { generation: advanced.generation, gliders, rising };
+            if rising >= GUN_CENSUSES {
+                earned.push(Achievement::GliderGun);
+            }
+        }
+    }
+    earned.retain(|achievement| !achievements.is_unlocked(*achievement));
+    unlocked.send_batch(earned.into_iter().map(AchievementUnlocked));
+}
+
+fn unlock(
+    mut unlocked: EventReader<AchievementUnlocked>,
+    mut achievements: ResMut<Achievements>,
+    mut toasts: ResMut<Toasts>,
+    mut log: EventWriter<LogEvent>,
+) {
+    let mut changed = false;
+    for AchievementUnlocked(achievement) in unlocked.read() {
+        if achievements.is_unlocked(*achievement) {
+            continue;
+        }
+        achievements.unlocked.push((*achievement, leaderboard::today()));
+        toasts.0.push_back((*achievement, Timer::from_seconds(TOAST_SECONDS, TimerMode::Once)));
+        log.send(LogEvent(format!("Achievement unlocked: {}", achievement.title())));
+        changed = true;
+    }
+    if changed {
+        if let Err(err) = achievements.save(ACHIEVEMENTS_PATH) {
+            println!("failed to save achievements: {}", err);
+        }
+    }
+}
+
+fn show_toasts(
+    time: Res<Time>,
+    mut toasts: ResMut<Toasts>,
+    mut query: Query<&mut Text, With<ToastText>>,
+) {
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+    // One at a time, oldest first.
+    if toasts.0.front_mut().is_some_and(|(_, timer)| timer.tick(time.delta()).finished()) {
+        toasts.0.pop_front();
+    }
+    let toast = toasts.0.front().map_or_else(String::new, |(achievement, _)| {
+        format!("Achievement unlocked: {}\n{}", achievement.title(), achievement.description())
+    });
+    if text.sections[0].value != toast {
+        text.sections[0].value = toast;
+    }
+}
+
+fn toggle_page(
+    keys: Res<Input<KeyCode>>,
+    achievements: Res<Achievements>,
+    mut page: Query<(&mut Text, &mut Visibility), With<AchievementsPage>>,
+) {
+    let Ok((mut text, mut visibility)) = page.get_single_mut() else {
+        return;
+    };
+    if keys.just_pressed(KeyCode::A) {
+        *visibility = if *visibility == Visibility::Hidden { Visibility::Visible } else { Visibility::Hidden };
+    }
+    if *visibility == Visibility::Hidden || !(visibility.is_changed() || achievements.is_changed()) {
+        return;
+    }
+    let mut lines = vec!["ACHIEVEMENTS".to_string(), String::new()];
+    for achievement in Achievement::ALL {
+        let date = achievements.unlocked.iter().find(|(unlocked, _)| *unlocked == achievement).map(|(_, date)| date);
+        let status = date.map_or_else(|| "locked".to_string(), |date| format!("unlocked {}", date));
+        lines.push(format!("{} - {} ({})", achievement.title(), achievement.description(), status));
+    }
+    lines.push(String::new());
+    lines.push("A - close".to_string());
+    text.sections[0].value = lines.join("\n");
+}
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<AchievementUnlocked>()
+        .insert_resource(Achievements::load(ACHIEVEMENTS_PATH))
+        .init_resource::<Toasts>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, (check_achievements, unlock, show_toasts, toggle_page).chain())
+        ;
+    }
+}
@@ -0,0 +1,111 @@
+// Notes pinned to the board, for explaining a construction: text labels and
+// arrows placed at cell coordinates, drawn in the world so they pan and zoom
+// along with the board. They're kept with save slots, in a `.labels.toml`
+// file next to the save.
+//
+//   label X Y TEXT          put TEXT with its bottom-left corner at cell X, Y
+//   arrow X1 Y1 X2 Y2       draw an arrow from one cell to another
+//   labels                  list them
+//   labels clear            remove them all
+
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use serde::{Deserialize, Serialize};
+
+use crate::conway::ConwayConfig;
+use crate::toml_file::TomlFile;
+
+pub struct AnnotationsPlugin;
+
+pub const EXTENSION: &str = "labels.toml";
+const FONT_SIZE: f32 = 20.0;
+const COLOR: Color = Color::rgb(1.0, 0.85, 0.3);
+// The arrowhead's length, and its angle either side of the shaft, in world units.
+const HEAD_LENGTH: f32 = 10.0;
+const HEAD_ANGLE: f32 = 0.5;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Label {
+    pub x: i32,
+    pub y: i32,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Arrow {
+    pub from: (i32, i32),
+    pub to: (i32, i32),
+}
+
+/// Everything pinned to the board.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Annotations {
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    #[serde(default)]
+    pub arrows: Vec<Arrow>,
+}
+
+impl Annotations {
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.arrows.is_empty()
+    }
+}
+
+impl TomlFile for Annotations {}
+
+// Marks the text of a label.
+#[derive(Component)]
+struct LabelText;
+
+fn show_labels(
+    mut commands: Commands,
+    annotations: Res<Annotations>,
+    config: Res<ConwayConfig>,
+    texts: Query<Entity, With<LabelText>>,
+) {
+    // A resized board moves every cell.
+    if !annotations.is_changed() && !config.is_changed() {
+        return;
+    }
+    for entity in &texts {
+        commands.entity(entity).despawn();
+    }
+    for label in &annotations.labels {
+        let position = config.cell_to_world(IVec2::new(label.x, label.y));
+        let style = TextStyle { font_size: FONT_SIZE, color: COLOR, ..default() };
+        commands.spawn((
+            LabelText,
+            Text2dBundle {
+                text: Text::from_section(label.text.clone(), style),
+                text_anchor: Anchor::BottomLeft,
+                // Over everything on the board.
+                transform: Transform::from_translation(position.extend(3.0)),
+                ..default()
+            },
+        ));
+    }
+}
+
+fn draw_arrows(annotations: Res<Annotations>, config: Res<ConwayConfig>, mut gizmos: Gizmos) {
+    for arrow in &annotations.arrows {
+        let from = config.cell_to_world(IVec2::from(arrow.from));
+        let to = config.cell_to_world(IVec2::from(arrow.to));
+        gizmos.line_2d(from, to, COLOR);
+        let Some(back) = (from - to).try_normalize() else {
+            continue;
+        };
+        for angle in [HEAD_ANGLE, -HEAD_ANGLE] {
+            gizmos.line_2d(to, to + Vec2::from_angle(angle).rotate(back) * HEAD_LENGTH, COLOR);
+        }
+    }
+}
+
+impl Plugin for AnnotationsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Annotations>()
+        .add_systems(Update, (show_labels, draw_arrows))
+        ;
+    }
+}
@@ -0,0 +1,264 @@
+// Langton's ant and other turmites: agents walking the board, flipping the
+// cells they leave behind. Each step an ant looks up the cell it's on in its
+// turmite's table, under the state it's in, which says what to leave in the
+// cell, which way to turn and what state to go into, then moves on a cell.
+// Ants that walk off the board are gone.
+//
+// They walk on the CPU, `SimulationState::speed` steps a frame while the
+// simulation isn't paused, writing through `ConwayCommand::SetCells` and
+// `ClearCells`. Under `SimulationKind::Ants` nothing else changes the board,
+// but they'll walk through the rule's cells just as well.
+//
+// The ant tool (0) puts an ant down at the cursor, heading up, and Alt-click
+// takes away any ants on that cell.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use bevy::prelude::*;
+
+use crate::conway::{BoardSnapshot, ConwayCommand, ConwayConfig, SimulationState};
+use crate::tools::{self, BoardCursor, Tool};
+
+pub struct AntsPlugin;
+
+const ANT_COLOR: Color = Color::rgb(1.0, 0.3, 0.2);
+
+// How many frames the board read back can lag the cells the ants wrote, so
+// until then an ant sees what was written rather than what was read.
+const READBACK_FRAMES: u32 = 3;
+
+const HEADINGS: [IVec2; 4] = [IVec2::new(0, -1), IVec2::new(1, 0), IVec2::new(0, 1), IVec2::new(-1, 0)];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Turn {
+    Left,
+    Right,
+    None,
+    Back,
+}
+
+impl Turn {
+    fn parse(letter: char) -> Option<Self> {
+        match letter {
+            'L' => Some(Self::Left),
+            'R' => Some(Self::Right),
+            'N' => Some(Self::None),
+            'U' => Some(Self::Back),
+            _ => None,
+        }
+    }
+
+    fn letter(self) -> char {
+        match self {
+            Self::Left => 'L',
+            Self::Right => 'R',
+            Self::None => 'N',
+            Self::Back => 'U',
+        }
+    }
+
+    // Quarter turns clockwise.
+    fn quarters(self) -> usize {
+        match self {
+            Self::None => 0,
+            Self::Right => 1,
+            Self::Back => 2,
+            Self::Left => 3,
+        }
+    }
+}
+
+/// What an ant in some state does on a dead or living cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TurmiteRule {
+    pub write: bool,
+    pub turn: Turn,
+    pub next: usize,
+}
+
+/// A turmite's table: for each state, the rules for a dead cell and a living
+/// one. Written as each rule's cell to leave, turn and next state, the two
+/// for a state split by commas and states by semicolons, so Langton's ant is
+/// `1R0,0L0` and Fibonacci's turmite `1R1,1L1;1R1,0R0`. A pair of turns
+/// alone, like `RL`, is an ant that flips every cell, turning the first way
+/// on dead cells and the second on living ones.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Turmite {
+    pub states: Vec<[TurmiteRule; 2]>,
+}
+
+impl Default for Turmite {
+    fn default() -> Self {
+        Self::parse("RL").unwrap()
+    }
+}
+
+fn parse_rule(text: &str) -> Result<TurmiteRule, String> {
+    let mut letters = text.chars();
+    let write = match letters.next() {
+        Some('0') => false,
+        Some('1') => true,
+        _ => return Err(format!("expected a rule to start with 0 or 1, got {}", text)),
+    };
+    let turn = letters
+        .next()
+        .and_then(Turn::parse)
+        .ok_or_else(|| format!("expected a turn of L, R, N or U in {}", text))?;
+    let next = letters.as_str().parse().map_err(|_| format!("expected a next state in {}", text))?;
+    Ok(TurmiteRule { write, turn, next })
+}
+
+impl Turmite {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim().to_ascii_uppercase();
+        if let &[Some(on_dead), Some(on_alive)] = text.chars().map(Turn::parse).collect::<Vec<_>>().as_slice() {
+            let flip = |write, turn| TurmiteRule { write, turn, next: 0 };
+            return Ok(Self { states: vec![[flip(true, on_dead), flip(false, on_alive)]] });
+        }
+        let states = text
+            .split(';')
+            .map(|state| {
+                let rules = state.split(',').map(parse_rule).collect::<Result<Vec<_>, _>>()?;
+                <[TurmiteRule; 2]>::try_from(rules)
+                    .map_err(|_| format!("expected a rule for dead cells and one for living ones in {}", state))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(rule) = states.iter().flatten().find(|rule| rule.next >= states.len()) {
+            return Err(format!("there's no state {} to go to", rule.next));
+        }
+        Ok(Self { states })
+    }
+
+    /// Moves `ant` on a step from a cell that's `alive`, returning what to
+    /// leave in the cell.
+    pub fn step(&self, ant: &mut Ant, alive: bool) -> bool {
+        let rule = self.states[ant.state][alive as usize];
+        ant.heading = (ant.heading + rule.turn.quarters()) % HEADINGS.len();
+        ant.state = rule.next;
+        ant.cell += HEADINGS[ant.heading];
+        rule.write
+    }
+}
+
+impl fmt::Display for Turmite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let states = self
+            .states
+            .iter()
+            .map(|rules| {
+                let rules = rules.map(|rule| format!("{}{}{}", rule.write as u8, rule.turn.letter(), rule.next));
+                rules.join(",")
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", states.join(";"))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ant {
+    pub cell: IVec2,
+    // An index into `HEADINGS`, up first and then clockwise.
+    pub heading: usize,
+    pub state: usize,
+}
+
+impl Ant {
+    pub fn new(cell: IVec2) -> Self {
+        Self { cell, heading: 0, state: 0 }
+    }
+}
+
+/// The ants on the board and the turmite they all follow.
+#[derive(Resource, Default)]
+pub struct Ants {
+    pub ants: Vec<Ant>,
+    pub turmite: Turmite,
+}
+
+// The cells the ants wrote lately, with the frame they were written in.
+#[derive(Resource, Default)]
+struct RecentWrites {
+    frame: u32,
+    cells: HashMap<IVec2, (bool, u32)>,
+}
+
+fn walk_ants(
+    simulation_state: Res<SimulationState>,
+    config: Res<ConwayConfig>,
+    board: Res<BoardSnapshot>,
+    mut ants: ResMut<Ants>,
+    mut recent: ResMut<RecentWrites>,
+    mut commands: EventWriter<ConwayCommand>,
+) {
+    recent.frame += 1;
+    let frame = recent.frame;
+    recent.cells.retain(|_, (_, written)| frame - *written <= READBACK_FRAMES);
+    if ants.ants.is_empty() || simulation_state.paused {
+        return;
+    }
+    // Only the last write to each cell is sent, as a frame's set cells all
+    // land before its cleared ones.
+    let mut writes = HashMap::new();
+    let Ants { ants, turmite } = &mut *ants;
+    for _ in 0..simulation_state.speed {
+        for ant in ants.iter_mut() {
+            let cell = ant.cell;
+            let alive = recent.cells.get(&cell).map_or_else(|| board.is_alive(cell), |(alive, _)| *alive);
+            let write = turmite.step(ant, alive);
+            recent.cells.insert(cell, (write, frame));
+            writes.insert(cell, write);
+        }
+        ants.retain(|ant| config.cell_index(ant.cell).is_some());
+    }
+    let (set, cleared): (Vec<_>, Vec<_>) = writes.into_iter().partition(|(_, alive)| *alive);
+    if !set.is_empty() {
+        commands.send(ConwayCommand::SetCells(set.into_iter().map(|(cell, _)| cell).collect()));
+    }
+    if !cleared.is_empty() {
+        commands.send(ConwayCommand::ClearCells(cleared.into_iter().map(|(cell, _)| cell).collect()));
+    }
+}
+
+fn use_ant_tool(
+    tool: Res<Tool>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    cursor: BoardCursor,
+    config: Res<ConwayConfig>,
+    mut ants: ResMut<Ants>,
+) {
+    if *tool != Tool::Ant || !mouse_button_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(cell) = cursor.cell().filter(|cell| config.cell_index(*cell).is_some()) else {
+        return;
+    };
+    if tools::erasing(&keys) {
+        ants.ants.retain(|ant| ant.cell != cell);
+    } else {
+        ants.ants.push(Ant::new(cell));
+    }
+}
+
+fn draw_ants(ants: Res<Ants>, config: Res<ConwayConfig>, mut gizmos: Gizmos) {
+    let scale = config.display_scale;
+    for ant in &ants.ants {
+        let center = config.cell_to_world(ant.cell) + Vec2::new(0.5, -0.5) * scale;
+        // Screen y is up where board y is down.
+        let heading = HEADINGS[ant.heading].as_vec2() * Vec2::new(1.0, -1.0);
+        let radius = (scale / 2.0).max(2.0);
+        gizmos.circle_2d(center, radius, ANT_COLOR);
+        gizmos.line_2d(center, center + heading * radius * 2.0, ANT_COLOR);
+    }
+}
+
+impl Plugin for AntsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Ants>()
+        .init_resource::<RecentWrites>()
+        .add_systems(Update, (walk_ants, use_ant_tool, draw_ants))
+        ;
+    }
+}
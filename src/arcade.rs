@@ -0,0 +1,452 @@
+// A plugin that turns the paddle game into an arcade mode played against the
+// Conway field: the ball clears any cells it touches, and a level is won once
+// enough of the field has been destroyed. Each level runs a rule of its own,
+// on a soup of its own with a pattern in the middle.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::conway::{
+    BoardSnapshot, ClearCells, ConwayCommand, ConwayConfig, Generation, LivingCells, SeedSettings, SetCells,
+};
+use crate::event_log::LogEvent;
+use crate::leaderboard::{self, HighScore, Leaderboard, LEADERBOARD_PATH, MAX_NAME_LENGTH};
+use crate::mixer::AudioMixer;
+use crate::paddle::{Ball, CollisionEvent, Velocity};
+use crate::patterns;
+
+pub struct ArcadePlugin;
+
+struct Level {
+    // As `ConwayCommand::set_rule_named` takes it.
+    rule: &'static str,
+    // Fraction of the field that starts alive.
+    density: f32,
+    seed: u32,
+    // Stamped in the middle of the field once it's seeded.
+    pattern: Option<&'static [(i32, i32)]>,
+    // The level is cleared once less than this fraction of the field is alive.
+    clear_fraction: f32,
+}
+
+// Levels are replayed from the start once exhausted, but the ball keeps getting faster.
+const LEVELS: &[Level] = &[
+    Level { rule: "B3/S23", density: 0.05, seed: 1, pattern: Some(patterns::GOSPER_GLIDER_GUN), clear_fraction: 0.004 },
+    Level { rule: "B36/S23", density: 0.1, seed: 2, pattern: Some(patterns::R_PENTOMINO), clear_fraction: 0.0065 },
+    Level { rule: "B368/S245", density: 0.2, seed: 3, pattern: Some(patterns::LWSS), clear_fraction: 0.009 },
+    Level { rule: "B3678/S34678", density: 0.35, seed: 4, pattern: None, clear_fraction: 0.011 },
+];
+
+const BALL_BASE_SPEED: f32 = 300.0;
+const BALL_SPEEDUP_PER_LEVEL: f32 = 75.0;
+// Cells live through a frame or two of readback latency, so don't bounce off them repeatedly.
+const BOUNCE_COOLDOWN_SECONDS: f32 = 0.2;
+// Give a freshly seeded field time to show up before checking the clear threshold.
+const LEVEL_GRACE_SECONDS: f32 = 2.0;
+const STARTING_LIVES: u32 = 3;
+// Wait for the ball to leave the impact point, or it would clear the glider straight away.
+const GLIDER_SPAWN_DELAY_SECONDS: f32 = 0.15;
+const PADDLE_HIT_FREQUENCY: f32 = 660.0;
+const PADDLE_HIT_SECONDS: f32 = 0.08;
+
+#[derive(States, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub enum ArcadeState {
+    #[default]
+    Menu,
+    Playing,
+    // Entering a name for a new high score.
+    NameEntry,
+    Leaderboard,
+}
+
+/// The (zero-based) level currently being played.
+#[derive(Resource, Default)]
+pub struct CurrentLevel(pub usize);
+
+#[derive(Resource, Default)]
+pub struct ArcadeScore {
+    // Number of living cells destroyed by the ball.
+    pub score: u64,
+    pub lives: u32,
+}
+
+// The name typed so far on the name entry screen.
+#[derive(Resource, Default)]
+struct PendingName(String);
+
+#[derive(Resource)]
+struct LevelGraceTimer(Timer);
+
+// The level's pattern, waiting for its soup to be seeded.
+#[derive(Resource, Default)]
+struct PendingPattern(Option<Vec<IVec2>>);
+
+// Gliders spawned by paddle hits, waiting to be stamped into the field.
+#[derive(Resource, Default)]
+struct PendingGliders(Vec<(Timer, Vec<IVec2>)>);
+
+#[derive(Component)]
+struct HudText;
+
+// Text for the menu, name entry and leaderboard screens.
+#[derive(Component)]
+struct ScreenText;
+
+fn ball_speed(level: usize) -> f32 {
+    BALL_BASE_SPEED + BALL_SPEEDUP_PER_LEVEL * level as f32
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        HudText,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 40.0,
+                color: Color::WHITE,
+                ..Default::default()
+            }
+        ).with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            right: Val::Px(5.0),
+            ..default()
+        }),
+    ));
+    commands.spawn((
+        ScreenText,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 40.0,
+                color: Color::WHITE,
+                ..Default::default()
+            }
+        ).with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(25.0),
+            left: Val::Percent(35.0),
+            ..default()
+        }),
+    ));
+}
+
+fn start_game(
+    mut level: ResMut<CurrentLevel>,
+    mut score: ResMut<ArcadeScore>,
+    mut ball_query: Query<(&mut Transform, &mut Velocity), With<Ball>>,
+) {
+    // Always mark the level as changed so that `start_level` reseeds the field.
+    level.0 = 0;
+    *score = ArcadeScore { score: 0, lives: STARTING_LIVES };
+    for (mut transform, mut velocity) in ball_query.iter_mut() {
+        transform.translation = Vec3::new(0.0, 0.0, transform.translation.z);
+        velocity.0 = Vec3::new(0.0, -ball_speed(0), 0.0);
+    }
+}
+
+fn stop_ball(mut ball_query: Query<&mut Velocity, With<Ball>>) {
+    for mut velocity in ball_query.iter_mut() {
+        velocity.0 = Vec3::ZERO;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn start_level(
+    level: Res<CurrentLevel>,
+    config: Res<ConwayConfig>,
+    mut seed_settings: ResMut<SeedSettings>,
+    mut grace_timer: ResMut<LevelGraceTimer>,
+    mut pending_pattern: ResMut<PendingPattern>,
+    mut ball_query: Query<&mut Velocity, With<Ball>>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut log: EventWriter<LogEvent>,
+) {
+    if !level.is_changed() {
+        return;
+    }
+    let settings = &LEVELS[level.0 % LEVELS.len()];
+    match ConwayCommand::set_rule_named(settings.rule) {
+        Ok(command) => commands.send(command),
+        Err(err) => log.send(LogEvent(format!("Couldn't load level {}'s rule: {}", level.0 + 1, err))),
+    }
+    // Reseeded straight away, rather than with `ConwayCommand::Reseed`, so
+    // `stamp_level_pattern` can't see the board from before.
+    seed_settings.density = settings.density;
+    seed_settings.seed = settings.seed;
+    seed_settings.reseed();
+    pending_pattern.0 = settings.pattern.map(|pattern| patterns::placed(pattern, config.size.as_ivec2() / 2));
+    grace_timer.0.reset();
+
+    for mut velocity in ball_query.iter_mut() {
+        velocity.0 = velocity.0.normalize_or_zero() * ball_speed(level.0);
+    }
+}
+
+// The init shader would write over a pattern put down before it's run.
+fn stamp_level_pattern(
+    generation: Res<Generation>,
+    seed_settings: Res<SeedSettings>,
+    mut pending_pattern: ResMut<PendingPattern>,
+    mut commands: EventWriter<ConwayCommand>,
+) {
+    if pending_pattern.0.is_none() || !generation.is_seeded_for(&seed_settings) {
+        return;
+    }
+    commands.send(ConwayCommand::SetCells(pending_pattern.0.take().unwrap()));
+}
+
+fn advance_level(
+    time: Res<Time>,
+    config: Res<ConwayConfig>,
+    living_cells: Res<LivingCells>,
+    mut grace_timer: ResMut<LevelGraceTimer>,
+    mut level: ResMut<CurrentLevel>,
+    mut log: EventWriter<LogEvent>,
+) {
+    if !grace_timer.0.tick(time.delta()).finished() {
+        return;
+    }
+    let clear_fraction = LEVELS[level.0 % LEVELS.len()].clear_fraction;
+    if (living_cells.0 as f32) < clear_fraction * config.cell_count() as f32 {
+        level.0 += 1;
+        log.send(LogEvent(format!("Level {} cleared", level.0)));
+    }
+}
+
+fn render_hud(
+    level: Res<CurrentLevel>,
+    score: Res<ArcadeScore>,
+    state: Res<State<ArcadeState>>,
+    mut query: Query<&mut Text, With<HudText>>,
+) {
+    let mut text = query.single_mut();
+    text.sections[0].value = if *state.get() == ArcadeState::Playing {
+        format!("Level {}  Score {}  Lives {}", level.0 + 1, score.score, score.lives)
+    } else {
+        String::new()
+    };
+}
+
+fn break_cells(
+    mut ball_query: Query<(&Transform, &mut Velocity), With<Ball>>,
+    board: Res<BoardSnapshot>,
+    config: Res<ConwayConfig>,
+    mut clear_cells: ResMut<ClearCells>,
+    mut score: ResMut<ArcadeScore>,
+    time: Res<Time>,
+    mut last_bounce: Local<f32>,
+) {
+    for (transform, mut velocity) in ball_query.iter_mut() {
+        let position = transform.translation.truncate();
+        let half_size = transform.scale.truncate() / 2.0;
+        // Cell rows grow downwards, so the top-left corner has the smallest cell coordinates.
+        let min = config.world_to_cell(position + Vec2::new(-half_size.x, half_size.y));
+        let max = config.world_to_cell(position + Vec2::new(half_size.x, -half_size.y));
+        let cells = (min.y..=max.y)
+            .flat_map(|y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+            .collect::<Vec<_>>();
+
+        let n_hit = cells.iter().filter(|cell| board.is_alive(**cell)).count();
+        if n_hit > 0 {
+            score.score += n_hit as u64;
+            clear_cells.0.extend(cells.into_iter().map(|cell| config.cell_to_clip(cell)));
+            if time.elapsed_seconds() - *last_bounce > BOUNCE_COOLDOWN_SECONDS {
+                *last_bounce = time.elapsed_seconds();
+                velocity.0.y = -velocity.0.y;
+            }
+        }
+    }
+}
+
+fn bounce_off_walls(
+    mut ball_query: Query<(&mut Transform, &mut Velocity), With<Ball>>,
+    level: Res<CurrentLevel>,
+    leaderboard: Res<Leaderboard>,
+    config: Res<ConwayConfig>,
+    mut score: ResMut<ArcadeScore>,
+    mut next_state: ResMut<NextState<ArcadeState>>,
+) {
+    let half_extents = config.extents() / 2.0;
+    for (mut transform, mut velocity) in ball_query.iter_mut() {
+        let position = transform.translation;
+        if (position.x < -half_extents.x && velocity.0.x < 0.0) ||
+            (position.x > half_extents.x && velocity.0.x > 0.0) {
+            velocity.0.x = -velocity.0.x;
+        }
+        if position.y > half_extents.y && velocity.0.y > 0.0 {
+            velocity.0.y = -velocity.0.y;
+        }
+        // The ball got past the paddle: serve it again from the middle.
+        if position.y < -half_extents.y {
+            transform.translation = Vec3::new(0.0, 0.0, position.z);
+            velocity.0 = Vec3::new(0.0, -ball_speed(level.0), 0.0);
+            score.lives = score.lives.saturating_sub(1);
+            if score.lives == 0 {
+                next_state.set(if leaderboard.qualifies(score.score) {
+                    ArcadeState::NameEntry
+                } else {
+                    ArcadeState::Leaderboard
+                });
+            }
+        }
+    }
+}
+
+fn queue_paddle_gliders(
+    mut commands: Commands,
+    mut collisions: EventReader<CollisionEvent>,
+    config: Res<ConwayConfig>,
+    mut pending_gliders: ResMut<PendingGliders>,
+    mut log: EventWriter<LogEvent>,
+    mut pitches: ResMut<Assets<Pitch>>,
+    mut mixer: ResMut<AudioMixer>,
+) {
+    for collision in collisions.read() {
+        commands.spawn(PitchBundle {
+            source: pitches.add(Pitch::new(PADDLE_HIT_FREQUENCY, Duration::from_secs_f32(PADDLE_HIT_SECONDS))),
+            settings: PlaybackSettings::DESPAWN,
+        });
+        mixer.duck();
+
+        let impact = config.world_to_cell(collision.position);
+        log.send(LogEvent(format!("Glider stamped at ({}, {})", impact.x, impact.y)));
+        // Gliders head down and to the right; flip them to travel away from the paddle.
+        let cells = patterns::oriented(patterns::GLIDER, collision.normal.x < 0.0, collision.normal.y > 0.0)
+            .into_iter()
+            .map(|offset| impact + offset - IVec2::ONE)
+            .collect();
+        pending_gliders.0.push((Timer::from_seconds(GLIDER_SPAWN_DELAY_SECONDS, TimerMode::Once), cells));
+    }
+}
+
+fn stamp_pending_gliders(
+    time: Res<Time>,
+    config: Res<ConwayConfig>,
+    mut pending_gliders: ResMut<PendingGliders>,
+    mut set_cells: ResMut<SetCells>,
+) {
+    pending_gliders.0.retain_mut(|(timer, cells)| {
+        if timer.tick(time.delta()).finished() {
+            set_cells.0.extend(cells.iter().map(|cell| config.cell_to_clip(*cell)));
+            false
+        } else {
+            true
+        }
+    });
+}
+
+fn render_screen(
+    state: Res<State<ArcadeState>>,
+    leaderboard: Res<Leaderboard>,
+    score: Res<ArcadeScore>,
+    pending_name: Res<PendingName>,
+    mut query: Query<&mut Text, With<ScreenText>>,
+) {
+    if !state.is_changed() && !leaderboard.is_changed() && !pending_name.is_changed() {
+        return;
+    }
+    let mut text = query.single_mut();
+    text.sections[0].value = match state.get() {
+        ArcadeState::Menu => "ARCADE\n\nEnter - play\nL - leaderboard".to_string(),
+        ArcadeState::Playing => String::new(),
+        ArcadeState::NameEntry => format!(
+            "New high score: {}\n\nName: {}_\n\nEnter - save",
+            score.score, pending_name.0,
+        ),
+        ArcadeState::Leaderboard => {
+            let mut lines = vec!["LEADERBOARD".to_string(), String::new()];
+            lines.extend(leaderboard.entries.iter().enumerate().map(|(i, entry)| {
+                format!("{:>2}. {:<12} {:>8}  L{}  {}", i + 1, entry.name, entry.score, entry.level, entry.date)
+            }));
+            lines.push(String::new());
+            lines.push("Enter - menu".to_string());
+            lines.join("\n")
+        }
+    };
+}
+
+fn menu_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut next_state: ResMut<NextState<ArcadeState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        next_state.set(ArcadeState::Playing);
+    } else if keyboard_input.just_pressed(KeyCode::L) {
+        next_state.set(ArcadeState::Leaderboard);
+    }
+}
+
+fn leaderboard_input(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut next_state: ResMut<NextState<ArcadeState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        next_state.set(ArcadeState::Menu);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn name_entry_input(
+    mut characters: EventReader<ReceivedCharacter>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut pending_name: ResMut<PendingName>,
+    mut leaderboard: ResMut<Leaderboard>,
+    level: Res<CurrentLevel>,
+    score: Res<ArcadeScore>,
+    mut next_state: ResMut<NextState<ArcadeState>>,
+    mut log: EventWriter<LogEvent>,
+) {
+    for event in characters.read() {
+        if !event.char.is_control() && pending_name.0.chars().count() < MAX_NAME_LENGTH {
+            pending_name.0.push(event.char);
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        pending_name.0.pop();
+    }
+    if keyboard_input.just_pressed(KeyCode::Return) && !pending_name.0.trim().is_empty() {
+        leaderboard.insert(HighScore {
+            name: pending_name.0.trim().to_string(),
+            score: score.score,
+            level: level.0 + 1,
+            date: leaderboard::today(),
+        });
+        if let Err(err) = leaderboard.save(LEADERBOARD_PATH) {
+            println!("failed to save leaderboard: {}", err);
+        }
+        log.send(LogEvent(format!("New high score: {} by {}", score.score, pending_name.0.trim())));
+        pending_name.0.clear();
+        next_state.set(ArcadeState::Leaderboard);
+    }
+}
+
+impl Plugin for ArcadePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_state::<ArcadeState>()
+        .init_resource::<CurrentLevel>()
+        .init_resource::<ArcadeScore>()
+        .init_resource::<PendingName>()
+        .init_resource::<PendingPattern>()
+        .init_resource::<PendingGliders>()
+        .insert_resource(Leaderboard::load(LEADERBOARD_PATH))
+        .insert_resource(LevelGraceTimer(Timer::from_seconds(LEVEL_GRACE_SECONDS, TimerMode::Once)))
+        .add_systems(Startup, setup)
+        .add_systems(OnEnter(ArcadeState::Playing), start_game)
+        .add_systems(OnEnter(ArcadeState::Menu), stop_ball)
+        .add_systems(OnExit(ArcadeState::Playing), stop_ball)
+        .add_systems(FixedUpdate, (break_cells, bounce_off_walls).run_if(in_state(ArcadeState::Playing)))
+        .add_systems(Update, (
+            (start_level, stamp_level_pattern, advance_level).chain().run_if(in_state(ArcadeState::Playing)),
+            (queue_paddle_gliders, stamp_pending_gliders).chain().run_if(in_state(ArcadeState::Playing)),
+            menu_input.run_if(in_state(ArcadeState::Menu)),
+            name_entry_input.run_if(in_state(ArcadeState::NameEntry)),
+            leaderboard_input.run_if(in_state(ArcadeState::Leaderboard)),
+            render_hud,
+            render_screen,
+        ))
+        ;
+    }
+}
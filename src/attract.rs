@@ -0,0 +1,164 @@
+// Attract mode, for running as a screensaver. After `idle_seconds` without any
+// input it takes over: every `SCENE_SECONDS` it stamps the next pattern (the
+// built-in ones, then the library) under the next rule preset while the camera
+// slowly pans around the board. Any key, click or mouse movement hands the
+// board back the way it was.
+
+use bevy::input::mouse::MouseWheel;
+use bevy::prelude::*;
+use bevy::window::CursorMoved;
+use rand::Rng;
+
+use crate::conway::{ActiveRuleTable, BoardSnapshot, ConwayCommand, ConwayConfig, RuleString, SimulationState};
+use crate::life_rule::LifeRule;
+use crate::patterns;
+use crate::rng::SimRng;
+
+pub struct AttractPlugin {
+    pub idle_seconds: f32,
+}
+
+const SCENE_SECONDS: f32 = 20.0;
+// How far the camera zooms in, so it has room to pan without leaving the board.
+const ZOOM: f32 = 0.6;
+// Seconds for one loop around the board.
+const PAN_PERIOD: f32 = 90.0;
+const RULES: &[&str] = &["B3/S23", "B36/S23", "B3678/S34678", "B368/S245", "B3/S12345"];
+
+#[derive(Resource)]
+struct IdleTimeout(f32);
+
+// What to put back when the user returns.
+struct Saved {
+    cells: Vec<IVec2>,
+    rule_table: ActiveRuleTable,
+    rule_string: RuleString,
+    paused: bool,
+}
+
+#[derive(Resource, Default)]
+struct Attract {
+    idle: f32,
+    // Set while attract mode is running.
+    saved: Option<Saved>,
+    scene: usize,
+    // Seconds into the current scene.
+    scene_time: f32,
+    pan_time: f32,
+}
+
+fn any_input(
+    keys: &Input<KeyCode>,
+    mouse_buttons: &Input<MouseButton>,
+    cursor_moves: &mut EventReader<CursorMoved>,
+    wheels: &mut EventReader<MouseWheel>,
+) -> bool {
+    // Read both so neither queue is left behind.
+    let moved = cursor_moves.read().count() > 0;
+    let scrolled = wheels.read().count() > 0;
+    keys.get_pressed().next().is_some() || mouse_buttons.get_pressed().next().is_some() || moved || scrolled
+}
+
+// The patterns cycled through, built-in ones first.
+fn scene_patterns() -> Vec<String> {
+    let mut names = patterns::NAMES.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+    names.extend(patterns::library_names());
+    names
+}
+
+fn show_scene(index: usize, board: &BoardSnapshot, rng: &mut SimRng, commands: &mut EventWriter<ConwayCommand>) {
+    let names = scene_patterns();
+    let name = &names[index % names.len()];
+    let rule = LifeRule::parse(RULES[index % RULES.len()]).unwrap_or_default();
+    commands.send(ConwayCommand::SetRule(rule));
+    // Somewhere near the middle, so the camera passes over it.
+    let size = board.size().as_ivec2();
+    let jitter = size / 8;
+    let origin = size / 2 + IVec2::new(rng.rng.gen_range(-jitter.x..=jitter.x), rng.rng.gen_range(-jitter.y..=jitter.y));
+    let cells = patterns::lookup(name, origin).unwrap_or_default();
+    commands.send(ConwayCommand::ClearCells(board.alive_cells_except(&cells)));
+    commands.send(ConwayCommand::SetCells(cells));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_attract(
+    time: Res<Time>,
+    timeout: Res<IdleTimeout>,
+    keys: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    mut cursor_moves: EventReader<CursorMoved>,
+    mut wheels: EventReader<MouseWheel>,
+    mut attract: ResMut<Attract>,
+    board: Res<BoardSnapshot>,
+    config: Res<ConwayConfig>,
+    rule_table: Res<ActiveRuleTable>,
+    rule_string: Res<RuleString>,
+    simulation_state: Res<SimulationState>,
+    mut rng: ResMut<SimRng>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut camera: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+) {
+    let attract = &mut *attract;
+    if any_input(&keys, &mouse_buttons, &mut cursor_moves, &mut wheels) {
+        attract.idle = 0.0;
+        let Some(saved) = attract.saved.take() else {
+            return;
+        };
+        commands.send(ConwayCommand::SetRule(saved.rule_string.0));
+        commands.send(ConwayCommand::SetRuleTable(saved.rule_table.0));
+        commands.send(ConwayCommand::ClearCells(board.alive_cells_except(&saved.cells)));
+        commands.send(ConwayCommand::SetCells(saved.cells));
+        if saved.paused {
+            commands.send(ConwayCommand::Pause);
+        }
+        for (mut transform, mut projection) in &mut camera {
+            transform.translation = Vec3::new(0.0, 0.0, transform.translation.z);
+            projection.scale = 1.0;
+        }
+        return;
+    }
+
+    attract.idle += time.delta_seconds();
+    if attract.saved.is_none() {
+        if attract.idle < timeout.0 {
+            return;
+        }
+        attract.saved = Some(Saved {
+            cells: board.alive_cells(),
+            rule_table: rule_table.clone(),
+            rule_string: *rule_string,
+            paused: simulation_state.paused,
+        });
+        commands.send(ConwayCommand::Resume);
+        attract.scene_time = 0.0;
+        attract.pan_time = 0.0;
+        show_scene(attract.scene, &board, &mut rng, &mut commands);
+    }
+
+    attract.scene_time += time.delta_seconds();
+    if attract.scene_time >= SCENE_SECONDS {
+        attract.scene_time = 0.0;
+        attract.scene += 1;
+        show_scene(attract.scene, &board, &mut rng, &mut commands);
+    }
+
+    // Drift along a figure eight that keeps the zoomed-in view on the board.
+    attract.pan_time += time.delta_seconds();
+    let angle = attract.pan_time / PAN_PERIOD * std::f32::consts::TAU;
+    let reach = config.extents() * (1.0 - ZOOM) / 2.0;
+    let offset = Vec2::new(angle.cos(), (2.0 * angle).sin()) * reach;
+    for (mut transform, mut projection) in &mut camera {
+        transform.translation = offset.extend(transform.translation.z);
+        projection.scale = ZOOM;
+    }
+}
+
+impl Plugin for AttractPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(IdleTimeout(self.idle_seconds))
+        .init_resource::<Attract>()
+        .add_systems(Update, run_attract)
+        ;
+    }
+}
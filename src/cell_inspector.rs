@@ -0,0 +1,145 @@
+// With the inspect tool (5), the inspector panel shows the cell under the
+// cursor: its state, how long it's been alive, its live neighbours, and what
+// the rule does to it next generation, worked out the way the update shaders
+// do. Portals, energy and blended rules aren't taken into account.
+
+use bevy::prelude::*;
+
+use crate::conway::{ActiveRuleTable, BoardMasks, BoardSnapshot, Edges, Lifespans, RuleString};
+use crate::inspector::Inspector;
+use crate::pnm::CellAges;
+use crate::rule_table::Neighborhood;
+use crate::tools::{BoardCursor, Tool};
+
+pub struct CellInspectorPlugin;
+
+const SECTION: &str = "Cell";
+
+// The centre, then its neighbours clockwise from north, as the shaders read them.
+const OFFSETS: [IVec2; 9] = [
+    IVec2::new(0, 0),
+    IVec2::new(0, -1),
+    IVec2::new(1, -1),
+    IVec2::new(1, 0),
+    IVec2::new(1, 1),
+    IVec2::new(0, 1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, 0),
+    IVec2::new(-1, -1),
+];
+const VON_NEUMANN: [usize; 5] = [0, 1, 3, 5, 7];
+
+// A cell's gray level as a state, like the table shader reads it.
+fn level_state(level: u8, states: u32) -> u32 {
+    (level as f32 / 255.0 * (states - 1) as f32).round() as u32
+}
+
+#[allow(clippy::too_many_arguments)]
+fn describe(
+    cell: IVec2,
+    board: &BoardSnapshot,
+    masks: &BoardMasks,
+    edges: Edges,
+    rule_table: &ActiveRuleTable,
+    rule_string: &RuleString,
+    lifespans: &Lifespans,
+    ages: &CellAges,
+) -> String {
+    let size = board.size().as_ivec2();
+    let level = |cell: IVec2| {
+        let cell = edges.fold(cell, size);
+        if cell.x < 0 || cell.y < 0 || cell.x >= size.x || cell.y >= size.y || masks.has(cell, BoardMasks::WALL) {
+            return 0;
+        }
+        board.row(cell.y as u32).get(cell.x as usize).copied().unwrap_or(0)
+    };
+    let states = rule_table.0.as_ref().map_or(rule_string.0.states, |table| table.states);
+    let indices = match rule_table.0.as_ref().map(|table| table.neighborhood) {
+        Some(Neighborhood::VonNeumann) => &VON_NEUMANN[..],
+        _ => &[0, 1, 2, 3, 4, 5, 6, 7, 8][..],
+    };
+    let neighborhood = indices.iter().map(|i| level_state(level(cell + OFFSETS[*i]), states)).collect::<Vec<_>>();
+    let state = neighborhood[0];
+    let live_neighbors = match (&rule_table.0, rule_string.0.larger) {
+        (None, Some(larger)) => {
+            let radius = larger.radius as i32;
+            let offsets = (-radius..=radius).flat_map(|y| (-radius..=radius).map(move |x| IVec2::new(x, y)));
+            offsets.filter(|offset| larger.contains(offset.x, offset.y) && level(cell + *offset) == 255).count()
+        }
+        _ => neighborhood[1..].iter().filter(|state| **state == states - 1).count(),
+    };
+    let age = if state > 0 { ages.get(cell).max(1) } else { 0 };
+
+    let mut next = match &rule_table.0 {
+        Some(table) => table.next_state(&neighborhood),
+        None => rule_string.0.next_state(state, live_neighbors as u32),
+    };
+    let lifespan = lifespans.0.get(rule_table.name(rule_string).as_ref()).copied();
+    let old_age = next > 0 && lifespan.is_some_and(|lifespan| age >= lifespan);
+    if old_age {
+        next = 0;
+    }
+    let frozen = masks.has(cell, BoardMasks::FROZEN);
+    let outcome = match (state, next) {
+        _ if masks.has(cell, BoardMasks::WALL) => "it's a wall".to_string(),
+        _ if frozen => "frozen, so it stays as it is".to_string(),
+        _ if old_age => "dies of old age".to_string(),
+        (0, 0) => "stays dead".to_string(),
+        (0, _) if states == 2 => "is born".to_string(),
+        (_, 0) => "dies".to_string(),
+        (state, next) if state == next => "survives".to_string(),
+        (_, next) => format!("becomes state {}", next),
+    };
+
+    let mut lines = vec![format!("({}, {})", cell.x, cell.y)];
+    lines.push(match (states, state) {
+        (2, 1) => "alive".to_string(),
+        (2, _) => "dead".to_string(),
+        (states, state) => format!("state {} of {}", state, states),
+    });
+    if age > 0 {
+        let lifespan = lifespan.map_or(String::new(), |lifespan| format!(" of {}", lifespan));
+        lines.push(format!("age {}{}", age, lifespan));
+    }
+    lines.push(format!("{} live neighbours", live_neighbors));
+    lines.push(format!("next: {}", outcome));
+    lines.join("\n")
+}
+
+#[allow(clippy::too_many_arguments)]
+fn inspect_cell(
+    tool: Res<Tool>,
+    cursor: BoardCursor,
+    board: Res<BoardSnapshot>,
+    masks: Res<BoardMasks>,
+    edges: Res<Edges>,
+    rule_table: Res<ActiveRuleTable>,
+    rule_string: Res<RuleString>,
+    lifespans: Res<Lifespans>,
+    ages: Res<CellAges>,
+    mut inspector: ResMut<Inspector>,
+    mut shown: Local<Option<String>>,
+) {
+    let cell = cursor.cell();
+    let on_board = cell.filter(|cell| cell.cmpge(IVec2::ZERO).all() && cell.cmplt(board.size().as_ivec2()).all());
+    let (Tool::Inspect, Some(cell)) = (*tool, on_board) else {
+        if shown.take().is_some() {
+            inspector.remove(SECTION);
+        }
+        return;
+    };
+    let text = describe(cell, &board, &masks, *edges, &rule_table, &rule_string, &lifespans, &ages);
+    // Only touch the inspector when something changed, since it redraws.
+    if shown.as_ref() != Some(&text) {
+        inspector.set(SECTION, text.clone());
+        *shown = Some(text);
+    }
+}
+
+impl Plugin for CellInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, inspect_cell)
+        ;
+    }
+}
@@ -0,0 +1,191 @@
+// A pattern recognizer for the ash a soup leaves behind. Live cells are split
+// into objects, which are named by matching them, in any orientation and
+// phase, against a list of common still lifes, oscillators and spaceships.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::OnceLock;
+
+use bevy::prelude::*;
+
+use crate::cpu::CpuBoard;
+use crate::formats;
+use crate::life_rule::LifeRule;
+use crate::patterns;
+
+// Objects to recognize, as plaintext rows, with their periods so every
+// phase gets recognized too.
+const KNOWN: &[(&str, &str, u32)] = &[
+    ("beehive", ".OO.\nO..O\n.OO.", 1),
+    ("loaf", ".OO.\nO..O\n.O.O\n..O.", 1),
+    ("boat", "OO.\nO.O\n.O.", 1),
+    ("ship", "OO.\nO.O\n.OO", 1),
+    ("tub", ".O.\nO.O\n.O.", 1),
+    ("pond", ".OO.\nO..O\nO..O\n.OO.", 1),
+    ("long boat", "OO..\nO.O.\n.O.O\n..O.", 1),
+    ("barge", ".O..\nO.O.\n.O.O\n..O.", 1),
+    ("mango", ".OO..\nO..O.\n.O..O\n..OO.", 1),
+    ("eater", "OO..\nO.O.\n..O.\n..OO", 1),
+    ("toad", ".OOO\nOOO.", 2),
+    ("beacon", "OO..\nOO..\n..OO\n..OO", 2),
+    ("pulsar", "..OOO...OOO..\n.............\nO....O.O....O\nO....O.O....O\nO....O.O....O\n..OOO...OOO..\n.............\n..OOO...OOO..\nO....O.O....O\nO....O.O....O\nO....O.O....O\n.............\n..OOO...OOO..", 3),
+];
+
+// Cells closer than this to each other are taken to be part of the same object.
+const OBJECT_GAP: i32 = 2;
+
+/// An object made of nearby live cells.
+#[derive(Clone, Debug)]
+pub struct Object {
+    pub name: Option<&'static str>,
+    pub cells: Vec<IVec2>,
+}
+
+impl Object {
+    /// The object's name, or a description of it if it wasn't recognized.
+    pub fn label(&self) -> String {
+        match self.name {
+            Some(name) => name.to_string(),
+            None => {
+                let size = self.max() - self.min() + IVec2::ONE;
+                format!("unknown {}x{} ({} cells)", size.x, size.y, self.cells.len())
+            }
+        }
+    }
+
+    pub fn min(&self) -> IVec2 {
+        self.cells.iter().copied().fold(IVec2::MAX, IVec2::min)
+    }
+
+    pub fn max(&self) -> IVec2 {
+        self.cells.iter().copied().fold(IVec2::MIN, IVec2::max)
+    }
+
+    pub fn is_spaceship(&self) -> bool {
+        matches!(self.name, Some("glider" | "lwss"))
+    }
+
+    /// Writes the object as RLE, moved to the origin. Soups are only ever run
+    /// under Life.
+    pub fn to_rle(&self) -> String {
+        let min = self.min();
+        let cells = self.cells.iter().map(|cell| *cell - min).collect::<Vec<_>>();
+        formats::write_rle(&self.label(), Some(&LifeRule::CONWAY.to_string()), &cells)
+    }
+}
+
+type Shape = Vec<(i32, i32)>;
+
+// The shape of `cells` in whichever of its eight orientations sorts first,
+// so the same object compares equal however it's turned.
+fn canonical(cells: &[IVec2]) -> Shape {
+    let orientations: [fn(IVec2) -> IVec2; 8] = [
+        |c| c,
+        |c| IVec2::new(-c.x, c.y),
+        |c| IVec2::new(c.x, -c.y),
+        |c| IVec2::new(-c.x, -c.y),
+        |c| IVec2::new(c.y, c.x),
+        |c| IVec2::new(-c.y, c.x),
+        |c| IVec2::new(c.y, -c.x),
+        |c| IVec2::new(-c.y, -c.x),
+    ];
+    orientations
+        .iter()
+        .map(|orient| {
+            let turned = cells.iter().map(|cell| orient(*cell)).collect::<Vec<_>>();
+            let min = turned.iter().copied().fold(IVec2::MAX, IVec2::min);
+            let mut shape = turned.iter().map(|cell| (cell.y - min.y, cell.x - min.x)).collect::<Shape>();
+            shape.sort();
+            shape
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+// Every phase of every known object, by shape.
+fn known_shapes() -> &'static HashMap<Shape, &'static str> {
+    static SHAPES: OnceLock<HashMap<Shape, &'static str>> = OnceLock::new();
+    SHAPES.get_or_init(|| {
+        let mut known = vec![
+            ("block", patterns::placed(patterns::BLOCK, IVec2::ZERO), 1),
+            ("blinker", patterns::placed(patterns::BLINKER, IVec2::ZERO), 2),
+            ("glider", patterns::placed(patterns::GLIDER, IVec2::ZERO), 4),
+            ("lwss", patterns::placed(patterns::LWSS, IVec2::ZERO), 4),
+        ];
+        for (name, rows, period) in KNOWN {
+            known.push((*name, formats::parse_plaintext(rows).expect("bad known object"), *period));
+        }
+
+        let mut shapes = HashMap::new();
+        for (name, cells, period) in known {
+            let mut board = CpuBoard::new(32, 32);
+            for cell in &cells {
+                board.set(*cell + IVec2::splat(8), true);
+            }
+            for _ in 0..period {
+                let alive = (0..32)
+                    .flat_map(|y| (0..32).map(move |x| IVec2::new(x, y)))
+                    .filter(|cell| board.is_alive(*cell))
+                    .collect::<Vec<_>>();
+                shapes.insert(canonical(&alive), name);
+                board.step();
+            }
+        }
+        shapes
+    })
+}
+
+// Groups cells that are within `gap` of each other.
+fn clusters(cells: &[IVec2], gap: i32) -> Vec<Vec<IVec2>> {
+    let mut unvisited = cells.iter().copied().collect::<HashSet<_>>();
+    let mut clusters = vec![];
+    for &start in cells {
+        if !unvisited.remove(&start) {
+            continue;
+        }
+        let mut cluster = vec![];
+        let mut queue = VecDeque::from([start]);
+        while let Some(cell) = queue.pop_front() {
+            cluster.push(cell);
+            for dy in -gap..=gap {
+                for dx in -gap..=gap {
+                    let neighbor = cell + IVec2::new(dx, dy);
+                    if unvisited.remove(&neighbor) {
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+        cluster.sort_by_key(|cell| (cell.y, cell.x));
+        clusters.push(cluster);
+    }
+    clusters
+}
+
+/// Splits live cells into objects and names the ones it recognizes.
+pub fn recognize(cells: &[IVec2]) -> Vec<Object> {
+    let known = known_shapes();
+    let mut objects = vec![];
+    // Some oscillators (toads, beacons) fall apart into pieces in some phases,
+    // so cells a little apart are tried together first. If that isn't
+    // anything known, it's probably separate objects sitting close together.
+    for cluster in clusters(cells, OBJECT_GAP) {
+        if let Some(name) = known.get(&canonical(&cluster)) {
+            objects.push(Object { name: Some(*name), cells: cluster });
+            continue;
+        }
+        for piece in clusters(&cluster, 1) {
+            let name = known.get(&canonical(&piece)).copied();
+            objects.push(Object { name, cells: piece });
+        }
+    }
+    objects
+}
+
+/// Counts objects by label.
+pub fn census(objects: &[Object]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for object in objects {
+        *counts.entry(object.label()).or_default() += 1;
+    }
+    counts
+}
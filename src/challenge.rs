@@ -0,0 +1,351 @@
+// Challenges turn the sandbox into a puzzle: a challenge file sets up the
+// board and marks goal zones on it, like "get a living cell into this square
+// within 500 generations", and the attempt is won once every zone's goal is
+// met. Load one with `--challenge FILE` or the console's `challenge FILE`.
+//
+//   name = "Special delivery"
+//   description = "Get a living cell into the square."
+//   generations = 500
+//   density = 0.0
+//
+//   [[stamps]]
+//   pattern = "glider"
+//   x = 100
+//   y = 100
+//
+//   [[zones]]
+//   x = 600
+//   y = 300
+//   width = 16
+//   height = 16
+//   goal = "alive"   # or "empty", to clear the zone out
+//   points = 100
+//
+// The board starts paused on a briefing, so edits can be made before Enter
+// starts the clock. Zones are checked against the board's readback, and an
+// attempt scores the points of the zones it holds at the end, plus the
+// generations to spare if it's won.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::conway::{BoardSnapshot, ConwayCommand, ConwayConfig, Generation, GenerationAdvanced, SeedSettings};
+use crate::event_log::LogEvent;
+use crate::inspector::Inspector;
+use crate::patterns;
+
+pub struct ChallengePlugin;
+
+const SECTION: &str = "Challenge";
+const MET_COLOR: Color = Color::rgb(0.2, 0.9, 0.3);
+const UNMET_COLOR: Color = Color::rgb(1.0, 0.8, 0.1);
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Goal {
+    // At least one living cell in the zone.
+    #[default]
+    Alive,
+    // No living cells in the zone.
+    Empty,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct GoalZone {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub goal: Goal,
+    #[serde(default = "default_points")]
+    pub points: u64,
+}
+
+fn default_points() -> u64 {
+    100
+}
+
+impl GoalZone {
+    /// Whether the zone's goal is met on the board. Parts of the zone off the
+    /// board count as empty.
+    pub fn is_met(&self, board: &BoardSnapshot) -> bool {
+        let size = board.size().as_ivec2();
+        let corner = IVec2::new(self.x, self.y);
+        let min = corner.clamp(IVec2::ZERO, size);
+        let max = (corner + UVec2::new(self.width, self.height).as_ivec2()).clamp(IVec2::ZERO, size);
+        let alive = (min.y..max.y).any(|y| {
+            let row = board.row(y as u32);
+            (min.x..max.x).any(|x| row.get(x as usize) == Some(&255))
+        });
+        match self.goal {
+            Goal::Alive => alive,
+            Goal::Empty => !alive,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ChallengeStamp {
+    pub pattern: String,
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Challenge {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    // Generations the zones have to be met within.
+    pub generations: u64,
+    // The soup the board starts from, empty by default.
+    #[serde(default)]
+    pub density: f32,
+    #[serde(default)]
+    pub seed: u32,
+    #[serde(default)]
+    pub stamps: Vec<ChallengeStamp>,
+    pub zones: Vec<GoalZone>,
+}
+
+impl Challenge {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let challenge: Self = toml::from_str(text).map_err(|err| err.to_string())?;
+        if challenge.zones.is_empty() {
+            return Err("a challenge needs at least one zone".to_string());
+        }
+        if challenge.generations == 0 {
+            return Err("a challenge needs at least one generation".to_string());
+        }
+        Ok(challenge)
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|err| format!("can't read {}: {}", path, err))?;
+        Self::parse(&text).map_err(|err| format!("bad challenge {}: {}", path, err))
+    }
+}
+
+#[derive(Event, Clone, Debug)]
+pub enum ChallengeCommand {
+    Start(Challenge),
+    Quit,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Outcome {
+    won: bool,
+    generation: u64,
+    score: u64,
+}
+
+enum Phase {
+    // Waiting for the board to be reseeded.
+    Setup,
+    // Paused on the briefing until Enter.
+    Briefing,
+    Running,
+    Finished(Outcome),
+}
+
+struct Attempt {
+    challenge: Challenge,
+    phase: Phase,
+    // Whether each zone's goal was met at the last check.
+    met: Vec<bool>,
+}
+
+impl Attempt {
+    fn score(&self, generation: u64, won: bool) -> u64 {
+        let points = self.challenge.zones.iter().zip(&self.met).filter(|(_, met)| **met).map(|(zone, _)| zone.points);
+        let spare = if won { self.challenge.generations.saturating_sub(generation) } else { 0 };
+        points.sum::<u64>() + spare
+    }
+}
+
+#[derive(Resource, Default)]
+struct ActiveChallenge(Option<Attempt>);
+
+#[derive(Component)]
+struct ChallengeText;
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        ChallengeText,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 36.0,
+                color: Color::WHITE,
+                ..Default::default()
+            }
+        ).with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(25.0),
+            left: Val::Percent(30.0),
+            ..default()
+        }),
+    ));
+}
+
+// Reseeds straight away, rather than with `ConwayCommand::Reseed`, so
+// `Generation::is_seeded_for` can't see the board from before.
+fn start(challenge: Challenge, seed_settings: &mut SeedSettings, commands: &mut EventWriter<ConwayCommand>) -> Attempt {
+    seed_settings.density = challenge.density;
+    seed_settings.seed = challenge.seed;
+    seed_settings.reseed();
+    commands.send(ConwayCommand::Pause);
+    let met = vec![false; challenge.zones.len()];
+    Attempt { challenge, phase: Phase::Setup, met }
+}
+
+fn handle_commands(
+    mut challenge_commands: EventReader<ChallengeCommand>,
+    mut active: ResMut<ActiveChallenge>,
+    mut seed_settings: ResMut<SeedSettings>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut inspector: ResMut<Inspector>,
+) {
+    for command in challenge_commands.read() {
+        match command {
+            ChallengeCommand::Start(challenge) => {
+                active.0 = Some(start(challenge.clone(), &mut seed_settings, &mut commands));
+            }
+            ChallengeCommand::Quit => {
+                active.0 = None;
+                inspector.remove(SECTION);
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn advance(
+    keys: Res<Input<KeyCode>>,
+    mut generations: EventReader<GenerationAdvanced>,
+    generation: Res<Generation>,
+    board: Res<BoardSnapshot>,
+    mut active: ResMut<ActiveChallenge>,
+    mut seed_settings: ResMut<SeedSettings>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut log: EventWriter<LogEvent>,
+) {
+    let advanced = generations.read().last().map(|advanced| advanced.generation);
+    let Some(attempt) = &mut active.0 else {
+        return;
+    };
+    match &mut attempt.phase {
+        Phase::Setup => {
+            if !generation.is_seeded_for(&seed_settings) {
+                return;
+            }
+            for stamp in &attempt.challenge.stamps {
+                match patterns::lookup(&stamp.pattern, IVec2::new(stamp.x, stamp.y)) {
+                    Some(cells) => commands.send(ConwayCommand::SetCells(cells)),
+                    None => log.send(LogEvent(format!("Challenge: unknown pattern {:?}", stamp.pattern))),
+                }
+            }
+            attempt.phase = Phase::Briefing;
+        }
+        Phase::Briefing => {
+            if keys.just_pressed(KeyCode::Return) {
+                commands.send(ConwayCommand::Resume);
+                attempt.phase = Phase::Running;
+            }
+        }
+        Phase::Running => {
+            let Some(generation) = advanced else {
+                return;
+            };
+            attempt.met = attempt.challenge.zones.iter().map(|zone| zone.is_met(&board)).collect();
+            let won = attempt.met.iter().all(|met| *met);
+            if !won && generation < attempt.challenge.generations {
+                return;
+            }
+            let outcome = Outcome { won, generation, score: attempt.score(generation, won) };
+            commands.send(ConwayCommand::Pause);
+            log.send(LogEvent(if won {
+                format!("{} solved at generation {}, scoring {}", attempt.challenge.name, generation, outcome.score)
+            } else {
+                format!("{} ran out of generations, scoring {}", attempt.challenge.name, outcome.score)
+            }));
+            attempt.phase = Phase::Finished(outcome);
+        }
+        Phase::Finished(_) => {
+            if keys.just_pressed(KeyCode::Return) {
+                *attempt = start(attempt.challenge.clone(), &mut seed_settings, &mut commands);
+            }
+        }
+    }
+}
+
+fn draw_zones(active: Res<ActiveChallenge>, config: Res<ConwayConfig>, mut gizmos: Gizmos) {
+    let Some(attempt) = &active.0 else {
+        return;
+    };
+    for (zone, met) in attempt.challenge.zones.iter().zip(&attempt.met) {
+        let corner = IVec2::new(zone.x, zone.y);
+        let top_left = config.cell_to_world(corner);
+        let bottom_right = config.cell_to_world(corner + UVec2::new(zone.width, zone.height).as_ivec2());
+        let color = if *met { MET_COLOR } else { UNMET_COLOR };
+        gizmos.rect_2d((top_left + bottom_right) / 2.0, 0.0, (bottom_right - top_left).abs(), color);
+    }
+}
+
+fn render_screen(
+    active: Res<ActiveChallenge>,
+    generation: Res<Generation>,
+    mut inspector: ResMut<Inspector>,
+    mut query: Query<&mut Text, With<ChallengeText>>,
+) {
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+    let Some(attempt) = &active.0 else {
+        text.sections[0].value.clear();
+        return;
+    };
+    let challenge = &attempt.challenge;
+    let met = attempt.met.iter().filter(|met| **met).count();
+    if let Phase::Running = attempt.phase {
+        inspector.set(SECTION, format!(
+            "{}\ngeneration {} of {}\nzones: {} of {}",
+            challenge.name, generation.get(), challenge.generations, met, challenge.zones.len(),
+        ));
+    }
+    let screen = match &attempt.phase {
+        Phase::Setup | Phase::Running => String::new(),
+        Phase::Briefing => format!(
+            "{}\n\n{}\n{} generations\n\nEnter - start",
+            challenge.name, challenge.description, challenge.generations,
+        ),
+        Phase::Finished(outcome) => {
+            let result = if outcome.won {
+                format!("Solved in {} generations", outcome.generation)
+            } else {
+                "Out of generations".to_string()
+            };
+            format!(
+                "{}\n\n{}\nZones held: {} of {}\nScore: {}\n\nEnter - try again",
+                challenge.name, result, met, challenge.zones.len(), outcome.score,
+            )
+        }
+    };
+    // Only touch the text when it changes, so it isn't laid out every frame.
+    if text.sections[0].value != screen {
+        text.sections[0].value = screen;
+    }
+}
+
+impl Plugin for ChallengePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<ChallengeCommand>()
+        .init_resource::<ActiveChallenge>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, (handle_commands, advance, render_screen).chain())
+        .add_systems(Update, draw_zones)
+        ;
+    }
+}
@@ -0,0 +1,163 @@
+// Checkpoints for long runs. With `--checkpoint N` the board is saved every N
+// generations in the native save format (see `save_file.rs`), along with its
+// rule, seed, generation and where `SimRng` is up to, encoded and written in a
+// background task. `--resume` starts from the last checkpoint instead of a
+// fresh soup, so a run can carry on after a crash or a reboot.
+
+use std::fs;
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy::tasks::{IoTaskPool, Task};
+use futures_lite::future;
+
+use crate::conway::{
+    ActiveRuleTable, BoardSnapshot, ConwayCommand, Generation, GenerationAdvanced, RuleString, SeedSettings,
+};
+use crate::event_log::LogEvent;
+use crate::rng::SimRng;
+use crate::save_file;
+use crate::snapshot::{Snapshot, SnapshotMetadata};
+
+pub struct CheckpointPlugin {
+    // Save a checkpoint every this many generations.
+    pub every: Option<u64>,
+    // Start from the last checkpoint.
+    pub resume: bool,
+}
+
+pub const CHECKPOINT_PATH: &str = "checkpoints/checkpoint.gol";
+
+#[derive(Resource)]
+struct Checkpoints {
+    every: u64,
+    // The generation the next checkpoint is due at.
+    next: u64,
+    // The checkpoint being written, if one is.
+    writing: Option<Task<Result<(), String>>>,
+}
+
+// The checkpoint to resume from, waiting for the board to be ready for it.
+#[derive(Resource)]
+struct PendingResume(Snapshot);
+
+fn load_checkpoint() -> Result<Snapshot, String> {
+    let bytes = fs::read(CHECKPOINT_PATH).map_err(|err| err.to_string())?;
+    save_file::decode(&bytes)
+}
+
+// Written next to the checkpoint and moved over it, so a crash part way
+// through leaves the last one intact.
+fn write_checkpoint(bytes: &[u8]) -> Result<(), String> {
+    let path = Path::new(CHECKPOINT_PATH);
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).map_err(|err| err.to_string())?;
+    }
+    let temporary = path.with_extension("gol.tmp");
+    fs::write(&temporary, bytes).and_then(|_| fs::rename(&temporary, path)).map_err(|err| err.to_string())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn save_checkpoints(
+    mut generations: EventReader<GenerationAdvanced>,
+    mut checkpoints: ResMut<Checkpoints>,
+    board: Res<BoardSnapshot>,
+    rule_table: Res<ActiveRuleTable>,
+    rule_string: Res<RuleString>,
+    seed_settings: Res<SeedSettings>,
+    rng: Res<SimRng>,
+    mut log: EventWriter<LogEvent>,
+) {
+    if let Some(task) = &mut checkpoints.writing {
+        let Some(written) = future::block_on(future::poll_once(task)) else {
+            return;
+        };
+        checkpoints.writing = None;
+        if let Err(err) = written {
+            log.send(LogEvent(format!("Failed to save a checkpoint: {}", err)));
+        }
+    }
+    let Some(advanced) = generations.read().last() else {
+        return;
+    };
+    // The board was reseeded, so start counting again.
+    if advanced.generation + checkpoints.every < checkpoints.next {
+        checkpoints.next = 0;
+    }
+    if advanced.generation < checkpoints.next {
+        return;
+    }
+    checkpoints.next = (advanced.generation / checkpoints.every + 1) * checkpoints.every;
+    let metadata = SnapshotMetadata {
+        rule: Some(rule_table.name(&rule_string).into_owned()),
+        seed: Some(seed_settings.seed),
+        density: Some(seed_settings.density),
+        generation: Some(advanced.generation),
+        rng_seed: Some(rng.seed),
+        rng_position: Some(rng.position()),
+        hash: None,
+    };
+    let (size, cells) = (board.size(), board.alive_cells());
+    checkpoints.writing = Some(IoTaskPool::get().spawn(async move {
+        write_checkpoint(&save_file::encode(size, &cells, &metadata)?)
+    }));
+}
+
+fn resume(
+    mut commands: Commands,
+    pending: Option<Res<PendingResume>>,
+    generation: Res<Generation>,
+    mut seed_settings: ResMut<SeedSettings>,
+    mut rng: ResMut<SimRng>,
+    mut conway_commands: EventWriter<ConwayCommand>,
+    mut log: EventWriter<LogEvent>,
+) {
+    // Edits only stick once the (empty) board has been seeded.
+    let Some(pending) = pending.filter(|_| generation.is_seeded()) else {
+        return;
+    };
+    let metadata = &pending.0.metadata;
+    if let Some(rule) = &metadata.rule {
+        match ConwayCommand::set_rule_named(rule) {
+            Ok(command) => conway_commands.send(command),
+            Err(err) => log.send(LogEvent(format!("Couldn't load the checkpoint's rule {}: {}", rule, err))),
+        }
+    }
+    // Left as they were, so reseeding still gives the run's soup.
+    if let (Some(seed), Some(density)) = (metadata.seed, metadata.density) {
+        seed_settings.seed = seed;
+        seed_settings.density = density;
+    }
+    // Older checkpoints reseeded the generator as they were saved.
+    match (metadata.rng_seed, metadata.rng_position) {
+        (Some(seed), Some(position)) => *rng = SimRng::resume(seed, position),
+        (Some(seed), None) => *rng = SimRng::from_seed(seed),
+        _ => {}
+    }
+    let resumed_at = metadata.generation.unwrap_or(0);
+    generation.set(resumed_at);
+    conway_commands.send(ConwayCommand::SetCells(pending.0.cells.clone()));
+    log.send(LogEvent(format!("Resumed from generation {}", resumed_at)));
+    commands.remove_resource::<PendingResume>();
+}
+
+impl Plugin for CheckpointPlugin {
+    fn build(&self, app: &mut App) {
+        if self.resume {
+            match load_checkpoint() {
+                Ok(checkpoint) => {
+                    // Start from an empty board for the checkpoint to go on.
+                    let mut seed_settings = SeedSettings::default();
+                    seed_settings.density = 0.0;
+                    app.insert_resource(seed_settings).insert_resource(PendingResume(checkpoint));
+                }
+                Err(err) => println!("failed to load checkpoint {}: {}", CHECKPOINT_PATH, err),
+            }
+        }
+        if let Some(every) = self.every {
+            app.insert_resource(Checkpoints { every: every.max(1), next: every.max(1), writing: None });
+            app.add_systems(Update, save_checkpoints);
+        }
+        app.add_systems(Update, resume);
+    }
+}
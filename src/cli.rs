@@ -0,0 +1,134 @@
+// Command line options.
+
+use bevy::prelude::*;
+
+use crate::conway::{ConwayConfig, Edges};
+
+#[derive(Resource, Clone, Default, Debug)]
+pub struct CliArgs {
+    // Play the paddle game against the Conway field.
+    pub arcade: bool,
+    // Seed for `SimRng`; picked at random when not given.
+    pub seed: Option<u64>,
+    // Run a multiplayer relay server on this address instead of the game.
+    pub relay: Option<String>,
+    // Run a headless authoritative server on this address instead of the game.
+    pub server: Option<String>,
+    // Join the multiplayer board relayed or served at this websocket URL.
+    pub connect: Option<String>,
+    // Serve the HTTP remote control API, and metrics, on this address. With
+    // `--server`, only the metrics.
+    pub http: Option<String>,
+    // Also serve live board thumbnails for spectators over HTTP.
+    pub spectate: bool,
+    // Let this Twitch channel's chat place patterns.
+    pub twitch: Option<String>,
+    // A Rhai script driving the scenario.
+    pub script: Option<String>,
+    // Run the tutorial script.
+    pub tutorial: bool,
+    // A challenge file to play.
+    pub challenge: Option<String>,
+    // An image to seed the board from.
+    pub import: Option<String>,
+    // An image to pull the board toward.
+    pub picture: Option<String>,
+    // A Golly `.table` or `.rule` file to run instead of B3/S23.
+    pub rule_table: Option<String>,
+    // Run the batch experiments in this spec instead of the game.
+    pub experiments: Option<String>,
+    // Run the genetic search in this spec instead of the game.
+    pub evolve: Option<String>,
+    // Run a soup search over this many soups instead of the game.
+    pub soup_search: Option<u64>,
+    // Pause once the board has stabilized.
+    pub auto_stop: bool,
+    // Generations the board has to repeat itself for to count as stabilized.
+    pub stable_for: Option<u64>,
+    // Seconds without input before attract mode starts.
+    pub attract: Option<f32>,
+    // Start with the energy layer on.
+    pub energy: bool,
+    // What's past the board's edges to start with, from `--edges` or
+    // `--wrap` for short.
+    pub edges: Option<Edges>,
+    // Points to average per pixel, per axis, when drawing the board.
+    pub supersample: Option<u32>,
+    // Treat the board's gray levels as sRGB when drawing it.
+    pub srgb: bool,
+    // Show density from a mip chain when zoomed out.
+    pub lod: bool,
+    // Save a checkpoint every this many generations.
+    pub checkpoint: Option<u64>,
+    // Start from the last checkpoint.
+    pub resume: bool,
+    // Stream statistics over OSC to this UDP address.
+    pub osc: Option<String>,
+    // Show the board fullscreen and the controls in a second window.
+    pub control_window: bool,
+    // The board's width and height in cells, given as WxH. See `board_config`.
+    pub size: Option<String>,
+    // Label the simulation's GPU work for frame debuggers, and capture with F12.
+    pub gpu_debug: bool,
+}
+
+impl CliArgs {
+    pub fn parse() -> Self {
+        let mut cli = Self::default();
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--arcade" => cli.arcade = true,
+                "--seed" => cli.seed = args.next().and_then(|value| value.parse().ok()),
+                "--relay" => cli.relay = args.next(),
+                "--server" => cli.server = args.next(),
+                "--connect" => cli.connect = args.next(),
+                "--http" => cli.http = args.next(),
+                "--spectate" => cli.spectate = true,
+                "--twitch" => cli.twitch = args.next(),
+                "--script" => cli.script = args.next(),
+                "--tutorial" => cli.tutorial = true,
+                "--challenge" => cli.challenge = args.next(),
+                "--import" => cli.import = args.next(),
+                "--picture" => cli.picture = args.next(),
+                "--rule-table" => cli.rule_table = args.next(),
+                "experiments" => cli.experiments = args.next(),
+                "evolve" => cli.evolve = args.next(),
+                "soup-search" => cli.soup_search = args.next().and_then(|value| value.parse().ok()),
+                "--auto-stop" => cli.auto_stop = true,
+                "--stable-for" => cli.stable_for = args.next().and_then(|value| value.parse().ok()),
+                "--energy" => cli.energy = true,
+                "--wrap" => cli.edges = Some(Edges::Wrap),
+                "--edges" => cli.edges = args.next().and_then(|value| Edges::parse(&value)),
+                "--srgb" => cli.srgb = true,
+                "--lod" => cli.lod = true,
+                "--supersample" => cli.supersample = args.next().and_then(|value| value.parse().ok()),
+                "--checkpoint" => cli.checkpoint = args.next().and_then(|value| value.parse().ok()),
+                "--resume" => cli.resume = true,
+                "--osc" => cli.osc = args.next(),
+                "--control-window" => cli.control_window = true,
+                "--attract" => cli.attract = args.next().and_then(|value| value.parse().ok()),
+                // Checked by `board_config`, so a bad size gets reported.
+                "--size" => cli.size = Some(args.next().unwrap_or_default()),
+                "--gpu-debug" => cli.gpu_debug = true,
+                _ => println!("ignoring unknown argument {:?}", arg),
+            }
+        }
+        cli
+    }
+
+    /// The board `--size` asks for, or the default one without it.
+    pub fn board_config(&self) -> Result<ConwayConfig, String> {
+        let Some(size) = &self.size else {
+            return Ok(ConwayConfig::default());
+        };
+        let size = parse_size(size).ok_or_else(|| format!("expected a size like 640x360, got {:?}", size))?;
+        ConwayConfig::fitting(size)
+    }
+}
+
+/// A size given as WxH, like 256x256.
+pub fn parse_size(value: &str) -> Option<UVec2> {
+    let (width, height) = value.split_once('x')?;
+    Some(UVec2::new(width.parse().ok()?, height.parse().ok()?))
+}
@@ -0,0 +1,161 @@
+// Comparing rules side by side (`compare RULE RULE [RULE [RULE]]` in the
+// console, `compare off` to stop): the board is copied into a board per B/S
+// rule, each stepped on the CPU as many generations as the simulation goes,
+// through the same `Edges`, and drawn in a 2 x 2 grid over the board, left to
+// right and top to bottom in the order given. Each copy is shown at half the
+// size, so the grid covers the board exactly and pans and zooms with it. The
+// inspector lists the rules and their populations.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+
+use crate::conway::{BoardSnapshot, ConwayConfig, Edges, Generation};
+use crate::cpu::CpuBoard;
+use crate::inspector::Inspector;
+use crate::life_rule::LifeRule;
+
+pub struct ComparePlugin;
+
+pub const MIN_RULES: usize = 2;
+pub const MAX_RULES: usize = 4;
+const SECTION: &str = "Compare";
+const ALIVE_COLOR: [u8; 4] = [255, 255, 255, 255];
+// Opaque, so the board doesn't show through.
+const DEAD_COLOR: [u8; 4] = [0, 0, 0, 255];
+
+#[derive(Event, Clone, Debug)]
+pub enum CompareCommand {
+    Start(Vec<LifeRule>),
+    Stop,
+}
+
+struct Panel {
+    rule: LifeRule,
+    board: CpuBoard,
+    image: Handle<Image>,
+}
+
+// The boards being compared, the sprites drawing them, and the generation
+// they've been stepped to.
+#[derive(Resource, Default)]
+struct Comparison {
+    panels: Vec<Panel>,
+    entities: Vec<Entity>,
+    generation: u64,
+}
+
+fn draw_panel(panel: &Panel, images: &mut Assets<Image>) {
+    let Some(image) = images.get_mut(&panel.image) else {
+        return;
+    };
+    for (pixel, cell) in image.data.chunks_exact_mut(4).zip(&panel.board.cells) {
+        pixel.copy_from_slice(if *cell == 255 { &ALIVE_COLOR } else { &DEAD_COLOR });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_commands(
+    mut commands: Commands,
+    mut compare_commands: EventReader<CompareCommand>,
+    board: Res<BoardSnapshot>,
+    config: Res<ConwayConfig>,
+    generation: Res<Generation>,
+    mut comparison: ResMut<Comparison>,
+    mut images: ResMut<Assets<Image>>,
+    mut inspector: ResMut<Inspector>,
+) {
+    for command in compare_commands.read() {
+        for entity in comparison.entities.drain(..) {
+            commands.entity(entity).despawn();
+        }
+        comparison.panels.clear();
+        inspector.remove(SECTION);
+        let CompareCommand::Start(rules) = command else {
+            continue;
+        };
+        let size = config.size;
+        let mut copy = CpuBoard::new(size.x, size.y);
+        for y in 0..size.y as i32 {
+            for x in 0..size.x as i32 {
+                copy.set(IVec2::new(x, y), board.is_alive(IVec2::new(x, y)));
+            }
+        }
+        comparison.generation = generation.get();
+        let extents = config.extents();
+        // Something under the grid's empty corners, with fewer than four rules.
+        let backdrop = commands
+            .spawn(SpriteBundle {
+                sprite: Sprite { color: Color::BLACK, custom_size: Some(extents), ..default() },
+                transform: Transform::from_xyz(0.0, 0.0, 2.4),
+                ..default()
+            })
+            .id();
+        comparison.entities.push(backdrop);
+        for (index, rule) in rules.iter().take(MAX_RULES).enumerate() {
+            let mut image = Image::new_fill(
+                Extent3d { width: size.x, height: size.y, depth_or_array_layers: 1 },
+                TextureDimension::D2,
+                &DEAD_COLOR,
+                TextureFormat::Rgba8UnormSrgb,
+            );
+            image.sampler = ImageSampler::nearest();
+            let panel = Panel { rule: *rule, board: copy.clone(), image: images.add(image) };
+            draw_panel(&panel, &mut images);
+            let (column, row) = ((index % 2) as f32, (index / 2) as f32);
+            let center = Vec2::new(column - 0.5, 0.5 - row) * extents / 2.0;
+            let entity = commands
+                .spawn(SpriteBundle {
+                    texture: panel.image.clone(),
+                    sprite: Sprite { custom_size: Some(extents / 2.0), ..default() },
+                    // Over everything drawn on the board.
+                    transform: Transform::from_translation(center.extend(2.5)),
+                    ..default()
+                })
+                .id();
+            comparison.entities.push(entity);
+            comparison.panels.push(panel);
+        }
+    }
+}
+
+fn step_panels(
+    generation: Res<Generation>,
+    edges: Res<Edges>,
+    mut comparison: ResMut<Comparison>,
+    mut images: ResMut<Assets<Image>>,
+    mut inspector: ResMut<Inspector>,
+) {
+    // Going back, after a reseed or a restore, leaves them where they are.
+    let current = generation.get();
+    let steps = current.saturating_sub(comparison.generation);
+    comparison.generation = current;
+    if comparison.panels.is_empty() || steps == 0 {
+        return;
+    }
+    for panel in &mut comparison.panels {
+        for _ in 0..steps {
+            panel.board.step_with_edges(&panel.rule, *edges);
+        }
+        draw_panel(panel, &mut images);
+    }
+    let lines = comparison
+        .panels
+        .iter()
+        .map(|panel| {
+            let population = panel.board.cells.iter().filter(|cell| **cell == 255).count();
+            format!("{}: {}", panel.rule, population)
+        })
+        .collect::<Vec<_>>();
+    inspector.set(SECTION, lines.join("\n"));
+}
+
+impl Plugin for ComparePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<CompareCommand>()
+        .init_resource::<Comparison>()
+        .add_systems(Update, (handle_commands, step_panels).chain())
+        ;
+    }
+}
@@ -0,0 +1,1109 @@
+// A drop-down command console, toggled with the backtick key. Commands map
+// onto `ConwayCommand`s, like the scripting API:
+//
+//   set X Y / clear X Y     set or clear a cell
+//   fill [X Y W H]          set the ruler's box, or W x H cells from X, Y, walls aside
+//   clear [X Y W H]         clear them
+//   rotate / flip h | v     turn the whole board a quarter clockwise, or mirror it left to right or top to bottom
+//   shift DX DY [wrap]      move everything DX, DY cells, round to the other side with `wrap` or off the edge
+//   shift center            move the living cells' bounding box to the middle of the board
+//   resize WxH [resample]   remake the board at W x H cells, seeded afresh or stretched from the old one
+//   crop                    shrink the board around the living cells, centred with room to grow
+//   brush R [DENSITY]       paint discs R cells out, setting each cell with probability DENSITY
+//   soup X Y W H [DENSITY]  fill a rectangle with a random soup, leaving the rest of the board
+//   soup DENSITY            set how dense the soup tool's soups are
+//   stamp NAME X Y          place a built-in or library pattern
+//   speed N                 advance N generations per frame
+//   seed N                  reseed the board with seed N
+//   pause / resume
+//   until G / advance N     run flat out to generation G, or for N generations, then pause
+//   until off               stop fast-forwarding
+//   rule B3/S23 | FILE      run a B/S rule like B36/S23 or R5,C0,M1,S34..58,B34..45,NM, or load a `.table`/`.rule` file
+//   save SLOT / load SLOT   save or load the board in `saves/SLOT.gol`
+//   snapshot SLOT           save the board as `saves/SLOT.png`, with its rule, seed and generation
+//   restore SLOT            load a snapshot back, rule and seed included
+//   diff A [B]              compare saved slots A and B (`.gol`, `.cells` or `.png`), or A and the board
+//   diff off                stop comparing
+//   challenge FILE          play the challenge in a `.toml` challenge file
+//   challenge off           go back to the sandbox
+//   import FILE [MODE]      seed the board from a PNG or JPEG; MODE is `dither` or a brightness from 0 to 1
+//   picture FILE [BIAS]     pull the board toward an image, each cell following it with chance BIAS a generation
+//   picture off             let the rule run free again
+//   flow DX DY              carry every cell along at DX, DY cells a generation, up to 1 each way
+//   flow off                stop the current
+//   tape record [K]         record the board to a tape, keeping it whole every K frames
+//   tape stop / tape clear  stop recording, or throw the tape away
+//   tape seek G             pause on generation G of the tape; comma and period step through it
+//   npy FILE [N]            export the board, or the next N generations, as `.npy`, or `.npz` if FILE says so
+//   pbm FILE / pgm FILE     export the board as a bitmap, or a graymap of cell ages
+//   figure FILE SCALE [X Y W H] [grid]
+//                           export the ruler's box, or W x H cells from X, Y, as a PNG at SCALE pixels a cell
+//   timelapse DIR N         save the board as `DIR/000000.png`, `DIR/000001.png`... every N generations
+//   timelapse off           stop
+//   at G NAME X Y           stamp a pattern when the board reaches generation G
+//   schedule                list the scheduled stamps
+//   unschedule N | all      drop one of them, or all of them
+//   morph A B SECS [FADE]   alternate between two rules (B/S or files) every SECS
+//   morph off               stop morphing
+//   compare RULE RULE [RULE [RULE]]
+//                           run copies of the board under each B/S rule, side by side
+//   compare off             stop comparing
+//   energy COST REGEN       make live cells spend energy, which empty cells regain
+//   energy off              stop
+//   lifespan N [RULE]       cells under RULE (the current one by default) die after N generations
+//   lifespan off [RULE]     let them live forever again
+//   lifespan                list the lifespans
+//   palette DEG/S           cycle the board's hue, DEG/S degrees a second
+//   palette on | off        start or stop cycling
+//   alert below N | above N | zero
+//                           log and chime when the population crosses N, or dies out
+//   alert off               drop all the alerts
+//   alert                   list the alerts
+//   garden LOW HIGH         plant patterns while the population is under LOW and cull it over HIGH
+//   garden off              stop gardening
+//   supersample N           average N x N points per pixel when drawing the board, up to 4; 1 is off
+//   lod [N] / lod off       show density when zoomed out, from a mip chain rebuilt every N frames (1)
+//   abtest N / abtest off   reseed and run the soup on the CPU too for N generations, comparing, or put it away
+//   pipelines [off]         list the render pipelines' states and dispatches in the inspector, or stop
+//   encoding linear | srgb  show the board's gray levels as they are, or decoded from sRGB
+//   fog [SECS] / fog off    hide the board but where you paint or look, fogging over after SECS (20)
+//   wrap on | off           wrap the board around at its edges, or not
+//   edges dead|wrap|mirror  what's past the board's edges: dead cells, the far side, or the edge reflected
+//   lenia [R MU SIGMA DT]   run Lenia instead of the rule, with a kernel R cells out and growth peaking at MU
+//   lenia off               go back to the rule
+//   ants [TURMITE]          leave the board to the ants, following TURMITE, like RL or 1R1,1L1;1R1,0R0
+//   ant X Y / ants off      put an ant down at X, Y, or take away the ants and go back to the rule
+//   elementary N            run elementary automaton N, from 0 to 255, a row a generation down the board
+//   elementary off          go back to the rule
+//   label X Y TEXT          pin TEXT to the board at cell X, Y
+//   arrow X1 Y1 X2 Y2       pin an arrow between two cells
+//   labels [clear]          list the labels and arrows, or remove them
+//   view N NAME / view N    bookmark the view as NAME under Shift+N, or go back to it
+//   view home / views       show the whole board, or list the bookmarks
+//   workspace NAME          switch to workspace NAME, saving this one, or make it if it's new
+//   workspaces              list the workspaces
+//   help
+//
+// Up and Down walk through the history and Tab completes commands and pattern
+// names. Keyboard input doesn't reach the rest of the game while it's open.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use bevy::ecs::system::SystemParam;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::render::renderer::RenderDevice;
+
+use crate::ab_test::AbTestCommand;
+use crate::annotations::{self, Annotations, Arrow, Label};
+use crate::ants::{Ant, Ants, Turmite};
+use crate::challenge::{Challenge, ChallengeCommand};
+use crate::cli;
+use crate::compare::{CompareCommand, MAX_RULES, MIN_RULES};
+use crate::conway::{
+    ActiveRuleTable, BoardSnapshot, BoardTransform, ConwayCommand, ConwayConfig, Edges, ElementaryRule, EnergySettings,
+    Generation, LeniaSettings, Lifespans, LiveBounds, RuleString, SeedSettings, SimulationKind, MAX_LENIA_RADIUS,
+    MAX_LIFESPAN,
+};
+use crate::diff::DiffCommand;
+use crate::display::{StateEncoding, Supersampling, MAX_SAMPLES};
+use crate::fast_forward::FastForwardCommand;
+use crate::figure::ExportFigure;
+use crate::fog::{FogOfWar, DEFAULT_FADE_SECONDS};
+use crate::formats;
+use crate::gardener::Gardener;
+use crate::image_import::{ImportImage, ImportMode, DEFAULT_THRESHOLD};
+use crate::life_rule::LifeRule;
+use crate::lod::LodSettings;
+use crate::morph::MorphCommand;
+use crate::npy::ExportNpy;
+use crate::palette::PaletteCycle;
+use crate::picture::{PictureCommand, DEFAULT_BIAS};
+use crate::pipeline_stats::PipelineOverlay;
+use crate::patterns;
+use crate::pnm::{ExportPnm, PnmFormat};
+use crate::rule_table::RuleTable;
+use crate::rng::SimRng;
+use crate::save_file;
+use crate::schedule::{Injection, InjectionSchedule};
+use crate::snapshot::{self, SnapshotMetadata};
+use crate::soup_stamp::{self, SoupStamp};
+use crate::tape::{TapeCommand, DEFAULT_KEYFRAME_EVERY};
+use crate::thresholds::{PopulationThresholds, Threshold};
+use crate::timelapse::TimeLapseCommand;
+use crate::toml_file::TomlFile;
+use crate::ruler::Measurement;
+use crate::tools::{Brush, MAX_BRUSH_RADIUS};
+use crate::views::{self, Bookmarks, ViewCommand};
+use crate::workspaces::{WorkspaceCommand, Workspaces};
+
+pub struct ConsolePlugin;
+
+const COMMANDS: &[&str] = &[
+    "abtest", "advance", "alert", "ant", "ants", "arrow", "at", "brush", "challenge", "clear", "compare", "crop",
+    "diff", "edges", "elementary", "encoding", "energy", "figure", "fill", "flip", "flow", "fog", "garden", "help",
+    "import", "label", "labels", "lenia", "lifespan", "load", "lod", "morph", "npy", "palette", "pause", "pbm", "pgm",
+    "picture", "pipelines", "resize", "restore", "resume", "rotate", "rule", "save", "schedule", "seed", "set", "shift",
+    "snapshot", "soup", "speed", "stamp", "supersample", "tape", "timelapse", "unschedule", "until", "view", "views",
+    "workspace", "workspaces", "wrap",
+];
+const SAVE_DIRECTORY: &str = "saves";
+// Empty cells left on each side of the living ones by `crop`.
+const CROP_MARGIN: u32 = 32;
+const FONT_SIZE: f32 = 20.0;
+// Lines of output kept on screen.
+const MAX_LINES: usize = 12;
+
+#[derive(Resource, Default)]
+struct Console {
+    open: bool,
+    input: String,
+    // Entered commands, oldest first.
+    history: Vec<String>,
+    // The entry of `history` being shown, while browsing it.
+    history_index: Option<usize>,
+    // Entered commands and their output, oldest first.
+    lines: VecDeque<String>,
+}
+
+impl Console {
+    fn print(&mut self, line: impl Into<String>) {
+        self.lines.push_back(line.into());
+        if self.lines.len() > MAX_LINES {
+            self.lines.pop_front();
+        }
+    }
+}
+
+#[derive(Component)]
+struct ConsoleRoot;
+
+#[derive(Component)]
+struct ConsoleText;
+
+fn setup(mut commands: Commands) {
+    commands
+        .spawn((
+            ConsoleRoot,
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    left: Val::Px(0.0),
+                    width: Val::Percent(100.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((ConsoleText, TextBundle::from_section("", TextStyle {
+                font_size: FONT_SIZE,
+                color: Color::WHITE,
+                ..default()
+            })));
+        });
+}
+
+fn parse_cell(x: &str, y: &str) -> Result<IVec2, String> {
+    match (x.parse(), y.parse()) {
+        (Ok(x), Ok(y)) => Ok(IVec2::new(x, y)),
+        _ => Err(format!("expected a cell position, got {} {}", x, y)),
+    }
+}
+
+// A rectangle as its top left cell and size, which can't be empty.
+fn parse_rect(x: &str, y: &str, width: &str, height: &str) -> Result<(IVec2, UVec2), String> {
+    let size = parse_cell(width, height).ok().filter(|size| size.cmpgt(IVec2::ZERO).all());
+    let size = size.ok_or_else(|| format!("expected a size, got {} {}", width, height))?;
+    Ok((parse_cell(x, y)?, size.as_uvec2()))
+}
+
+fn parse_density(density: &str) -> Result<f32, String> {
+    density
+        .parse()
+        .ok()
+        .filter(|density| (0.0..=1.0).contains(density))
+        .ok_or_else(|| format!("expected a density from 0 to 1, got {}", density))
+}
+
+fn parse_velocity(velocity: &str) -> Result<f32, String> {
+    velocity
+        .parse()
+        .ok()
+        .filter(|velocity: &f32| velocity.abs() <= 1.0)
+        .ok_or_else(|| format!("expected a velocity from -1 to 1, got {}", velocity))
+}
+
+// Slot names become file names, so keep them to something safe.
+fn slot_path(slot: &str, extension: &str) -> Result<String, String> {
+    if slot.is_empty() || !slot.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("bad slot name {:?}", slot));
+    }
+    Ok(format!("{}/{}.{}", SAVE_DIRECTORY, slot, extension))
+}
+
+// The live cells in a saved slot, from `save` or `snapshot`. Older saves
+// were `.cells` files.
+fn load_slot(slot: &str) -> Result<Vec<IVec2>, String> {
+    let path = slot_path(slot, save_file::EXTENSION)?;
+    if std::path::Path::new(&path).exists() {
+        let bytes = std::fs::read(&path).map_err(|err| format!("failed to load {}: {}", path, err))?;
+        return save_file::decode(&bytes)
+            .map(|save| save.cells)
+            .map_err(|err| format!("failed to load {}: {}", path, err));
+    }
+    let path = slot_path(slot, "cells")?;
+    if std::path::Path::new(&path).exists() {
+        let text = std::fs::read_to_string(&path).map_err(|err| format!("failed to load {}: {}", path, err))?;
+        return formats::parse_plaintext(&text).map_err(|err| format!("failed to load {}: {}", path, err));
+    }
+    let path = slot_path(slot, "png")?;
+    let bytes = std::fs::read(&path).map_err(|err| format!("failed to load {}: {}", path, err))?;
+    let snapshot = snapshot::decode(&bytes).map_err(|err| format!("failed to load {}: {}", path, err))?;
+    Ok(snapshot.cells)
+}
+
+// Everything commands read or drive.
+#[derive(SystemParam)]
+struct CommandTargets<'w> {
+    board: Res<'w, BoardSnapshot>,
+    seed_settings: ResMut<'w, SeedSettings>,
+    generation: Res<'w, Generation>,
+    rule_table: Res<'w, ActiveRuleTable>,
+    rule_string: Res<'w, RuleString>,
+    lifespans: Res<'w, Lifespans>,
+    commands: EventWriter<'w, ConwayCommand>,
+    exports: EventWriter<'w, ExportNpy>,
+    pnm_exports: EventWriter<'w, ExportPnm>,
+    schedule: ResMut<'w, InjectionSchedule>,
+    morphs: EventWriter<'w, MorphCommand>,
+    compares: EventWriter<'w, CompareCommand>,
+    palette: ResMut<'w, PaletteCycle>,
+    thresholds: ResMut<'w, PopulationThresholds>,
+    gardener: ResMut<'w, Gardener>,
+    time_lapses: EventWriter<'w, TimeLapseCommand>,
+    diffs: EventWriter<'w, DiffCommand>,
+    challenges: EventWriter<'w, ChallengeCommand>,
+    imports: EventWriter<'w, ImportImage>,
+    pictures: EventWriter<'w, PictureCommand>,
+    tapes: EventWriter<'w, TapeCommand>,
+    fast_forwards: EventWriter<'w, FastForwardCommand>,
+    supersampling: ResMut<'w, Supersampling>,
+    lod: ResMut<'w, LodSettings>,
+    pipeline_overlay: ResMut<'w, PipelineOverlay>,
+    fog: ResMut<'w, FogOfWar>,
+    encoding: ResMut<'w, StateEncoding>,
+    brush: ResMut<'w, Brush>,
+    soup: ResMut<'w, SoupStamp>,
+    rng: ResMut<'w, SimRng>,
+    annotations: ResMut<'w, Annotations>,
+    view_commands: EventWriter<'w, ViewCommand>,
+    bookmarks: Res<'w, Bookmarks>,
+    figures: EventWriter<'w, ExportFigure>,
+    measurement: Res<'w, Measurement>,
+    live_bounds: Res<'w, LiveBounds>,
+    ab_tests: EventWriter<'w, AbTestCommand>,
+    workspace_commands: EventWriter<'w, WorkspaceCommand>,
+    workspaces: Res<'w, Workspaces>,
+    simulation_kind: ResMut<'w, SimulationKind>,
+    lenia: ResMut<'w, LeniaSettings>,
+    ants: ResMut<'w, Ants>,
+    elementary_rule: ResMut<'w, ElementaryRule>,
+    render_device: Res<'w, RenderDevice>,
+}
+
+fn run_command(line: &str, targets: &mut CommandTargets) -> Result<String, String> {
+    let CommandTargets {
+        board, seed_settings, generation, rule_table, rule_string, lifespans, commands, exports, pnm_exports, schedule,
+        morphs, compares, palette, thresholds, gardener, time_lapses, diffs, challenges, imports, pictures,
+        tapes, fast_forwards, supersampling, lod, pipeline_overlay, fog, encoding, brush, soup, rng,
+        annotations, view_commands, bookmarks, figures, measurement, ab_tests, live_bounds, workspace_commands,
+        workspaces, simulation_kind, lenia, ants, elementary_rule, render_device,
+    } = targets;
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    match words.as_slice() {
+        [] => Ok(String::new()),
+        ["help"] => Ok(format!("commands: {}", COMMANDS.join(", "))),
+        ["set", x, y] => {
+            commands.send(ConwayCommand::SetCells(vec![parse_cell(x, y)?]));
+            Ok(String::new())
+        }
+        ["clear", x, y] => {
+            commands.send(ConwayCommand::ClearCells(vec![parse_cell(x, y)?]));
+            Ok(String::new())
+        }
+        [verb @ ("fill" | "clear"), region @ ..] if region.is_empty() || region.len() == 4 => {
+            let (corner, size) = match region {
+                [x, y, width, height] => parse_rect(x, y, width, height)?,
+                _ => measurement.region().ok_or("measure a box with the ruler first, or give one")?,
+            };
+            commands.send(ConwayCommand::FillRect { corner, size, alive: *verb == "fill" });
+            Ok(String::new())
+        }
+        ["rotate"] => {
+            commands.send(ConwayCommand::Transform(BoardTransform::Rotate));
+            Ok(String::new())
+        }
+        ["flip", axis] => {
+            let transform = match *axis {
+                "h" => BoardTransform::FlipHorizontal,
+                "v" => BoardTransform::FlipVertical,
+                _ => return Err(format!("expected h or v, got {}", axis)),
+            };
+            commands.send(ConwayCommand::Transform(transform));
+            Ok(String::new())
+        }
+        ["shift", "center"] => {
+            let (min, max) = live_bounds.0.ok_or("there's nothing alive to center")?;
+            let offset = board.size().as_ivec2() / 2 - (min + max) / 2;
+            commands.send(ConwayCommand::Transform(BoardTransform::Shift { offset, wrap: false }));
+            Ok(format!("shifted the board by {} {}", offset.x, offset.y))
+        }
+        ["shift", dx, dy, wrap @ ..] if wrap.is_empty() || wrap == ["wrap"] => {
+            let offset = parse_cell(dx, dy).map_err(|_| format!("expected an offset, got {} {}", dx, dy))?;
+            commands.send(ConwayCommand::Transform(BoardTransform::Shift { offset, wrap: !wrap.is_empty() }));
+            Ok(String::new())
+        }
+        ["resize", size, resample @ ..] if resample.is_empty() || resample == ["resample"] => {
+            let size = cli::parse_size(size).ok_or_else(|| format!("expected a size like 640x360, got {}", size))?;
+            ConwayConfig::fitting(size)?.check_limits(&render_device.limits())?;
+            commands.send(ConwayCommand::Resize { size, resample: !resample.is_empty() });
+            Ok(format!("resized the board to {}x{}", size.x, size.y))
+        }
+        ["crop"] => {
+            let (min, max) = live_bounds.0.ok_or("there's nothing alive to crop around")?;
+            // Never any bigger than it was.
+            let size = (max - min).as_uvec2() + 1 + 2 * CROP_MARGIN;
+            let size = ConwayConfig::padded(size).min(board.size());
+            let corner = (min + max) / 2 - size.as_ivec2() / 2;
+            ConwayConfig::fitting(size)?.check_limits(&render_device.limits())?;
+            commands.send(ConwayCommand::Crop { corner, size });
+            Ok(format!("cropped the board to {}x{}", size.x, size.y))
+        }
+        ["brush", radius, density @ ..] if density.len() <= 1 => {
+            let radius = radius
+                .parse()
+                .ok()
+                .filter(|radius| *radius <= MAX_BRUSH_RADIUS)
+                .ok_or_else(|| format!("expected a radius up to {}, got {}", MAX_BRUSH_RADIUS, radius))?;
+            let density = density.first().map_or(Ok(1.0), |density| parse_density(density))?;
+            **brush = Brush { radius, density };
+            Ok(String::new())
+        }
+        ["soup", x, y, width, height, density @ ..] if density.len() <= 1 => {
+            let corner = parse_cell(x, y)?;
+            let size = parse_cell(width, height).ok().filter(|size| size.cmpgt(IVec2::ZERO).all());
+            let size = size.ok_or_else(|| format!("expected a size, got {} {}", width, height))?;
+            let density = density.first().map_or(Ok(soup.density), |density| parse_density(density))?;
+            commands.send_batch(soup_stamp::stamp(corner, size, density, board.size(), rng));
+            Ok(String::new())
+        }
+        ["soup", density] => {
+            soup.density = parse_density(density)?;
+            Ok(String::new())
+        }
+        ["stamp", name, x, y] => {
+            let cells = patterns::lookup(name, parse_cell(x, y)?).ok_or_else(|| format!("unknown pattern {:?}", name))?;
+            commands.send(ConwayCommand::SetCells(cells));
+            Ok(String::new())
+        }
+        ["speed", speed] => {
+            let speed = speed.parse().map_err(|_| format!("expected a speed, got {}", speed))?;
+            commands.send(ConwayCommand::SetSpeed(speed));
+            Ok(String::new())
+        }
+        ["seed", seed] => {
+            let seed = seed.parse().map_err(|_| format!("expected a seed, got {}", seed))?;
+            commands.send(ConwayCommand::Reseed { density: seed_settings.density, seed });
+            Ok(String::new())
+        }
+        ["pause"] => {
+            commands.send(ConwayCommand::Pause);
+            Ok(String::new())
+        }
+        ["resume"] => {
+            commands.send(ConwayCommand::Resume);
+            Ok(String::new())
+        }
+        ["rule", rule] => match LifeRule::parse(rule) {
+            Ok(rule) => {
+                commands.send(ConwayCommand::SetRule(rule));
+                Ok(format!("the rule is {}", rule))
+            }
+            Err(_) => {
+                let table = RuleTable::load(rule).map_err(|err| format!("failed to load {}: {}", rule, err))?;
+                let message = format!("the rule is {} ({} states)", table.name, table.states);
+                commands.send(ConwayCommand::SetRuleTable(Some(Arc::new(table))));
+                Ok(message)
+            }
+        },
+        ["save", slot] => {
+            let path = slot_path(slot, save_file::EXTENSION)?;
+            let metadata = SnapshotMetadata {
+                rule: Some(rule_table.name(rule_string).into_owned()),
+                seed: Some(seed_settings.seed),
+                density: Some(seed_settings.density),
+                generation: Some(generation.get()),
+                rng_seed: None,
+                rng_position: None,
+                hash: None,
+            };
+            let bytes = save_file::encode(board.size(), &board.alive_cells(), &metadata)?;
+            std::fs::create_dir_all(SAVE_DIRECTORY)
+                .and_then(|_| std::fs::write(&path, bytes))
+                .map_err(|err| format!("failed to save {}: {}", path, err))?;
+            // The labels go alongside, and an old file goes if there aren't any.
+            let labels_path = slot_path(slot, annotations::EXTENSION)?;
+            let saved_labels = if annotations.is_empty() {
+                std::fs::remove_file(&labels_path).or_else(|err| match err.kind() {
+                    std::io::ErrorKind::NotFound => Ok(()),
+                    _ => Err(err),
+                })
+            } else {
+                std::fs::write(&labels_path, annotations.to_toml()?)
+            };
+            saved_labels.map_err(|err| format!("failed to save {}: {}", labels_path, err))?;
+            Ok(format!("saved {}", path))
+        }
+        ["load", slot] => {
+            let cells = load_slot(slot)?;
+            let labels_path = slot_path(slot, annotations::EXTENSION)?;
+            **annotations = match std::fs::read_to_string(&labels_path) {
+                Ok(text) => {
+                    Annotations::from_toml(&text).map_err(|err| format!("failed to load {}: {}", labels_path, err))?
+                }
+                Err(_) => Annotations::default(),
+            };
+            commands.send(ConwayCommand::ClearCells(board.alive_cells_except(&cells)));
+            commands.send(ConwayCommand::SetCells(cells));
+            Ok(format!("loaded {}", slot))
+        }
+        ["until", "off"] => {
+            fast_forwards.send(FastForwardCommand::Cancel);
+            Ok(String::new())
+        }
+        ["until", target] => {
+            let target = target.parse().map_err(|_| format!("expected a generation, got {}", target))?;
+            fast_forwards.send(FastForwardCommand::Until(target));
+            Ok(String::new())
+        }
+        ["advance", generations] => {
+            let generations = generations
+                .parse()
+                .map_err(|_| format!("expected a number of generations, got {}", generations))?;
+            fast_forwards.send(FastForwardCommand::By(generations));
+            Ok(String::new())
+        }
+        ["diff", "off"] => {
+            diffs.send(DiffCommand::Hide);
+            Ok(String::new())
+        }
+        ["diff", a, b @ ..] if b.len() <= 1 => {
+            let b = b.first().map(|b| load_slot(b)).transpose()?;
+            diffs.send(DiffCommand::Show { a: load_slot(a)?, b });
+            Ok(String::new())
+        }
+        ["challenge", "off"] => {
+            challenges.send(ChallengeCommand::Quit);
+            Ok(String::new())
+        }
+        ["challenge", path] => {
+            challenges.send(ChallengeCommand::Start(Challenge::load(path)?));
+            Ok(String::new())
+        }
+        ["snapshot", slot] => {
+            let path = slot_path(slot, "png")?;
+            let metadata = SnapshotMetadata {
+                rule: Some(rule_table.name(rule_string).into_owned()),
+                seed: Some(seed_settings.seed),
+                density: Some(seed_settings.density),
+                generation: Some(generation.get()),
+                rng_seed: None,
+                rng_position: None,
+                hash: None,
+            };
+            let bytes = snapshot::encode(board.size(), &board.alive_cells(), &metadata)?;
+            std::fs::create_dir_all(SAVE_DIRECTORY)
+                .and_then(|_| std::fs::write(&path, bytes))
+                .map_err(|err| format!("failed to save {}: {}", path, err))?;
+            Ok(format!("saved {}", path))
+        }
+        ["restore", slot] => {
+            let path = slot_path(slot, "png")?;
+            let bytes = std::fs::read(&path).map_err(|err| format!("failed to load {}: {}", path, err))?;
+            let snapshot = snapshot::decode(&bytes).map_err(|err| format!("failed to load {}: {}", path, err))?;
+            let mut notes = vec![format!("restored {}", path)];
+            if snapshot.size != board.size() {
+                notes.push(format!("it's {}x{}, unlike this board", snapshot.size.x, snapshot.size.y));
+            }
+            match snapshot.metadata.hash {
+                None => notes.push("it has no board hash to check".to_string()),
+                Some(_) if !snapshot.hash_matches() => notes.push("its board doesn't match its hash".to_string()),
+                Some(_) => {}
+            }
+            let metadata = &snapshot.metadata;
+            if let Some(rule) = &metadata.rule {
+                match ConwayCommand::set_rule_named(rule) {
+                    Ok(command) => commands.send(command),
+                    Err(_) => notes.push(format!("couldn't find its rule {}", rule)),
+                }
+            }
+            if let (Some(seed), Some(density)) = (metadata.seed, metadata.density) {
+                seed_settings.seed = seed;
+                seed_settings.density = density;
+                notes.push(format!("seed {} at density {}", seed, density));
+            }
+            if let Some(saved) = metadata.generation {
+                // It carries on counting from where it was saved.
+                generation.set(saved);
+                notes.push(format!("back at generation {}", saved));
+            }
+            commands.send(ConwayCommand::ClearCells(board.alive_cells_except(&snapshot.cells)));
+            commands.send(ConwayCommand::SetCells(snapshot.cells));
+            Ok(notes.join("\n"))
+        }
+        ["import", path, mode @ ..] if mode.len() <= 1 => {
+            let mode = match mode.first() {
+                None => ImportMode::Threshold(DEFAULT_THRESHOLD),
+                Some(&"dither") => ImportMode::Dither,
+                Some(threshold) => ImportMode::Threshold(parse_density(threshold)?),
+            };
+            imports.send(ImportImage { path: path.to_string(), mode });
+            Ok(String::new())
+        }
+        ["picture", "off"] => {
+            pictures.send(PictureCommand::Stop);
+            Ok(String::new())
+        }
+        ["picture", path, bias @ ..] if bias.len() <= 1 => {
+            let bias = bias.first().map_or(Ok(DEFAULT_BIAS), |bias| parse_density(bias))?;
+            pictures.send(PictureCommand::Start { path: path.to_string(), bias });
+            Ok(String::new())
+        }
+        ["flow", "off"] => {
+            commands.send(ConwayCommand::ClearFlow);
+            Ok(String::new())
+        }
+        ["flow", dx, dy] => {
+            let velocity = Vec2::new(parse_velocity(dx)?, parse_velocity(dy)?);
+            commands.send(ConwayCommand::FillFlow { corner: IVec2::ZERO, size: board.size(), velocity });
+            Ok(String::new())
+        }
+        ["tape", "record", keyframe_every @ ..] if keyframe_every.len() <= 1 => {
+            let keyframe_every = match keyframe_every.first() {
+                Some(every) => every
+                    .parse()
+                    .ok()
+                    .filter(|every| *every > 0)
+                    .ok_or_else(|| format!("expected a number of frames, got {}", every))?,
+                None => DEFAULT_KEYFRAME_EVERY,
+            };
+            tapes.send(TapeCommand::Record { keyframe_every });
+            Ok(String::new())
+        }
+        ["tape", "stop"] => {
+            tapes.send(TapeCommand::Stop);
+            Ok(String::new())
+        }
+        ["tape", "clear"] => {
+            tapes.send(TapeCommand::Clear);
+            Ok(String::new())
+        }
+        ["tape", "seek", target] => {
+            let target = target.parse().map_err(|_| format!("expected a generation, got {}", target))?;
+            tapes.send(TapeCommand::Seek(target));
+            Ok(String::new())
+        }
+        ["npy", path] => {
+            exports.send(ExportNpy { path: path.to_string(), generations: 1 });
+            Ok(String::new())
+        }
+        ["npy", path, generations] => {
+            let generations = generations.parse().map_err(|_| format!("expected a generation count, got {}", generations))?;
+            exports.send(ExportNpy { path: path.to_string(), generations });
+            Ok(String::new())
+        }
+        ["pbm", path] => {
+            pnm_exports.send(ExportPnm { path: path.to_string(), format: PnmFormat::Pbm });
+            Ok(String::new())
+        }
+        ["pgm", path] => {
+            pnm_exports.send(ExportPnm { path: path.to_string(), format: PnmFormat::Pgm });
+            Ok(String::new())
+        }
+        ["abtest", "off"] => {
+            ab_tests.send(AbTestCommand::Stop);
+            Ok(String::new())
+        }
+        ["abtest", generations] => {
+            let generations = generations
+                .parse()
+                .ok()
+                .filter(|generations| *generations > 0)
+                .ok_or_else(|| format!("expected a number of generations, got {}", generations))?;
+            ab_tests.send(AbTestCommand::Start { generations });
+            Ok(String::new())
+        }
+        ["figure", path, scale, rest @ ..] => {
+            let scale = scale.parse().map_err(|_| format!("expected a scale, got {}", scale))?;
+            let (region, grid) = match rest.split_last() {
+                Some((&"grid", region)) => (region, true),
+                _ => (rest, false),
+            };
+            let (corner, size) = match region {
+                [] => measurement.region().ok_or("measure a box with the ruler first, or give one")?,
+                [x, y, width, height] => parse_rect(x, y, width, height)?,
+                _ => return Err("expected figure FILE SCALE [X Y W H] [grid]".to_string()),
+            };
+            figures.send(ExportFigure { path: path.to_string(), corner, size, scale, grid });
+            Ok(String::new())
+        }
+        ["at", generation, name, x, y] => {
+            let generation = generation.parse().map_err(|_| format!("expected a generation, got {}", generation))?;
+            if patterns::lookup(name, IVec2::ZERO).is_none() {
+                return Err(format!("unknown pattern {:?}", name));
+            }
+            let position = parse_cell(x, y)?;
+            schedule.add(Injection { generation, pattern: name.to_string(), position });
+            Ok(format!("stamping {} at {} {} at generation {}", name, position.x, position.y, generation))
+        }
+        ["schedule"] if schedule.injections().is_empty() => Ok("nothing is scheduled".to_string()),
+        ["schedule"] => Ok(schedule
+            .injections()
+            .iter()
+            .enumerate()
+            .map(|(i, injection)| format!(
+                "{}: {} at {} {} at generation {}",
+                i, injection.pattern, injection.position.x, injection.position.y, injection.generation,
+            ))
+            .collect::<Vec<_>>()
+            .join("\n")),
+        ["unschedule", "all"] => {
+            schedule.clear();
+            Ok(String::new())
+        }
+        ["unschedule", index] => {
+            let index = index.parse().map_err(|_| format!("expected an entry number, got {}", index))?;
+            let injection = schedule.remove(index).ok_or_else(|| format!("there's no entry {}", index))?;
+            Ok(format!("dropped {} at generation {}", injection.pattern, injection.generation))
+        }
+        ["energy", "off"] => {
+            commands.send(ConwayCommand::SetEnergy(EnergySettings { enabled: false, ..default() }));
+            Ok(String::new())
+        }
+        ["energy", cost, regen] => {
+            let (Ok(cost), Ok(regen)) = (cost.parse(), regen.parse()) else {
+                return Err(format!("expected an energy cost and regeneration rate, got {} {}", cost, regen));
+            };
+            commands.send(ConwayCommand::SetEnergy(EnergySettings { enabled: true, cost, regen }));
+            Ok(String::new())
+        }
+        ["lifespan"] if lifespans.0.is_empty() => Ok("cells live forever under every rule".to_string()),
+        ["lifespan"] => {
+            let mut entries = lifespans.0.iter().collect::<Vec<_>>();
+            entries.sort();
+            Ok(entries
+                .into_iter()
+                .map(|(rule, generations)| format!("{}: {} generations", rule, generations))
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        ["lifespan", generations, rule @ ..] if rule.len() <= 1 => {
+            let rule = rule.first().map_or_else(|| rule_table.name(rule_string).into_owned(), |rule| rule.to_string());
+            let generations = match *generations {
+                "off" => 0,
+                generations => generations
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|generations| (1..=MAX_LIFESPAN).contains(generations))
+                    .ok_or_else(|| format!("expected a lifespan from 1 to {}, got {}", MAX_LIFESPAN, generations))?,
+            };
+            commands.send(ConwayCommand::SetLifespan { rule, generations });
+            Ok(String::new())
+        }
+        ["timelapse", "off"] => {
+            time_lapses.send(TimeLapseCommand::Stop);
+            Ok(String::new())
+        }
+        ["timelapse", directory, every] => {
+            let every = every
+                .parse()
+                .ok()
+                .filter(|every| *every > 0)
+                .ok_or_else(|| format!("expected a number of generations, got {}", every))?;
+            time_lapses.send(TimeLapseCommand::Start { directory: directory.to_string(), every });
+            Ok(String::new())
+        }
+        ["alert"] if thresholds.0.is_empty() => Ok("no alerts".to_string()),
+        ["alert"] => Ok(thresholds.0.iter().map(|threshold| threshold.to_string()).collect::<Vec<_>>().join(", ")),
+        ["alert", "off"] => {
+            thresholds.0.clear();
+            Ok(String::new())
+        }
+        ["alert", "zero"] => {
+            thresholds.0.push(Threshold::Extinct);
+            Ok(String::new())
+        }
+        ["alert", direction @ ("below" | "above"), population] => {
+            let population = population.parse().map_err(|_| format!("expected a population, got {}", population))?;
+            let threshold = if *direction == "below" { Threshold::Below(population) } else { Threshold::Above(population) };
+            thresholds.0.push(threshold);
+            Ok(String::new())
+        }
+        ["label", x, y, text @ ..] if !text.is_empty() => {
+            let cell = parse_cell(x, y)?;
+            annotations.labels.push(Label { x: cell.x, y: cell.y, text: text.join(" ") });
+            Ok(String::new())
+        }
+        ["arrow", x1, y1, x2, y2] => {
+            let (from, to) = (parse_cell(x1, y1)?, parse_cell(x2, y2)?);
+            annotations.arrows.push(Arrow { from: from.into(), to: to.into() });
+            Ok(String::new())
+        }
+        ["labels"] if annotations.is_empty() => Ok("no labels".to_string()),
+        ["labels"] => {
+            let labels = annotations.labels.iter().map(|label| format!("{:?} at {}, {}", label.text, label.x, label.y));
+            let arrows = annotations.arrows.iter().map(|arrow| {
+                format!("arrow {}, {} to {}, {}", arrow.from.0, arrow.from.1, arrow.to.0, arrow.to.1)
+            });
+            Ok(labels.chain(arrows).collect::<Vec<_>>().join(", "))
+        }
+        ["labels", "clear"] => {
+            **annotations = Annotations::default();
+            Ok(String::new())
+        }
+        ["view", "home"] => {
+            view_commands.send(ViewCommand::Home);
+            Ok(String::new())
+        }
+        ["view", slot, name @ ..] => {
+            let slot = slot
+                .parse()
+                .ok()
+                .filter(|slot| (1..=views::SLOTS).contains(slot))
+                .ok_or_else(|| format!("expected a bookmark from 1 to {}, got {}", views::SLOTS, slot))?;
+            view_commands.send(match name {
+                [] => ViewCommand::Recall(slot),
+                name => ViewCommand::Bookmark { slot, name: name.join(" ") },
+            });
+            Ok(String::new())
+        }
+        ["views"] if bookmarks.bookmarks.is_empty() => Ok("no bookmarks".to_string()),
+        ["views"] => Ok(bookmarks
+            .bookmarks
+            .iter()
+            .map(|bookmark| format!("{}: {}", bookmark.slot, bookmark.name))
+            .collect::<Vec<_>>()
+            .join(", ")),
+        ["workspace", name] => {
+            workspace_commands.send(WorkspaceCommand::Switch(name.to_string()));
+            Ok(String::new())
+        }
+        ["workspaces"] if workspaces.names.is_empty() => Ok("no workspaces".to_string()),
+        ["workspaces"] => Ok(workspaces
+            .names
+            .iter()
+            .map(|name| match workspaces.current.as_ref() == Some(name) {
+                true => format!("{} (open)", name),
+                false => name.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")),
+        ["garden", "off"] => {
+            gardener.enabled = false;
+            Ok(String::new())
+        }
+        ["garden", low, high] => {
+            let parse = |text: &str| text.parse().map_err(|_| format!("expected a population, got {}", text));
+            let (low, high) = (parse(low)?, parse(high)?);
+            if low >= high {
+                return Err(format!("expected LOW under HIGH, got {} and {}", low, high));
+            }
+            **gardener = Gardener { enabled: true, low, high };
+            Ok(String::new())
+        }
+        ["supersample", samples] => {
+            supersampling.0 = samples
+                .parse()
+                .ok()
+                .filter(|samples| (1..=MAX_SAMPLES).contains(samples))
+                .ok_or_else(|| format!("expected 1 to {} samples, got {}", MAX_SAMPLES, samples))?;
+            Ok(String::new())
+        }
+        ["lod", "off"] => {
+            lod.enabled = false;
+            Ok(String::new())
+        }
+        ["lod", every @ ..] if every.len() <= 1 => {
+            let every = match every.first() {
+                Some(every) => every
+                    .parse()
+                    .ok()
+                    .filter(|every| *every > 0)
+                    .ok_or_else(|| format!("expected a number of frames, got {}", every))?,
+                None => 1,
+            };
+            **lod = LodSettings { enabled: true, every };
+            Ok(String::new())
+        }
+        ["pipelines"] => {
+            pipeline_overlay.enabled = true;
+            Ok(String::new())
+        }
+        ["pipelines", "off"] => {
+            pipeline_overlay.enabled = false;
+            Ok(String::new())
+        }
+        ["encoding", "linear"] => {
+            **encoding = StateEncoding::Linear;
+            Ok(String::new())
+        }
+        ["encoding", "srgb"] => {
+            **encoding = StateEncoding::Srgb;
+            Ok(String::new())
+        }
+        ["lenia", "off"] => {
+            **simulation_kind = SimulationKind::Life;
+            Ok(String::new())
+        }
+        ["lenia", settings @ ..] if settings.is_empty() || settings.len() == 4 => {
+            if let [radius, mu, sigma, dt] = settings {
+                let number = |text: &str| text.parse::<f32>().ok().filter(|number| *number > 0.0);
+                let radius = radius
+                    .parse()
+                    .ok()
+                    .filter(|radius| (1..=MAX_LENIA_RADIUS).contains(radius))
+                    .ok_or_else(|| format!("expected a radius from 1 to {}, got {}", MAX_LENIA_RADIUS, radius))?;
+                let mu = number(mu).ok_or_else(|| format!("expected MU over 0, got {}", mu))?;
+                let sigma = number(sigma).ok_or_else(|| format!("expected SIGMA over 0, got {}", sigma))?;
+                let step = number(dt).filter(|step| *step <= 1.0);
+                let dt = step.ok_or_else(|| format!("expected DT from 0 to 1, got {}", dt))?;
+                **lenia = LeniaSettings { radius, mu, sigma, dt };
+            }
+            **simulation_kind = SimulationKind::Lenia;
+            Ok(format!("running Lenia: R{} mu {} sigma {} dt {}", lenia.radius, lenia.mu, lenia.sigma, lenia.dt))
+        }
+        ["ants", "off"] => {
+            ants.ants.clear();
+            **simulation_kind = SimulationKind::Life;
+            Ok(String::new())
+        }
+        ["ants", turmite @ ..] if turmite.len() <= 1 => {
+            if let Some(turmite) = turmite.first() {
+                ants.turmite = Turmite::parse(turmite)?;
+                // The new turmite may not have the states the ants were in.
+                ants.ants.iter_mut().for_each(|ant| ant.state = 0);
+            }
+            **simulation_kind = SimulationKind::Ants;
+            Ok(format!("{} ants following {}", ants.ants.len(), ants.turmite))
+        }
+        ["ant", x, y] => {
+            ants.ants.push(Ant::new(parse_cell(x, y)?));
+            Ok(String::new())
+        }
+        ["elementary", "off"] => {
+            **simulation_kind = SimulationKind::Life;
+            Ok(String::new())
+        }
+        ["elementary", rule] => {
+            let rule = rule.parse().map_err(|_| format!("expected a rule from 0 to 255, got {}", rule))?;
+            **elementary_rule = ElementaryRule(rule);
+            **simulation_kind = SimulationKind::Elementary;
+            Ok(String::new())
+        }
+        ["fog", "off"] => {
+            fog.enabled = false;
+            Ok(String::new())
+        }
+        ["fog", seconds @ ..] if seconds.len() <= 1 => {
+            let fade_seconds = match seconds.first() {
+                Some(seconds) => seconds
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|seconds| *seconds > 0.0)
+                    .ok_or_else(|| format!("expected a number of seconds, got {}", seconds))?,
+                None => DEFAULT_FADE_SECONDS,
+            };
+            **fog = FogOfWar { enabled: true, fade_seconds };
+            Ok(String::new())
+        }
+        ["wrap", "on"] => {
+            commands.send(ConwayCommand::SetEdges(Edges::Wrap));
+            Ok(String::new())
+        }
+        ["wrap", "off"] => {
+            commands.send(ConwayCommand::SetEdges(Edges::Dead));
+            Ok(String::new())
+        }
+        ["edges", mode] => {
+            let edges = Edges::parse(mode).ok_or_else(|| format!("expected dead, wrap or mirror, got {}", mode))?;
+            commands.send(ConwayCommand::SetEdges(edges));
+            Ok(String::new())
+        }
+        ["palette", "on"] => {
+            palette.enabled = true;
+            Ok(String::new())
+        }
+        ["palette", "off"] => {
+            palette.enabled = false;
+            Ok(String::new())
+        }
+        ["palette", speed] => {
+            palette.speed = speed.parse().map_err(|_| format!("expected degrees per second, got {}", speed))?;
+            palette.enabled = true;
+            Ok(String::new())
+        }
+        ["morph", "off"] => {
+            morphs.send(MorphCommand::Stop);
+            Ok(String::new())
+        }
+        ["morph", a, b, period, rest @ ..] if rest.len() <= 1 => {
+            let load = |rule: &str| {
+                RuleTable::parse_or_load(rule).map(Arc::new).map_err(|err| format!("failed to load {}: {}", rule, err))
+            };
+            let seconds = |text: &str| text.parse::<f32>().ok().filter(|seconds| *seconds >= 0.0);
+            let period = seconds(period).ok_or_else(|| format!("expected a number of seconds, got {}", period))?;
+            // Crossfade over a fifth of each half unless told otherwise.
+            let fade = match rest.first() {
+                Some(fade) => seconds(fade).ok_or_else(|| format!("expected a number of seconds, got {}", fade))?,
+                None => period / 5.0,
+            };
+            morphs.send(MorphCommand::Start { a: load(a)?, b: load(b)?, period, fade });
+            Ok(String::new())
+        }
+        ["compare", "off"] => {
+            compares.send(CompareCommand::Stop);
+            Ok(String::new())
+        }
+        ["compare", rules @ ..] if (MIN_RULES..=MAX_RULES).contains(&rules.len()) => {
+            let rules = rules.iter().map(|rule| LifeRule::parse(rule)?.for_cpu()).collect::<Result<Vec<_>, _>>()?;
+            compares.send(CompareCommand::Start(rules));
+            Ok(String::new())
+        }
+        [command, ..] if COMMANDS.contains(command) => Err(format!("wrong arguments for {}", command)),
+        [command, ..] => Err(format!("unknown command {:?}, try help", command)),
+    }
+}
+
+// The longest string all of `options` start with.
+fn common_prefix<'a>(options: &[&'a str]) -> &'a str {
+    let Some(first) = options.first() else {
+        return "";
+    };
+    let length = options[1..].iter().fold(first.len(), |length, option| {
+        first.bytes().zip(option.bytes()).take(length).take_while(|(a, b)| a == b).count()
+    });
+    &first[..length]
+}
+
+fn complete(console: &mut Console) {
+    let (head, partial, options) = match console.input.split_once(' ') {
+        None => (String::new(), console.input.clone(), COMMANDS),
+        Some(("stamp", name)) if !name.contains(' ') => ("stamp ".to_string(), name.to_string(), patterns::NAMES),
+        Some(_) => return,
+    };
+    let matches = options.iter().copied().filter(|option| option.starts_with(partial.as_str())).collect::<Vec<_>>();
+    match matches.as_slice() {
+        [] => {}
+        [only] => console.input = format!("{}{} ", head, only),
+        _ => {
+            console.input = format!("{}{}", head, common_prefix(&matches));
+            console.print(matches.join("  "));
+        }
+    }
+}
+
+fn handle_input(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut characters: EventReader<ReceivedCharacter>,
+    mut console: ResMut<Console>,
+    mut targets: CommandTargets,
+) {
+    if keys.just_pressed(KeyCode::Grave) {
+        console.open = !console.open;
+        console.history_index = None;
+        characters.clear();
+        keys.reset_all();
+        return;
+    }
+    if !console.open {
+        characters.clear();
+        return;
+    }
+
+    for character in characters.read() {
+        if !character.char.is_control() {
+            console.input.push(character.char);
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        console.input.pop();
+    }
+    if keys.just_pressed(KeyCode::Tab) {
+        complete(&mut console);
+    }
+    if keys.just_pressed(KeyCode::Up) && !console.history.is_empty() {
+        let index = console.history_index.map_or(console.history.len() - 1, |index| index.saturating_sub(1));
+        console.history_index = Some(index);
+        console.input = console.history[index].clone();
+    }
+    if keys.just_pressed(KeyCode::Down) {
+        if let Some(index) = console.history_index {
+            if index + 1 < console.history.len() {
+                console.history_index = Some(index + 1);
+                console.input = console.history[index + 1].clone();
+            } else {
+                console.history_index = None;
+                console.input.clear();
+            }
+        }
+    }
+    if keys.just_pressed(KeyCode::Return) {
+        let line = std::mem::take(&mut console.input);
+        console.history_index = None;
+        console.print(format!("> {}", line));
+        if !line.trim().is_empty() {
+            console.history.push(line.clone());
+        }
+        match run_command(&line, &mut targets) {
+            Ok(output) if output.is_empty() => {}
+            Ok(output) => console.print(output),
+            Err(err) => console.print(format!("error: {}", err)),
+        }
+    }
+    if keys.just_pressed(KeyCode::Escape) {
+        console.open = false;
+    }
+    // Swallow the keys so the rest of the game doesn't react to typing.
+    keys.reset_all();
+}
+
+fn render_console(
+    console: Res<Console>,
+    mut root: Query<&mut Style, With<ConsoleRoot>>,
+    mut text: Query<&mut Text, With<ConsoleText>>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+    root.single_mut().display = if console.open { Display::Flex } else { Display::None };
+    let lines = console.lines.iter().map(String::as_str).collect::<Vec<_>>().join("\n");
+    text.single_mut().sections[0].value = format!("{}\n> {}_", lines, console.input);
+}
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Console>()
+        .add_systems(Startup, setup)
+        .add_systems(PreUpdate, handle_input.after(InputSystem))
+        .add_systems(Update, render_console)
+        ;
+    }
+}
@@ -0,0 +1,215 @@
+// Moves the controls into a second window, for projection and installation
+// setups: the main window goes fullscreen with nothing but the board, and the
+// HUD (console, inspector, event log and so on) shows in the control window
+// instead, along with buttons (a row for the board, a row for editing it)
+// and a population graph.
+//
+// Bevy lays UI out against the main window, so the control window opens at
+// the same size to keep everything anchored to its right and bottom edges on
+// screen.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::view::RenderLayers;
+use bevy::window::{PrimaryWindow, WindowMode, WindowRef, WindowResolution};
+use rand::Rng;
+
+use crate::conway::{BoardTransform, ConwayCommand, GenerationAdvanced, SeedSettings, SimulationState};
+use crate::rng::SimRng;
+
+pub struct ControlWindowPlugin;
+
+// Nothing is drawn on this layer, so the control camera only shows the UI.
+const CONTROL_LAYER: u8 = 1;
+const FONT_SIZE: f32 = 18.0;
+// One bar per generation the graph shows.
+const GRAPH_BARS: usize = 150;
+const GRAPH_HEIGHT: f32 = 80.0;
+const BAR_WIDTH: f32 = 2.0;
+
+#[derive(Component)]
+struct ControlCamera;
+
+#[derive(Component, Clone, Copy)]
+enum ControlButton {
+    PauseResume,
+    Slower,
+    Faster,
+    Reseed,
+    Rotate,
+    FlipHorizontal,
+    FlipVertical,
+}
+
+impl ControlButton {
+    const ALL: [ControlButton; 4] =
+        [ControlButton::PauseResume, ControlButton::Slower, ControlButton::Faster, ControlButton::Reseed];
+    // A second row, for editing the board.
+    const EDIT: [ControlButton; 3] =
+        [ControlButton::Rotate, ControlButton::FlipHorizontal, ControlButton::FlipVertical];
+
+    fn label(self) -> &'static str {
+        match self {
+            ControlButton::PauseResume => "pause / resume",
+            ControlButton::Slower => "slower",
+            ControlButton::Faster => "faster",
+            ControlButton::Reseed => "reseed",
+            ControlButton::Rotate => "rotate",
+            ControlButton::FlipHorizontal => "flip left / right",
+            ControlButton::FlipVertical => "flip top / bottom",
+        }
+    }
+}
+
+#[derive(Component)]
+struct GraphBar(usize);
+
+// Recent populations, oldest first.
+#[derive(Resource, Default)]
+struct PopulationHistory(VecDeque<u64>);
+
+fn setup(mut commands: Commands, mut primary: Query<&mut Window, With<PrimaryWindow>>) {
+    let Ok(mut primary) = primary.get_single_mut() else {
+        return;
+    };
+    let resolution = WindowResolution::new(primary.width(), primary.height());
+    primary.mode = WindowMode::BorderlessFullscreen;
+
+    let window = commands.spawn(Window { title: "Conway controls".to_string(), resolution, ..default() }).id();
+    commands.spawn((
+        ControlCamera,
+        Camera2dBundle {
+            camera: Camera { target: RenderTarget::Window(WindowRef::Entity(window)), ..default() },
+            ..default()
+        },
+        RenderLayers::layer(CONTROL_LAYER),
+    ));
+
+    let text_style = TextStyle { font_size: FONT_SIZE, color: Color::WHITE, ..default() };
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(60.0),
+                left: Val::Px(8.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|panel| {
+            for buttons in [&ControlButton::ALL[..], &ControlButton::EDIT[..]] {
+                panel
+                    .spawn(NodeBundle { style: Style { column_gap: Val::Px(8.0), ..default() }, ..default() })
+                    .with_children(|row| {
+                        for button in buttons {
+                            row.spawn((
+                                *button,
+                                ButtonBundle {
+                                    style: Style { padding: UiRect::all(Val::Px(6.0)), ..default() },
+                                    background_color: Color::DARK_GRAY.into(),
+                                    ..default()
+                                },
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn(TextBundle::from_section(button.label(), text_style.clone()));
+                            });
+                        }
+                    });
+            }
+            panel.spawn(TextBundle::from_section("population", text_style.clone()));
+            panel
+                .spawn(NodeBundle {
+                    style: Style {
+                        height: Val::Px(GRAPH_HEIGHT),
+                        align_items: AlignItems::FlexEnd,
+                        ..default()
+                    },
+                    background_color: Color::rgba(1.0, 1.0, 1.0, 0.1).into(),
+                    ..default()
+                })
+                .with_children(|graph| {
+                    for i in 0..GRAPH_BARS {
+                        graph.spawn((
+                            GraphBar(i),
+                            NodeBundle {
+                                style: Style { width: Val::Px(BAR_WIDTH), height: Val::Px(0.0), ..default() },
+                                background_color: Color::WHITE.into(),
+                                ..default()
+                            },
+                        ));
+                    }
+                });
+        });
+}
+
+// The UI can only show through one camera, so hide it on the board's.
+#[allow(clippy::type_complexity)]
+fn hide_ui_on_board(
+    mut commands: Commands,
+    cameras: Query<(Entity, &Camera), (Without<ControlCamera>, Without<UiCameraConfig>)>,
+) {
+    for (entity, camera) in &cameras {
+        if let RenderTarget::Window(WindowRef::Primary) = camera.target {
+            commands.entity(entity).insert(UiCameraConfig { show_ui: false });
+        }
+    }
+}
+
+fn press_buttons(
+    buttons: Query<(&Interaction, &ControlButton), Changed<Interaction>>,
+    simulation_state: Res<SimulationState>,
+    seed_settings: Res<SeedSettings>,
+    mut rng: ResMut<SimRng>,
+    mut commands: EventWriter<ConwayCommand>,
+) {
+    for (interaction, button) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        commands.send(match button {
+            ControlButton::PauseResume if simulation_state.paused => ConwayCommand::Resume,
+            ControlButton::PauseResume => ConwayCommand::Pause,
+            ControlButton::Slower => ConwayCommand::SetSpeed(simulation_state.speed / 2),
+            ControlButton::Faster => ConwayCommand::SetSpeed(simulation_state.speed * 2),
+            ControlButton::Reseed => ConwayCommand::Reseed { density: seed_settings.density, seed: rng.rng.gen() },
+            ControlButton::Rotate => ConwayCommand::Transform(BoardTransform::Rotate),
+            ControlButton::FlipHorizontal => ConwayCommand::Transform(BoardTransform::FlipHorizontal),
+            ControlButton::FlipVertical => ConwayCommand::Transform(BoardTransform::FlipVertical),
+        });
+    }
+}
+
+fn update_graph(
+    mut generations: EventReader<GenerationAdvanced>,
+    mut history: ResMut<PopulationHistory>,
+    mut bars: Query<(&GraphBar, &mut Style)>,
+) {
+    let Some(advanced) = generations.read().last() else {
+        return;
+    };
+    history.0.push_back(advanced.population);
+    if history.0.len() > GRAPH_BARS {
+        history.0.pop_front();
+    }
+    let peak = history.0.iter().copied().max().unwrap_or(0).max(1);
+    // Newest on the right.
+    let offset = GRAPH_BARS - history.0.len();
+    for (bar, mut style) in &mut bars {
+        let population = bar.0.checked_sub(offset).and_then(|i| history.0.get(i)).copied().unwrap_or(0);
+        style.height = Val::Px(GRAPH_HEIGHT * population as f32 / peak as f32);
+    }
+}
+
+impl Plugin for ControlWindowPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<PopulationHistory>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, (hide_ui_on_board, press_buttons, update_graph))
+        ;
+    }
+}
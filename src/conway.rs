@@ -1,6 +1,9 @@
 // A plugin that implements Conway's Game of Life using a compute shader.
 
 use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 
 use bevy::{
     prelude::*,
@@ -8,110 +11,1231 @@ use bevy::{
         render_resource::*,
         extract_resource::{ExtractResource, ExtractResourcePlugin},
         renderer::{RenderDevice, RenderContext, RenderQueue},
+        settings::WgpuLimits,
         render_asset::RenderAssets,
         RenderApp, Render, RenderSet, 
         render_graph::{RenderGraph, Node as RenderNode, self},
         MainWorld,
         texture::ImageSampler,
-    }, window::PrimaryWindow
+    },
     };
+use bevy::ecs::system::SystemParam;
+use rand::Rng;
 
-pub struct ConwayPlugin;
+use crate::life_rule::LifeRule;
+use crate::query::{answer_region_requests, RegionRequests};
+use crate::rng::SimRng;
+use crate::rule_table::{Neighborhood, RuleTable};
+use crate::tools::{self, BoardCursor, Brush, Tool};
+
+/// Runs the board, sized by the `ConwayConfig` it's made with.
+#[derive(Default)]
+pub struct ConwayPlugin {
+    config: ConwayConfig,
+}
+
+impl ConwayPlugin {
+    pub fn new(config: ConwayConfig) -> Self {
+        Self { config }
+    }
+}
 
 // How much to parallelize the compute shader.
-const SCALE_FACTOR: u32 = 10;
-const SIZE: (u32, u32) = (128 * SCALE_FACTOR, 72 * SCALE_FACTOR);
-const WORKGROUP_SIZE: (u32, u32) = (8, 8);
+pub(crate) const WORKGROUP_SIZE: (u32, u32) = (8, 8);
+// Runs written by each workgroup of `write_runs`.
+const RUNS_WORKGROUP_SIZE: u32 = 64;
+// Cells of a row written by each workgroup of `update_elementary`.
+const ROW_WORKGROUP_SIZE: u32 = 64;
+// Reading the board back copies rows of four bytes a cell, which wgpu wants
+// in multiples of 256 bytes.
+const WIDTH_MULTIPLE: u32 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT / 4;
+// Runs and rects pack their coordinates into 16 bits each way. The GPU
+// usually can't make textures anywhere near that big, see `check_limits`.
+const MAX_SIDE: u32 = 1 << 16;
+// The window the board's drawn to fit by default.
+const DEFAULT_VIEW: Vec2 = Vec2::new(1280.0, 720.0);
+
+/// The board's size in cells and how many world units each is drawn across.
+/// The width has to be a multiple of 64 and the height of 8, to split into
+/// workgroups and read back whole rows. It's in both the main and the render
+/// world, and changes with a `ConwayCommand::Resize`; the render world's
+/// board textures catch up a frame later.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, ExtractResource)]
+pub struct ConwayConfig {
+    pub size: UVec2,
+    pub display_scale: f32,
+}
+
+impl ConwayConfig {
+    pub const DEFAULT: Self = Self { size: UVec2::new(1280, 720), display_scale: 1.0 };
+
+    pub fn new(size: UVec2, display_scale: f32) -> Result<Self, String> {
+        for (side, length, multiple) in [("width", size.x, WIDTH_MULTIPLE), ("height", size.y, WORKGROUP_SIZE.1)] {
+            if length == 0 || length % multiple != 0 || length >= MAX_SIDE {
+                let expected = format!("a {} that's a multiple of {} under {}", side, multiple, MAX_SIDE);
+                return Err(format!("expected {}, got {}", expected, length));
+            }
+        }
+        if !(display_scale > 0.0 && display_scale.is_finite()) {
+            return Err(format!("expected a positive display scale, got {}", display_scale));
+        }
+        Ok(Self { size, display_scale })
+    }
+
+    /// A board of `size` cells, scaled to fill the default window.
+    pub fn fitting(size: UVec2) -> Result<Self, String> {
+        Self::new(size, (DEFAULT_VIEW / size.as_vec2()).min_element())
+    }
+
+    /// The smallest size a board can be that holds `size` cells.
+    pub fn padded(size: UVec2) -> UVec2 {
+        let multiple = UVec2::new(WIDTH_MULTIPLE, WORKGROUP_SIZE.1);
+        (size.max(UVec2::ONE) + multiple - 1) / multiple * multiple
+    }
+
+    /// Whether a GPU with `limits` can make the textures and buffers a board
+    /// this size needs.
+    pub fn check_limits(&self, limits: &WgpuLimits) -> Result<(), String> {
+        let side = self.size.max_element();
+        if side > limits.max_texture_dimension_2d {
+            return Err(format!("expected sides up to {} on this GPU, got {}", limits.max_texture_dimension_2d, side));
+        }
+        // Reading the board back takes four bytes a cell.
+        let cells = self.cell_count() as u64;
+        if cells * 4 > limits.max_buffer_size {
+            return Err(format!("expected up to {} cells on this GPU, got {}", limits.max_buffer_size / 4, cells));
+        }
+        Ok(())
+    }
+
+    /// The number of cells on the board.
+    pub fn cell_count(&self) -> usize {
+        self.size.x as usize * self.size.y as usize
+    }
+
+    /// Where a cell is in arrays of the board row by row, like
+    /// `BoardSnapshot::cells`, if it's on the board.
+    pub fn cell_index(&self, cell: IVec2) -> Option<usize> {
+        board_index(self.size, cell)
+    }
+
+    /// Converts a world-space position into board cell coordinates.
+    pub fn world_to_cell(&self, position: Vec2) -> IVec2 {
+        let (size, scale) = (self.size.as_vec2(), self.display_scale);
+        IVec2::new(
+            (position.x / scale + size.x / 2.0).floor() as i32,
+            (size.y / 2.0 - position.y / scale).floor() as i32,
+        )
+    }
+
+    /// Converts board cell coordinates into the world-space position of the
+    /// cell's top left corner.
+    pub fn cell_to_world(&self, cell: IVec2) -> Vec2 {
+        let (size, scale) = (self.size.as_vec2(), self.display_scale);
+        Vec2::new(
+            (cell.x as f32 - size.x / 2.0) * scale,
+            (size.y / 2.0 - cell.y as f32) * scale,
+        )
+    }
+
+    /// Converts board cell coordinates into the clip-space points used by `SetCells`.
+    pub fn cell_to_clip(&self, cell: IVec2) -> Vec2 {
+        let size = self.size.as_vec2();
+        Vec2::new(
+            (cell.x as f32 + 0.5) / size.x * 2.0 - 1.0,
+            1.0 - (cell.y as f32 + 0.5) / size.y * 2.0,
+        )
+    }
+
+    /// Converts a clip-space point used by `SetCells` back into board cell coordinates.
+    pub fn clip_to_cell(&self, point: Vec2) -> IVec2 {
+        let size = self.size.as_vec2();
+        IVec2::new(
+            ((point.x + 1.0) / 2.0 * size.x).floor() as i32,
+            ((1.0 - point.y) / 2.0 * size.y).floor() as i32,
+        )
+    }
+
+    /// The size of the displayed board in world units.
+    pub fn extents(&self) -> Vec2 {
+        self.size.as_vec2() * self.display_scale
+    }
+}
+
+// Where `cell` is in the row by row cells of a board of `size`, if it's on it.
+fn board_index(size: UVec2, cell: IVec2) -> Option<usize> {
+    let size = size.as_ivec2();
+    let on_board = cell.cmpge(IVec2::ZERO).all() && cell.cmplt(size).all();
+    on_board.then(|| cell.y as usize * size.x as usize + cell.x as usize)
+}
+
+impl Default for ConwayConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The number of living cells -- this is computed by the compute shader
+/// and shared to the MainWorld.
+#[derive(Resource, Default)]
+pub struct LivingCells(pub u64);
+
+/// A CPU copy of the board (one byte per cell, 255 is alive), read back
+/// from the GPU each frame and shared to the MainWorld. Until the first
+/// readback it has no cells, though it has the board's size.
+#[derive(Resource, Clone, Default)]
+pub struct BoardSnapshot {
+    pub cells: Vec<u8>,
+    size: UVec2,
+}
+
+impl BoardSnapshot {
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    pub fn row(&self, y: u32) -> &[u8] {
+        let width = self.size.x;
+        let start = (y * width) as usize;
+        self.cells.get(start..start + width as usize).unwrap_or(&[])
+    }
+
+    pub fn is_alive(&self, cell: IVec2) -> bool {
+        board_index(self.size, cell).and_then(|index| self.cells.get(index)) == Some(&255)
+    }
+
+    /// The positions of every living cell, row by row.
+    pub fn alive_cells(&self) -> Vec<IVec2> {
+        let width = self.size.x;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| **cell == 255)
+            .map(|(i, _)| IVec2::new((i as u32 % width) as i32, (i as u32 / width) as i32))
+            .collect()
+    }
+
+    /// The living cells that aren't among `cells`, to clear when replacing
+    /// the board with `cells`. Clearing everything instead would undo the
+    /// new cells, since the board sets cells before clearing them.
+    pub fn alive_cells_except(&self, cells: &[IVec2]) -> Vec<IVec2> {
+        let keep = cells.iter().collect::<HashSet<_>>();
+        self.alive_cells().into_iter().filter(|cell| !keep.contains(cell)).collect()
+    }
+}
+
+/// How the board changed over the last step, computed alongside `LivingCells`.
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct BoardStats {
+    pub births: u64,
+    pub deaths: u64,
+}
+
+/// Regions per side of the `DensityGrid`.
+pub const DENSITY_GRID_SIZE: u32 = 8;
+
+/// Living cells in each of an 8 x 8 grid of regions across the board, row by
+/// row from the top left, summed up on the GPU alongside `LivingCells`.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct DensityGrid {
+    pub counts: [u32; (DENSITY_GRID_SIZE * DENSITY_GRID_SIZE) as usize],
+}
+
+// Arrays this long don't derive `Default`.
+impl Default for DensityGrid {
+    fn default() -> Self {
+        Self { counts: [0; (DENSITY_GRID_SIZE * DENSITY_GRID_SIZE) as usize] }
+    }
+}
+
+/// The box around every living cell, as its top left and bottom right cells,
+/// summed up on the GPU alongside the `DensityGrid`. `None` while nothing's
+/// alive.
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct LiveBounds(pub Option<(IVec2, IVec2)>);
+
+impl DensityGrid {
+    /// The region a cell on a board of `size` falls in.
+    pub fn region(cell: IVec2, size: UVec2) -> UVec2 {
+        let cell = cell.clamp(IVec2::ZERO, size.as_ivec2() - IVec2::ONE).as_uvec2();
+        cell * DENSITY_GRID_SIZE / size
+    }
+
+    pub fn count(&self, region: UVec2) -> u32 {
+        self.counts[(region.y * DENSITY_GRID_SIZE + region.x) as usize]
+    }
+
+    /// The fraction of a region's cells that are alive, on a board of `size`.
+    pub fn density(&self, region: UVec2, size: UVec2) -> f32 {
+        let regions = size / DENSITY_GRID_SIZE;
+        let cells = regions.x * regions.y;
+        self.count(region) as f32 / cells as f32
+    }
+}
+
+/// The number of generations since the board was last seeded. This is shared
+/// with the render world, which bumps it whenever it dispatches an update.
+#[derive(Resource, Clone, Default)]
+pub struct Generation {
+    count: Arc<AtomicU64>,
+    seeded: Arc<AtomicBool>,
+    // The `SeedSettings` epoch the init shader last ran for.
+    seeded_epoch: Arc<AtomicU32>,
+}
+
+impl Generation {
+    pub fn get(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Whether the init shader has run, so edits to the board will stick.
+    pub fn is_seeded(&self) -> bool {
+        self.seeded.load(Ordering::Relaxed)
+    }
+
+    /// Whether the init shader has run since `seed_settings` last asked for a
+    /// reseed, rather than only before it.
+    pub fn is_seeded_for(&self, seed_settings: &SeedSettings) -> bool {
+        self.is_seeded() && self.seeded_epoch.load(Ordering::Relaxed) == seed_settings.epoch
+    }
+
+    /// Carries on counting from `generation`, for picking a run back up.
+    pub fn set(&self, generation: u64) {
+        self.count.store(generation, Ordering::Relaxed);
+    }
+
+    fn restart(&self) {
+        self.count.store(0, Ordering::Relaxed);
+        self.seeded.store(false, Ordering::Relaxed);
+    }
+}
+
+/// What the render world's pipelines did last frame.
+#[derive(Clone, Debug, Default)]
+pub struct PipelineReport {
+    /// Each pipeline's cached state, "queued", "ok" or the error, by label.
+    pub states: BTreeMap<&'static str, String>,
+    /// The dispatches, or for render pipelines draws, each pipeline made.
+    pub dispatches: BTreeMap<&'static str, u32>,
+    /// The cells in the set and clear batches.
+    pub set_cells: usize,
+    pub clear_cells: usize,
+}
+
+/// Pipeline statistics for debugging, shared between the worlds like
+/// `Generation` so the render graph can count into it.
+#[derive(Resource, Clone, Default)]
+pub struct PipelineStats {
+    report: Arc<Mutex<PipelineReport>>,
+    // This frame's dispatches, until the frame is done.
+    dispatching: Arc<Mutex<BTreeMap<&'static str, u32>>>,
+}
+
+impl PipelineStats {
+    pub fn report(&self) -> PipelineReport {
+        self.report.lock().unwrap().clone()
+    }
+
+    pub fn dispatched(&self, label: &'static str, count: u32) {
+        *self.dispatching.lock().unwrap().entry(label).or_default() += count;
+    }
+
+    pub fn set_state(&self, label: &'static str, state: &CachedPipelineState) {
+        let state = match state {
+            CachedPipelineState::Queued => "queued".to_string(),
+            CachedPipelineState::Ok(_) => "ok".to_string(),
+            CachedPipelineState::Err(err) => format!("error: {}", err),
+        };
+        self.report.lock().unwrap().states.insert(label, state);
+    }
+
+    fn finish_frame(&self, set_cells: usize, clear_cells: usize) {
+        let dispatches = std::mem::take(&mut *self.dispatching.lock().unwrap());
+        let mut report = self.report.lock().unwrap();
+        report.dispatches = dispatches;
+        report.set_cells = set_cells;
+        report.clear_cells = clear_cells;
+    }
+}
+
+/// Sent once a frame when the board has advanced, with the latest statistics.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct GenerationAdvanced {
+    pub generation: u64,
+    pub population: u64,
+    pub births: u64,
+    pub deaths: u64,
+}
+
+/// Marks the text showing the number of living cells.
+#[derive(Component)]
+struct LivingCellsText;
+
+
+/// Marks the sprite showing the board. Its color tints the board.
+#[derive(Component)]
+pub struct BoardSprite;
+
+/// The texture that stores the Conway's game state, for binding in other
+/// materials too (as a mask, say, or a displacement map). It's inserted
+/// during `Startup`, and is in both the main and the render world.
+///
+/// The texture is `BOARD_FORMAT`, `ConwayConfig::size` texels across with y down,
+/// sampled nearest. Each texel is a cell, with
+///
+/// - r: its state, 1 alive and 0 dead, or a gray level under blended rules
+/// - g: its energy, always 1 unless energy is on
+/// - b: 1 - age / 255 while lifespans are on, otherwise the same as r
+/// - a: the same as r, for cells the rules have written
+///
+/// It's written by the `BOARD_NODE` render graph node, ahead of the camera
+/// driver, so anything drawn in a frame sees the board after that frame's
+/// generations. To read it from a node of your own, add an edge from
+/// `BOARD_NODE` to it. The board's own display doesn't rely on anything
+/// more than this.
+#[derive(Resource, Clone, Deref, ExtractResource)]
+pub struct ConwayWorld(pub Handle<Image>);
+
+/// While paused, the board's next generation, worked out into a texture of
+/// its own without touching the board. It's laid out like `ConwayWorld`.
+#[derive(Resource, Clone, Deref, ExtractResource)]
+pub struct ConwayGhost(pub Handle<Image>);
+
+/// Whether `ConwayGhost` is kept up to date while paused.
+#[derive(Resource, Clone, Copy, Default, ExtractResource)]
+pub struct GhostPreview(pub bool);
+
+/// The format of the `ConwayWorld` texture.
+pub const BOARD_FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+/// The render graph node that updates the `ConwayWorld` texture.
+pub const BOARD_NODE: &str = "conway_state";
+
+
+/// Cells to set in the compute shader.
+#[derive(Resource, Clone, ExtractResource)]
+pub struct SetCells(pub Vec<Vec2>);
+
+/// Cells to clear in the compute shader.
+#[derive(Resource, Clone, ExtractResource)]
+pub struct ClearCells(pub Vec<Vec2>);
+
+/// A row of cells to set or clear, starting at `start` and running right.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellRun {
+    pub start: UVec2,
+    pub length: u32,
+    pub alive: bool,
+}
+
+impl CellRun {
+    pub fn cells(&self) -> impl Iterator<Item = IVec2> {
+        let start = self.start.as_ivec2();
+        (0..self.length as i32).map(move |x| start + IVec2::new(x, 0))
+    }
+
+    // Packed for the `write_runs` shader as (x | y << 16, length | alive << 31).
+    fn packed(&self) -> [u32; 2] {
+        [self.start.x | self.start.y << 16, self.length | (self.alive as u32) << 31]
+    }
+}
+
+/// Gathers `cells` into runs along their rows, each cell once.
+pub fn cell_runs(cells: &[IVec2], alive: bool) -> Vec<CellRun> {
+    let mut cells = cells.to_vec();
+    cells.sort_unstable_by_key(|cell| (cell.y, cell.x));
+    cells.dedup();
+    let mut runs: Vec<CellRun> = vec![];
+    for cell in cells.into_iter().map(|cell| cell.as_uvec2()) {
+        match runs.last_mut() {
+            Some(run) if run.start.y == cell.y && run.start.x + run.length == cell.x => run.length += 1,
+            _ => runs.push(CellRun { start: cell, length: 1, alive }),
+        }
+    }
+    runs
+}
+
+// Batches of at least this many cells are written as runs by a compute pass,
+// rather than drawn as a point each.
+const BULK_CELLS: usize = 4096;
+// The most runs written in a frame, so a huge paste is spread over a few
+// frames rather than stalling one.
+const MAX_RUNS_PER_FRAME: usize = 65_536;
+
+/// Runs waiting their turn to be written, oldest first.
+#[derive(Resource, Default)]
+pub struct PendingRuns(VecDeque<CellRun>);
+
+impl PendingRuns {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// The runs being written this frame, taken from the front of the queue.
+#[derive(Resource, Clone, Default, ExtractResource)]
+pub struct CellRuns(pub Vec<CellRun>);
+
+/// A rectangle of cells to set or clear, already clipped to the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellRect {
+    pub corner: UVec2,
+    pub size: UVec2,
+    pub alive: bool,
+}
+
+impl CellRect {
+    /// `size` cells from `corner`, clipped to a board of `board` cells, if any are on it.
+    pub fn clipped(corner: IVec2, size: UVec2, alive: bool, board: UVec2) -> Option<Self> {
+        let end = |start: i32, length: u32, limit: u32| (start as i64 + length as i64).min(limit as i64) as i32;
+        let end = IVec2::new(end(corner.x, size.x, board.x), end(corner.y, size.y, board.y));
+        let corner = corner.max(IVec2::ZERO);
+        if end.cmple(corner).any() {
+            return None;
+        }
+        Some(Self { corner: corner.as_uvec2(), size: (end - corner).as_uvec2(), alive })
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = IVec2> {
+        let (corner, size) = (self.corner.as_ivec2(), self.size.as_ivec2());
+        (0..size.y).flat_map(move |y| (0..size.x).map(move |x| corner + IVec2::new(x, y)))
+    }
+
+    // Packed for the `write_rects` shader as (x | y << 16, width | height << 16, alive).
+    fn packed(&self) -> [u32; 4] {
+        [self.corner.x | self.corner.y << 16, self.size.x | self.size.y << 16, self.alive as u32, 0]
+    }
+}
+
+/// The rectangles filled or cleared this frame, before any other edits.
+#[derive(Resource, Clone, Default, ExtractResource)]
+pub struct CellRects(pub Vec<CellRect>);
+
+/// A change to the whole board at once. Cells move, along with their energy
+/// and age, but masks, portals and the flow field stay where they were
+/// painted, and cells moved onto walls are lost.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoardTransform {
+    // A quarter turn clockwise about the centre. On a board that isn't
+    // square, what turns off the ends is lost and the sides come in empty.
+    Rotate,
+    // Left to right.
+    FlipHorizontal,
+    // Top to bottom.
+    FlipVertical,
+    // Move everything `offset` cells, x right and y down. What goes off one
+    // edge comes back on the other with `wrap`, and is lost without it.
+    Shift { offset: IVec2, wrap: bool },
+}
+
+impl BoardTransform {
+    /// Where the cell that lands on `cell` of a board of `size` comes from,
+    /// which may be off the board.
+    pub fn source(self, cell: IVec2, size: UVec2) -> IVec2 {
+        let size = size.as_ivec2();
+        match self {
+            // Doubling the offsets from the centre keeps them whole, since
+            // the config makes both sides even.
+            Self::Rotate => {
+                let offset = cell * 2 + 1 - size;
+                (IVec2::new(offset.y, -offset.x) + size - 1) / 2
+            }
+            Self::FlipHorizontal => IVec2::new(size.x - 1 - cell.x, cell.y),
+            Self::FlipVertical => IVec2::new(cell.x, size.y - 1 - cell.y),
+            Self::Shift { offset, wrap: true } => (cell - offset).rem_euclid(size),
+            Self::Shift { offset, wrap: false } => cell - offset,
+        }
+    }
+
+    // Packed for the `transform` shader as (kind, offset, wrap).
+    fn packed(self) -> [i32; 4] {
+        match self {
+            Self::Rotate => [0; 4],
+            Self::FlipHorizontal => [1, 0, 0, 0],
+            Self::FlipVertical => [2, 0, 0, 0],
+            Self::Shift { offset, wrap } => [3, offset.x, offset.y, wrap as i32],
+        }
+    }
+}
+
+/// The transforms to apply this frame, in order, after the frame's edits.
+#[derive(Resource, Clone, Default, ExtractResource)]
+pub struct BoardTransforms(pub Vec<BoardTransform>);
+
+/// How the board is seeded by the `init` shader. Call `reseed` to run it
+/// again with the current settings.
+#[derive(Resource, Clone, ExtractResource)]
+pub struct SeedSettings {
+    // Fraction of cells that start alive.
+    pub density: f32,
+    pub seed: u32,
+    // Bumped every time the board should be re-initialized.
+    epoch: u32,
+}
+
+impl Default for SeedSettings {
+    fn default() -> Self {
+        Self { density: 0.1, seed: 0, epoch: 0 }
+    }
+}
+
+impl SeedSettings {
+    pub fn reseed(&mut self) {
+        self.epoch += 1;
+    }
+}
+
+/// Commands for driving the board from other plugins, scripts and remote APIs.
+/// Cells are in board coordinates; ones off the board are ignored.
+#[derive(Event, Clone, Debug)]
+pub enum ConwayCommand {
+    SetCells(Vec<IVec2>),
+    ClearCells(Vec<IVec2>),
+    // Re-run the init shader with a fresh soup.
+    Reseed { density: f32, seed: u32 },
+    Pause,
+    Resume,
+    // Generations to advance per frame.
+    SetSpeed(u32),
+    // Switch to a transition table, or back to the `RuleString` with `None`.
+    SetRuleTable(Option<Arc<RuleTable>>),
+    // Run a B/S rule, putting away any transition table.
+    SetRule(LifeRule),
+    // Turn one of the `BoardMasks` flags on or off for some cells.
+    SetMask { cells: Vec<IVec2>, mask: u8, enabled: bool },
+    // Mix another table into the `ActiveRuleTable`, or stop with `None`.
+    SetRuleBlend { table: Option<Arc<RuleTable>>, amount: f32 },
+    SetEnergy(EnergySettings),
+    // Change what's past the board's edges.
+    SetEdges(Edges),
+    // Make cells under a rule (see `ActiveRuleTable::name`) die after living
+    // this many generations, or live forever again with 0.
+    SetLifespan { rule: String, generations: u32 },
+    // Connect two regions. Ignored past `MAX_PORTALS`.
+    AddPortal(Portal),
+    ClearPortals,
+    // Pull the board toward a picture: `cells` are the ones that should be
+    // alive, and `bias` is the `TargetBias`. An empty picture with no bias
+    // stops.
+    SetTarget { cells: Vec<IVec2>, bias: f32 },
+    // Set the `FlowField` under some cells, in cells per generation.
+    SetFlow { cells: Vec<IVec2>, velocity: Vec2 },
+    // Set it over every cell from `corner` spanning `size`, without listing
+    // them.
+    FillFlow { corner: IVec2, size: UVec2, velocity: Vec2 },
+    ClearFlow,
+    // Set every cell from `corner` spanning `size`, walls aside, or clear
+    // them, in one dispatch rather than a point each.
+    FillRect { corner: IVec2, size: UVec2, alive: bool },
+    // Rotate or flip the whole board.
+    Transform(BoardTransform),
+    // Remake the board at a new size, scaled to fit the default window,
+    // either stretching what's on it across or seeding it afresh. Sizes
+    // `ConwayConfig::fitting` turns down are ignored.
+    Resize { size: UVec2, resample: bool },
+    // Remake the board at a new size too, keeping what's on it from
+    // `corner` across, with nothing past the old board's edges.
+    Crop { corner: IVec2, size: UVec2 },
+}
+
+impl ConwayCommand {
+    /// Switches to a rule by the name `ActiveRuleTable::name` gives it: a
+    /// B/S rule string, or else a table file to load.
+    pub fn set_rule_named(name: &str) -> Result<Self, String> {
+        match LifeRule::parse(name) {
+            Ok(rule) => Ok(Self::SetRule(rule)),
+            Err(_) => RuleTable::load(name).map(|table| Self::SetRuleTable(Some(Arc::new(table)))),
+        }
+    }
+}
+
+/// Sent once the board's been remade at a new size, for anything sized from it.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct BoardResized {
+    pub size: UVec2,
+}
+
+/// Cells laid out row by row over a board of size `from`, cut down or
+/// padded out to a board of size `to` from `corner`, which may be off the
+/// board. Cells past its edges come in as their default.
+pub fn crop<T: Copy + Default>(cells: &[T], from: UVec2, corner: IVec2, to: UVec2) -> Vec<T> {
+    let from = from.as_ivec2();
+    (0..to.y as i32)
+        .flat_map(|y| (0..to.x as i32).map(move |x| corner + IVec2::new(x, y)))
+        .map(|cell| match cell.cmpge(IVec2::ZERO).all() && cell.cmplt(from).all() {
+            true => cells[(cell.y * from.x + cell.x) as usize],
+            false => T::default(),
+        })
+        .collect()
+}
+
+/// Cells laid out row by row over a board of size `from`, stretched or
+/// squeezed onto one of size `to`, each taking after the nearest.
+pub fn resample<T: Copy>(cells: &[T], from: UVec2, to: UVec2) -> Vec<T> {
+    (0..to.y)
+        .flat_map(|y| (0..to.x).map(move |x| UVec2::new(x, y) * from / to))
+        .map(|cell| cells[(cell.y * from.x + cell.x) as usize])
+        .collect()
+}
+
+/// The transition table the board is running, if it isn't a plain B/S rule
+/// from the `RuleString`. Multi-state tables store each cell's state as a
+/// gray level, so set cells get the highest state and only those count as
+/// living.
+#[derive(Resource, Clone, Default, ExtractResource)]
+pub struct ActiveRuleTable(pub Option<Arc<RuleTable>>);
+
+impl ActiveRuleTable {
+    /// The rule's name, the `RuleString`'s without a table.
+    pub fn name<'a>(&'a self, rule_string: &RuleString) -> Cow<'a, str> {
+        match &self.0 {
+            Some(table) => Cow::Borrowed(&table.name),
+            None => Cow::Owned(rule_string.0.to_string()),
+        }
+    }
+}
+
+/// The B/S rule the update shader runs without an `ActiveRuleTable`, like
+/// B3/S23 (Conway's Life), B36/S23 (HighLife), B3678/S34678 (Day & Night) or
+/// B2/S (Seeds). Its birth and survival counts go up as bitmasks. With decay
+/// states, like B2/S/C3 (Brian's Brain), dying cells are the gray levels
+/// between live and dead, a step dimmer each generation.
+#[derive(Resource, Clone, Copy, Default, ExtractResource)]
+pub struct RuleString(pub LifeRule);
+
+/// The longest cells may live under each rule, keyed by rule name. Ages are
+/// stored in 8 bits, so lifespans are at most `MAX_LIFESPAN`.
+#[derive(Resource, Clone, Default, ExtractResource)]
+pub struct Lifespans(pub HashMap<String, u32>);
+
+pub const MAX_LIFESPAN: u32 = 255;
+
+/// Another table blended into the `ActiveRuleTable`: each update, every cell
+/// follows it instead with probability `amount`. Only used when the active
+/// table is set and has the same states and neighbourhood.
+#[derive(Resource, Clone, Default, ExtractResource)]
+pub struct RuleBlend {
+    pub table: Option<Arc<RuleTable>>,
+    pub amount: f32,
+}
+
+/// The optional energy layer. While it's enabled, live cells spend `cost`
+/// energy from their spot each generation and die when there isn't enough
+/// left, while empty spots regain `regen`, up to a full 1. Setting a cell
+/// fills its spot and clearing one empties it.
+#[derive(Resource, Clone, Copy, Debug, ExtractResource)]
+pub struct EnergySettings {
+    pub enabled: bool,
+    pub cost: f32,
+    pub regen: f32,
+}
+
+impl Default for EnergySettings {
+    fn default() -> Self {
+        Self { enabled: false, cost: 0.05, regen: 0.01 }
+    }
+}
+
+/// The chance each update of a cell taking the state it has in the picture
+/// marked by `BoardMasks::TARGET` instead of the one the rule gives it. 0
+/// leaves the rule alone.
+#[derive(Resource, Clone, Copy, Default, ExtractResource)]
+pub struct TargetBias(pub f32);
+
+/// A current for every cell, which shifts the board's contents before each
+/// update. Velocities are in cells per generation, x right and y down, up to
+/// 1 each way, and stored as signed bytes; fractions move every so many
+/// generations. Nothing moves until some flow has been set.
+#[derive(Resource, Clone, ExtractResource)]
+pub struct FlowField {
+    pub cells: Vec<[i8; 2]>,
+    pub enabled: bool,
+    size: UVec2,
+}
+
+impl FromWorld for FlowField {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(world.resource::<ConwayConfig>().size)
+    }
+}
+
+impl FlowField {
+    /// No flow anywhere on a board of `size`.
+    pub fn new(size: UVec2) -> Self {
+        Self { cells: vec![[0; 2]; size.x as usize * size.y as usize], enabled: false, size }
+    }
+
+    pub fn velocity(&self, cell: IVec2) -> Vec2 {
+        let Some(&[x, y]) = board_index(self.size, cell).and_then(|index| self.cells.get(index)) else {
+            return Vec2::ZERO;
+        };
+        Vec2::new(x as f32, y as f32) / i8::MAX as f32
+    }
+
+    // A velocity as it's stored, clamped to a cell a generation each way.
+    fn stored(velocity: Vec2) -> [i8; 2] {
+        let velocity = velocity.clamp(-Vec2::ONE, Vec2::ONE) * i8::MAX as f32;
+        [velocity.x.round() as i8, velocity.y.round() as i8]
+    }
+}
+
+/// What the update shaders find past the board's edges: nothing but dead
+/// cells, the far side of the board as on a torus, so patterns leaving one
+/// side come back on the other, or the cells along the edge reflected back.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, ExtractResource)]
+pub enum Edges {
+    #[default]
+    Dead,
+    Wrap,
+    Mirror,
+}
+
+impl Edges {
+    pub const ALL: [Self; 3] = [Self::Dead, Self::Wrap, Self::Mirror];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Dead => "dead",
+            Self::Wrap => "wrap",
+            Self::Mirror => "mirror",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|edges| edges.name() == name)
+    }
+
+    /// The cell read in place of `cell`, which may be just past an edge of a
+    /// board of `size`, the same as `load_previous` in the shader. Past dead
+    /// edges it's still off the board.
+    pub fn fold(self, cell: IVec2, size: IVec2) -> IVec2 {
+        match self {
+            Self::Dead => cell,
+            Self::Wrap => cell.rem_euclid(size),
+            Self::Mirror => {
+                let cell = IVec2::select(cell.cmplt(IVec2::ZERO), -cell - 1, cell);
+                IVec2::select(cell.cmpge(size), size * 2 - cell - 1, cell)
+            }
+        }
+    }
+
+    /// Packed for the shaders' `edges` settings, see `edges.wgsl`.
+    pub fn code(self) -> u32 {
+        match self {
+            Self::Dead => 0,
+            Self::Wrap => 1,
+            Self::Mirror => 2,
+        }
+    }
+}
+
+/// What runs on the board: the rules above, with cells alive or dead or
+/// decaying between, or Lenia, where every cell's state is anywhere from 0
+/// to 1 and grows or shrinks with a smooth weighting of those around it. See
+/// `LeniaSettings`. Lenia keeps its states at full precision in a float
+/// texture of its own, writing them to the board's gray levels after every
+/// step, and picks up any cells edited on the board in between. Or neither,
+/// leaving the board to the ants in `ants.rs` walking it. Or a one
+/// dimensional `ElementaryRule`, each generation a row under the one before.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, ExtractResource)]
+pub enum SimulationKind {
+    #[default]
+    Life,
+    Lenia,
+    Ants,
+    Elementary,
+}
+
+impl SimulationKind {
+    pub const ALL: [Self; 4] = [Self::Life, Self::Lenia, Self::Ants, Self::Elementary];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Life => "life",
+            Self::Lenia => "lenia",
+            Self::Ants => "ants",
+            Self::Elementary => "elementary",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// Lenia's kernel, a ring `radius` cells out weighting each cell around by
+/// how near it is to halfway out, and its growth function, which a cell's
+/// weighted neighbourhood `mu` adds the most to and one more than `sigma` or
+/// so either side takes away from, `dt` of it a step. The defaults grow
+/// Orbium, the glider-like creature Lenia's best known for.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, ExtractResource)]
+pub struct LeniaSettings {
+    pub radius: u32,
+    pub mu: f32,
+    pub sigma: f32,
+    pub dt: f32,
+}
+
+impl Default for LeniaSettings {
+    fn default() -> Self {
+        Self { radius: 13, mu: 0.15, sigma: 0.015, dt: 0.1 }
+    }
+}
+
+/// The widest kernel there is, since the shader visits every cell in it.
+pub const MAX_LENIA_RADIUS: u32 = 24;
+
+impl LeniaSettings {
+    /// The kernel's weights over the square `radius` cells out each way, row
+    /// by row, summing to 1.
+    pub fn kernel(&self) -> Vec<f32> {
+        let radius = self.radius.clamp(1, MAX_LENIA_RADIUS) as i32;
+        let weights = (-radius..=radius)
+            .flat_map(|y| (-radius..=radius).map(move |x| IVec2::new(x, y).as_vec2().length() / radius as f32))
+            // An exponential bump, 1 halfway out and nothing at the middle or past the rim.
+            .map(|r| if r > 0.0 && r < 1.0 { (4.0 - 1.0 / (r * (1.0 - r))).exp() } else { 0.0 })
+            .collect::<Vec<_>>();
+        let total = weights.iter().sum::<f32>();
+        weights.into_iter().map(|weight| weight / total).collect()
+    }
+
+    /// How much a cell grows, from -1 to 1, with the weighting `potential`.
+    pub fn growth(&self, potential: f32) -> f32 {
+        2.0 * (-(potential - self.mu).powi(2) / (2.0 * self.sigma * self.sigma)).exp() - 1.0
+    }
+}
+
+/// A Wolfram-style elementary automaton's rule number: each cell of a row is
+/// alive if the bit of it the three cells above spell out is set, reading
+/// them left to right as a binary number. Under `SimulationKind::Elementary`
+/// each generation writes the row below the last, wrapping round to the top
+/// once it's filled the board. Switching to it, or to another rule, starts
+/// the board over at generation 0 from a single living cell in the middle of
+/// the top row.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq, ExtractResource)]
+pub struct ElementaryRule(pub u8);
+
+impl Default for ElementaryRule {
+    fn default() -> Self {
+        Self(30)
+    }
+}
+
+impl ElementaryRule {
+    pub fn next(self, left: bool, middle: bool, right: bool) -> bool {
+        let pattern = (left as u8) << 2 | (middle as u8) << 1 | right as u8;
+        self.0 >> pattern & 1 == 1
+    }
+}
+
+/// Flags for each cell that the update shaders respect, one byte per cell
+/// like `BoardSnapshot`.
+#[derive(Resource, Clone, ExtractResource)]
+pub struct BoardMasks {
+    pub cells: Vec<u8>,
+    size: UVec2,
+}
+
+impl FromWorld for BoardMasks {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(world.resource::<ConwayConfig>().size)
+    }
+}
+
+impl BoardMasks {
+    /// Walls are always dead, and cells can't be set on them.
+    pub const WALL: u8 = 1;
+    /// Frozen cells keep their state through updates, though they can still be edited.
+    pub const FROZEN: u8 = 2;
+    /// Alive in the picture the board is pulled toward. See `TargetBias`.
+    pub const TARGET: u8 = 4;
+
+    /// No flags anywhere on a board of `size`.
+    pub fn new(size: UVec2) -> Self {
+        Self { cells: vec![0; size.x as usize * size.y as usize], size }
+    }
+
+    pub fn has(&self, cell: IVec2, mask: u8) -> bool {
+        board_index(self.size, cell).and_then(|index| self.cells.get(index)).is_some_and(|flags| flags & mask != 0)
+    }
+}
+
+/// Two same-sized rectangles glued together: cells leaving one through an
+/// edge come back in through the opposite edge of the other, so what looks
+/// past the right edge of `a` sees the left column of `b`, and so on.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Portal {
+    // The top-left corners of the two rectangles.
+    pub a: IVec2,
+    pub b: IVec2,
+    pub size: IVec2,
+}
+
+impl Portal {
+    /// Whether both rectangles are on a board of `size` and don't overlap.
+    pub fn is_valid(&self, size: UVec2) -> bool {
+        let on_board = |min: IVec2| {
+            min.cmpge(IVec2::ZERO).all() && (min + self.size).cmple(size.as_ivec2()).all()
+        };
+        let overlaps = (self.a - self.b).abs().cmplt(self.size).all();
+        self.size.cmpgt(IVec2::ZERO).all() && on_board(self.a) && on_board(self.b) && !overlaps
+    }
+}
+
+pub const MAX_PORTALS: usize = 8;
 
-/// The number of living cells -- this is computed by the compute shader
-/// and shared to the MainWorld.
-#[derive(Resource, Default)]
-struct LivingCells(u64);
+/// The portals on the board.
+#[derive(Resource, Clone, Default, ExtractResource)]
+pub struct Portals(pub Vec<Portal>);
 
+/// Whether and how fast the board advances. Edits still apply while paused.
+#[derive(Resource, Clone, ExtractResource)]
+pub struct SimulationState {
+    pub paused: bool,
+    // Generations per frame.
+    pub speed: u32,
+}
 
-/// The texture that stores the Conway's game state.
-#[derive(Resource, Clone, Deref, ExtractResource)]
-struct ConwayWorld(Handle<Image>);
+impl Default for SimulationState {
+    fn default() -> Self {
+        Self { paused: false, speed: 1 }
+    }
+}
 
+impl SimulationState {
+    /// Generations the board advances by in a frame it steps, which is just
+    /// the one if stepping while paused.
+    pub fn generations_per_frame(&self) -> u32 {
+        if self.paused { 1 } else { self.speed }
+    }
+}
 
-/// Cells to set in the compute shader.
-#[derive(Resource, Clone, ExtractResource)]
-struct SetCells(Vec<Vec2>);
+/// Set to advance the paused board a single generation this frame. It's
+/// cleared at the start of the next, once it's been extracted.
+#[derive(Resource, Clone, Default, ExtractResource)]
+pub struct StepRequest(pub bool);
 
+/// Set while the board's paused and nothing's been changing it, so the render
+/// world can leave off working out the ghost and reading the board back.
+#[derive(Resource, Clone, Copy, Default, ExtractResource)]
+pub struct Idle(pub bool);
+
+/// Set to wrap the simulation's passes in debug groups, with a marker for
+/// each generation, for telling them apart in a frame debugger.
+#[derive(Resource, Clone, Copy, Default, ExtractResource)]
+pub struct DebugGroups(pub bool);
+
+// Speeds above this would make frames take too long on big boards.
+pub const MAX_SPEED: u32 = 64;
 
 impl Plugin for ConwayPlugin {
     fn build(&self, app: &mut App) {
         app
+        // Before anything's sized from it.
+        .insert_resource(self.config)
         .init_resource::<LivingCells>()
+        .insert_resource(BoardSnapshot { cells: vec![], size: self.config.size })
+        .init_resource::<BoardStats>()
+        .init_resource::<DensityGrid>()
+        .init_resource::<LiveBounds>()
+        .init_resource::<SeedSettings>()
+        .init_resource::<SimulationState>()
+        .init_resource::<ActiveRuleTable>()
+        .init_resource::<RuleString>()
+        .init_resource::<BoardMasks>()
+        .init_resource::<Portals>()
+        .init_resource::<RuleBlend>()
+        .init_resource::<EnergySettings>()
+        .init_resource::<Lifespans>()
+        .init_resource::<Edges>()
+        .init_resource::<SimulationKind>()
+        .init_resource::<LeniaSettings>()
+        .init_resource::<ElementaryRule>()
+        .init_resource::<TargetBias>()
+        .init_resource::<FlowField>()
+        .init_resource::<GhostPreview>()
+        .init_resource::<RegionRequests>()
+        .init_resource::<SimRng>()
+        .init_resource::<Tool>()
+        .init_resource::<Brush>()
         .insert_resource(SetCells(vec![]))
+        .insert_resource(ClearCells(vec![]))
+        .init_resource::<PendingRuns>()
+        .init_resource::<CellRuns>()
+        .init_resource::<CellRects>()
+        .init_resource::<StepRequest>()
+        .init_resource::<Idle>()
+        .init_resource::<DebugGroups>()
+        .init_resource::<BoardTransforms>()
+        .add_plugins(ExtractResourcePlugin::<ConwayConfig>::default())
         .add_plugins(ExtractResourcePlugin::<ConwayWorld>::default())
         .add_plugins(ExtractResourcePlugin::<SetCells>::default())
-        .add_systems(First, clear_set_cells)
+        .add_plugins(ExtractResourcePlugin::<ClearCells>::default())
+        .add_plugins(ExtractResourcePlugin::<CellRuns>::default())
+        .add_plugins(ExtractResourcePlugin::<CellRects>::default())
+        .add_plugins(ExtractResourcePlugin::<SeedSettings>::default())
+        .add_plugins(ExtractResourcePlugin::<SimulationState>::default())
+        .add_plugins(ExtractResourcePlugin::<StepRequest>::default())
+        .add_plugins(ExtractResourcePlugin::<Idle>::default())
+        .add_plugins(ExtractResourcePlugin::<DebugGroups>::default())
+        .add_plugins(ExtractResourcePlugin::<BoardTransforms>::default())
+        .add_plugins(ExtractResourcePlugin::<ActiveRuleTable>::default())
+        .add_plugins(ExtractResourcePlugin::<RuleString>::default())
+        .add_plugins(ExtractResourcePlugin::<BoardMasks>::default())
+        .add_plugins(ExtractResourcePlugin::<Portals>::default())
+        .add_plugins(ExtractResourcePlugin::<RuleBlend>::default())
+        .add_plugins(ExtractResourcePlugin::<EnergySettings>::default())
+        .add_plugins(ExtractResourcePlugin::<Lifespans>::default())
+        .add_plugins(ExtractResourcePlugin::<Edges>::default())
+        .add_plugins(ExtractResourcePlugin::<SimulationKind>::default())
+        .add_plugins(ExtractResourcePlugin::<LeniaSettings>::default())
+        .add_plugins(ExtractResourcePlugin::<ElementaryRule>::default())
+        .add_plugins(ExtractResourcePlugin::<TargetBias>::default())
+        .add_plugins(ExtractResourcePlugin::<FlowField>::default())
+        .add_plugins(ExtractResourcePlugin::<ConwayGhost>::default())
+        .add_plugins(ExtractResourcePlugin::<GhostPreview>::default())
+        .add_event::<ConwayCommand>()
+        .add_event::<GenerationAdvanced>()
+        .add_event::<BoardResized>()
+        .add_systems(First, (clear_set_cells, clear_step_request, send_generation_advanced, answer_region_requests))
         .add_systems(Startup, setup)
         .add_systems(FixedUpdate, render_living_cells)
-        .add_systems(Update, handle_mouse_click)
+        .add_systems(Update, (
+            toggle_pause,
+            step_once,
+            handle_mouse_click,
+            (start_elementary, apply_commands, release_cell_runs).chain(),
+            queue_transforms,
+            // So a table sent alongside a rule string still goes on.
+            set_rule_string.before(apply_commands),
+        ))
+        .add_systems(Last, resize_board)
         ;
 
         // Add the compute shader to the render app.
         // The compute shader happens in the render pass, so we need to add it to the render graph.  
+        let generation = Generation::default();
+        app.insert_resource(generation.clone());
+        let pipeline_stats = PipelineStats::default();
+        app.insert_resource(pipeline_stats.clone());
         let render_app = app.sub_app_mut(RenderApp);
+        render_app.insert_resource(self.config);
+        render_app.insert_resource(generation);
+        render_app.insert_resource(pipeline_stats);
         render_app.insert_resource(LivingCells(10));
+        render_app.init_resource::<BoardSnapshot>();
+        render_app.init_resource::<BoardStats>();
+        render_app.init_resource::<DensityGrid>();
+        render_app.init_resource::<LiveBounds>();
         render_app.insert_resource(SetCells(vec![]));
+        render_app.insert_resource(ClearCells(vec![]));
+        render_app.init_resource::<CellRuns>();
+        render_app.init_resource::<CellRects>();
+        render_app.init_resource::<SeedSettings>();
+        render_app.init_resource::<SimulationState>();
+        render_app.init_resource::<StepRequest>();
+        render_app.init_resource::<Idle>();
+        render_app.init_resource::<DebugGroups>();
+        render_app.init_resource::<BoardTransforms>();
+        render_app.init_resource::<ActiveRuleTable>();
+        render_app.init_resource::<RuleString>();
+        render_app.init_resource::<BoardMasks>();
+        render_app.init_resource::<Portals>();
+        render_app.init_resource::<RuleBlend>();
+        render_app.init_resource::<EnergySettings>();
+        render_app.init_resource::<Lifespans>();
+        render_app.init_resource::<Edges>();
+        render_app.init_resource::<SimulationKind>();
+        render_app.init_resource::<LeniaSettings>();
+        render_app.init_resource::<ElementaryRule>();
+        render_app.init_resource::<ElementaryUniformBuffer>();
+        render_app.init_resource::<TargetBias>();
+        render_app.init_resource::<FlowField>();
+        render_app.init_resource::<GhostPreview>();
         render_app.add_systems(Render, (
             view_mouse_click.in_set(RenderSet::PrepareBindGroups),
             prepare_bind_group.in_set(RenderSet::PrepareBindGroups),
+            prepare_lenia_bind_group.in_set(RenderSet::PrepareBindGroups),
+            prepare_elementary_bind_group.in_set(RenderSet::PrepareBindGroups),
             prepare_resources.in_set(RenderSet::PrepareResources),
+            (fit_board_textures, upload_masks, upload_flow).chain().in_set(RenderSet::PrepareResources),
             update_living_cells.in_set(RenderSet::Cleanup),
+            report_pipelines.in_set(RenderSet::Cleanup),
         ));
         // TODO(arun): this should move to after the rendering stage.
         render_app.add_systems(ExtractSchedule, copy_living_cells);
         let mut render_graph = render_app.world.resource_mut::<RenderGraph>();
         render_graph.add_node(
-            "conway_state",
+            BOARD_NODE,
             ConwayRenderNode::default(),
         );
         render_graph.add_node_edge(
-            "conway_state",
+            BOARD_NODE,
             bevy::render::main_graph::node::CAMERA_DRIVER
         );
     }
 
     fn finish(&self, app: &mut App) {
-        app.sub_app_mut(RenderApp).init_resource::<ConwayPipeline>();
+        // The GPU's only there to check the board against once it's been set up.
+        let limits = app.world.resource::<RenderDevice>().limits();
+        if let Err(err) = self.config.check_limits(&limits) {
+            panic!("can't make a board that size: {}", err);
+        }
+        app.sub_app_mut(RenderApp)
+            .init_resource::<ConwayPipeline>()
+            .init_resource::<PreviousBoard>()
+            .init_resource::<MaskTexture>()
+            .init_resource::<FlowTexture>()
+            .init_resource::<LeniaTextures>();
     }
 }
 
-fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
-    // Create a new image that will be used as a texture.
+// A new, empty board texture of `size` cells.
+fn board_image(size: UVec2) -> Image {
     let mut image = Image::new_fill(
                 Extent3d {
-                    width: SIZE.0,
-                    height: SIZE.1,
+                    width: size.x,
+                    height: size.y,
                     depth_or_array_layers: 1,
                 },
                 TextureDimension::D2,
                 &[0, 0, 0, 255],
-                TextureFormat::Rgba8Unorm,
+                BOARD_FORMAT,
     );
     image.texture_descriptor.usage =
         TextureUsages::COPY_SRC | TextureUsages::RENDER_ATTACHMENT |
         TextureUsages::COPY_DST | TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING;
     image.sampler = ImageSampler::nearest();
+    image
+}
+
+fn setup(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut seed_settings: ResMut<SeedSettings>,
+    mut rng: ResMut<SimRng>,
+    config: Res<ConwayConfig>,
+) {
+    seed_settings.seed = rng.rng.gen();
+
+    let image = board_image(config.size);
+    commands.insert_resource(ConwayGhost(images.add(image.clone())));
     let image_handle = images.add(image);
 
-    commands.spawn(
+    commands.spawn((
+        BoardSprite,
         SpriteBundle {
             texture: image_handle.clone(),
             transform: Transform {
-                scale: Vec3::new(10.0 / (SCALE_FACTOR as f32), 10.0 / (SCALE_FACTOR as f32), 1.0),
+                scale: Vec3::new(config.display_scale, config.display_scale, 1.0),
                 ..default()
             },
             ..default()
-        }
-    );
+        },
+    ));
     commands.insert_resource(ConwayWorld(image_handle));
 
-    commands.spawn(
+    commands.spawn((
+        LivingCellsText,
         TextBundle::from_section(
             "Living cells: 0",
             TextStyle {
@@ -119,37 +1243,388 @@ fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
                 color: Color::WHITE,
                 ..Default::default()
             }
-        )
-    );
+        ),
+    ));
 }
 
 fn render_living_cells(
-    mut query: Query<&mut Text>,
+    mut query: Query<&mut Text, With<LivingCellsText>>,
     living_cells: Res<LivingCells>,
 ) {
     let mut text = query.single_mut();
     text.sections[0].value = format!("Living cells: {}", living_cells.0);
 }
 
-fn clear_set_cells(mut set_cells: ResMut<SetCells>) {
+fn clear_set_cells(
+    mut set_cells: ResMut<SetCells>,
+    mut clear_cells: ResMut<ClearCells>,
+    mut cell_rects: ResMut<CellRects>,
+    mut board_transforms: ResMut<BoardTransforms>,
+) {
     set_cells.0.clear();
+    clear_cells.0.clear();
+    cell_rects.0.clear();
+    board_transforms.0.clear();
+}
+
+fn clear_step_request(mut step_request: ResMut<StepRequest>) {
+    step_request.0 = false;
+}
+
+fn send_generation_advanced(
+    generation: Res<Generation>,
+    living_cells: Res<LivingCells>,
+    stats: Res<BoardStats>,
+    mut events: EventWriter<GenerationAdvanced>,
+    mut last_generation: Local<u64>,
+) {
+    let current = generation.get();
+    if current != *last_generation {
+        *last_generation = current;
+        events.send(GenerationAdvanced {
+            generation: current,
+            population: living_cells.0,
+            births: stats.births,
+            deaths: stats.deaths,
+        });
+    }
+}
+
+// The cell edits `apply_commands` queues up, and the board they're placed on,
+// gathered for room.
+#[derive(SystemParam)]
+struct CellEdits<'w> {
+    config: Res<'w, ConwayConfig>,
+    set_cells: ResMut<'w, SetCells>,
+    clear_cells: ResMut<'w, ClearCells>,
+    pending_runs: ResMut<'w, PendingRuns>,
+    cell_rects: ResMut<'w, CellRects>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn apply_commands(
+    mut commands: EventReader<ConwayCommand>,
+    mut edits: CellEdits,
+    mut seed_settings: ResMut<SeedSettings>,
+    mut simulation_state: ResMut<SimulationState>,
+    mut rule_table: ResMut<ActiveRuleTable>,
+    mut masks: ResMut<BoardMasks>,
+    mut portals: ResMut<Portals>,
+    mut rule_blend: ResMut<RuleBlend>,
+    mut energy: ResMut<EnergySettings>,
+    mut lifespans: ResMut<Lifespans>,
+    mut edges: ResMut<Edges>,
+    mut target_bias: ResMut<TargetBias>,
+    mut flow: ResMut<FlowField>,
+) {
+    let CellEdits { config, set_cells, clear_cells, pending_runs, cell_rects } = &mut edits;
+    let on_board = |cell: &&IVec2| config.cell_index(**cell).is_some();
+    for command in commands.read() {
+        match command {
+            ConwayCommand::SetCells(cells) => {
+                let cells = cells.iter().filter(on_board).filter(|cell| !masks.has(**cell, BoardMasks::WALL));
+                let cells = cells.copied().collect::<Vec<_>>();
+                // Anything after a bulk write waits its turn behind it.
+                if cells.len() >= BULK_CELLS || !pending_runs.0.is_empty() {
+                    pending_runs.0.extend(cell_runs(&cells, true));
+                } else {
+                    set_cells.0.extend(cells.iter().map(|cell| config.cell_to_clip(*cell)));
+                }
+            }
+            ConwayCommand::ClearCells(cells) => {
+                let cells = cells.iter().filter(on_board).copied().collect::<Vec<_>>();
+                if cells.len() >= BULK_CELLS || !pending_runs.0.is_empty() {
+                    pending_runs.0.extend(cell_runs(&cells, false));
+                } else {
+                    clear_cells.0.extend(cells.iter().map(|cell| config.cell_to_clip(*cell)));
+                }
+            }
+            ConwayCommand::Reseed { density, seed } => {
+                seed_settings.density = *density;
+                seed_settings.seed = *seed;
+                seed_settings.reseed();
+            }
+            ConwayCommand::Pause => simulation_state.paused = true,
+            ConwayCommand::Resume => simulation_state.paused = false,
+            ConwayCommand::SetSpeed(speed) => simulation_state.speed = (*speed).clamp(1, MAX_SPEED),
+            ConwayCommand::SetRuleTable(table) => rule_table.0 = table.clone(),
+            ConwayCommand::SetMask { cells, mask, enabled } => {
+                for index in cells.iter().filter_map(|cell| config.cell_index(*cell)) {
+                    let flags = &mut masks.cells[index];
+                    *flags = if *enabled { *flags | mask } else { *flags & !mask };
+                }
+                // Whatever was alive under a new wall is gone.
+                if *enabled && mask & BoardMasks::WALL != 0 {
+                    clear_cells.0.extend(cells.iter().filter(on_board).map(|cell| config.cell_to_clip(*cell)));
+                }
+            }
+            ConwayCommand::SetRuleBlend { table, amount } => {
+                rule_blend.table = table.clone();
+                rule_blend.amount = amount.clamp(0.0, 1.0);
+            }
+            ConwayCommand::SetEnergy(settings) => {
+                *energy = EnergySettings { cost: settings.cost.max(0.0), regen: settings.regen.max(0.0), ..*settings };
+            }
+            ConwayCommand::SetEdges(mode) => *edges = *mode,
+            ConwayCommand::SetLifespan { rule, generations: 0 } => {
+                lifespans.0.remove(rule);
+            }
+            ConwayCommand::SetLifespan { rule, generations } => {
+                lifespans.0.insert(rule.clone(), (*generations).min(MAX_LIFESPAN));
+            }
+            ConwayCommand::AddPortal(portal) => {
+                if portal.is_valid(config.size) && portals.0.len() < MAX_PORTALS {
+                    portals.0.push(*portal);
+                }
+            }
+            ConwayCommand::ClearPortals => portals.0.clear(),
+            ConwayCommand::SetTarget { cells, bias } => {
+                for flags in &mut masks.cells {
+                    *flags &= !BoardMasks::TARGET;
+                }
+                for index in cells.iter().filter_map(|cell| config.cell_index(*cell)) {
+                    masks.cells[index] |= BoardMasks::TARGET;
+                }
+                target_bias.0 = bias.clamp(0.0, 1.0);
+            }
+            ConwayCommand::SetFlow { cells, velocity } => {
+                let stored = FlowField::stored(*velocity);
+                for index in cells.iter().filter_map(|cell| config.cell_index(*cell)) {
+                    flow.cells[index] = stored;
+                }
+                flow.enabled |= stored != [0; 2];
+            }
+            ConwayCommand::FillFlow { corner, size, velocity } => {
+                let Some(rect) = CellRect::clipped(*corner, *size, true, config.size) else {
+                    continue;
+                };
+                let stored = FlowField::stored(*velocity);
+                let width = config.size.x as usize;
+                for y in rect.corner.y..rect.corner.y + rect.size.y {
+                    let start = y as usize * width + rect.corner.x as usize;
+                    flow.cells[start..start + rect.size.x as usize].fill(stored);
+                }
+                flow.enabled |= stored != [0; 2];
+            }
+            ConwayCommand::ClearFlow => *flow = FlowField::new(config.size),
+            ConwayCommand::FillRect { corner, size, alive } => {
+                let Some(rect) = CellRect::clipped(*corner, *size, *alive, config.size) else {
+                    continue;
+                };
+                // Rects are written first, so one after other edits goes as runs to keep its place.
+                if set_cells.0.is_empty() && clear_cells.0.is_empty() && pending_runs.0.is_empty() {
+                    cell_rects.0.push(rect);
+                } else {
+                    let cells = rect.cells().filter(|cell| !(*alive && masks.has(*cell, BoardMasks::WALL)));
+                    pending_runs.0.extend(cell_runs(&cells.collect::<Vec<_>>(), *alive));
+                }
+            }
+            // Queued by `queue_transforms`, done by `resize_board`, and set
+            // by `set_rule_string`.
+            ConwayCommand::Transform(_)
+            | ConwayCommand::Resize { .. }
+            | ConwayCommand::Crop { .. }
+            | ConwayCommand::SetRule(_) => {}
+        }
+    }
 }
 
+// Kept apart from `apply_commands`, which has no room for another resource.
+fn queue_transforms(mut commands: EventReader<ConwayCommand>, mut board_transforms: ResMut<BoardTransforms>) {
+    for command in commands.read() {
+        if let ConwayCommand::Transform(transform) = command {
+            board_transforms.0.push(*transform);
+        }
+    }
+}
+
+// Also kept apart for room.
+fn set_rule_string(
+    mut commands: EventReader<ConwayCommand>,
+    mut rule_string: ResMut<RuleString>,
+    mut rule_table: ResMut<ActiveRuleTable>,
+) {
+    for command in commands.read() {
+        if let ConwayCommand::SetRule(rule) = command {
+            rule_string.0 = *rule;
+            rule_table.0 = None;
+        }
+    }
+}
+
+// How a resize carries the board across, if it does.
+#[derive(Clone, Copy)]
+enum Carry {
+    Resample,
+    Crop(IVec2),
+}
+
+impl Carry {
+    fn carry<T: Copy + Default>(self, cells: &[T], from: UVec2, to: UVec2) -> Vec<T> {
+        match self {
+            Self::Resample => resample(cells, from, to),
+            Self::Crop(corner) => crop(cells, from, corner, to),
+        }
+    }
+}
+
+// Resizing goes last in the frame, so everything before saw one size, and
+// the render world picks the new one up straight after.
+#[allow(clippy::too_many_arguments)]
+fn resize_board(
+    mut commands: EventReader<ConwayCommand>,
+    mut config: ResMut<ConwayConfig>,
+    render_device: Res<RenderDevice>,
+    mut images: ResMut<Assets<Image>>,
+    board: Res<ConwayWorld>,
+    ghost: Res<ConwayGhost>,
+    mut snapshot: ResMut<BoardSnapshot>,
+    mut masks: ResMut<BoardMasks>,
+    mut flow: ResMut<FlowField>,
+    mut pending_runs: ResMut<PendingRuns>,
+    mut seed_settings: ResMut<SeedSettings>,
+    mut sprites: Query<&mut Transform, With<BoardSprite>>,
+    mut resized: EventWriter<BoardResized>,
+) {
+    for command in commands.read() {
+        let (size, carry) = match command {
+            ConwayCommand::Resize { size, resample } => (size, resample.then_some(Carry::Resample)),
+            ConwayCommand::Crop { corner, size } => (size, Some(Carry::Crop(*corner))),
+            _ => continue,
+        };
+        // The console's already said what's wrong with a size that won't do.
+        let Ok(resized_config) = ConwayConfig::fitting(*size) else {
+            continue;
+        };
+        if resized_config.check_limits(&render_device.limits()).is_err() {
+            continue;
+        }
+        let from = config.size;
+        *config = resized_config;
+        // Anything still waiting was placed on the old board.
+        pending_runs.0.clear();
+        let mut image = board_image(*size);
+        snapshot.size = *size;
+        if let Some(carry) = carry.filter(|_| snapshot.cells.len() == (from.x * from.y) as usize) {
+            snapshot.cells = carry.carry(&snapshot.cells, from, *size);
+            // Full energy, and as young as they come.
+            for (pixel, level) in image.data.chunks_exact_mut(4).zip(&snapshot.cells) {
+                pixel.copy_from_slice(&if *level == 0 { [0, 0, 0, 255] } else { [*level, 255, *level, *level] });
+            }
+            masks.cells = carry.carry(&masks.cells, from, *size);
+            masks.size = *size;
+            flow.cells = carry.carry(&flow.cells, from, *size);
+            flow.size = *size;
+        } else {
+            snapshot.cells = vec![0; config.cell_count()];
+            *masks = BoardMasks::new(*size);
+            *flow = FlowField::new(*size);
+            seed_settings.reseed();
+        }
+        images.insert(ghost.0.id(), board_image(*size));
+        images.insert(board.0.id(), image);
+        for mut transform in &mut sprites {
+            transform.scale = Vec3::new(config.display_scale, config.display_scale, 1.0);
+        }
+        resized.send(BoardResized { size: *size });
+    }
+}
+
+// Hands this frame's share of the pending runs to the render world. They're
+// all written at once, so a run touching one already taken, like setting
+// cells that were just cleared, waits for the next frame to land after it.
+fn release_cell_runs(mut pending_runs: ResMut<PendingRuns>, mut cell_runs: ResMut<CellRuns>) {
+    // The runs taken, by row and start, to their ends. They never overlap, so
+    // only the one starting nearest before a run's end can overlap it.
+    let mut taken = BTreeMap::new();
+    let count = pending_runs.0.iter().take(MAX_RUNS_PER_FRAME).take_while(|run| {
+        let (row, start, end) = (run.start.y, run.start.x, run.start.x + run.length);
+        let before = taken.range(..(row, end)).next_back();
+        if before.is_some_and(|(&(before_row, _), &before_end)| before_row == row && before_end > start) {
+            return false;
+        }
+        taken.insert((row, start), end);
+        true
+    }).count();
+    cell_runs.0 = pending_runs.0.drain(..count).collect();
+}
+
+// An elementary automaton starts over on an empty board from a single living
+// cell, as its row 0, at generation 0, so row 1 comes next.
+fn start_elementary(
+    simulation_kind: Res<SimulationKind>,
+    elementary_rule: Res<ElementaryRule>,
+    config: Res<ConwayConfig>,
+    generation: Res<Generation>,
+    mut commands: EventWriter<ConwayCommand>,
+) {
+    let switched = simulation_kind.is_changed() || elementary_rule.is_changed();
+    if !switched || *simulation_kind != SimulationKind::Elementary {
+        return;
+    }
+    generation.set(0);
+    commands.send(ConwayCommand::FillRect { corner: IVec2::ZERO, size: config.size, alive: false });
+    commands.send(ConwayCommand::SetCells(vec![IVec2::new(config.size.x as i32 / 2, 0)]));
+}
+
+// Space pauses and resumes.
+fn toggle_pause(
+    keys: Res<Input<KeyCode>>,
+    simulation_state: Res<SimulationState>,
+    mut commands: EventWriter<ConwayCommand>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        commands.send(if simulation_state.paused { ConwayCommand::Resume } else { ConwayCommand::Pause });
+    }
+}
+
+// N steps a generation while paused.
+fn step_once(
+    keys: Res<Input<KeyCode>>,
+    simulation_state: Res<SimulationState>,
+    mut step_request: ResMut<StepRequest>,
+) {
+    if keys.just_pressed(KeyCode::N) && simulation_state.paused {
+        step_request.0 = true;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn handle_mouse_click(
-    mut set_cells: ResMut<SetCells>,
     mouse_button_input: Res<Input<MouseButton>>,
-    window: Query<&Window, With<PrimaryWindow>>,
+    keys: Res<Input<KeyCode>>,
+    cursor: BoardCursor,
+    tool: Res<Tool>,
+    brush: Res<Brush>,
+    mut rng: ResMut<SimRng>,
+    mut commands: EventWriter<ConwayCommand>,
+    // Where the stroke was last frame.
+    mut last_cell: Local<Option<IVec2>>,
 ) {
-    if mouse_button_input.just_pressed(MouseButton::Left) {
-        let window = window.single();
-        if let Some(cursor_pos) = window.cursor_position() {
-            println!("user clicked {:?}", cursor_pos);
-            // Transform to clip space
-            let x = (cursor_pos.x / window.width()) * 2.0 - 1.0;
-            let y = (cursor_pos.y / window.height()) * 2.0 - 1.0;
-            set_cells.0.push(Vec2::new(x, -y));
-        }
+    if *tool != Tool::Cells || !mouse_button_input.pressed(MouseButton::Left) {
+        *last_cell = None;
+        return;
     }
+    let Some(cell) = cursor.cell() else {
+        return;
+    };
+    // Holding still doesn't keep filling in a soft brush.
+    if *last_cell == Some(cell) {
+        return;
+    }
+    let mut cells = HashSet::new();
+    for point in tools::line_cells(last_cell.unwrap_or(cell), cell) {
+        cells.extend(brush.cells(point));
+    }
+    *last_cell = Some(cell);
+    let mut cells = cells.into_iter().collect::<Vec<_>>();
+    // Sorted, so the same stroke with the same seed sets the same cells.
+    cells.sort_by_key(|cell| (cell.y, cell.x));
+    cells.retain(|_| brush.density >= 1.0 || rng.rng.gen::<f32>() < brush.density);
+    commands.send(match tools::erasing(&keys) {
+        true => ConwayCommand::ClearCells(cells),
+        false => ConwayCommand::SetCells(cells),
+    });
 }
 
 // Render World stuff.
@@ -171,8 +1646,32 @@ struct ConwayPipeline {
     init_pipeline: CachedComputePipelineId,
     // Pipeline for updating Conway State each step.
     update_pipeline: CachedComputePipelineId,
+    // Pipeline for updating the state with an `ActiveRuleTable` instead.
+    table_update_pipeline: CachedComputePipelineId,
+    // Pipeline for moving cells along the `FlowField`.
+    advect_pipeline: CachedComputePipelineId,
     // Pipeline for setting cells.
     set_cells_pipeline: CachedRenderPipelineId,
+    // Pipeline for clearing cells.
+    clear_cells_pipeline: CachedRenderPipelineId,
+    // Pipeline for writing `CellRuns`, and the layout of the runs it reads.
+    runs_bind_group_layout: BindGroupLayout,
+    write_runs_pipeline: CachedComputePipelineId,
+    // Likewise for `CellRects`.
+    rects_bind_group_layout: BindGroupLayout,
+    write_rects_pipeline: CachedComputePipelineId,
+    // Pipeline for applying a `BoardTransform`, and the layout of the one it's told to.
+    transform_bind_group_layout: BindGroupLayout,
+    transform_pipeline: CachedComputePipelineId,
+    // Pipeline for a step of Lenia, and the layout of its textures, kernel and settings.
+    lenia_bind_group_layout: BindGroupLayout,
+    lenia_pipeline: CachedComputePipelineId,
+    // Pipeline for a row of an `ElementaryRule`, and the layout of the rule and row it's told.
+    elementary_bind_group_layout: BindGroupLayout,
+    elementary_pipeline: CachedComputePipelineId,
+    // Pipeline for adding the board up at readback, and the layout of the sums it writes.
+    reduce_bind_group_layout: BindGroupLayout,
+    reduce_pipeline: CachedComputePipelineId,
 }
 
 impl FromWorld for ConwayPipeline {
@@ -189,8 +1688,68 @@ impl FromWorld for ConwayPipeline {
                         visibility: ShaderStages::COMPUTE,
                         ty: BindingType::StorageTexture {
                             access: StorageTextureAccess::ReadWrite,
-                            format: TextureFormat::Rgba8Unorm,
+                            format: BOARD_FORMAT,
+                            view_dimension: TextureViewDimension::D2,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: Some(ConwayUniforms::min_size()),
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Storage { read_only: true },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Texture {
+                            sample_type: TextureSampleType::Float { filterable: false },
                             view_dimension: TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 4,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Texture {
+                            sample_type: TextureSampleType::Uint,
+                            view_dimension: TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 5,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Storage { read_only: true },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 6,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Texture {
+                            sample_type: TextureSampleType::Float { filterable: false },
+                            view_dimension: TextureViewDimension::D2,
+                            multisampled: false,
                         },
                         count: None,
                     },
@@ -198,42 +1757,229 @@ impl FromWorld for ConwayPipeline {
                 label: Some("conway_state_bind_group_layout"),
             },
         );
-
-        let vertex_buffer_layout = VertexBufferLayout {
-            array_stride: std::mem::size_of::<Vec2>() as u64,
-            step_mode: VertexStepMode::Vertex,
-            attributes: vec![
-                VertexAttribute {
-                    format: VertexFormat::Float32x2,
-                    offset: 0,
-                    shader_location: 0,
-                },
-            ]
-        };
-
-        let init_pipeline = pipeline_cache.queue_compute_pipeline(
+
+        // The runs, rects and transform to write, each in a storage buffer of group 1.
+        let edits_bind_group_layout = |binding: u32, label: &'static str| {
+            render_device.create_bind_group_layout(
+                &BindGroupLayoutDescriptor {
+                    entries: &[
+                        BindGroupLayoutEntry {
+                            binding,
+                            visibility: ShaderStages::COMPUTE,
+                            ty: BindingType::Buffer {
+                                ty: BufferBindingType::Storage { read_only: true },
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                    ],
+                    label: Some(label),
+                },
+            )
+        };
+        let runs_bind_group_layout = edits_bind_group_layout(0, "conway_runs_bind_group_layout");
+        let rects_bind_group_layout = edits_bind_group_layout(1, "conway_rects_bind_group_layout");
+        let transform_bind_group_layout = edits_bind_group_layout(2, "conway_transform_bind_group_layout");
+        // Bound at a different offset for each generation of a frame.
+        let elementary_bind_group_layout = render_device.create_bind_group_layout(
+            &BindGroupLayoutDescriptor {
+                entries: &[
+                    BindGroupLayoutEntry {
+                        binding: 7,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Uniform,
+                            has_dynamic_offset: true,
+                            min_binding_size: Some(ElementaryUniforms::min_size()),
+                        },
+                        count: None,
+                    },
+                ],
+                label: Some("conway_elementary_bind_group_layout"),
+            },
+        );
+        // The sums `reduce` adds the board up into, read back with the board.
+        let reduce_bind_group_layout = render_device.create_bind_group_layout(
+            &BindGroupLayoutDescriptor {
+                entries: &[
+                    BindGroupLayoutEntry {
+                        binding: 8,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Storage { read_only: false },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+                label: Some("conway_reduce_bind_group_layout"),
+            },
+        );
+        let lenia_bind_group_layout = render_device.create_bind_group_layout(
+            &BindGroupLayoutDescriptor {
+                entries: &[
+                    BindGroupLayoutEntry {
+                        binding: 3,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Texture {
+                            sample_type: TextureSampleType::Float { filterable: false },
+                            view_dimension: TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 4,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::StorageTexture {
+                            access: StorageTextureAccess::WriteOnly,
+                            format: LENIA_FORMAT,
+                            view_dimension: TextureViewDimension::D2,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 5,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Storage { read_only: true },
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                    BindGroupLayoutEntry {
+                        binding: 6,
+                        visibility: ShaderStages::COMPUTE,
+                        ty: BindingType::Buffer {
+                            ty: BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: Some(LeniaUniforms::min_size()),
+                        },
+                        count: None,
+                    },
+                ],
+                label: Some("conway_lenia_bind_group_layout"),
+            },
+        );
+
+        let vertex_buffer_layout = VertexBufferLayout {
+            array_stride: std::mem::size_of::<Vec2>() as u64,
+            step_mode: VertexStepMode::Vertex,
+            attributes: vec![
+                VertexAttribute {
+                    format: VertexFormat::Float32x2,
+                    offset: 0,
+                    shader_location: 0,
+                },
+            ]
+        };
+
+        let init_pipeline = pipeline_cache.queue_compute_pipeline(
+            ComputePipelineDescriptor {
+                label: Some(Cow::from("conway_init_pipeline")),
+                layout: vec![texture_bind_group_layout.clone()],
+                push_constant_ranges: vec![],
+                shader: shader.clone(),
+                shader_defs: vec![],
+                entry_point: Cow::from("init"),
+            }
+        );
+        let update_pipeline = pipeline_cache.queue_compute_pipeline(
+            ComputePipelineDescriptor {
+                label: Some(Cow::from("conway_update_pipeline")),
+                layout: vec![texture_bind_group_layout.clone()],
+                push_constant_ranges: vec![],
+                shader: shader.clone(),
+                shader_defs: vec![],
+                entry_point: Cow::from("update"),
+            },
+        );
+        let table_update_pipeline = pipeline_cache.queue_compute_pipeline(
+            ComputePipelineDescriptor {
+                label: Some(Cow::from("conway_table_update_pipeline")),
+                layout: vec![texture_bind_group_layout.clone()],
+                push_constant_ranges: vec![],
+                shader: shader.clone(),
+                shader_defs: vec![],
+                entry_point: Cow::from("update_table"),
+            },
+        );
+        let advect_pipeline = pipeline_cache.queue_compute_pipeline(
+            ComputePipelineDescriptor {
+                label: Some(Cow::from("conway_advect_pipeline")),
+                layout: vec![texture_bind_group_layout.clone()],
+                push_constant_ranges: vec![],
+                shader: shader.clone(),
+                shader_defs: vec![],
+                entry_point: Cow::from("advect"),
+            },
+        );
+        let write_runs_pipeline = pipeline_cache.queue_compute_pipeline(
+            ComputePipelineDescriptor {
+                label: Some(Cow::from("conway_write_runs_pipeline")),
+                layout: vec![texture_bind_group_layout.clone(), runs_bind_group_layout.clone()],
+                push_constant_ranges: vec![],
+                shader: shader.clone(),
+                shader_defs: vec![],
+                entry_point: Cow::from("write_runs"),
+            },
+        );
+        let write_rects_pipeline = pipeline_cache.queue_compute_pipeline(
+            ComputePipelineDescriptor {
+                label: Some(Cow::from("conway_write_rects_pipeline")),
+                layout: vec![texture_bind_group_layout.clone(), rects_bind_group_layout.clone()],
+                push_constant_ranges: vec![],
+                shader: shader.clone(),
+                shader_defs: vec![],
+                entry_point: Cow::from("write_rects"),
+            },
+        );
+        let transform_pipeline = pipeline_cache.queue_compute_pipeline(
             ComputePipelineDescriptor {
-                label: Some(Cow::from("conway_init_pipeline")),
-                layout: vec![texture_bind_group_layout.clone()],
+                label: Some(Cow::from("conway_transform_pipeline")),
+                layout: vec![texture_bind_group_layout.clone(), transform_bind_group_layout.clone()],
                 push_constant_ranges: vec![],
                 shader: shader.clone(),
                 shader_defs: vec![],
-                entry_point: Cow::from("init"),
-            }
+                entry_point: Cow::from("transform"),
+            },
         );
-        let update_pipeline = pipeline_cache.queue_compute_pipeline(
+        let lenia_pipeline = pipeline_cache.queue_compute_pipeline(
             ComputePipelineDescriptor {
-                label: Some(Cow::from("conway_update_pipeline")),
-                layout: vec![texture_bind_group_layout.clone()],
+                label: Some(Cow::from("conway_lenia_pipeline")),
+                layout: vec![texture_bind_group_layout.clone(), lenia_bind_group_layout.clone()],
                 push_constant_ranges: vec![],
                 shader: shader.clone(),
                 shader_defs: vec![],
-                entry_point: Cow::from("update"),
+                entry_point: Cow::from("update_lenia"),
             },
         );
-        let set_cells_pipeline = pipeline_cache.queue_render_pipeline(
+        let elementary_pipeline = pipeline_cache.queue_compute_pipeline(
+            ComputePipelineDescriptor {
+                label: Some(Cow::from("conway_elementary_pipeline")),
+                layout: vec![texture_bind_group_layout.clone(), elementary_bind_group_layout.clone()],
+                push_constant_ranges: vec![],
+                shader: shader.clone(),
+                shader_defs: vec![],
+                entry_point: Cow::from("update_elementary"),
+            },
+        );
+        let reduce_pipeline = pipeline_cache.queue_compute_pipeline(
+            ComputePipelineDescriptor {
+                label: Some(Cow::from("conway_reduce_pipeline")),
+                layout: vec![texture_bind_group_layout.clone(), reduce_bind_group_layout.clone()],
+                push_constant_ranges: vec![],
+                shader: shader.clone(),
+                shader_defs: vec![],
+                entry_point: Cow::from("reduce"),
+            },
+        );
+        let cells_pipeline_descriptor = |label: &'static str, fragment_entry_point: &'static str| {
             RenderPipelineDescriptor {
-                label: Some(Cow::from("conway_set_cell_pipeline")),
+                label: Some(Cow::from(label)),
                 layout: vec![],
                 primitive: PrimitiveState {
                     topology: PrimitiveTopology::PointList,
@@ -243,14 +1989,14 @@ impl FromWorld for ConwayPipeline {
                     entry_point: Cow::from("set_cells_vs"),
                     shader: shader.clone(),
                     shader_defs: vec![],
-                    buffers: vec![vertex_buffer_layout],
+                    buffers: vec![vertex_buffer_layout.clone()],
                 },
                 fragment: Some(FragmentState {
-                    entry_point: Cow::from("set_cells_fs"),
+                    entry_point: Cow::from(fragment_entry_point),
                     shader: shader.clone(),
                     shader_defs: vec![],
                     targets: vec![Some(ColorTargetState {
-                        format: TextureFormat::Rgba8Unorm,
+                        format: BOARD_FORMAT,
                         blend: Some(BlendState::ALPHA_BLENDING),
                         write_mask: ColorWrites::ALL,
                     })],
@@ -258,41 +2004,545 @@ impl FromWorld for ConwayPipeline {
                 depth_stencil: None,
                 multisample: MultisampleState::default(),
                 push_constant_ranges: vec![],
-            },
+            }
+        };
+        let set_cells_pipeline = pipeline_cache.queue_render_pipeline(
+            cells_pipeline_descriptor("conway_set_cell_pipeline", "set_cells_fs"),
+        );
+        let clear_cells_pipeline = pipeline_cache.queue_render_pipeline(
+            cells_pipeline_descriptor("conway_clear_cell_pipeline", "clear_cells_fs"),
         );
         Self {
             texture_bind_group_layout,
             init_pipeline,
             update_pipeline,
+            table_update_pipeline,
+            advect_pipeline,
             set_cells_pipeline,
+            clear_cells_pipeline,
+            runs_bind_group_layout,
+            write_runs_pipeline,
+            rects_bind_group_layout,
+            write_rects_pipeline,
+            transform_bind_group_layout,
+            transform_pipeline,
+            lenia_bind_group_layout,
+            lenia_pipeline,
+            elementary_bind_group_layout,
+            elementary_pipeline,
+            reduce_bind_group_layout,
+            reduce_pipeline,
         }
     }
 }
 
+// Uniforms shared by the compute shaders.
+#[derive(ShaderType, Clone, Copy)]
+struct ConwayUniforms {
+    density: f32,
+    seed: u32,
+    // The `ActiveRuleTable`'s state count and neighbourhood (0 is Moore, 1 von Neumann).
+    table_states: u32,
+    table_neighborhood: u32,
+    // The chance of following the `RuleBlend` table instead, and a seed for
+    // picking which cells do.
+    blend_amount: f32,
+    blend_seed: u32,
+    portal_count: u32,
+    // The `EnergySettings`.
+    energy_enabled: u32,
+    energy_cost: f32,
+    energy_regen: f32,
+    // The active rule's lifespan from `Lifespans`, or 0.
+    max_age: u32,
+    // 0 for dead `Edges`, 1 to wrap and 2 to mirror.
+    edges: u32,
+    // The `RuleString`'s birth and survival counts, a bit per count, and
+    // its state count, over 2 with decay states.
+    birth: u32,
+    survival: u32,
+    rule_states: u32,
+    // Its Larger than Life radius, 0 without one, the neighbourhood's shape
+    // (0 is Moore, 1 von Neumann), whether the middle cell counts, and the
+    // birth and survival ranges.
+    radius: u32,
+    neighborhood: u32,
+    middle: u32,
+    birth_min: u32,
+    birth_max: u32,
+    survival_min: u32,
+    survival_max: u32,
+    // The `TargetBias`.
+    target_bias: f32,
+    // Each portal's two rectangles, as (x, y, width, height).
+    portals: [IVec4; MAX_PORTALS * 2],
+}
+
+// The `LeniaSettings` the Lenia shader reads, its kernel aside.
+#[derive(ShaderType, Clone, Copy)]
+struct LeniaUniforms {
+    radius: u32,
+    mu: f32,
+    sigma: f32,
+    dt: f32,
+}
+
+// The `ElementaryRule` and the row the elementary shader writes with it.
+#[derive(ShaderType, Clone, Copy)]
+struct ElementaryUniforms {
+    rule: u32,
+    row: u32,
+}
+
+// One `ElementaryUniforms` for each generation of the frame, kept from frame
+// to frame so its buffer's only remade when it has to grow.
+#[derive(Resource, Default)]
+struct ElementaryUniformBuffer(DynamicUniformBuffer<ElementaryUniforms>);
+
+// What the elementary shader's bound with, and the offset into it for each
+// generation of the frame.
+#[derive(Resource)]
+struct ElementaryBindGroup {
+    bind_group: BindGroup,
+    offsets: Vec<u32>,
+}
+
 // Instantiate a bind group for the conway pipeline.
 #[derive(Resource)]
 struct ConwayStateBindGroup(BindGroup);
+
+// The Lenia pipeline's textures, kernel and settings.
+#[derive(Resource)]
+struct LeniaBindGroup(BindGroup);
+
+// The same, but writing to the `ConwayGhost` instead of the board.
+#[derive(Resource)]
+struct GhostBindGroup(BindGroup);
+
+// The settings that go into the uniforms, gathered up since a system can
+// only take so many parameters.
+#[derive(SystemParam)]
+struct UniformSources<'w> {
+    seed_settings: Res<'w, SeedSettings>,
+    portals: Res<'w, Portals>,
+    rule_blend: Res<'w, RuleBlend>,
+    generation: Res<'w, Generation>,
+    energy: Res<'w, EnergySettings>,
+    lifespans: Res<'w, Lifespans>,
+    edges: Res<'w, Edges>,
+    rule_string: Res<'w, RuleString>,
+    target_bias: Res<'w, TargetBias>,
+}
+
 // Instantiate a bind group for the conway pipeline.
+#[allow(clippy::too_many_arguments)]
 fn prepare_bind_group(
     mut commands: Commands,
     pipeline: Res<ConwayPipeline>,
     gpu_images: Res<RenderAssets<Image>>,
     conway_state: Res<ConwayWorld>,
+    ghost: Res<ConwayGhost>,
+    rule_table: Res<ActiveRuleTable>,
     render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    previous_board: Res<PreviousBoard>,
+    mask_texture: Res<MaskTexture>,
+    flow_texture: Res<FlowTexture>,
+    sources: UniformSources,
+    // The uploaded lookup table, which only changes with the rule.
+    mut lookup_buffer: Local<Option<Buffer>>,
+    // And the blended one.
+    mut blend_buffer: Local<Option<Buffer>>,
+    mut uploaded_blend: Local<Option<Arc<RuleTable>>>,
 ) {
     // Get the image for conway state from the GPU asset server.
     let image = gpu_images.get(&conway_state.0).unwrap();
-    let bind_group = render_device.create_bind_group(
-        Some("conway_state_bind_group"),
+    let UniformSources {
+        seed_settings, portals, rule_blend, generation, energy, lifespans, edges, rule_string, target_bias,
+    } = sources;
+    let table = rule_table.0.as_deref();
+    let larger = rule_string.0.larger;
+    let blend = rule_blend.table.as_ref().filter(|blend| {
+        table.is_some_and(|table| table.states == blend.states && table.neighborhood == blend.neighborhood)
+    });
+    let mut uniforms = UniformBuffer::from(ConwayUniforms {
+        density: seed_settings.density,
+        seed: seed_settings.seed,
+        table_states: table.map_or(2, |table| table.states),
+        table_neighborhood: match table.map(|table| table.neighborhood) {
+            Some(Neighborhood::VonNeumann) => 1,
+            _ => 0,
+        },
+        blend_amount: blend.map_or(0.0, |_| rule_blend.amount),
+        blend_seed: generation.get() as u32,
+        portal_count: portals.0.len() as u32,
+        energy_enabled: energy.enabled as u32,
+        energy_cost: energy.cost,
+        energy_regen: energy.regen,
+        max_age: lifespans.0.get(rule_table.name(&rule_string).as_ref()).copied().unwrap_or(0),
+        edges: edges.code(),
+        birth: rule_string.0.birth as u32,
+        survival: rule_string.0.survival as u32,
+        rule_states: rule_string.0.states,
+        radius: larger.map_or(0, |larger| larger.radius),
+        neighborhood: match larger.map(|larger| larger.neighborhood) {
+            Some(Neighborhood::VonNeumann) => 1,
+            _ => 0,
+        },
+        middle: larger.is_some_and(|larger| larger.middle) as u32,
+        birth_min: larger.map_or(0, |larger| larger.birth.0),
+        birth_max: larger.map_or(0, |larger| larger.birth.1),
+        survival_min: larger.map_or(0, |larger| larger.survival.0),
+        survival_max: larger.map_or(0, |larger| larger.survival.1),
+        target_bias: target_bias.0,
+        portals: std::array::from_fn(|i| {
+            let Some(portal) = portals.0.get(i / 2) else {
+                return IVec4::ZERO;
+            };
+            let corner = if i % 2 == 0 { portal.a } else { portal.b };
+            corner.extend(portal.size.x).extend(portal.size.y)
+        }),
+    });
+    uniforms.write_buffer(&render_device, &render_queue);
+    if rule_table.is_changed() || lookup_buffer.is_none() {
+        // Storage buffers can't be empty, so B3/S23 gets a placeholder.
+        let packed = table.map_or_else(|| vec![0], RuleTable::packed);
+        *lookup_buffer = Some(render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("conway_rule_table_buffer"),
+            usage: BufferUsages::STORAGE,
+            contents: bytemuck::cast_slice(&packed),
+        }));
+    }
+    let blend_changed = match (blend, uploaded_blend.as_ref()) {
+        (Some(blend), Some(uploaded)) => !Arc::ptr_eq(blend, uploaded),
+        (None, None) => false,
+        _ => true,
+    };
+    if blend_changed || blend_buffer.is_none() {
+        let packed = blend.map_or_else(|| vec![0], |blend| blend.packed());
+        *blend_buffer = Some(render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("conway_rule_blend_buffer"),
+            usage: BufferUsages::STORAGE,
+            contents: bytemuck::cast_slice(&packed),
+        }));
+        *uploaded_blend = blend.cloned();
+    }
+    let create_bind_group = |label, view| render_device.create_bind_group(
+        Some(label),
         &pipeline.texture_bind_group_layout,
         &[
             BindGroupEntry {
                 binding: 0,
-                resource: BindingResource::TextureView(&image.texture_view),
+                resource: BindingResource::TextureView(view),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: uniforms.binding().unwrap(),
+            },
+            BindGroupEntry {
+                binding: 2,
+                resource: lookup_buffer.as_ref().unwrap().as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 3,
+                resource: BindingResource::TextureView(&previous_board.view),
+            },
+            BindGroupEntry {
+                binding: 4,
+                resource: BindingResource::TextureView(&mask_texture.view),
+            },
+            BindGroupEntry {
+                binding: 5,
+                resource: blend_buffer.as_ref().unwrap().as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 6,
+                resource: BindingResource::TextureView(&flow_texture.view),
             },
         ]
     );
-    commands.insert_resource(ConwayStateBindGroup(bind_group));
+    commands.insert_resource(ConwayStateBindGroup(create_bind_group("conway_state_bind_group", &image.texture_view)));
+    if let Some(ghost) = gpu_images.get(&ghost.0) {
+        commands.insert_resource(GhostBindGroup(create_bind_group("conway_ghost_bind_group", &ghost.texture_view)));
+    }
+}
+
+// A board of `size` as a texture's size.
+fn board_extent(size: UVec2) -> Extent3d {
+    Extent3d { width: size.x, height: size.y, depth_or_array_layers: 1 }
+}
+
+/// Begins a compute pass named after what it dispatches, as `PipelineStats`
+/// counts it, so frame debuggers show which pass is which.
+pub fn compute_pass<'a>(encoder: &'a mut CommandEncoder, label: &'static str) -> ComputePass<'a> {
+    encoder.begin_compute_pass(&ComputePassDescriptor { label: Some(label) })
+}
+
+// A workgroup for every block of the board, which the config guarantees the
+// board divides into.
+fn dispatch_board(pass: &mut wgpu::ComputePass, size: UVec2) {
+    pass.dispatch_workgroups(size.x / WORKGROUP_SIZE.0, size.y / WORKGROUP_SIZE.1, 1);
+}
+
+// A texture the update shaders read alongside the board, and as big.
+fn board_texture(
+    render_device: &RenderDevice,
+    label: &'static str,
+    size: UVec2,
+    format: TextureFormat,
+) -> (Texture, TextureView) {
+    let texture = render_device.create_texture(&TextureDescriptor {
+        label: Some(label),
+        size: board_extent(size),
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format,
+        usage: TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&TextureViewDescriptor::default());
+    (texture, view)
+}
+
+fn prepare_lenia_bind_group(
+    mut commands: Commands,
+    pipeline: Res<ConwayPipeline>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    lenia_textures: Res<LeniaTextures>,
+    settings: Res<LeniaSettings>,
+    // The uploaded kernel, which only changes with the radius.
+    mut kernel_buffer: Local<Option<(u32, Buffer)>>,
+) {
+    let radius = settings.radius.clamp(1, MAX_LENIA_RADIUS);
+    if kernel_buffer.as_ref().map(|(uploaded, _)| *uploaded) != Some(radius) {
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("conway_lenia_kernel_buffer"),
+            usage: BufferUsages::STORAGE,
+            contents: bytemuck::cast_slice(&settings.kernel()),
+        });
+        *kernel_buffer = Some((radius, buffer));
+    }
+    let mut uniforms = UniformBuffer::from(LeniaUniforms {
+        radius,
+        mu: settings.mu,
+        sigma: settings.sigma,
+        dt: settings.dt,
+    });
+    uniforms.write_buffer(&render_device, &render_queue);
+    let bind_group = render_device.create_bind_group(
+        Some("conway_lenia_bind_group"),
+        &pipeline.lenia_bind_group_layout,
+        &[
+            BindGroupEntry { binding: 3, resource: BindingResource::TextureView(&lenia_textures.previous_view) },
+            BindGroupEntry { binding: 4, resource: BindingResource::TextureView(&lenia_textures.state_view) },
+            BindGroupEntry { binding: 5, resource: kernel_buffer.as_ref().unwrap().1.as_entire_binding() },
+            BindGroupEntry { binding: 6, resource: uniforms.binding().unwrap() },
+        ],
+    );
+    commands.insert_resource(LeniaBindGroup(bind_group));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn prepare_elementary_bind_group(
+    mut commands: Commands,
+    pipeline: Res<ConwayPipeline>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    simulation_kind: Res<SimulationKind>,
+    simulation_state: Res<SimulationState>,
+    elementary_rule: Res<ElementaryRule>,
+    generation: Res<Generation>,
+    gpu_images: Res<RenderAssets<Image>>,
+    conway_world: Res<ConwayWorld>,
+    mut uniforms: ResMut<ElementaryUniformBuffer>,
+) {
+    if *simulation_kind != SimulationKind::Elementary {
+        return;
+    }
+    let Some(board) = gpu_images.get(&conway_world.0) else {
+        return;
+    };
+    let height = board.size.y as u64;
+    uniforms.0.clear();
+    // Row 0's the seed, so generation 1 is row 1, and so on round.
+    let start = generation.get();
+    let offsets = (0..simulation_state.generations_per_frame())
+        .map(|generation| {
+            let row = ((start + generation as u64 + 1) % height) as u32;
+            uniforms.0.push(ElementaryUniforms { rule: elementary_rule.0 as u32, row })
+        })
+        .collect();
+    uniforms.0.write_buffer(&render_device, &render_queue);
+    let Some(binding) = uniforms.0.binding() else {
+        return;
+    };
+    let bind_group = render_device.create_bind_group(
+        Some("conway_elementary_bind_group"),
+        &pipeline.elementary_bind_group_layout,
+        &[BindGroupEntry { binding: 7, resource: binding }],
+    );
+    commands.insert_resource(ElementaryBindGroup { bind_group, offsets });
+}
+
+// Remakes the textures sized like the board once the board's own has been
+// remade at a new size.
+fn fit_board_textures(
+    render_device: Res<RenderDevice>,
+    gpu_images: Res<RenderAssets<Image>>,
+    conway_world: Res<ConwayWorld>,
+    mut previous_board: ResMut<PreviousBoard>,
+    mut mask_texture: ResMut<MaskTexture>,
+    mut flow_texture: ResMut<FlowTexture>,
+    mut lenia_textures: ResMut<LeniaTextures>,
+) {
+    let Some(board) = gpu_images.get(&conway_world.0) else {
+        return;
+    };
+    let size = board.size.as_uvec2();
+    if UVec2::new(previous_board.texture.width(), previous_board.texture.height()) == size {
+        return;
+    }
+    *previous_board = PreviousBoard::new(&render_device, size);
+    *mask_texture = MaskTexture::new(&render_device, size);
+    *flow_texture = FlowTexture::new(&render_device, size);
+    *lenia_textures = LeniaTextures::new(&render_device, size);
+}
+
+// A copy of the board from before the current update, which the update
+// shaders read neighbours from.
+#[derive(Resource)]
+struct PreviousBoard {
+    texture: Texture,
+    view: TextureView,
+}
+
+impl FromWorld for PreviousBoard {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(world.resource::<RenderDevice>(), world.resource::<ConwayConfig>().size)
+    }
+}
+
+impl PreviousBoard {
+    fn new(render_device: &RenderDevice, size: UVec2) -> Self {
+        let (texture, view) = board_texture(render_device, "conway_previous_board", size, BOARD_FORMAT);
+        Self { texture, view }
+    }
+}
+
+// `BoardMasks` on the GPU.
+#[derive(Resource)]
+struct MaskTexture {
+    texture: Texture,
+    view: TextureView,
+}
+
+impl FromWorld for MaskTexture {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(world.resource::<RenderDevice>(), world.resource::<ConwayConfig>().size)
+    }
+}
+
+impl MaskTexture {
+    fn new(render_device: &RenderDevice, size: UVec2) -> Self {
+        let (texture, view) = board_texture(render_device, "conway_masks", size, TextureFormat::R8Uint);
+        Self { texture, view }
+    }
+}
+
+fn upload_masks(masks: Res<BoardMasks>, mask_texture: Res<MaskTexture>, render_queue: Res<RenderQueue>) {
+    let size = mask_texture.texture.size();
+    // Fresh textures need filling too, once the masks have been resized to match.
+    if !(masks.is_changed() || mask_texture.is_changed()) || masks.cells.len() != (size.width * size.height) as usize {
+        return;
+    }
+    render_queue.write_texture(
+        ImageCopyTexture {
+            texture: &mask_texture.texture,
+            mip_level: 0,
+            origin: Origin3d::ZERO,
+            aspect: TextureAspect::All,
+        },
+        &masks.cells,
+        ImageDataLayout { offset: 0, bytes_per_row: Some(size.width), rows_per_image: None },
+        size,
+    );
+}
+
+// The `FlowField` on the GPU.
+#[derive(Resource)]
+struct FlowTexture {
+    texture: Texture,
+    view: TextureView,
+}
+
+impl FromWorld for FlowTexture {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(world.resource::<RenderDevice>(), world.resource::<ConwayConfig>().size)
+    }
+}
+
+impl FlowTexture {
+    fn new(render_device: &RenderDevice, size: UVec2) -> Self {
+        let (texture, view) = board_texture(render_device, "conway_flow", size, TextureFormat::Rg8Snorm);
+        Self { texture, view }
+    }
+}
+
+fn upload_flow(flow: Res<FlowField>, flow_texture: Res<FlowTexture>, render_queue: Res<RenderQueue>) {
+    let size = flow_texture.texture.size();
+    if !(flow.is_changed() || flow_texture.is_changed()) || flow.cells.len() != (size.width * size.height) as usize {
+        return;
+    }
+    render_queue.write_texture(
+        ImageCopyTexture {
+            texture: &flow_texture.texture,
+            mip_level: 0,
+            origin: Origin3d::ZERO,
+            aspect: TextureAspect::All,
+        },
+        bytemuck::cast_slice(&flow.cells),
+        ImageDataLayout { offset: 0, bytes_per_row: Some(size.width * 2), rows_per_image: None },
+        size,
+    );
+}
+
+// The format of Lenia's own textures.
+const LENIA_FORMAT: TextureFormat = TextureFormat::R32Float;
+
+// Lenia's states, full precision: the latest, which the Lenia shader
+// writes, and a copy of the ones before, which it reads.
+#[derive(Resource)]
+struct LeniaTextures {
+    state: Texture,
+    state_view: TextureView,
+    previous: Texture,
+    previous_view: TextureView,
+}
+
+impl FromWorld for LeniaTextures {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(world.resource::<RenderDevice>(), world.resource::<ConwayConfig>().size)
+    }
+}
+
+impl LeniaTextures {
+    fn new(render_device: &RenderDevice, size: UVec2) -> Self {
+        let state = render_device.create_texture(&TextureDescriptor {
+            label: Some("conway_lenia_state"),
+            size: board_extent(size),
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: LENIA_FORMAT,
+            usage: TextureUsages::STORAGE_BINDING | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let state_view = state.create_view(&TextureViewDescriptor::default());
+        let (previous, previous_view) = board_texture(render_device, "conway_lenia_previous", size, LENIA_FORMAT);
+        Self { state, state_view, previous, previous_view }
+    }
 }
 
 #[derive(Resource, Clone)]
@@ -300,17 +2550,45 @@ struct OutputBuffer {
     buffer: Buffer,
 }
 
+// Words in `reduce`'s sums: the alive level, the `DensityGrid`'s counts, then
+// the `LiveBounds` as min x, min y, max x and max y.
+const BOUNDS_WORD: usize = 1 + (DENSITY_GRID_SIZE * DENSITY_GRID_SIZE) as usize;
+const REDUCTION_WORDS: usize = BOUNDS_WORD + 4;
+
+// Where `reduce` writes its sums, and the copy of them to read back.
+#[derive(Resource)]
+struct ReductionBuffers {
+    sums: Buffer,
+    readback: Buffer,
+}
+
 fn prepare_resources(
     mut commands: Commands,
     render_device: Res<RenderDevice>,
+    gpu_images: Res<RenderAssets<Image>>,
+    conway_world: Res<ConwayWorld>,
 ) {
+    let board = gpu_images.get(&conway_world.0).unwrap();
     let buffer = render_device.create_buffer(&BufferDescriptor {
         label: Some("conway_output_buffer"),
-        size: (SIZE.0 * SIZE.1 * 4) as u64,
+        size: board.size.x as u64 * board.size.y as u64 * 4,
         usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
         mapped_at_creation: false,
     });
     commands.insert_resource(OutputBuffer { buffer });
+    let reduction_buffer = |label: &'static str, usage: BufferUsages| render_device.create_buffer(&BufferDescriptor {
+        label: Some(label),
+        size: (REDUCTION_WORDS * 4) as u64,
+        usage,
+        mapped_at_creation: false,
+    });
+    commands.insert_resource(ReductionBuffers {
+        sums: reduction_buffer(
+            "conway_reduction_buffer",
+            BufferUsages::STORAGE | BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
+        ),
+        readback: reduction_buffer("conway_reduction_readback", BufferUsages::COPY_DST | BufferUsages::MAP_READ),
+    });
 }
 
 // The RenderGraph for Conway's game.
@@ -323,56 +2601,146 @@ enum ConwayState {
 }
 
 #[derive(Default)]
-struct ConwayRenderNode(ConwayState);
+struct ConwayRenderNode {
+    state: ConwayState,
+    // The `SeedSettings` epoch the board was last initialized with.
+    seed_epoch: u32,
+}
 
 impl RenderNode for ConwayRenderNode {
     fn update(&mut self, world: &mut World) {
         let pipeline = world.resource::<ConwayPipeline>();
         let pipeline_cache = world.resource::<PipelineCache>();
+        let seed_epoch = world.resource::<SeedSettings>().epoch;
 
-        match self {
-            ConwayRenderNode(ConwayState::Loading) => {
+        match self.state {
+            ConwayState::Loading => {
                 if let CachedPipelineState::Ok(_) = pipeline_cache.get_compute_pipeline_state(pipeline.init_pipeline) {
-                    *self = ConwayRenderNode(ConwayState::Init);
+                    self.state = ConwayState::Init;
+                    self.seed_epoch = seed_epoch;
+                    world.resource::<Generation>().restart();
                 }
             }
-            ConwayRenderNode(ConwayState::Init) => {
+            ConwayState::Init => {
                 if let CachedPipelineState::Ok(_) = pipeline_cache.get_compute_pipeline_state(pipeline.update_pipeline) {
-                    *self = ConwayRenderNode(ConwayState::Update);
+                    self.state = ConwayState::Update;
+                    let generation = world.resource::<Generation>();
+                    generation.seeded_epoch.store(self.seed_epoch, Ordering::Relaxed);
+                    generation.seeded.store(true, Ordering::Relaxed);
+                }
+            }
+            ConwayState::Update => {
+                // Re-run the init shader when a reseed was requested.
+                if self.seed_epoch != seed_epoch {
+                    self.state = ConwayState::Init;
+                    self.seed_epoch = seed_epoch;
+                    world.resource::<Generation>().restart();
                 }
             }
-            ConwayRenderNode(ConwayState::Update) => {}
         }
     }
 
     fn run(
         &self,
         _graph: &mut render_graph::RenderGraphContext,
-        render_context: &mut RenderContext,
+        _render_context: &mut RenderContext,
         world: &World,
     ) -> Result<(), render_graph::NodeRunError> {
+        // wgpu only gives us the one queue, so the closest to running the
+        // simulation alongside the frame is submitting it on its own, before
+        // the rest of the graph is even encoded. The GPU can then get going on
+        // a long run of warp-speed passes while the main passes and everything
+        // after are still being recorded, instead of receiving it all in the
+        // graph's single submission at the end.
+        let render_device = world.resource::<RenderDevice>();
+        let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor { label: Some("conway_simulation") });
+        let debug_groups = world.resource::<DebugGroups>().0;
+        if debug_groups {
+            encoder.push_debug_group("conway_simulation");
+        }
+        self.encode(world, &mut encoder);
+        if debug_groups {
+            encoder.pop_debug_group();
+        }
+        world.resource::<RenderQueue>().submit(Some(encoder.finish()));
+        Ok(())
+    }
+}
+
+impl ConwayRenderNode {
+    fn encode(&self, world: &World, encoder: &mut CommandEncoder) {
         let pipeline = world.resource::<ConwayPipeline>();
         let pipeline_cache = world.resource::<PipelineCache>();
         let render_device = world.resource::<RenderDevice>();
         let texture_bind_group = &world.resource::<ConwayStateBindGroup>().0;
         let gpu_images = world.resource::<RenderAssets<Image>>();
         let conway_state = world.resource::<ConwayWorld>();
+        let gpu_image = gpu_images.get(&conway_state.0).unwrap();
+        // Rather than `ConwayConfig::size`, which runs a frame ahead after a resize.
+        let size = gpu_image.size.as_uvec2();
         let set_cells = world.resource::<SetCells>();
+        let clear_cells = world.resource::<ClearCells>();
+        let cell_runs = world.resource::<CellRuns>();
+        let cell_rects = world.resource::<CellRects>();
+        let board_transforms = world.resource::<BoardTransforms>();
+        let simulation_state = world.resource::<SimulationState>();
+        let step_request = world.resource::<StepRequest>();
+        let flowing = world.resource::<FlowField>().enabled;
+        let stats = world.resource::<PipelineStats>();
+        let (update_label, update_pipeline) = match world.resource::<ActiveRuleTable>().0 {
+            Some(_) => ("table_update", pipeline.table_update_pipeline),
+            None => ("update", pipeline.update_pipeline),
+        };
 
-        let encoder = render_context.command_encoder();
-
-        if !set_cells.0.is_empty() {
-            let gpu_image = gpu_images.get(&conway_state.0).unwrap();
-            let set_cell_data = bytemuck::cast_slice(set_cells.0.as_slice());
-            let vertex_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
-                usage: BufferUsages::VERTEX,
-                label: Some("Mesh Vertex Buffer"),
-                contents: set_cell_data,
+        // Rects first, each a workgroup layer of one dispatch as big as the biggest.
+        let write_rects_pipeline = pipeline_cache.get_compute_pipeline(pipeline.write_rects_pipeline);
+        if let (false, Some(write_rects_pipeline)) = (cell_rects.0.is_empty(), write_rects_pipeline) {
+            let packed = cell_rects.0.iter().map(CellRect::packed).collect::<Vec<_>>();
+            let rects_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+                label: Some("conway_rects_buffer"),
+                usage: BufferUsages::STORAGE,
+                contents: bytemuck::cast_slice(&packed),
             });
+            let rects_bind_group = render_device.create_bind_group(
+                Some("conway_rects_bind_group"),
+                &pipeline.rects_bind_group_layout,
+                &[BindGroupEntry { binding: 1, resource: rects_buffer.as_entire_binding() }],
+            );
+            let biggest = cell_rects.0.iter().fold(UVec2::ZERO, |biggest, rect| biggest.max(rect.size));
+            let mut pass = compute_pass(encoder, "write_rects");
+            pass.set_bind_group(0, texture_bind_group, &[]);
+            pass.set_bind_group(1, &rects_bind_group, &[]);
+            pass.set_pipeline(write_rects_pipeline);
+            pass.dispatch_workgroups(
+                biggest.x.div_ceil(WORKGROUP_SIZE.0),
+                biggest.y.div_ceil(WORKGROUP_SIZE.1),
+                packed.len() as u32,
+            );
+            stats.dispatched("write_rects", 1);
+        }
+
+        // Upload the cells to set and clear, along with the pipeline that writes them.
+        let cell_batches = [
+            ("set_cells", &set_cells.0, pipeline.set_cells_pipeline),
+            ("clear_cells", &clear_cells.0, pipeline.clear_cells_pipeline),
+        ]
+            .into_iter()
+            .filter(|(_, cells, _)| !cells.is_empty())
+            .map(|(label, cells, pipeline_id)| {
+                stats.dispatched(label, 1);
+                let vertex_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+                    usage: BufferUsages::VERTEX,
+                    label: Some("conway_cells_buffer"),
+                    contents: bytemuck::cast_slice(cells.as_slice()),
+                });
+                (vertex_buffer, pipeline_id, cells.len() as u32)
+            })
+            .collect::<Vec<_>>();
 
+        if !cell_batches.is_empty() {
             let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
                 label: Some("set_cells_render"),
-                color_attachments: &vec![Some(RenderPassColorAttachment {
+                color_attachments: &[Some(RenderPassColorAttachment {
                     view: &gpu_image.texture_view,
                     resolve_target: None,
                     ops: Operations { load: LoadOp::Load, store: true }
@@ -380,45 +2748,264 @@ impl RenderNode for ConwayRenderNode {
                 depth_stencil_attachment: None,
             });
             // pass.set_bind_group(0, set_cells_bind_group, &[]);
-            pass.set_pipeline(&pipeline_cache.get_render_pipeline(pipeline.set_cells_pipeline).unwrap());
-            // Load the buffer with the cells to set.
-            pass.set_vertex_buffer(0, *vertex_buffer.slice(..));
-            pass.draw(0..set_cells.0.len() as u32, 0..1);
+            for (vertex_buffer, pipeline_id, count) in &cell_batches {
+                pass.set_pipeline(pipeline_cache.get_render_pipeline(*pipeline_id).unwrap());
+                // Load the buffer with the cells to write.
+                pass.set_vertex_buffer(0, *vertex_buffer.slice(..));
+                pass.draw(0..*count, 0..1);
+            }
         }
 
-        match self {
-            ConwayRenderNode(ConwayState::Loading) => {
-                return Ok(())
-            }
-            ConwayRenderNode(ConwayState::Init) => {
-                let mut pass = encoder.begin_compute_pass(
-                    &ComputePassDescriptor::default());
+        // Then the runs, after any points so they land in the order they were sent.
+        let write_runs_pipeline = pipeline_cache.get_compute_pipeline(pipeline.write_runs_pipeline);
+        if let (false, Some(write_runs_pipeline)) = (cell_runs.0.is_empty(), write_runs_pipeline) {
+            let packed = cell_runs.0.iter().map(CellRun::packed).collect::<Vec<_>>();
+            let runs_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+                label: Some("conway_runs_buffer"),
+                usage: BufferUsages::STORAGE,
+                contents: bytemuck::cast_slice(&packed),
+            });
+            let runs_bind_group = render_device.create_bind_group(
+                Some("conway_runs_bind_group"),
+                &pipeline.runs_bind_group_layout,
+                &[BindGroupEntry { binding: 0, resource: runs_buffer.as_entire_binding() }],
+            );
+            let mut pass = compute_pass(encoder, "write_runs");
+            pass.set_bind_group(0, texture_bind_group, &[]);
+            pass.set_bind_group(1, &runs_bind_group, &[]);
+            pass.set_pipeline(write_runs_pipeline);
+            pass.dispatch_workgroups((packed.len() as u32).div_ceil(RUNS_WORKGROUP_SIZE), 1, 1);
+            stats.dispatched("write_runs", 1);
+        }
+
+        // Then the transforms, each reading the board back from a copy in `PreviousBoard`.
+        let transform_pipeline = pipeline_cache.get_compute_pipeline(pipeline.transform_pipeline);
+        if let (false, Some(transform_pipeline)) = (board_transforms.0.is_empty(), transform_pipeline) {
+            let previous_board = world.resource::<PreviousBoard>();
+            for transform in &board_transforms.0 {
+                encoder.copy_texture_to_texture(
+                    gpu_image.texture.as_image_copy(),
+                    previous_board.texture.as_image_copy(),
+                    board_extent(size),
+                );
+                let transform_buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+                    label: Some("conway_transform_buffer"),
+                    usage: BufferUsages::STORAGE,
+                    contents: bytemuck::cast_slice(&transform.packed()),
+                });
+                let transform_bind_group = render_device.create_bind_group(
+                    Some("conway_transform_bind_group"),
+                    &pipeline.transform_bind_group_layout,
+                    &[BindGroupEntry { binding: 2, resource: transform_buffer.as_entire_binding() }],
+                );
+                let mut pass = compute_pass(encoder, "transform");
                 pass.set_bind_group(0, texture_bind_group, &[]);
-                pass.set_pipeline(&pipeline_cache.get_compute_pipeline(pipeline.init_pipeline).unwrap());
-                pass.dispatch_workgroups(SIZE.0 / WORKGROUP_SIZE.0, SIZE.1 / WORKGROUP_SIZE.1, 1);
-            } ConwayRenderNode(ConwayState::Update) => {
-                let mut pass = encoder.begin_compute_pass(
-                    &ComputePassDescriptor::default());
+                pass.set_bind_group(1, &transform_bind_group, &[]);
+                pass.set_pipeline(transform_pipeline);
+                dispatch_board(&mut pass, size);
+            }
+            stats.dispatched("transform", board_transforms.0.len() as u32);
+        }
+
+        match self.state {
+            ConwayState::Loading => {}
+            ConwayState::Init => {
+                let mut pass = compute_pass(encoder, "init");
                 pass.set_bind_group(0, texture_bind_group, &[]);
-                pass.set_pipeline(&pipeline_cache.get_compute_pipeline(pipeline.update_pipeline).unwrap());
-                pass.dispatch_workgroups(SIZE.0 / WORKGROUP_SIZE.0, SIZE.1 / WORKGROUP_SIZE.1, 1);
+                pass.set_pipeline(pipeline_cache.get_compute_pipeline(pipeline.init_pipeline).unwrap());
+                dispatch_board(&mut pass, size);
+                stats.dispatched("init", 1);
+            } ConwayState::Update => {
+                // The table pipeline may still be compiling after a rule change.
+                let Some(update_pipeline) = pipeline_cache.get_compute_pipeline(update_pipeline) else {
+                    return
+                };
+                let advect_pipeline = pipeline_cache.get_compute_pipeline(pipeline.advect_pipeline);
+                // Lenia runs in place of the rules, once its pipeline's ready.
+                let simulation_kind = *world.resource::<SimulationKind>();
+                let lenia = match simulation_kind {
+                    SimulationKind::Life | SimulationKind::Elementary => None,
+                    SimulationKind::Lenia => {
+                        let lenia_pipeline = pipeline_cache.get_compute_pipeline(pipeline.lenia_pipeline);
+                        let lenia_bind_group = world.get_resource::<LeniaBindGroup>();
+                        let (Some(lenia_pipeline), Some(lenia_bind_group)) = (lenia_pipeline, lenia_bind_group) else {
+                            return
+                        };
+                        Some((lenia_pipeline, &lenia_bind_group.0, world.resource::<LeniaTextures>()))
+                    }
+                    // The ants write through the set-cells passes above.
+                    SimulationKind::Ants => return,
+                };
+                let elementary = match simulation_kind {
+                    SimulationKind::Elementary => {
+                        let elementary_pipeline = pipeline_cache.get_compute_pipeline(pipeline.elementary_pipeline);
+                        let elementary_bind_group = world.get_resource::<ElementaryBindGroup>();
+                        let (Some(elementary_pipeline), Some(elementary_bind_group)) =
+                            (elementary_pipeline, elementary_bind_group)
+                        else {
+                            return
+                        };
+                        Some((elementary_pipeline, elementary_bind_group))
+                    }
+                    _ => None,
+                };
+                let previous_board = world.resource::<PreviousBoard>();
+                // Updating in place would race with neighbours that have
+                // already been written, so read from a copy instead.
+                let copy_to_previous = |encoder: &mut CommandEncoder| encoder.copy_texture_to_texture(
+                    gpu_image.texture.as_image_copy(),
+                    previous_board.texture.as_image_copy(),
+                    board_extent(size),
+                );
+                if simulation_state.paused && !step_request.0 {
+                    // A step of the rule into the ghost instead of the board,
+                    // leaving out the current, picking up any edits made. Idle,
+                    // there aren't any, so the last one still stands.
+                    let ghost_bind_group = world.get_resource::<GhostBindGroup>();
+                    let preview = world.resource::<GhostPreview>().0
+                        && !world.resource::<Idle>().0
+                        && lenia.is_none()
+                        && elementary.is_none();
+                    if let Some(ghost_bind_group) = ghost_bind_group.filter(|_| preview) {
+                        copy_to_previous(encoder);
+                        let mut pass = compute_pass(encoder, "ghost");
+                        pass.set_bind_group(0, &ghost_bind_group.0, &[]);
+                        pass.set_pipeline(update_pipeline);
+                        dispatch_board(&mut pass, size);
+                        stats.dispatched("ghost", 1);
+                    }
+                    return
+                }
+                let generations = simulation_state.generations_per_frame();
+                // One pass per generation so each sees the previous one's writes.
+                let debug_groups = world.resource::<DebugGroups>().0;
+                for generation in 0..generations {
+                    if debug_groups {
+                        encoder.insert_debug_marker(&format!("generation {}", generation));
+                    }
+                    copy_to_previous(encoder);
+                    if let Some((elementary_pipeline, elementary_bind_group)) = elementary {
+                        let offset = elementary_bind_group.offsets[generation as usize];
+                        let mut pass = compute_pass(encoder, "elementary");
+                        pass.set_bind_group(0, texture_bind_group, &[]);
+                        pass.set_bind_group(1, &elementary_bind_group.bind_group, &[offset]);
+                        pass.set_pipeline(elementary_pipeline);
+                        pass.dispatch_workgroups(size.x.div_ceil(ROW_WORKGROUP_SIZE), 1, 1);
+                        stats.dispatched("elementary", 1);
+                        continue;
+                    }
+                    if let Some((lenia_pipeline, lenia_bind_group, lenia_textures)) = lenia {
+                        encoder.copy_texture_to_texture(
+                            lenia_textures.state.as_image_copy(),
+                            lenia_textures.previous.as_image_copy(),
+                            board_extent(size),
+                        );
+                        let mut pass = compute_pass(encoder, "lenia");
+                        pass.set_bind_group(0, texture_bind_group, &[]);
+                        pass.set_bind_group(1, lenia_bind_group, &[]);
+                        pass.set_pipeline(lenia_pipeline);
+                        dispatch_board(&mut pass, size);
+                        stats.dispatched("lenia", 1);
+                        continue;
+                    }
+                    // The current moves cells before the rule sees them.
+                    if let Some(advect_pipeline) = advect_pipeline.filter(|_| flowing) {
+                        {
+                            let mut pass = compute_pass(encoder, "advect");
+                            pass.set_bind_group(0, texture_bind_group, &[]);
+                            pass.set_pipeline(advect_pipeline);
+                            dispatch_board(&mut pass, size);
+                        }
+                        stats.dispatched("advect", 1);
+                        copy_to_previous(encoder);
+                    }
+                    let mut pass = compute_pass(encoder, update_label);
+                    pass.set_bind_group(0, texture_bind_group, &[]);
+                    pass.set_pipeline(update_pipeline);
+                    dispatch_board(&mut pass, size);
+                    stats.dispatched(update_label, 1);
+                }
+                world.resource::<Generation>().count.fetch_add(generations as u64, Ordering::Relaxed);
             }
         }
-        Ok(())
     }
 }
 
+// What `update_living_cells` adds the board up with on the GPU.
+#[derive(SystemParam)]
+struct Reducer<'w> {
+    pipeline: Res<'w, ConwayPipeline>,
+    pipeline_cache: Res<'w, PipelineCache>,
+    bind_group: Option<Res<'w, ConwayStateBindGroup>>,
+    previous_board: Res<'w, PreviousBoard>,
+    buffers: Res<'w, ReductionBuffers>,
+}
 
+#[allow(clippy::too_many_arguments)]
 fn update_living_cells(
     render_device: Res<RenderDevice>,
     render_queue: Res<RenderQueue>,
     output_buffer: Res<OutputBuffer>,
     conway_world: Res<ConwayWorld>,
     images: Res<RenderAssets<Image>>,
-    mut living_cells: ResMut<LivingCells>
+    mut living_cells: ResMut<LivingCells>,
+    mut board: ResMut<BoardSnapshot>,
+    mut stats: ResMut<BoardStats>,
+    mut density_grid: ResMut<DensityGrid>,
+    mut live_bounds: ResMut<LiveBounds>,
+    idle: Res<Idle>,
+    simulation_kind: Res<SimulationKind>,
+    reducer: Reducer,
 ) {
+    // Nothing's changed since the last readback.
+    if idle.0 {
+        return;
+    }
     let gpu_image = images.get(&conway_world.0).unwrap();
-    let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor { label: Some("conway_encoder") });
+    let size = gpu_image.size.as_uvec2();
+    let mut encoder =
+        render_device.create_command_encoder(&CommandEncoderDescriptor { label: Some("conway_readback") });
+    // Lenia's cells are hardly ever all the way alive, so the more alive
+    // than not count instead.
+    let alive_level = match *simulation_kind {
+        SimulationKind::Life | SimulationKind::Ants | SimulationKind::Elementary => 255,
+        SimulationKind::Lenia => 128,
+    };
+
+    // The density grid and live bounds are summed up on the GPU, from a copy
+    // of the board as the update shaders read it, while the pipeline's ready.
+    let reduce_pipeline = reducer.pipeline_cache.get_compute_pipeline(reducer.pipeline.reduce_pipeline);
+    let reducing = if let (Some(reduce_pipeline), Some(bind_group)) = (reduce_pipeline, &reducer.bind_group) {
+        let mut sums = [0u32; REDUCTION_WORDS];
+        sums[0] = (alive_level as f32).to_bits();
+        // The mins start as high as they go, so they cross the maxes until a
+        // living cell brings them down.
+        sums[BOUNDS_WORD] = u32::MAX;
+        sums[BOUNDS_WORD + 1] = u32::MAX;
+        render_queue.write_buffer(&reducer.buffers.sums, 0, bytemuck::cast_slice(&sums));
+        encoder.copy_texture_to_texture(
+            gpu_image.texture.as_image_copy(),
+            reducer.previous_board.texture.as_image_copy(),
+            board_extent(size),
+        );
+        let reduce_bind_group = render_device.create_bind_group(
+            Some("conway_reduce_bind_group"),
+            &reducer.pipeline.reduce_bind_group_layout,
+            &[BindGroupEntry { binding: 8, resource: reducer.buffers.sums.as_entire_binding() }],
+        );
+        {
+            let mut pass = compute_pass(&mut encoder, "reduce");
+            pass.set_bind_group(0, &bind_group.0, &[]);
+            pass.set_bind_group(1, &reduce_bind_group, &[]);
+            pass.set_pipeline(reduce_pipeline);
+            dispatch_board(&mut pass, size);
+        }
+        let sums = &reducer.buffers.sums;
+        encoder.copy_buffer_to_buffer(sums, 0, &reducer.buffers.readback, 0, (REDUCTION_WORDS * 4) as u64);
+        true
+    } else {
+        false
+    };
 
     encoder.copy_texture_to_buffer(
         gpu_image.texture.as_image_copy(),
@@ -426,8 +3013,8 @@ fn update_living_cells(
             buffer: &output_buffer.buffer,
             layout: ImageDataLayout {
                 offset: 0,
-                bytes_per_row: Some(4 * SIZE.0),
-                rows_per_image: Some(SIZE.1),
+                bytes_per_row: Some(4 * size.x),
+                rows_per_image: Some(size.y),
             },
         },
         Extent3d {
@@ -438,27 +3025,189 @@ fn update_living_cells(
     );
     render_queue.submit(Some(encoder.finish()));
 
-    let buffer_slice = output_buffer.buffer.slice(..);
-
-    let (tx, rx) = async_channel::bounded(1);
-    render_device.map_buffer(&buffer_slice, MapMode::Read, move |result| {
-        let err = result.err();
-        if err.is_some() {
-            panic!("{}", err.unwrap().to_string());
-        }
-        tx.try_send(()).unwrap();
-    });
+    let mut buffers = vec![&output_buffer.buffer];
+    if reducing {
+        buffers.push(&reducer.buffers.readback);
+    }
+    let (tx, rx) = async_channel::bounded(buffers.len());
+    for buffer in &buffers {
+        let tx = tx.clone();
+        render_device.map_buffer(&buffer.slice(..), MapMode::Read, move |result| {
+            if let Err(err) = result {
+                panic!("{}", err);
+            }
+            tx.try_send(()).unwrap();
+        });
+    }
     render_device.wgpu_device().poll(wgpu::Maintain::Wait);
-    rx.try_recv().unwrap();
+    for _ in &buffers {
+        rx.try_recv().unwrap();
+    }
     let data = output_buffer.buffer.slice(..).get_mapped_range();
     let result = Vec::from(&*data).chunks(4).map(|x| x[0]).collect::<Vec<u8>>();
-    let n_alive = result.iter().fold(0 as u64, |acc, x| acc + (*x == 255) as u64);
+    if reducing {
+        let sums = reducer.buffers.readback.slice(..).get_mapped_range();
+        let words =
+            sums.chunks_exact(4).map(|word| u32::from_le_bytes(word.try_into().unwrap())).collect::<Vec<_>>();
+        density_grid.counts.copy_from_slice(&words[1..BOUNDS_WORD]);
+        let min = UVec2::new(words[BOUNDS_WORD], words[BOUNDS_WORD + 1]);
+        let max = UVec2::new(words[BOUNDS_WORD + 2], words[BOUNDS_WORD + 3]);
+        live_bounds.0 = (min.x <= max.x).then(|| (min.as_ivec2(), max.as_ivec2()));
+    }
+    let alive = |cell: &u8| *cell >= alive_level;
+    let n_alive = result.iter().fold(0_u64, |acc, x| acc + alive(x) as u64);
     living_cells.0 = n_alive;
+    // Compare against the previous readback to count what changed.
+    if board.cells.len() == result.len() {
+        let (births, deaths) = board.cells.iter().zip(result.iter()).fold((0, 0), |(births, deaths), (old, new)| {
+            (births + (!alive(old) && alive(new)) as u64, deaths + (alive(old) && !alive(new)) as u64)
+        });
+        *stats = BoardStats { births, deaths };
+    }
+    board.cells = result;
+    board.size = size;
+}
+
+fn report_pipelines(
+    pipeline: Res<ConwayPipeline>,
+    pipeline_cache: Res<PipelineCache>,
+    set_cells: Res<SetCells>,
+    clear_cells: Res<ClearCells>,
+    cell_runs: Res<CellRuns>,
+    cell_rects: Res<CellRects>,
+    stats: Res<PipelineStats>,
+) {
+    for (label, id) in [
+        ("init", pipeline.init_pipeline),
+        ("update", pipeline.update_pipeline),
+        ("table_update", pipeline.table_update_pipeline),
+        ("advect", pipeline.advect_pipeline),
+        ("write_runs", pipeline.write_runs_pipeline),
+        ("write_rects", pipeline.write_rects_pipeline),
+        ("transform", pipeline.transform_pipeline),
+        ("lenia", pipeline.lenia_pipeline),
+        ("elementary", pipeline.elementary_pipeline),
+        ("reduce", pipeline.reduce_pipeline),
+    ] {
+        stats.set_state(label, pipeline_cache.get_compute_pipeline_state(id));
+    }
+    for (label, id) in [("set_cells", pipeline.set_cells_pipeline), ("clear_cells", pipeline.clear_cells_pipeline)] {
+        stats.set_state(label, pipeline_cache.get_render_pipeline_state(id));
+    }
+    // Counting the cells in each run and rect, whichever way they're written.
+    let bulk_cells = |alive| {
+        let runs = cell_runs.0.iter().filter(|run| run.alive == alive).map(|run| run.length as usize);
+        let rects = cell_rects.0.iter().filter(|rect| rect.alive == alive);
+        let rects = rects.map(|rect| (rect.size.x * rect.size.y) as usize);
+        runs.chain(rects).sum::<usize>()
+    };
+    stats.finish_frame(set_cells.0.len() + bulk_cells(true), clear_cells.0.len() + bulk_cells(false));
 }
 
 fn copy_living_cells(
     render_living_cells: Res<LivingCells>,
+    render_board: Res<BoardSnapshot>,
+    render_stats: Res<BoardStats>,
+    render_density_grid: Res<DensityGrid>,
+    render_live_bounds: Res<LiveBounds>,
     mut main_world: ResMut<MainWorld>,
 ) {
     main_world.resource_mut::<LivingCells>().0 = render_living_cells.0;
+    // Until the render world's caught up with a resize, it's still reading back the old board.
+    if render_board.size == main_world.resource::<ConwayConfig>().size {
+        main_world.resource_mut::<BoardSnapshot>().cells.clone_from(&render_board.cells);
+    }
+    *main_world.resource_mut::<BoardStats>() = *render_stats;
+    *main_world.resource_mut::<DensityGrid>() = *render_density_grid;
+    *main_world.resource_mut::<LiveBounds>() = *render_live_bounds;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rects_are_clipped_to_the_board() {
+        let size = ConwayConfig::DEFAULT.size;
+        let rect = CellRect::clipped(IVec2::new(-2, size.y as i32 - 3), UVec2::new(5, 10), true, size).unwrap();
+        assert_eq!((rect.corner, rect.size), (UVec2::new(0, size.y - 3), UVec2::new(3, 3)));
+        assert_eq!(rect.cells().count(), 9);
+        assert!(CellRect::clipped(IVec2::new(-5, 0), UVec2::new(5, 5), false, size).is_none());
+        assert!(CellRect::clipped(IVec2::new(i32::MAX, 0), UVec2::new(u32::MAX, 1), true, size).is_none());
+    }
+
+    #[test]
+    fn board_configs_have_to_split_into_workgroups_and_rows() {
+        let config = ConwayConfig::fitting(UVec2::new(256, 256)).unwrap();
+        assert_eq!(config.display_scale, 720.0 / 256.0);
+        assert!(ConwayConfig::fitting(UVec2::new(4096, 4096)).is_ok());
+        assert!(ConwayConfig::fitting(UVec2::new(100, 256)).is_err());
+        assert!(ConwayConfig::fitting(UVec2::new(256, 0)).is_err());
+        assert!(ConwayConfig::new(UVec2::new(256, 256), 0.0).is_err());
+    }
+
+    #[test]
+    fn board_configs_have_to_fit_on_the_gpu() {
+        let fits = |size, limits: &WgpuLimits| ConwayConfig::fitting(size).unwrap().check_limits(limits).is_ok();
+        let limits = WgpuLimits::downlevel_webgl2_defaults();
+        assert!(fits(UVec2::new(2048, 2048), &limits));
+        assert!(!fits(UVec2::new(4096, 256), &limits));
+        // Reading the board back takes four bytes a cell.
+        let limits = WgpuLimits { max_buffer_size: 1 << 20, ..WgpuLimits::default() };
+        assert!(fits(UVec2::new(512, 512), &limits));
+        assert!(!fits(UVec2::new(1024, 512), &limits));
+    }
+
+    #[test]
+    fn board_transforms_come_back_round() {
+        let board = ConwayConfig::DEFAULT.size;
+        let size = board.as_ivec2();
+        let cells = [IVec2::ZERO, IVec2::new(17, 3), size - 1, IVec2::new(size.x / 2, size.y / 2)];
+        for cell in cells {
+            let turned = (0..4).fold(cell, |cell, _| BoardTransform::Rotate.source(cell, board));
+            assert_eq!(turned, cell);
+            for flip in [BoardTransform::FlipHorizontal, BoardTransform::FlipVertical] {
+                assert_eq!(flip.source(flip.source(cell, board), board), cell);
+            }
+        }
+        // Shifting right pulls the left edge in from the right, or from off the board.
+        let transform = |wrap| BoardTransform::Shift { offset: IVec2::new(3, -1), wrap };
+        let shift = |wrap| transform(wrap).source(IVec2::new(1, size.y - 1), board);
+        assert_eq!(shift(true), IVec2::new(size.x - 2, 0));
+        assert_eq!(shift(false), IVec2::new(-2, size.y));
+        // A quarter turn clockwise brings the middle row, half the board's height
+        // left of centre, up to the top.
+        let centre = size / 2;
+        let top = BoardTransform::Rotate.source(IVec2::new(centre.x - 1, 0), board);
+        assert_eq!(top, IVec2::new(centre.x - size.y / 2, centre.y));
+    }
+
+    #[test]
+    fn resampling_takes_after_the_nearest_cell() {
+        let cells = [1, 2, 3, 4];
+        let grown = resample(&cells, UVec2::new(2, 2), UVec2::new(4, 2));
+        assert_eq!(grown, [1, 1, 2, 2, 3, 3, 4, 4]);
+        assert_eq!(resample(&grown, UVec2::new(4, 2), UVec2::new(2, 1)), [1, 2]);
+    }
+
+    #[test]
+    fn edges_fold_cells_back_onto_the_board() {
+        let size = IVec2::new(8, 5);
+        let past = [IVec2::new(-1, 2), IVec2::new(8, 4), IVec2::new(3, -1)];
+        let fold = |edges: Edges| past.map(|cell| edges.fold(cell, size));
+        assert_eq!(fold(Edges::Dead), past);
+        assert_eq!(fold(Edges::Wrap), [IVec2::new(7, 2), IVec2::new(0, 4), IVec2::new(3, 4)]);
+        assert_eq!(fold(Edges::Mirror), [IVec2::new(0, 2), IVec2::new(7, 4), IVec2::new(3, 0)]);
+        assert_eq!(Edges::Mirror.fold(IVec2::new(2, 3), size), IVec2::new(2, 3));
+    }
+
+    #[test]
+    fn cropping_keeps_cells_in_place_and_pads_with_empty_ones() {
+        let cells = [1, 2, 3, 4, 5, 6];
+        let cropped = crop(&cells, UVec2::new(3, 2), IVec2::new(1, 0), UVec2::new(2, 2));
+        assert_eq!(cropped, [2, 3, 5, 6]);
+        let padded = crop(&cells, UVec2::new(3, 2), IVec2::new(-1, 1), UVec2::new(3, 2));
+        assert_eq!(padded, [0, 4, 5, 0, 0, 0]);
+        assert_eq!(ConwayConfig::padded(UVec2::new(65, 3)), UVec2::new(128, 8));
+    }
 }
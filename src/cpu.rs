@@ -0,0 +1,201 @@
+// A CPU implementation of the board, following the same rules as the compute
+// shader. Cells are stored one byte each with 255 meaning alive, like `BoardSnapshot`.
+//
+// Stepping packs each row into 64-bit words, a cell a bit, and works out the
+// next generation 64 cells at a time: the eight neighbours of every cell in a
+// word are dealt out as shifted copies of the rows above, below and itself,
+// summed with bitwise adders into a 4-bit count per cell, bit-sliced across
+// four words, and matched against the rule's counts. Bands of rows step in
+// parallel on rayon's pool, so even a full-size board keeps up. Past the
+// edges, rows come from wherever `Edges` folds them to, and each row's cells
+// either side of it ride along as an extra bit at each end.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use bevy::prelude::*;
+use rand::Rng;
+use rayon::prelude::*;
+
+use crate::conway::{Edges, WORKGROUP_SIZE};
+use crate::life_rule::LifeRule;
+
+// Rows stepped together on one thread.
+const BAND_ROWS: usize = 16;
+
+#[derive(Clone)]
+pub struct CpuBoard {
+    pub width: u32,
+    pub height: u32,
+    pub cells: Vec<u8>,
+}
+
+// (sum, carry) of three bit-sliced one-bit numbers.
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let partial = a ^ b;
+    (partial ^ c, (a & b) | (c & partial))
+}
+
+// The count of the eight neighbours of each cell in a word, as its four
+// bits, lowest first, from the words at and either side of it in the rows
+// above and below and its own.
+fn count_neighbors(above: [u64; 3], row: [u64; 3], below: [u64; 3]) -> [u64; 4] {
+    // The neighbours to the west of each cell, from the word before's top
+    // bit, and likewise east.
+    let west = |[before, word, _]: [u64; 3]| word << 1 | before >> 63;
+    let east = |[_, word, after]: [u64; 3]| word >> 1 | after << 63;
+    let (sum_a, carry_a) = full_add(west(above), above[1], east(above));
+    let (sum_b, carry_b) = full_add(west(below), below[1], east(below));
+    let (sum_c, carry_c) = (west(row) ^ east(row), west(row) & east(row));
+    let (ones, carry_d) = full_add(sum_a, sum_b, sum_c);
+    let (twos, carry_e) = full_add(carry_a, carry_b, carry_c);
+    let (twos, carry_f) = (twos ^ carry_d, twos & carry_d);
+    [ones, twos, carry_e ^ carry_f, carry_e & carry_f]
+}
+
+// `hash` in the shader.
+fn shader_hash(value: u32) -> u32 {
+    let mut state = value ^ 2747636419;
+    state = state.wrapping_mul(2654435769);
+    state ^= state >> 16;
+    state = state.wrapping_mul(2654435769);
+    state ^= state >> 16;
+    state.wrapping_mul(2654435769)
+}
+
+// The cells in a word with `n` neighbours, from their bit-sliced counts.
+fn with_count(counts: &[u64; 4], n: u32) -> u64 {
+    counts.iter().enumerate().fold(!0, |matches, (bit, slice)| {
+        matches & if n >> bit & 1 == 1 { *slice } else { !*slice }
+    })
+}
+
+impl CpuBoard {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height, cells: vec![0; (width * height) as usize] }
+    }
+
+    /// Fills the board with a random soup where each cell is alive with probability `density`.
+    pub fn seed(&mut self, density: f32, rng: &mut impl Rng) {
+        for cell in self.cells.iter_mut() {
+            *cell = if rng.gen::<f32>() < density { 255 } else { 0 };
+        }
+    }
+
+    /// Fills the board with the soup the `init` shader seeds a board this
+    /// size with from `seed`, walls aside.
+    pub fn seed_like_gpu(&mut self, density: f32, seed: u32) {
+        let (width, workgroups) = (self.width, self.width / WORKGROUP_SIZE.0);
+        for (index, cell) in self.cells.iter_mut().enumerate() {
+            let (x, y) = (index as u32 % width, index as u32 / width);
+            let value = shader_hash(shader_hash(seed).wrapping_add(x.wrapping_mul(workgroups)).wrapping_add(y));
+            *cell = if (value as f32 / 4294967295.0) < density { 255 } else { 0 };
+        }
+    }
+
+    pub fn index(&self, cell: IVec2) -> Option<usize> {
+        if cell.x < 0 || cell.y < 0 || cell.x >= self.width as i32 || cell.y >= self.height as i32 {
+            return None;
+        }
+        Some((cell.y as u32 * self.width + cell.x as u32) as usize)
+    }
+
+    // Cells beyond the edges are dead, as they are in the shader.
+    pub fn is_alive(&self, cell: IVec2) -> bool {
+        self.index(cell).is_some_and(|i| self.cells[i] == 255)
+    }
+
+    /// The indices of the living cells, in order.
+    pub fn alive_indices(&self) -> Vec<usize> {
+        self.cells.iter().enumerate().filter(|(_, cell)| **cell == 255).map(|(i, _)| i).collect()
+    }
+
+    pub fn set(&mut self, cell: IVec2, alive: bool) {
+        if let Some(i) = self.index(cell) {
+            self.cells[i] = if alive { 255 } else { 0 };
+        }
+    }
+
+    /// Advances the board by one generation of B3/S23.
+    pub fn step(&mut self) {
+        self.step_with(&LifeRule::CONWAY);
+    }
+
+    /// Advances the board by one generation of `rule`, which has to be one
+    /// `LifeRule::for_cpu` lets through.
+    pub fn step_with(&mut self, rule: &LifeRule) {
+        self.step_with_edges(rule, Edges::Dead);
+    }
+
+    /// The same, with `edges` past the sides of the board.
+    pub fn step_with_edges(&mut self, rule: &LifeRule, edges: Edges) {
+        debug_assert!(rule.larger.is_none(), "{} can't run on the CPU", rule);
+        let width = self.width as usize;
+        if width == 0 || self.height == 0 {
+            return;
+        }
+        let size = IVec2::new(self.width as i32, self.height as i32);
+        // Whether the cells just past the left and right of each row read as alive.
+        let sides = (0..size.y)
+            .map(|y| [-1, size.x].map(|x| self.is_alive(edges.fold(IVec2::new(x, y), size))))
+            .collect::<Vec<_>>();
+        let words = width.div_ceil(64);
+        let mut packed = vec![0u64; words * self.height as usize];
+        let rows = packed.par_chunks_mut(words).zip(self.cells.par_chunks(width)).zip(&sides);
+        rows.for_each(|((packed_row, row), [_, right])| {
+            for (x, cell) in row.iter().enumerate() {
+                packed_row[x / 64] |= ((*cell == 255) as u64) << (x % 64);
+            }
+            // The cell past the right fits after the row's last, unless that fills its word.
+            if !width.is_multiple_of(64) {
+                packed_row[words - 1] |= (*right as u64) << (width % 64);
+            }
+        });
+
+        // Rows past the top and bottom are read from where the edges fold
+        // them to, and the words either side of a row hold just the cell
+        // past each end.
+        let word = |y: isize, w: isize| {
+            let y = edges.fold(IVec2::new(0, y as i32), size).y;
+            if y < 0 || y >= size.y {
+                return 0;
+            }
+            let y = y as usize;
+            match w {
+                -1 => (sides[y][0] as u64) << 63,
+                w if w == words as isize && width.is_multiple_of(64) => sides[y][1] as u64,
+                w if w < 0 || w >= words as isize => 0,
+                w => packed[y * words + w as usize],
+            }
+        };
+        let neighborhood = |y: isize, w: isize| [word(y, w - 1), word(y, w), word(y, w + 1)];
+        let mut next = vec![0; self.cells.len()];
+        next.par_chunks_mut(width * BAND_ROWS).enumerate().for_each(|(band, rows)| {
+            for (i, row) in rows.chunks_mut(width).enumerate() {
+                let y = (band * BAND_ROWS + i) as isize;
+                for (w, cells) in row.chunks_mut(64).enumerate() {
+                    let w = w as isize;
+                    let counts = count_neighbors(neighborhood(y - 1, w), neighborhood(y, w), neighborhood(y + 1, w));
+                    let alive = word(y, w);
+                    let next_word = (0..=8).fold(0, |next_word, n| {
+                        let born = if rule.birth & 1 << n != 0 { !alive } else { 0 };
+                        let survives = if rule.survival & 1 << n != 0 { alive } else { 0 };
+                        next_word | with_count(&counts, n) & (born | survives)
+                    });
+                    for (bit, cell) in cells.iter_mut().enumerate() {
+                        *cell = if next_word >> bit & 1 == 1 { 255 } else { 0 };
+                    }
+                }
+            }
+        });
+        self.cells = next;
+    }
+}
+
+/// A hash of a board's cells, one byte each, for spotting when it repeats
+/// itself. It's only good within a run; `snapshot::board_hash` is stable.
+pub fn cells_hash(cells: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    cells.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,134 @@
+// A cursor for each tool, so it's plain which one's in hand: the system
+// cursors where they fit (a crosshair for painting, a text beam for the text
+// tool and so on), and an eraser drawn in their place while Alt's held. With
+// a brush wider than a cell, its outline follows the cursor too.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+use bevy::window::{CursorIcon, PrimaryWindow};
+
+use crate::conway::ConwayConfig;
+use crate::tools::{self, BoardCursor, Brush, Tool};
+use crate::views::BoardCamera;
+
+pub struct CursorsPlugin;
+
+// The eraser's size in pixels, and its colours from the rubber to the sleeve.
+const ERASER_SIZE: (u32, u32) = (14, 22);
+const RUBBER: [u8; 4] = [240, 130, 150, 255];
+const SLEEVE: [u8; 4] = [90, 130, 220, 255];
+const OUTLINE: [u8; 4] = [20, 20, 20, 255];
+const BRUSH_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.6);
+
+#[derive(Component)]
+struct EraserCursor;
+
+/// The system cursor shown over the board for a tool.
+pub fn tool_icon(tool: Tool) -> CursorIcon {
+    match tool {
+        Tool::Cells | Tool::Soup | Tool::Ruler | Tool::Ant => CursorIcon::Crosshair,
+        Tool::Walls | Tool::Freeze => CursorIcon::Cell,
+        Tool::Portal => CursorIcon::Alias,
+        Tool::Inspect => CursorIcon::Help,
+        Tool::Text => CursorIcon::Text,
+        Tool::Flow => CursorIcon::Move,
+    }
+}
+
+// An eraser, rubber end down, in an outline, with the sleeve over the top
+// two thirds.
+fn eraser_image() -> Image {
+    let (width, height) = ERASER_SIZE;
+    let mut data = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let edge = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+            data.extend(match (edge, y < height * 2 / 3) {
+                (true, _) => OUTLINE,
+                (false, true) => SLEEVE,
+                (false, false) => RUBBER,
+            });
+        }
+    }
+    let mut image = Image::new(
+        Extent3d { width, height, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    commands.spawn((
+        EraserCursor,
+        SpriteBundle { texture: images.add(eraser_image()), visibility: Visibility::Hidden, ..default() },
+    ));
+}
+
+fn set_icon(
+    tool: Res<Tool>,
+    keys: Res<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+    let icon = if mouse_button_input.pressed(MouseButton::Middle) { CursorIcon::Grabbing } else { tool_icon(*tool) };
+    // The eraser's drawn instead.
+    let visible = !tools::erasing(&keys);
+    if window.cursor.icon != icon {
+        window.cursor.icon = icon;
+    }
+    if window.cursor.visible != visible {
+        window.cursor.visible = visible;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn follow_cursor(
+    keys: Res<Input<KeyCode>>,
+    tool: Res<Tool>,
+    brush: Res<Brush>,
+    cursor: BoardCursor,
+    config: Res<ConwayConfig>,
+    cameras: Query<&OrthographicProjection, With<BoardCamera>>,
+    mut erasers: Query<(&mut Transform, &mut Visibility), With<EraserCursor>>,
+    mut gizmos: Gizmos,
+) {
+    let Ok((mut transform, mut visibility)) = erasers.get_single_mut() else {
+        return;
+    };
+    let Some(position) = cursor.world() else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    if tools::erasing(&keys) {
+        // The same size on screen however far in the camera is, with the
+        // rubber's corner at the cursor.
+        let scale = cameras.get_single().map_or(1.0, |projection| projection.scale);
+        let offset = Vec2::new(ERASER_SIZE.0 as f32, ERASER_SIZE.1 as f32) / 2.0 * scale;
+        transform.translation = (position + offset).extend(10.0);
+        transform.scale = Vec3::new(scale, scale, 1.0);
+        *visibility = Visibility::Visible;
+    } else {
+        *visibility = Visibility::Hidden;
+    }
+    if *tool == Tool::Cells && brush.radius > 0 {
+        let cell = config.world_to_cell(position);
+        let center = config.cell_to_world(cell) + Vec2::new(0.5, -0.5) * config.display_scale;
+        gizmos.circle_2d(center, (brush.radius as f32 + 0.5) * config.display_scale, BRUSH_COLOR);
+    }
+}
+
+impl Plugin for CursorsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Startup, setup)
+        .add_systems(Update, (set_icon, follow_cursor))
+        ;
+    }
+}
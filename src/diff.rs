@@ -0,0 +1,178 @@
+// Compares two boards, like saved states or a saved state and the live board:
+// an overlay covers the board with the cells only in A red, only in B green
+// and in both white, and the inspector counts them. Handy for checking two
+// backends agree or finding where two runs diverged.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+
+use crate::conway::{BoardResized, BoardSnapshot, ConwayConfig};
+use crate::inspector::Inspector;
+
+pub struct DiffPlugin;
+
+const SECTION: &str = "Diff";
+const ONLY_A_COLOR: [u8; 4] = [230, 40, 40, 255];
+const ONLY_B_COLOR: [u8; 4] = [40, 220, 60, 255];
+const BOTH_COLOR: [u8; 4] = [255, 255, 255, 255];
+// Covers the board, so cells in neither don't show through.
+const NEITHER_COLOR: [u8; 4] = [0, 0, 0, 255];
+
+#[derive(Event, Clone, Debug)]
+pub enum DiffCommand {
+    // Compare `a` against `b`, or against the live board without it.
+    Show { a: Vec<IVec2>, b: Option<Vec<IVec2>> },
+    Hide,
+}
+
+// The boards being compared, one entry per cell row by row.
+struct Comparison {
+    a: Vec<bool>,
+    b: Option<Vec<bool>>,
+}
+
+#[derive(Resource, Default)]
+struct DiffView {
+    comparison: Option<Comparison>,
+    overlay: Option<(Entity, Handle<Image>)>,
+}
+
+fn board_cells(cells: &[IVec2], size: UVec2) -> Vec<bool> {
+    let mut board = vec![false; (size.x * size.y) as usize];
+    for cell in cells {
+        if cell.x >= 0 && cell.y >= 0 && (cell.x as u32) < size.x && (cell.y as u32) < size.y {
+            board[(cell.y as u32 * size.x + cell.x as u32) as usize] = true;
+        }
+    }
+    board
+}
+
+fn handle_commands(
+    mut commands: Commands,
+    mut diff_commands: EventReader<DiffCommand>,
+    config: Res<ConwayConfig>,
+    mut view: ResMut<DiffView>,
+    mut images: ResMut<Assets<Image>>,
+    mut visibilities: Query<&mut Visibility>,
+    mut inspector: ResMut<Inspector>,
+) {
+    for command in diff_commands.read() {
+        let shown = match command {
+            DiffCommand::Show { a, b } => {
+                let (a, b) = (board_cells(a, config.size), b.as_deref().map(|b| board_cells(b, config.size)));
+                view.comparison = Some(Comparison { a, b });
+                true
+            }
+            DiffCommand::Hide => {
+                view.comparison = None;
+                inspector.remove(SECTION);
+                false
+            }
+        };
+        match &view.overlay {
+            Some((entity, _)) => {
+                if let Ok(mut visibility) = visibilities.get_mut(*entity) {
+                    *visibility = if shown { Visibility::Visible } else { Visibility::Hidden };
+                }
+            }
+            None if shown => {
+                let size = config.size;
+                let mut image = Image::new_fill(
+                    Extent3d { width: size.x, height: size.y, depth_or_array_layers: 1 },
+                    TextureDimension::D2,
+                    &NEITHER_COLOR,
+                    TextureFormat::Rgba8UnormSrgb,
+                );
+                image.sampler = ImageSampler::nearest();
+                let handle = images.add(image);
+                let entity = commands
+                    .spawn(SpriteBundle {
+                        texture: handle.clone(),
+                        transform: Transform {
+                            // Over the board and the masks.
+                            translation: Vec3::new(0.0, 0.0, 1.5),
+                            scale: Vec3::new(config.display_scale, config.display_scale, 1.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .id();
+                view.overlay = Some((entity, handle));
+            }
+            None => {}
+        }
+    }
+}
+
+fn update_overlay(
+    view: Res<DiffView>,
+    board: Res<BoardSnapshot>,
+    mut images: ResMut<Assets<Image>>,
+    mut inspector: ResMut<Inspector>,
+) {
+    let (Some(comparison), Some((_, handle))) = (&view.comparison, &view.overlay) else {
+        return;
+    };
+    // Against the live board, follow it as it changes.
+    if !view.is_changed() && (comparison.b.is_some() || !board.is_changed()) {
+        return;
+    }
+    let Some(image) = images.get_mut(handle) else {
+        return;
+    };
+    let live = comparison.b.is_none().then(|| board.cells.iter().map(|cell| *cell == 255).collect::<Vec<_>>());
+    let b = comparison.b.as_ref().or(live.as_ref()).unwrap();
+    let mut counts = [0u64; 3];
+    for ((pixel, a), b) in image.data.chunks_exact_mut(4).zip(&comparison.a).zip(b) {
+        let color = match (*a, *b) {
+            (true, false) => {
+                counts[0] += 1;
+                ONLY_A_COLOR
+            }
+            (false, true) => {
+                counts[1] += 1;
+                ONLY_B_COLOR
+            }
+            (true, true) => {
+                counts[2] += 1;
+                BOTH_COLOR
+            }
+            (false, false) => NEITHER_COLOR,
+        };
+        pixel.copy_from_slice(&color);
+    }
+    let b_name = if comparison.b.is_some() { "B" } else { "the board" };
+    inspector.set(SECTION, format!(
+        "only in A: {}\nonly in {}: {}\nin both: {}",
+        counts[0], b_name, counts[1], counts[2],
+    ));
+}
+
+// What was compared was the old board's size, so a resize puts it away. The
+// next comparison gets an overlay the new size.
+fn stop_on_resize(
+    mut commands: Commands,
+    mut resized: EventReader<BoardResized>,
+    mut view: ResMut<DiffView>,
+    mut inspector: ResMut<Inspector>,
+) {
+    if resized.read().count() == 0 {
+        return;
+    }
+    if let Some((entity, _)) = view.overlay.take() {
+        commands.entity(entity).despawn();
+    }
+    view.comparison = None;
+    inspector.remove(SECTION);
+}
+
+impl Plugin for DiffPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<DiffCommand>()
+        .init_resource::<DiffView>()
+        .add_systems(Update, (stop_on_resize, handle_commands, update_overlay).chain())
+        ;
+    }
+}
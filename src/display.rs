@@ -0,0 +1,262 @@
+// Options for how the board is drawn, beyond what the board sprite does on
+// its own. While any of them is on, a quad drawing the board with them stands in
+// for the sprite.
+//
+// Supersampling: the sprite samples its texture at one point per pixel, so at
+// zooms and window sizes that aren't a whole number of pixels per cell, cells
+// shimmer as they move. Supersampling averages N x N points across each
+// pixel's footprint on the board instead.
+//
+// State encoding: the sprite shows the board's gray levels as linear values,
+// which an sRGB surface brightens on the way out, washing out multi-state
+// rules. Treating them as sRGB instead decodes them first, the way an
+// `Rgba8UnormSrgb` texture would be read. The board itself can't be sRGB,
+// since the compute shaders write to it as a storage texture.
+//
+// Level of detail: once a pixel covers more than a cell each way, reading
+// single cells turns the board into noise. With `LodSettings` on, those
+// pixels read the level of the board's mip chain (see `lod.rs`) that matches
+// instead, showing how dense each part of the board is.
+//
+// Fate (F): live cells are colored by what happens to them next generation,
+// green if they survive, blue if they die of loneliness and red if they die
+// of overcrowding, from their neighbours under the current rule. A cell dying
+// with fewer neighbours than it could survive with is lonely. Multi-state and
+// von Neumann rules have no counts to go by, so every live cell shows as
+// lonely under them.
+//
+// Decay: under a `RuleString` with decay states, dying cells are colored from
+// orange just after they die to purple as they're about to go, rather than
+// fading out in gray.
+
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef, ShaderType};
+use bevy::sprite::{Material2d, Material2dPlugin, MaterialMesh2dBundle, Mesh2dHandle};
+
+use crate::conway::{ActiveRuleTable, BoardResized, BoardSprite, ConwayConfig, ConwayWorld, Edges, RuleString};
+use crate::event_log::LogEvent;
+use crate::lod::{BoardLod, LodSettings};
+use crate::rule_table::{Neighborhood, RuleTable};
+
+pub struct DisplayPlugin;
+
+pub const MAX_SAMPLES: u32 = 4;
+
+/// Points sampled across each pixel, per axis. 1 leaves it to the sprite.
+#[derive(Resource)]
+pub struct Supersampling(pub u32);
+
+impl Default for Supersampling {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+/// How the board's gray levels are turned into colors.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StateEncoding {
+    // As they are, like the sprite.
+    #[default]
+    Linear,
+    // Decoded from sRGB first.
+    Srgb,
+}
+
+/// Whether live cells are colored by their fate next generation.
+#[derive(Resource, Default)]
+pub struct FatePreview {
+    pub enabled: bool,
+}
+
+/// The neighbour counts a live cell survives with under `rule_table`, or the
+/// `RuleString` without one, as a bit per count. Only two-state Moore rules
+/// have counts to go by, so anything else survives with none.
+pub fn survival_counts(rule_table: &ActiveRuleTable, rule_string: &RuleString) -> u32 {
+    let Some(table) = &rule_table.0 else {
+        return rule_string.0.survival as u32;
+    };
+    if table.states != 2 || table.neighborhood != Neighborhood::Moore {
+        return 0;
+    }
+    (0..=8).filter(|n| survives(table, *n)).fold(0, |mask, n| mask | 1 << n)
+}
+
+// Whether a live cell with the first `n` of its neighbours alive survives.
+fn survives(table: &RuleTable, n: usize) -> bool {
+    let states = std::iter::once(1).chain((0..8).map(|i| (i < n) as u32)).collect::<Vec<_>>();
+    table.next_state(&states) == 1
+}
+
+#[derive(ShaderType, Debug, Clone)]
+struct DisplaySettings {
+    tint: Vec4,
+    samples: u32,
+    // 1 to decode the board from sRGB.
+    srgb: u32,
+    // 1 to read the mip chain when zoomed out.
+    lod: u32,
+    // 1 to color live cells by their fate, from the `survival` counts.
+    fate: u32,
+    survival: u32,
+    // The `RuleString`'s state count while dying cells are colored, or 0.
+    decay_states: u32,
+    // `Edges::code`, for finding the neighbours of cells along the edges.
+    edges: u32,
+}
+
+#[derive(AsBindGroup, Asset, TypePath, Debug, Clone)]
+struct DisplayMaterial {
+    #[uniform(0)]
+    settings: DisplaySettings,
+    // Read with `textureLoad`, so no sampler.
+    #[texture(1)]
+    board: Handle<Image>,
+    #[texture(2)]
+    mips: Handle<Image>,
+}
+
+impl Material2d for DisplayMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/display.wgsl".into()
+    }
+}
+
+// The quad drawn instead of the board sprite, spawned the first time it's needed.
+#[derive(Resource)]
+struct DisplayedBoard {
+    entity: Entity,
+    material: Handle<DisplayMaterial>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn show_board(
+    mut commands: Commands,
+    supersampling: Res<Supersampling>,
+    encoding: Res<StateEncoding>,
+    lod_settings: Res<LodSettings>,
+    fate_preview: Res<FatePreview>,
+    rule_table: Res<ActiveRuleTable>,
+    rule_string: Res<RuleString>,
+    edges: Res<Edges>,
+    config: Res<ConwayConfig>,
+    board: Option<Res<ConwayWorld>>,
+    mips: Option<Res<BoardLod>>,
+    displayed: Option<Res<DisplayedBoard>>,
+    mut sprites: Query<(&Sprite, &mut Visibility), With<BoardSprite>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<DisplayMaterial>>,
+    mut visibilities: Query<&mut Visibility, Without<BoardSprite>>,
+) {
+    let (Some(board), Some(mips)) = (board, mips) else {
+        return;
+    };
+    let changed = [
+        supersampling.is_changed(),
+        encoding.is_changed(),
+        lod_settings.is_changed(),
+        fate_preview.is_changed(),
+        rule_table.is_changed(),
+        rule_string.is_changed(),
+        edges.is_changed(),
+    ];
+    if !changed.contains(&true) {
+        return;
+    }
+    let samples = supersampling.0.clamp(1, MAX_SAMPLES);
+    let srgb = (*encoding == StateEncoding::Srgb) as u32;
+    let lod = lod_settings.enabled as u32;
+    let fate = fate_preview.enabled as u32;
+    let survival = survival_counts(&rule_table, &rule_string);
+    let decay_states = if rule_table.0.is_none() && rule_string.0.decays() { rule_string.0.states } else { 0 };
+    let edges = edges.code();
+    let shown = samples > 1 || srgb == 1 || lod == 1 || fate == 1 || decay_states > 0;
+    let Ok((sprite, mut sprite_visibility)) = sprites.get_single_mut() else {
+        return;
+    };
+    *sprite_visibility = if shown { Visibility::Hidden } else { Visibility::Visible };
+    let tint = sprite.color.as_linear_rgba_f32().into();
+    match displayed {
+        Some(displayed) => {
+            if let Some(material) = materials.get_mut(&displayed.material) {
+                material.settings.samples = samples;
+                material.settings.srgb = srgb;
+                material.settings.lod = lod;
+                material.settings.fate = fate;
+                material.settings.survival = survival;
+                material.settings.decay_states = decay_states;
+                material.settings.edges = edges;
+            }
+            if let Ok(mut visibility) = visibilities.get_mut(displayed.entity) {
+                *visibility = if shown { Visibility::Visible } else { Visibility::Hidden };
+            }
+        }
+        None if shown => {
+            let material = materials.add(DisplayMaterial {
+                settings: DisplaySettings { tint, samples, srgb, lod, fate, survival, decay_states, edges },
+                board: board.0.clone(),
+                mips: mips.0.clone(),
+            });
+            let entity = commands
+                .spawn(MaterialMesh2dBundle {
+                    mesh: meshes.add(shape::Quad::new(config.extents()).into()).into(),
+                    material: material.clone(),
+                    // Where the sprite is, under the overlays.
+                    transform: Transform::from_xyz(0.0, 0.0, 0.0),
+                    ..default()
+                })
+                .id();
+            commands.insert_resource(DisplayedBoard { entity, material });
+        }
+        None => {}
+    }
+}
+
+fn toggle_fate(keys: Res<Input<KeyCode>>, mut fate_preview: ResMut<FatePreview>, mut log: EventWriter<LogEvent>) {
+    if keys.just_pressed(KeyCode::F) {
+        fate_preview.enabled = !fate_preview.enabled;
+        log.send(LogEvent(format!("Fate preview {}", if fate_preview.enabled { "on" } else { "off" })));
+    }
+}
+
+// A resized board can be a new shape, so the quad's made again to match.
+fn fit_board(
+    mut resized: EventReader<BoardResized>,
+    displayed: Option<Res<DisplayedBoard>>,
+    config: Res<ConwayConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut quads: Query<&mut Mesh2dHandle>,
+) {
+    let (true, Some(displayed)) = (resized.read().count() > 0, displayed) else {
+        return;
+    };
+    if let Ok(mut quad) = quads.get_mut(displayed.entity) {
+        *quad = meshes.add(shape::Quad::new(config.extents()).into()).into();
+    }
+}
+
+fn follow_tint(
+    displayed: Option<Res<DisplayedBoard>>,
+    sprites: Query<&Sprite, (With<BoardSprite>, Changed<Sprite>)>,
+    mut materials: ResMut<Assets<DisplayMaterial>>,
+) {
+    let (Some(displayed), Ok(sprite)) = (displayed, sprites.get_single()) else {
+        return;
+    };
+    if let Some(material) = materials.get_mut(&displayed.material) {
+        material.settings.tint = sprite.color.as_linear_rgba_f32().into();
+    }
+}
+
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Supersampling>()
+        .init_resource::<StateEncoding>()
+        .init_resource::<FatePreview>()
+        .add_plugins(Material2dPlugin::<DisplayMaterial>::default())
+        .add_systems(Update, (toggle_fate, show_board, follow_tint).chain())
+        .add_systems(Update, fit_board)
+        ;
+    }
+}
@@ -0,0 +1,84 @@
+// A plugin that shows a scrolling on-screen log of recent notable events,
+// fading each one out after a few seconds.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+pub struct EventLogPlugin;
+
+/// Send this to add a line to the event log.
+#[derive(Event)]
+pub struct LogEvent(pub String);
+
+#[derive(Resource, Default)]
+struct EventLog {
+    // Oldest first, each with the time it has left on screen.
+    entries: VecDeque<(String, Timer)>,
+}
+
+#[derive(Component)]
+struct EventLogText;
+
+const TEXT_FONT_SIZE: f32 = 24.0;
+const MAX_ENTRIES: usize = 8;
+const ENTRY_SECONDS: f32 = 6.0;
+// Entries fade out over the end of their time on screen.
+const FADE_SECONDS: f32 = 2.0;
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        EventLogText,
+        TextBundle::default().with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(5.0),
+            left: Val::Px(5.0),
+            ..default()
+        }),
+    ));
+}
+
+fn add_entries(mut events: EventReader<LogEvent>, mut log: ResMut<EventLog>) {
+    for event in events.read() {
+        log.entries.push_back((event.0.clone(), Timer::from_seconds(ENTRY_SECONDS, TimerMode::Once)));
+        if log.entries.len() > MAX_ENTRIES {
+            log.entries.pop_front();
+        }
+    }
+}
+
+fn expire_entries(time: Res<Time>, mut log: ResMut<EventLog>) {
+    for (_, timer) in log.entries.iter_mut() {
+        timer.tick(time.delta());
+    }
+    log.entries.retain(|(_, timer)| !timer.finished());
+}
+
+fn render_log(mut query: Query<&mut Text, With<EventLogText>>, log: Res<EventLog>) {
+    let mut text = query.single_mut();
+    text.sections = log.entries
+        .iter()
+        .map(|(line, timer)| {
+            let alpha = (timer.remaining_secs() / FADE_SECONDS).min(1.0);
+            TextSection::new(
+                format!("{}\n", line),
+                TextStyle {
+                    font_size: TEXT_FONT_SIZE,
+                    color: Color::rgba(1.0, 1.0, 1.0, alpha),
+                    ..Default::default()
+                },
+            )
+        })
+        .collect();
+}
+
+impl Plugin for EventLogPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<LogEvent>()
+        .init_resource::<EventLog>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, (add_entries, expire_entries, render_log).chain())
+        ;
+    }
+}
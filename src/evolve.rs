@@ -0,0 +1,215 @@
+// Genetic search (`evolve SPEC.toml`): evolves small seed patterns that score
+// well on an objective, running every candidate headlessly on the CPU. The
+// best finds are saved to the pattern library, where they can be stamped like
+// the built-in patterns.
+//
+//   objective = "longevity"   # or "population" or "growth"
+//   population = 32           # candidates per round
+//   rounds = 20
+//   side = 8                  # seeds are side x side cells
+//   max_generations = 1000    # how long each candidate runs, at most
+//   board = 96                # the side of the board candidates run on
+//   keep = 3                  # finds saved to the library
+//
+// Everything but the objective is optional. Runs are reproducible from `--seed`.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::Deserialize;
+
+use crate::cpu::{self, CpuBoard};
+use crate::formats;
+use crate::life_rule::LifeRule;
+use crate::patterns;
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(rename_all = "lowercase")]
+enum Objective {
+    // Generations before the pattern settles into a cycle.
+    Longevity,
+    // Live cells at the end of the run.
+    Population,
+    // How much bigger the bounding box ends up than the seed.
+    Growth,
+}
+
+impl Objective {
+    fn name(&self) -> &'static str {
+        match self {
+            Objective::Longevity => "longevity",
+            Objective::Population => "population",
+            Objective::Growth => "growth",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct EvolveSpec {
+    objective: Objective,
+    #[serde(default = "default_population")]
+    population: usize,
+    #[serde(default = "default_rounds")]
+    rounds: u32,
+    #[serde(default = "default_side")]
+    side: u32,
+    #[serde(default = "default_max_generations")]
+    max_generations: u32,
+    #[serde(default = "default_board")]
+    board: u32,
+    #[serde(default = "default_keep")]
+    keep: usize,
+}
+
+fn default_population() -> usize {
+    32
+}
+
+fn default_rounds() -> u32 {
+    20
+}
+
+fn default_side() -> u32 {
+    8
+}
+
+fn default_max_generations() -> u32 {
+    1000
+}
+
+fn default_board() -> u32 {
+    96
+}
+
+fn default_keep() -> usize {
+    3
+}
+
+// Candidates carried over unchanged into the next round.
+const ELITES: usize = 2;
+// Candidates picked for each tournament when choosing parents.
+const TOURNAMENT_SIZE: usize = 3;
+const INITIAL_DENSITY: f64 = 0.4;
+
+// A seed pattern, one entry per cell of the `side` x `side` square.
+type Genome = Vec<bool>;
+
+fn cells(genome: &Genome, side: u32) -> Vec<IVec2> {
+    genome
+        .iter()
+        .enumerate()
+        .filter(|(_, alive)| **alive)
+        .map(|(i, _)| IVec2::new((i as u32 % side) as i32, (i as u32 / side) as i32))
+        .collect()
+}
+
+fn bounding_area(board: &CpuBoard) -> u64 {
+    let mut min = IVec2::MAX;
+    let mut max = IVec2::MIN;
+    for y in 0..board.height as i32 {
+        for x in 0..board.width as i32 {
+            if board.is_alive(IVec2::new(x, y)) {
+                min = min.min(IVec2::new(x, y));
+                max = max.max(IVec2::new(x, y));
+            }
+        }
+    }
+    if min.x > max.x {
+        return 0;
+    }
+    let size = max - min + IVec2::ONE;
+    size.x as u64 * size.y as u64
+}
+
+fn fitness(genome: &Genome, spec: &EvolveSpec) -> u64 {
+    let mut board = CpuBoard::new(spec.board, spec.board);
+    let corner = IVec2::splat((spec.board - spec.side) as i32 / 2);
+    for cell in cells(genome, spec.side) {
+        board.set(corner + cell, true);
+    }
+    let initial_area = bounding_area(&board).max(1);
+
+    let mut seen = HashSet::from([cpu::cells_hash(&board.cells)]);
+    let mut lifetime = spec.max_generations;
+    for generation in 1..=spec.max_generations {
+        board.step();
+        if !seen.insert(cpu::cells_hash(&board.cells)) {
+            lifetime = generation;
+            break;
+        }
+    }
+    match spec.objective {
+        Objective::Longevity => lifetime as u64,
+        Objective::Population => board.cells.iter().filter(|cell| **cell == 255).count() as u64,
+        Objective::Growth => bounding_area(&board) / initial_area,
+    }
+}
+
+// Scores every candidate, spreading them over every core.
+fn score(candidates: &[Genome], spec: &EvolveSpec) -> Vec<u64> {
+    candidates.par_iter().map(|genome| fitness(genome, spec)).collect()
+}
+
+fn pick_parent<'a>(candidates: &'a [Genome], scores: &[u64], rng: &mut impl Rng) -> &'a Genome {
+    let best = (0..TOURNAMENT_SIZE).map(|_| rng.gen_range(0..candidates.len())).max_by_key(|i| scores[*i]).unwrap();
+    &candidates[best]
+}
+
+// Uniform crossover, then flip each cell with a small chance.
+fn offspring(a: &Genome, b: &Genome, rng: &mut impl Rng) -> Genome {
+    let mutation_rate = 1.0 / a.len() as f64;
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (if rng.gen_bool(0.5) { *a } else { *b }) ^ rng.gen_bool(mutation_rate))
+        .collect()
+}
+
+/// Runs the genetic search in the spec at `path` and saves the best finds.
+pub fn run_evolution(path: &str, seed: u64) -> Result<(), String> {
+    let text = std::fs::read_to_string(path).map_err(|err| format!("failed to read {}: {}", path, err))?;
+    let spec: EvolveSpec = toml::from_str(&text).map_err(|err| format!("bad spec {}: {}", path, err))?;
+    if spec.population <= ELITES || spec.side == 0 || spec.side > spec.board {
+        return Err("the population must be bigger than 2 and the seeds must fit on the board".to_string());
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let genome_length = (spec.side * spec.side) as usize;
+    let mut candidates = (0..spec.population)
+        .map(|_| (0..genome_length).map(|_| rng.gen_bool(INITIAL_DENSITY)).collect::<Genome>())
+        .collect::<Vec<_>>();
+    let mut scores = score(&candidates, &spec);
+    for round in 1..=spec.rounds {
+        let mut ranked = (0..candidates.len()).collect::<Vec<_>>();
+        ranked.sort_by_key(|i| std::cmp::Reverse(scores[*i]));
+        let mut next = ranked[..ELITES].iter().map(|i| candidates[*i].clone()).collect::<Vec<_>>();
+        while next.len() < spec.population {
+            let a = pick_parent(&candidates, &scores, &mut rng);
+            let b = pick_parent(&candidates, &scores, &mut rng);
+            next.push(offspring(a, b, &mut rng));
+        }
+        candidates = next;
+        scores = score(&candidates, &spec);
+        println!("round {}: best {} {}", round, spec.objective.name(), scores.iter().max().unwrap());
+    }
+
+    // Save the best distinct finds.
+    let mut ranked = candidates.iter().zip(&scores).collect::<Vec<_>>();
+    ranked.sort_by_key(|(_, score)| std::cmp::Reverse(**score));
+    let mut saved = HashSet::new();
+    ranked.retain(|(genome, _)| saved.insert(*genome));
+    std::fs::create_dir_all(patterns::LIBRARY_DIRECTORY)
+        .map_err(|err| format!("failed to create {}: {}", patterns::LIBRARY_DIRECTORY, err))?;
+    // The candidates were all run under Life.
+    let conway_rule = LifeRule::CONWAY.to_string();
+    for (rank, (genome, score)) in ranked.iter().take(spec.keep).enumerate() {
+        let name = format!("evolved-{}-{}-{}", spec.objective.name(), seed, rank + 1);
+        let path = patterns::library_path(&name);
+        std::fs::write(&path, formats::write_rle(&name, Some(&conway_rule), &cells(genome, spec.side)))
+            .map_err(|err| format!("failed to write {}: {}", path, err))?;
+        println!("saved {} ({} {})", path, spec.objective.name(), score);
+    }
+    Ok(())
+}
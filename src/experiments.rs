@@ -0,0 +1,146 @@
+// Batch experiments (`experiments SPEC.toml`): every combination of the
+// spec's rules, densities and seeds is run headlessly on the CPU and its
+// statistics are written to CSV, or JSON if the output ends in `.json`.
+//
+//   rules = ["B3/S23", "B36/S23"]
+//   densities = [0.1, 0.3, 0.5]
+//   seeds = [1, 2, 3]
+//   generations = 2000
+//   width = 256
+//   height = 256
+//   output = "results.csv"
+
+use std::collections::HashMap;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rayon::prelude::*;
+use serde::Deserialize;
+
+use crate::cpu::{self, CpuBoard};
+use crate::life_rule::LifeRule;
+
+#[derive(Deserialize)]
+struct ExperimentSpec {
+    #[serde(default = "default_rules")]
+    rules: Vec<String>,
+    densities: Vec<f32>,
+    seeds: Vec<u64>,
+    generations: u32,
+    #[serde(default = "default_side")]
+    width: u32,
+    #[serde(default = "default_side")]
+    height: u32,
+    output: String,
+}
+
+fn default_rules() -> Vec<String> {
+    vec!["B3/S23".to_string()]
+}
+
+fn default_side() -> u32 {
+    256
+}
+
+struct Run {
+    rule: LifeRule,
+    density: f32,
+    seed: u64,
+}
+
+struct RunStats {
+    final_population: usize,
+    // The first generation of the cycle the board settled into, if it did.
+    stabilized_at: Option<u32>,
+    // 1 for still lifes, more for oscillators.
+    period: Option<u32>,
+}
+
+fn population(board: &CpuBoard) -> usize {
+    board.cells.iter().filter(|cell| **cell == 255).count()
+}
+
+fn run_experiment(run: &Run, spec: &ExperimentSpec) -> RunStats {
+    let mut board = CpuBoard::new(spec.width, spec.height);
+    board.seed(run.density, &mut StdRng::seed_from_u64(run.seed));
+    let mut populations = vec![population(&board)];
+    // The generation each board state was first seen at.
+    let mut seen = HashMap::from([(cpu::cells_hash(&board.cells), 0)]);
+
+    for generation in 1..=spec.generations {
+        board.step_with(&run.rule);
+        populations.push(population(&board));
+        if let Some(&start) = seen.get(&cpu::cells_hash(&board.cells)) {
+            // The board repeats from here on, so the rest of the run is known.
+            let period = generation - start;
+            let last = start + (spec.generations - start) % period;
+            return RunStats {
+                final_population: populations[last as usize],
+                stabilized_at: Some(start),
+                period: Some(period),
+            };
+        }
+        seen.insert(cpu::cells_hash(&board.cells), generation);
+    }
+    RunStats { final_population: population(&board), stabilized_at: None, period: None }
+}
+
+fn optional(value: Option<u32>, missing: &str) -> String {
+    value.map_or_else(|| missing.to_string(), |value| value.to_string())
+}
+
+fn write_csv(runs: &[Run], stats: &[RunStats]) -> String {
+    let mut text = "rule,density,seed,final_population,stabilized_at,period\n".to_string();
+    for (run, stats) in runs.iter().zip(stats) {
+        text.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            run.rule, run.density, run.seed, stats.final_population,
+            optional(stats.stabilized_at, ""), optional(stats.period, ""),
+        ));
+    }
+    text
+}
+
+fn write_json(runs: &[Run], stats: &[RunStats]) -> String {
+    let rows = runs
+        .iter()
+        .zip(stats)
+        .map(|(run, stats)| format!(
+            "  {{\"rule\": \"{}\", \"density\": {}, \"seed\": {}, \"final_population\": {}, \"stabilized_at\": {}, \"period\": {}}}",
+            run.rule, run.density, run.seed, stats.final_population,
+            optional(stats.stabilized_at, "null"), optional(stats.period, "null"),
+        ))
+        .collect::<Vec<_>>();
+    format!("[\n{}\n]\n", rows.join(",\n"))
+}
+
+/// Runs every experiment in the spec at `path` and writes out the results.
+pub fn run_experiments(path: &str) -> Result<(), String> {
+    let text = std::fs::read_to_string(path).map_err(|err| format!("failed to read {}: {}", path, err))?;
+    let spec: ExperimentSpec = toml::from_str(&text).map_err(|err| format!("bad spec {}: {}", path, err))?;
+
+    let mut runs = vec![];
+    for rule in &spec.rules {
+        let rule = LifeRule::parse(rule)?.for_cpu()?;
+        for &density in &spec.densities {
+            for &seed in &spec.seeds {
+                runs.push(Run { rule, density, seed });
+            }
+        }
+    }
+
+    // Runs are independent, so spread them over every core.
+    let stats = runs
+        .par_iter()
+        .map(|run| {
+            let stats = run_experiment(run, &spec);
+            println!("{} density {} seed {}: population {}", run.rule, run.density, run.seed, stats.final_population);
+            stats
+        })
+        .collect::<Vec<_>>();
+
+    let output = if spec.output.ends_with(".json") { write_json(&runs, &stats) } else { write_csv(&runs, &stats) };
+    std::fs::write(&spec.output, output).map_err(|err| format!("failed to write {}: {}", spec.output, err))?;
+    println!("wrote {} runs to {}", runs.len(), spec.output);
+    Ok(())
+}
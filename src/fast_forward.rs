@@ -0,0 +1,122 @@
+// Runs the board flat out to a generation and pauses there, showing how far
+// along it is in the inspector. It slows down near the end so frames still in
+// flight don't carry it past, though it can land a generation late.
+
+use bevy::prelude::*;
+
+use crate::conway::{ConwayCommand, Generation, GenerationAdvanced, SimulationState, MAX_SPEED};
+use crate::event_log::LogEvent;
+use crate::inspector::Inspector;
+
+pub struct FastForwardPlugin;
+
+const SECTION: &str = "Fast-forward";
+
+#[derive(Event, Clone, Copy, Debug)]
+pub enum FastForwardCommand {
+    // Run until the board reaches this generation.
+    Until(u64),
+    // Run this many generations.
+    By(u64),
+    Cancel,
+}
+
+struct Run {
+    from: u64,
+    target: u64,
+    // The speed to go back to afterwards.
+    speed: u32,
+}
+
+#[derive(Resource, Default)]
+struct FastForward(Option<Run>);
+
+// Half the remaining generations a frame, so it eases in on the target.
+fn speed_for(remaining: u64) -> u32 {
+    (remaining / 2).clamp(1, MAX_SPEED as u64) as u32
+}
+
+fn handle_commands(
+    mut fast_forward_commands: EventReader<FastForwardCommand>,
+    mut fast_forward: ResMut<FastForward>,
+    generation: Res<Generation>,
+    simulation_state: Res<SimulationState>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut inspector: ResMut<Inspector>,
+    mut log: EventWriter<LogEvent>,
+) {
+    for command in fast_forward_commands.read() {
+        let current = generation.get();
+        let target = match command {
+            FastForwardCommand::Until(target) => *target,
+            FastForwardCommand::By(generations) => match current.checked_add(*generations) {
+                Some(target) => target,
+                None => {
+                    log.send(LogEvent(format!("Can't run {} generations on from generation {}", generations, current)));
+                    continue;
+                }
+            },
+            FastForwardCommand::Cancel => {
+                if let Some(run) = fast_forward.0.take() {
+                    commands.send(ConwayCommand::SetSpeed(run.speed));
+                    inspector.remove(SECTION);
+                }
+                continue;
+            }
+        };
+        if target <= current {
+            log.send(LogEvent(format!("Already at generation {}", current)));
+            continue;
+        }
+        // Keep the speed from before an earlier run that's being replaced.
+        let speed = fast_forward.0.as_ref().map_or(simulation_state.speed, |run| run.speed);
+        fast_forward.0 = Some(Run { from: current, target, speed });
+        commands.send(ConwayCommand::SetSpeed(speed_for(target - current)));
+        commands.send(ConwayCommand::Resume);
+        inspector.set(SECTION, format!("to generation {}", target));
+    }
+}
+
+fn advance(
+    mut generations: EventReader<GenerationAdvanced>,
+    mut fast_forward: ResMut<FastForward>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut inspector: ResMut<Inspector>,
+    mut log: EventWriter<LogEvent>,
+) {
+    let Some(run) = &fast_forward.0 else {
+        return;
+    };
+    let Some(generation) = generations.read().last().map(|advanced| advanced.generation) else {
+        return;
+    };
+    // The board was reseeded underneath it.
+    if generation < run.from {
+        commands.send(ConwayCommand::SetSpeed(run.speed));
+        inspector.remove(SECTION);
+        fast_forward.0 = None;
+        return;
+    }
+    if generation >= run.target {
+        commands.send(ConwayCommand::Pause);
+        commands.send(ConwayCommand::SetSpeed(run.speed));
+        inspector.remove(SECTION);
+        log.send(LogEvent(format!("Paused at generation {}", generation)));
+        fast_forward.0 = None;
+        return;
+    }
+    let remaining = run.target - generation;
+    commands.send(ConwayCommand::SetSpeed(speed_for(remaining)));
+    let progress = (generation - run.from) as f32 / (run.target - run.from) as f32 * 100.0;
+    inspector.set(SECTION, format!("to generation {}: {:.0}%, {} to go", run.target, progress, remaining));
+}
+
+impl Plugin for FastForwardPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<FastForwardCommand>()
+        .init_resource::<FastForward>()
+        .add_systems(Update, (handle_commands, advance).chain())
+        ;
+    }
+}
@@ -0,0 +1,132 @@
+// Figures for articles and forum posts: a region of the board exported as a
+// PNG at a whole number of pixels per cell, so every cell comes out as a
+// crisp square however it's viewed. With grid lines, a one pixel line runs
+// between cells and around the outside, which takes a pixel of each cell.
+//
+//   figure FILE SCALE [grid]              the box last measured with the ruler
+//   figure FILE SCALE X Y W H [grid]      the W x H cells from X, Y
+//
+// It's drawn from the board read back, not the screen, so it's the same
+// whatever the camera, palette or overlays are doing.
+
+use bevy::prelude::*;
+
+use crate::conway::BoardSnapshot;
+use crate::event_log::LogEvent;
+
+pub struct FigurePlugin;
+
+pub const MAX_SCALE: u32 = 32;
+// Grid lines take a pixel of each cell, so leave at least two for the cell.
+pub const MIN_GRID_SCALE: u32 = 3;
+// Keeps a big region at a big scale from eating all the memory.
+const MAX_PIXELS: u64 = 8192 * 8192;
+const ALIVE: u8 = 0;
+const DEAD: u8 = 255;
+const GRID: u8 = 192;
+
+/// Send this to export cells `corner` to `corner + size` as a figure.
+#[derive(Event, Clone, Debug)]
+pub struct ExportFigure {
+    pub path: String,
+    pub corner: IVec2,
+    pub size: UVec2,
+    pub scale: u32,
+    pub grid: bool,
+}
+
+// The figure's size in pixels, if it can be drawn.
+fn dimensions(size: UVec2, scale: u32, grid: bool) -> Result<UVec2, String> {
+    if size.cmpeq(UVec2::ZERO).any() {
+        return Err("the region is empty".to_string());
+    }
+    if scale == 0 || scale > MAX_SCALE {
+        return Err(format!("expected a scale from 1 to {}, got {}", MAX_SCALE, scale));
+    }
+    if grid && scale < MIN_GRID_SCALE {
+        return Err(format!("grid lines need a scale of at least {}", MIN_GRID_SCALE));
+    }
+    let border = grid as u64;
+    let (width, height) = (size.x as u64 * scale as u64 + border, size.y as u64 * scale as u64 + border);
+    if width.saturating_mul(height) > MAX_PIXELS {
+        return Err(format!("a {} x {} figure is too big", width, height));
+    }
+    Ok(UVec2::new(width as u32, height as u32))
+}
+
+/// Encodes a figure as a grayscale PNG, `scale` pixels to a cell, with dark
+/// cells on white. `alive` has one entry per cell of the region, row by row.
+pub fn encode(size: UVec2, alive: &[bool], scale: u32, grid: bool) -> Result<Vec<u8>, String> {
+    let UVec2 { x: width, y: height } = dimensions(size, scale, grid)?;
+    let mut pixels = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let on_line = grid && (x % scale == 0 || y % scale == 0);
+            let cell = (y / scale).min(size.y - 1) * size.x + (x / scale).min(size.x - 1);
+            pixels.push(match (on_line, alive.get(cell as usize)) {
+                (true, _) => GRID,
+                (false, Some(true)) => ALIVE,
+                (false, _) => DEAD,
+            });
+        }
+    }
+
+    let mut bytes = vec![];
+    let mut encoder = png::Encoder::new(&mut bytes, width, height);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(|err| err.to_string())?;
+    Ok(bytes)
+}
+
+fn save(export: &ExportFigure, board: &BoardSnapshot) -> Result<(), String> {
+    dimensions(export.size, export.scale, export.grid)?;
+    let alive = (0..export.size.y as i32)
+        .flat_map(|y| (0..export.size.x as i32).map(move |x| IVec2::new(x, y)))
+        .map(|offset| board.is_alive(export.corner + offset))
+        .collect::<Vec<_>>();
+    let bytes = encode(export.size, &alive, export.scale, export.grid)?;
+    std::fs::write(&export.path, bytes).map_err(|err| err.to_string())
+}
+
+fn run_exports(mut exports: EventReader<ExportFigure>, board: Res<BoardSnapshot>, mut log: EventWriter<LogEvent>) {
+    for export in exports.read() {
+        match save(export, &board) {
+            Ok(()) => log.send(LogEvent(format!(
+                "Exported {} x {} cells at {}x to {}",
+                export.size.x, export.size.y, export.scale, export.path
+            ))),
+            Err(err) => log.send(LogEvent(format!("Failed to export {}: {}", export.path, err))),
+        }
+    }
+}
+
+impl Plugin for FigurePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<ExportFigure>()
+        .add_systems(Update, run_exports)
+        ;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn figures_draw_whole_cells_between_grid_lines() {
+        let bytes = encode(UVec2::new(2, 1), &[true, false], 3, true).unwrap();
+        let mut reader = png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        assert_eq!(reader.info().size(), (7, 4));
+        // A line along the top, then two rows through the cells.
+        assert_eq!(&pixels[..7], &[192; 7]);
+        assert_eq!(&pixels[7..14], &[192, 0, 0, 192, 255, 255, 192]);
+        assert!(encode(UVec2::new(2, 1), &[true, false], 2, true).is_err());
+    }
+}
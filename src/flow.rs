@@ -0,0 +1,89 @@
+// The flow tool (8): drag across the board to lay down a current in the
+// direction of the stroke, which carries cells along before each update (see
+// `FlowField`). Holding Alt erases it. While the tool is picked the field is
+// drawn as arrows on a coarse grid. The console's `flow DX DY` sets the same
+// current everywhere and `flow off` stops it.
+
+use bevy::prelude::*;
+
+use crate::conway::{ConwayCommand, ConwayConfig, FlowField};
+use crate::tools::{self, BoardCursor, Tool};
+
+pub struct FlowPlugin;
+
+// Strokes are broad, since a current one cell wide just shears patterns apart.
+const BRUSH_RADIUS: i32 = 24;
+// Cells per generation a stroke's current moves at.
+const SPEED: f32 = 0.5;
+// Cells between the arrows, and how long a full-speed arrow is.
+const ARROW_SPACING: i32 = 40;
+const ARROW_LENGTH: f32 = 30.0;
+const ARROW_COLOR: Color = Color::rgba(0.3, 0.7, 1.0, 0.8);
+
+fn use_flow_tool(
+    tool: Res<Tool>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    cursor: BoardCursor,
+    mut commands: EventWriter<ConwayCommand>,
+    // Where the stroke was last frame.
+    mut last_cell: Local<Option<IVec2>>,
+) {
+    if *tool != Tool::Flow || !mouse_button_input.pressed(MouseButton::Left) {
+        *last_cell = None;
+        return;
+    }
+    let Some(cell) = cursor.cell() else {
+        return;
+    };
+    let Some(from) = last_cell.replace(cell) else {
+        return;
+    };
+    let erasing = tools::erasing(&keys);
+    // Erasing works holding still, but a current needs a direction.
+    if from == cell && !erasing {
+        return;
+    }
+    let velocity = if erasing { Vec2::ZERO } else { (cell - from).as_vec2().normalize() * SPEED };
+    let mut cells = tools::line_cells(from, cell)
+        .into_iter()
+        .flat_map(|point| {
+            (-BRUSH_RADIUS..=BRUSH_RADIUS)
+                .flat_map(|y| (-BRUSH_RADIUS..=BRUSH_RADIUS).map(move |x| IVec2::new(x, y)))
+                .filter(|offset| offset.length_squared() <= BRUSH_RADIUS * BRUSH_RADIUS)
+                .map(move |offset| point + offset)
+        })
+        .collect::<Vec<_>>();
+    cells.sort_by_key(|cell| (cell.y, cell.x));
+    cells.dedup();
+    commands.send(ConwayCommand::SetFlow { cells, velocity });
+}
+
+fn draw_field(tool: Res<Tool>, flow: Res<FlowField>, config: Res<ConwayConfig>, mut gizmos: Gizmos) {
+    if *tool != Tool::Flow || !flow.enabled {
+        return;
+    }
+    let size = config.size.as_ivec2();
+    for y in (ARROW_SPACING / 2..size.y).step_by(ARROW_SPACING as usize) {
+        for x in (ARROW_SPACING / 2..size.x).step_by(ARROW_SPACING as usize) {
+            let cell = IVec2::new(x, y);
+            let velocity = flow.velocity(cell);
+            if velocity == Vec2::ZERO {
+                continue;
+            }
+            // Cells have y down and the world y up.
+            let base = config.cell_to_world(cell);
+            let tip = base + Vec2::new(velocity.x, -velocity.y) * ARROW_LENGTH;
+            gizmos.line_2d(base, tip, ARROW_COLOR);
+            gizmos.circle_2d(tip, 2.0, ARROW_COLOR);
+        }
+    }
+}
+
+impl Plugin for FlowPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, (use_flow_tool, draw_field))
+        ;
+    }
+}
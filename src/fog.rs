@@ -0,0 +1,181 @@
+// Fog of war (`fog [SECS]` in the console, `fog off` to lift it): the board
+// is hidden under fog, and only the parts near where you've painted or where
+// the camera has moved recently show through. Revealed ground fogs over again
+// across SECS seconds (20), so exploring a big seeded board means going back
+// to look at what's become of it.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+
+use crate::conway::{BoardResized, ConwayConfig};
+use crate::tools::BoardCursor;
+
+pub struct FogPlugin;
+
+pub const DEFAULT_FADE_SECONDS: f32 = 20.0;
+// Cells across each texel of the fog.
+const FOG_CELL: u32 = 8;
+// How far around the cursor painting reveals, and around the middle of the
+// view moving the camera does, in cells.
+const PAINT_RADIUS: f32 = 48.0;
+const CAMERA_RADIUS: f32 = 160.0;
+
+/// Whether the fog is down, and how long revealed ground takes to fog over.
+#[derive(Resource)]
+pub struct FogOfWar {
+    pub enabled: bool,
+    pub fade_seconds: f32,
+}
+
+impl Default for FogOfWar {
+    fn default() -> Self {
+        Self { enabled: false, fade_seconds: DEFAULT_FADE_SECONDS }
+    }
+}
+
+// The fog overlay, spawned the first time it comes down, and how clear each
+// of its texels is, from 0 to 1.
+#[derive(Resource)]
+struct Fog {
+    entity: Entity,
+    image: Handle<Image>,
+    clear: Vec<f32>,
+    size: UVec2,
+}
+
+// The fog's texels across a board of `size`.
+fn fog_size(size: UVec2) -> UVec2 {
+    size / FOG_CELL
+}
+
+fn fog_image(size: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d { width: size.x, height: size.y, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    // Soft edges, since each texel covers a block of cells.
+    image.sampler = ImageSampler::linear();
+    image
+}
+
+fn reveal(clear: &mut [f32], size: UVec2, center: Vec2, radius: f32) {
+    let (center, radius) = (center / FOG_CELL as f32, radius / FOG_CELL as f32);
+    let low = (center - radius).floor().max(Vec2::ZERO).as_uvec2();
+    let high = (center + radius).ceil().as_uvec2().min(size);
+    for y in low.y..high.y {
+        for x in low.x..high.x {
+            let distance = (Vec2::new(x as f32, y as f32) + 0.5).distance(center);
+            // Fully clear in the middle, feathering out at the edge.
+            let amount = (1.0 - distance / radius).clamp(0.0, 1.0).sqrt();
+            let texel = &mut clear[(y * size.x + x) as usize];
+            *texel = texel.max(amount);
+        }
+    }
+}
+
+fn show_fog(
+    mut commands: Commands,
+    fog_of_war: Res<FogOfWar>,
+    fog: Option<ResMut<Fog>>,
+    config: Res<ConwayConfig>,
+    mut images: ResMut<Assets<Image>>,
+    mut visibilities: Query<&mut Visibility>,
+) {
+    if !fog_of_war.is_changed() {
+        return;
+    }
+    let Some(mut fog) = fog else {
+        if !fog_of_war.enabled {
+            return;
+        }
+        let size = fog_size(config.size);
+        let image = images.add(fog_image(size));
+        let entity = commands
+            .spawn(SpriteBundle {
+                texture: image.clone(),
+                sprite: Sprite { custom_size: Some(config.extents()), ..default() },
+                // Over the board and its overlays, under the kaleidoscope.
+                transform: Transform::from_xyz(0.0, 0.0, 1.8),
+                ..default()
+            })
+            .id();
+        commands.insert_resource(Fog { entity, image, clear: vec![0.0; (size.x * size.y) as usize], size });
+        return;
+    };
+    // Coming down again hides everything again.
+    if fog_of_war.enabled {
+        fog.clear.fill(0.0);
+    }
+    if let Ok(mut visibility) = visibilities.get_mut(fog.entity) {
+        *visibility = if fog_of_war.enabled { Visibility::Visible } else { Visibility::Hidden };
+    }
+}
+
+// The fog comes down over all of a resized board.
+fn fit_fog(
+    mut resized: EventReader<BoardResized>,
+    fog: Option<ResMut<Fog>>,
+    config: Res<ConwayConfig>,
+    mut images: ResMut<Assets<Image>>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    let (Some(resized), Some(mut fog)) = (resized.read().last(), fog) else {
+        return;
+    };
+    let size = fog_size(resized.size);
+    fog.clear = vec![0.0; (size.x * size.y) as usize];
+    fog.size = size;
+    images.insert(fog.image.id(), fog_image(size));
+    if let Ok(mut sprite) = sprites.get_mut(fog.entity) {
+        sprite.custom_size = Some(config.extents());
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_fog(
+    time: Res<Time>,
+    fog_of_war: Res<FogOfWar>,
+    fog: Option<ResMut<Fog>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    cursor: BoardCursor,
+    config: Res<ConwayConfig>,
+    cameras: Query<&Transform, (With<Camera2d>, Changed<Transform>)>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Some(mut fog) = fog else {
+        return;
+    };
+    let size = fog.size;
+    if !fog_of_war.enabled {
+        return;
+    }
+    let fade = time.delta_seconds() / fog_of_war.fade_seconds.max(f32::EPSILON);
+    fog.clear.iter_mut().for_each(|texel| *texel = (*texel - fade).max(0.0));
+    if mouse_button_input.pressed(MouseButton::Left) {
+        if let Some(cell) = cursor.cell() {
+            reveal(&mut fog.clear, size, cell.as_vec2(), PAINT_RADIUS);
+        }
+    }
+    for transform in &cameras {
+        let cell = config.world_to_cell(transform.translation.truncate());
+        reveal(&mut fog.clear, size, cell.as_vec2(), CAMERA_RADIUS);
+    }
+    let Some(image) = images.get_mut(&fog.image) else {
+        return;
+    };
+    for (pixel, clear) in image.data.chunks_exact_mut(4).zip(&fog.clear) {
+        pixel[3] = ((1.0 - clear) * 255.0) as u8;
+    }
+}
+
+impl Plugin for FogPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<FogOfWar>()
+        .add_systems(Update, (fit_fog, show_fog, update_fog).chain())
+        ;
+    }
+}
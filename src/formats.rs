@@ -0,0 +1,169 @@
+// Reading and writing patterns in the plaintext `.cells` format (`!` comment
+// lines followed by one line per row, with `O` for live and `.` for dead cells)
+// and in run-length encoded `.rle` files.
+
+use bevy::prelude::*;
+
+// Patterns reaching further than this are refused rather than risking a
+// huge allocation on a malformed file.
+const MAX_EXTENT: i32 = 1 << 16;
+// And ones with more live cells than this.
+const MAX_CELLS: usize = 1 << 22;
+// Long RLE lines are wrapped at this width, as other tools expect.
+const RLE_LINE_WIDTH: usize = 70;
+
+/// Writes cells as plaintext, keeping their positions relative to (0, 0).
+/// Cells with negative coordinates are dropped.
+pub fn write_plaintext(name: &str, cells: &[IVec2]) -> String {
+    let cells = cells.iter().filter(|cell| cell.x >= 0 && cell.y >= 0).collect::<Vec<_>>();
+    let height = cells.iter().map(|cell| cell.y + 1).max().unwrap_or(0);
+    let mut rows = vec![vec![]; height as usize];
+    for cell in cells {
+        let row = &mut rows[cell.y as usize];
+        if row.len() <= cell.x as usize {
+            row.resize(cell.x as usize + 1, b'.');
+        }
+        row[cell.x as usize] = b'O';
+    }
+    let mut text = format!("!Name: {}\n", name);
+    for row in rows {
+        text.push_str(std::str::from_utf8(&row).unwrap());
+        text.push('\n');
+    }
+    text
+}
+
+/// Parses plaintext into the positions of its live cells.
+pub fn parse_plaintext(text: &str) -> Result<Vec<IVec2>, String> {
+    let mut cells = vec![];
+    let rows = text.lines().filter(|line| !line.starts_with('!'));
+    for (y, row) in rows.enumerate() {
+        if y >= MAX_EXTENT as usize {
+            return Err("pattern is too tall".to_string());
+        }
+        for (x, c) in row.trim_end().chars().enumerate() {
+            if x >= MAX_EXTENT as usize {
+                return Err("pattern is too wide".to_string());
+            }
+            match c {
+                'O' | 'o' | '*' => cells.push(IVec2::new(x as i32, y as i32)),
+                '.' => {}
+                _ => return Err(format!("unexpected {:?} at row {}, column {}", c, y + 1, x + 1)),
+            }
+        }
+    }
+    Ok(cells)
+}
+
+/// Writes cells as RLE, keeping their positions relative to (0, 0), under
+/// `rule` if it's known. Cells with negative coordinates are dropped.
+pub fn write_rle(name: &str, rule: Option<&str>, cells: &[IVec2]) -> String {
+    let mut cells = cells.iter().copied().filter(|cell| cell.x >= 0 && cell.y >= 0).collect::<Vec<_>>();
+    cells.sort_by_key(|cell| (cell.y, cell.x));
+    cells.dedup();
+    let width = cells.iter().map(|cell| cell.x + 1).max().unwrap_or(0);
+    let height = cells.iter().map(|cell| cell.y + 1).max().unwrap_or(0);
+
+    // Runs of `o` for live cells, `b` for dead ones and `$` for row ends.
+    let mut runs: Vec<(usize, char)> = vec![];
+    let mut push = |count: usize, tag: char| match runs.last_mut() {
+        Some((previous, last)) if *last == tag => *previous += count,
+        _ if count > 0 => runs.push((count, tag)),
+        _ => {}
+    };
+    let mut position = IVec2::ZERO;
+    for cell in cells {
+        if cell.y > position.y {
+            push((cell.y - position.y) as usize, '$');
+            position = IVec2::new(0, cell.y);
+        }
+        push((cell.x - position.x) as usize, 'b');
+        push(1, 'o');
+        position.x = cell.x + 1;
+    }
+
+    // Left out when it isn't known, rather than passing the cells off as Life.
+    let rule = rule.map(|rule| format!(", rule = {}", rule)).unwrap_or_default();
+    let mut text = format!("#N {}\nx = {}, y = {}{}\n", name, width, height, rule);
+    let mut line = String::new();
+    let items = runs
+        .iter()
+        .map(|&(count, tag)| if count == 1 { tag.to_string() } else { format!("{}{}", count, tag) })
+        .chain(["!".to_string()]);
+    for item in items {
+        if line.len() + item.len() > RLE_LINE_WIDTH {
+            text.push_str(&line);
+            text.push('\n');
+            line.clear();
+        }
+        line.push_str(&item);
+    }
+    text.push_str(&line);
+    text.push('\n');
+    text
+}
+
+/// Parses RLE into the positions of its live cells. Multi-state letters all
+/// count as alive.
+pub fn parse_rle(text: &str) -> Result<Vec<IVec2>, String> {
+    let mut cells = vec![];
+    let mut size = None;
+    let mut position = IVec2::ZERO;
+    let mut count: Option<i32> = None;
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+    if let Some(header) = lines.clone().next().filter(|line| line.starts_with('x')) {
+        lines.next();
+        let mut width = None;
+        let mut height = None;
+        for setting in header.split(',') {
+            let (key, value) = setting.split_once('=').ok_or_else(|| format!("bad header {:?}", header))?;
+            let value = || value.trim().parse::<i32>().ok().filter(|value| (0..=MAX_EXTENT).contains(value));
+            match key.trim() {
+                "x" => width = Some(value().ok_or("bad pattern width")?),
+                "y" => height = Some(value().ok_or("bad pattern height")?),
+                _ => {}
+            }
+        }
+        size = Some(IVec2::new(width.ok_or("header is missing x")?, height.ok_or("header is missing y")?));
+    }
+
+    'lines: for line in lines {
+        for c in line.chars() {
+            if let Some(digit) = c.to_digit(10) {
+                let run = count.unwrap_or(0) * 10 + digit as i32;
+                if run > MAX_EXTENT {
+                    return Err("run is too long".to_string());
+                }
+                count = Some(run);
+                continue;
+            }
+            if c.is_whitespace() {
+                continue;
+            }
+            let run = count.take().unwrap_or(1);
+            match c {
+                'b' | '.' => position.x += run,
+                '$' => position = IVec2::new(0, position.y + run),
+                '!' => break 'lines,
+                'o' | 'A'..='X' => {
+                    if position.x + run > MAX_EXTENT || cells.len() > MAX_CELLS {
+                        return Err("pattern is too big".to_string());
+                    }
+                    cells.extend((0..run).map(|dx| position + IVec2::new(dx, 0)));
+                    position.x += run;
+                }
+                _ => return Err(format!("unexpected {:?}", c)),
+            }
+            if position.x > MAX_EXTENT || position.y > MAX_EXTENT {
+                return Err("pattern is too big".to_string());
+            }
+        }
+    }
+    if let Some(size) = size {
+        if cells.iter().any(|cell| cell.x >= size.x || cell.y >= size.y) {
+            return Err(format!("pattern is bigger than its {}x{} header", size.x, size.y));
+        }
+    }
+    Ok(cells)
+}
@@ -0,0 +1,71 @@
+// Hooks for debugging the simulation on the GPU, from `--gpu-debug`. The
+// simulation's passes get wrapped in a debug group, with a marker for each
+// generation, so they're easy to pick out in a frame debugger, and F12 asks
+// RenderDoc to capture the next frame. That needs the `renderdoc` feature,
+// and the game launched from RenderDoc so it's hooked in before the device
+// is made.
+
+use bevy::prelude::*;
+
+use crate::conway::DebugGroups;
+use crate::event_log::LogEvent;
+
+pub struct FrameCapturePlugin;
+
+const CAPTURE_KEY: KeyCode = KeyCode::F12;
+
+// RenderDoc's in-app API, when there's one to ask. It's not to be shared
+// between threads, so it's kept as a non-send resource.
+#[derive(Default)]
+struct FrameCapture {
+    #[cfg(feature = "renderdoc")]
+    renderdoc: Option<renderdoc::RenderDoc<renderdoc::V110>>,
+}
+
+impl FrameCapture {
+    #[cfg(feature = "renderdoc")]
+    fn new() -> Self {
+        Self { renderdoc: renderdoc::RenderDoc::new().ok() }
+    }
+
+    #[cfg(not(feature = "renderdoc"))]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(feature = "renderdoc")]
+    fn trigger(&mut self) -> Result<(), String> {
+        let renderdoc = self.renderdoc.as_mut().ok_or("Not running under RenderDoc")?;
+        renderdoc.trigger_capture();
+        Ok(())
+    }
+
+    #[cfg(not(feature = "renderdoc"))]
+    fn trigger(&mut self) -> Result<(), String> {
+        Err("Built without the renderdoc feature".to_string())
+    }
+}
+
+fn capture_on_key(
+    keys: Res<Input<KeyCode>>,
+    mut frame_capture: NonSendMut<FrameCapture>,
+    mut log: EventWriter<LogEvent>,
+) {
+    if !keys.just_pressed(CAPTURE_KEY) {
+        return;
+    }
+    match frame_capture.trigger() {
+        Ok(()) => log.send(LogEvent("Capturing the next frame".to_string())),
+        Err(err) => log.send(LogEvent(format!("Can't capture a frame: {}", err))),
+    }
+}
+
+impl Plugin for FrameCapturePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(DebugGroups(true))
+        .insert_non_send_resource(FrameCapture::new())
+        .add_systems(Update, capture_on_key)
+        ;
+    }
+}
@@ -0,0 +1,79 @@
+// The gardener (`garden LOW HIGH` in the console, `garden off` to stop) keeps
+// an unattended board going forever. While the population is under LOW it
+// plants a random pattern somewhere every so often, and while it's over HIGH
+// it clears a random patch instead, so the board neither dies out nor
+// settles into a uniform soup.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::conway::{BoardSnapshot, ConwayCommand, GenerationAdvanced};
+use crate::patterns;
+use crate::rng::SimRng;
+
+pub struct GardenerPlugin;
+
+// Seconds between plantings or cullings, so each has time to take.
+const TEND_SECONDS: f32 = 0.5;
+// How big a patch a culling clears, in cells per side, and how near the edges
+// patterns are planted, both cut down to fit smaller boards.
+const CULL_SIZE: i32 = 96;
+const MARGIN: i32 = 40;
+
+/// The population the gardener keeps the board between, when it's on.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct Gardener {
+    pub enabled: bool,
+    pub low: u64,
+    pub high: u64,
+}
+
+fn tend(
+    time: Res<Time>,
+    mut generations: EventReader<GenerationAdvanced>,
+    gardener: Res<Gardener>,
+    board: Res<BoardSnapshot>,
+    mut rng: ResMut<SimRng>,
+    mut commands: EventWriter<ConwayCommand>,
+    // Seconds since the gardener last did anything.
+    mut idle: Local<f32>,
+) {
+    *idle += time.delta_seconds();
+    let Some(advanced) = generations.read().last() else {
+        return;
+    };
+    if !gardener.enabled || *idle < TEND_SECONDS {
+        return;
+    }
+    let size = board.size().as_ivec2();
+    if advanced.population < gardener.low {
+        let name = patterns::NAMES[rng.rng.gen_range(0..patterns::NAMES.len())];
+        let margin = IVec2::splat(MARGIN).min((size - 1) / 2);
+        let origin = IVec2::new(
+            rng.rng.gen_range(margin.x..size.x - margin.x),
+            rng.rng.gen_range(margin.y..size.y - margin.y),
+        );
+        if let Some(cells) = patterns::lookup(name, origin) {
+            commands.send(ConwayCommand::SetCells(cells));
+        }
+        *idle = 0.0;
+    } else if advanced.population > gardener.high {
+        let cull = IVec2::splat(CULL_SIZE).min(size);
+        let corner = IVec2::new(rng.rng.gen_range(0..=size.x - cull.x), rng.rng.gen_range(0..=size.y - cull.y));
+        let cells = (corner.y..corner.y + cull.y)
+            .flat_map(|y| (corner.x..corner.x + cull.x).map(move |x| IVec2::new(x, y)))
+            .filter(|cell| board.is_alive(*cell))
+            .collect();
+        commands.send(ConwayCommand::ClearCells(cells));
+        *idle = 0.0;
+    }
+}
+
+impl Plugin for GardenerPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Gardener>()
+        .add_systems(Update, tend)
+        ;
+    }
+}
@@ -0,0 +1,81 @@
+// The next generation as a ghost (G): while paused, the board is stepped once
+// more into a scratch texture (see `ConwayGhost`), which is drawn faintly over
+// the board, so you can see what an edit is about to do before stepping.
+// Nothing is committed to the board until it's resumed or advanced.
+
+use bevy::prelude::*;
+
+use crate::conway::{BoardResized, ConwayConfig, ConwayGhost, GhostPreview, SimulationState};
+use crate::event_log::LogEvent;
+
+pub struct GhostPlugin;
+
+const GHOST_COLOR: Color = Color::rgba(0.4, 0.8, 1.0, 0.45);
+
+// The ghost's sprite, spawned the first time it's shown.
+#[derive(Resource)]
+struct GhostSprite(Entity);
+
+fn toggle_ghost(keys: Res<Input<KeyCode>>, mut preview: ResMut<GhostPreview>, mut log: EventWriter<LogEvent>) {
+    if keys.just_pressed(KeyCode::G) {
+        preview.0 = !preview.0;
+        log.send(LogEvent(format!("Next generation preview {}", if preview.0 { "on" } else { "off" })));
+    }
+}
+
+fn show_ghost(
+    mut commands: Commands,
+    preview: Res<GhostPreview>,
+    simulation_state: Res<SimulationState>,
+    ghost: Option<Res<ConwayGhost>>,
+    sprite: Option<Res<GhostSprite>>,
+    config: Res<ConwayConfig>,
+    mut visibilities: Query<&mut Visibility>,
+) {
+    let shown = preview.0 && simulation_state.paused;
+    match sprite {
+        Some(sprite) => {
+            if let Ok(mut visibility) = visibilities.get_mut(sprite.0) {
+                *visibility = if shown { Visibility::Visible } else { Visibility::Hidden };
+            }
+        }
+        None if shown => {
+            let Some(ghost) = ghost else {
+                return;
+            };
+            let entity = commands
+                .spawn(SpriteBundle {
+                    texture: ghost.0.clone(),
+                    sprite: Sprite { color: GHOST_COLOR, custom_size: Some(config.extents()), ..default() },
+                    // Just over the board.
+                    transform: Transform::from_xyz(0.0, 0.0, 0.3),
+                    ..default()
+                })
+                .id();
+            commands.insert_resource(GhostSprite(entity));
+        }
+        None => {}
+    }
+}
+
+fn fit_ghost(
+    mut resized: EventReader<BoardResized>,
+    sprite: Option<Res<GhostSprite>>,
+    config: Res<ConwayConfig>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    if resized.read().count() == 0 {
+        return;
+    }
+    if let Some(mut sprite) = sprite.and_then(|sprite| sprites.get_mut(sprite.0).ok()) {
+        sprite.custom_size = Some(config.extents());
+    }
+}
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, ((toggle_ghost, show_ghost).chain(), fit_ghost))
+        ;
+    }
+}
@@ -0,0 +1,127 @@
+// A headless Conway app for deterministic tests: the render app and
+// `ConwayPlugin` without a window, advanced one frame at a time.
+//
+//   let mut harness = ConwayTestHarness::new();
+//   harness.set_cells(&patterns::placed(patterns::GLIDER, IVec2::new(10, 10)));
+//   let snapshots = harness.run(4);
+//   assert!(snapshots[3].is_alive(IVec2::new(12, 13)));
+//
+// Boards are advanced on the GPU, so this needs an adapter to run.
+
+use bevy::prelude::*;
+use bevy::app::PluginsState;
+use bevy::render::pipelined_rendering::PipelinedRenderingPlugin;
+use bevy::window::ExitCondition;
+use bevy::winit::WinitPlugin;
+
+use crate::conway::{BoardSnapshot, ConwayCommand, ConwayPlugin, Generation, SeedSettings, SimulationState};
+
+// Frames to wait for the shaders to compile and the board to be seeded.
+const MAX_SEED_FRAMES: usize = 10_000;
+
+pub struct ConwayTestHarness {
+    app: App,
+}
+
+impl Default for ConwayTestHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConwayTestHarness {
+    /// Starts the app paused on an empty board.
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app
+        .add_plugins(
+            DefaultPlugins
+                .build()
+                .disable::<WinitPlugin>()
+                // Rendering on the main thread keeps each frame's readback in step with `update`.
+                .disable::<PipelinedRenderingPlugin>()
+                .set(WindowPlugin {
+                    primary_window: None,
+                    exit_condition: ExitCondition::DontExit,
+                    close_when_requested: false,
+                }),
+        )
+        .add_plugins(ConwayPlugin::default())
+        .insert_resource(SimulationState { paused: true, speed: 1 })
+        ;
+        app.world.resource_mut::<SeedSettings>().density = 0.0;
+
+        while app.plugins_state() == PluginsState::Adding {
+            bevy::tasks::tick_global_task_pools_on_main_thread();
+        }
+        app.finish();
+        app.cleanup();
+
+        let mut harness = Self { app };
+        harness.wait_until_seeded();
+        harness
+    }
+
+    fn wait_until_seeded(&mut self) {
+        for _ in 0..MAX_SEED_FRAMES {
+            if self.app.world.resource::<Generation>().is_seeded() {
+                // One more frame brings the seeded board back to the main world.
+                self.app.update();
+                return;
+            }
+            self.app.update();
+        }
+        panic!("the board was never seeded");
+    }
+
+    /// Replaces the board with a random soup.
+    pub fn reseed(&mut self, density: f32, seed: u32) {
+        self.app.world.send_event(ConwayCommand::Reseed { density, seed });
+        self.app.update();
+        self.wait_until_seeded();
+    }
+
+    /// Sets cells, in board coordinates, without advancing the board.
+    pub fn set_cells(&mut self, cells: &[IVec2]) {
+        self.app.world.send_event(ConwayCommand::SetCells(cells.to_vec()));
+        self.app.update();
+        self.app.update();
+    }
+
+    /// Clears cells, in board coordinates, without advancing the board.
+    pub fn clear_cells(&mut self, cells: &[IVec2]) {
+        self.app.world.send_event(ConwayCommand::ClearCells(cells.to_vec()));
+        self.app.update();
+        self.app.update();
+    }
+
+    /// Advances the board by `generations` and returns it afterwards.
+    pub fn step(&mut self, generations: u32) -> &BoardSnapshot {
+        *self.app.world.resource_mut::<SimulationState>() = SimulationState { paused: false, speed: generations };
+        self.app.update();
+        self.app.world.resource_mut::<SimulationState>().paused = true;
+        // The readback reaches the main world with the next frame's extract.
+        self.app.update();
+        self.snapshot()
+    }
+
+    /// Advances the board one generation at a time, returning the board after each.
+    pub fn run(&mut self, generations: u32) -> Vec<BoardSnapshot> {
+        (0..generations).map(|_| self.step(1).clone()).collect()
+    }
+
+    /// The board as of the last step.
+    pub fn snapshot(&self) -> &BoardSnapshot {
+        self.app.world.resource::<BoardSnapshot>()
+    }
+
+    /// Generations since the board was last seeded.
+    pub fn generation(&self) -> u64 {
+        self.app.world.resource::<Generation>().get()
+    }
+
+    /// The underlying app, for adding plugins or poking at resources.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+}
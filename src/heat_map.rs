@@ -0,0 +1,105 @@
+// A heat map of where the board's cells are, from the `DensityGrid`: H lays a
+// translucent 8 x 8 grid over the board, from blue for the emptiest regions
+// to red for the busiest.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+
+use crate::conway::{BoardResized, ConwayConfig, DensityGrid, DENSITY_GRID_SIZE};
+use crate::event_log::LogEvent;
+
+pub struct HeatMapPlugin;
+
+const ALPHA: f32 = 0.35;
+// Hues for the emptiest and busiest regions.
+const COLD_HUE: f32 = 240.0;
+const HOT_HUE: f32 = 0.0;
+
+// The overlay, spawned the first time it's shown.
+#[derive(Resource)]
+struct HeatMap {
+    entity: Entity,
+    image: Handle<Image>,
+}
+
+fn toggle_heat_map(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    heat_map: Option<Res<HeatMap>>,
+    config: Res<ConwayConfig>,
+    mut images: ResMut<Assets<Image>>,
+    mut visibilities: Query<&mut Visibility>,
+    mut log: EventWriter<LogEvent>,
+) {
+    if !keys.just_pressed(KeyCode::H) {
+        return;
+    }
+    let Some(heat_map) = heat_map else {
+        let mut image = Image::new_fill(
+            Extent3d { width: DENSITY_GRID_SIZE, height: DENSITY_GRID_SIZE, depth_or_array_layers: 1 },
+            TextureDimension::D2,
+            &[0, 0, 0, 0],
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        image.sampler = ImageSampler::nearest();
+        let image = images.add(image);
+        let entity = commands
+            .spawn(SpriteBundle {
+                texture: image.clone(),
+                sprite: Sprite { custom_size: Some(config.extents()), ..default() },
+                // Over the masks, under the diff.
+                transform: Transform::from_xyz(0.0, 0.0, 1.2),
+                ..default()
+            })
+            .id();
+        commands.insert_resource(HeatMap { entity, image });
+        log.send(LogEvent("Heat map shown".to_string()));
+        return;
+    };
+    if let Ok(mut visibility) = visibilities.get_mut(heat_map.entity) {
+        let shown = *visibility == Visibility::Hidden;
+        *visibility = if shown { Visibility::Visible } else { Visibility::Hidden };
+        log.send(LogEvent(format!("Heat map {}", if shown { "shown" } else { "hidden" })));
+    }
+}
+
+fn update_heat_map(
+    density_grid: Res<DensityGrid>,
+    heat_map: Option<Res<HeatMap>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Some(image) = heat_map.and_then(|heat_map| images.get_mut(&heat_map.image)) else {
+        return;
+    };
+    // Relative to the busiest region, so sparse boards still show up.
+    let busiest = density_grid.counts.iter().copied().max().unwrap_or(0).max(1);
+    for (pixel, count) in image.data.chunks_exact_mut(4).zip(density_grid.counts) {
+        let heat = count as f32 / busiest as f32;
+        let alpha = if count == 0 { 0.0 } else { ALPHA };
+        let color = Color::hsla(COLD_HUE + (HOT_HUE - COLD_HUE) * heat, 1.0, 0.5, alpha);
+        pixel.copy_from_slice(&color.as_rgba_u8());
+    }
+}
+
+fn fit_heat_map(
+    mut resized: EventReader<BoardResized>,
+    heat_map: Option<Res<HeatMap>>,
+    config: Res<ConwayConfig>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    if resized.read().count() == 0 {
+        return;
+    }
+    if let Some(mut sprite) = heat_map.and_then(|heat_map| sprites.get_mut(heat_map.entity).ok()) {
+        sprite.custom_size = Some(config.extents());
+    }
+}
+
+impl Plugin for HeatMapPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, ((toggle_heat_map, update_heat_map).chain(), fit_heat_map))
+        ;
+    }
+}
@@ -0,0 +1,166 @@
+// Hints for a first go: until they've drawn on the board, paused it and
+// zoomed in, a player sees a line for each in the corner, gently pulsing,
+// and each goes away for good once it's been done. What's been done is kept
+// in `hints.toml`, so a fresh profile, without one, starts with them all.
+
+use bevy::input::mouse::MouseWheel;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::conway::SimulationState;
+use crate::event_log::LogEvent;
+use crate::toml_file::TomlFile;
+use crate::tools::BoardCursor;
+
+pub struct HintsPlugin;
+
+pub const HINTS_PATH: &str = "hints.toml";
+const FONT_SIZE: f32 = 22.0;
+const COLOR: Color = Color::rgb(0.9, 0.9, 0.6);
+// Radians a second the hints pulse at, and how far they bob, in pixels.
+const PULSE_SPEED: f32 = 3.0;
+const BOB: f32 = 4.0;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Hint {
+    Draw,
+    Pause,
+    Zoom,
+}
+
+impl Hint {
+    pub const ALL: [Self; 3] = [Self::Draw, Self::Pause, Self::Zoom];
+
+    pub fn text(self) -> &'static str {
+        match self {
+            Self::Draw => "click to draw",
+            Self::Pause => "space to pause",
+            Self::Zoom => "scroll to zoom",
+        }
+    }
+}
+
+/// The hints whose action has been done, and so aren't shown any more.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct HintProgress {
+    #[serde(default)]
+    pub done: Vec<Hint>,
+}
+
+impl HintProgress {
+    pub fn is_done(&self, hint: Hint) -> bool {
+        self.done.contains(&hint)
+    }
+
+    pub fn finish(&mut self, hint: Hint) {
+        if !self.is_done(hint) {
+            self.done.push(hint);
+        }
+    }
+}
+
+// None done for a fresh profile, or one that can't be read.
+impl TomlFile for HintProgress {}
+
+#[derive(Component)]
+struct HintText(Hint);
+
+fn setup(mut commands: Commands, progress: Res<HintProgress>) {
+    let hints = Hint::ALL.into_iter().filter(|hint| !progress.is_done(*hint)).collect::<Vec<_>>();
+    if hints.is_empty() {
+        return;
+    }
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                right: Val::Px(20.0),
+                bottom: Val::Px(60.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::FlexEnd,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for hint in hints {
+                parent.spawn((
+                    HintText(hint),
+                    TextBundle::from_section(hint.text(), TextStyle { font_size: FONT_SIZE, color: COLOR, ..default() })
+                        .with_style(Style { margin: UiRect::vertical(Val::Px(BOB)), ..default() }),
+                ));
+            }
+        });
+}
+
+fn watch_actions(
+    mouse_button_input: Res<Input<MouseButton>>,
+    mut wheels: EventReader<MouseWheel>,
+    simulation_state: Res<SimulationState>,
+    cursor: BoardCursor,
+    mut progress: ResMut<HintProgress>,
+    mut log: EventWriter<LogEvent>,
+) {
+    let scrolled = wheels.read().count() > 0;
+    let performed = [
+        (Hint::Draw, mouse_button_input.just_pressed(MouseButton::Left) && cursor.cell().is_some()),
+        (Hint::Pause, simulation_state.paused),
+        (Hint::Zoom, scrolled),
+    ];
+    let new = performed.into_iter().filter(|(hint, performed)| *performed && !progress.is_done(*hint));
+    let new = new.map(|(hint, _)| hint).collect::<Vec<_>>();
+    if new.is_empty() {
+        return;
+    }
+    for hint in new {
+        progress.finish(hint);
+    }
+    if let Err(err) = progress.save(HINTS_PATH) {
+        log.send(LogEvent(format!("Failed to save {}: {}", HINTS_PATH, err)));
+    }
+}
+
+fn animate_hints(
+    time: Res<Time>,
+    progress: Res<HintProgress>,
+    mut commands: Commands,
+    mut hints: Query<(Entity, &HintText, &mut Text, &mut Style)>,
+) {
+    for (index, (entity, hint, mut text, mut style)) in hints.iter_mut().enumerate() {
+        if progress.is_done(hint.0) {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        // Each a little behind the one above.
+        let phase = time.elapsed_seconds() * PULSE_SPEED - index as f32;
+        text.sections[0].style.color = COLOR.with_a(0.65 + 0.35 * phase.sin());
+        style.margin.bottom = Val::Px(BOB * (1.0 + phase.cos()));
+    }
+}
+
+impl Plugin for HintsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(HintProgress::load(HINTS_PATH))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (watch_actions, animate_hints).chain())
+        ;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hint_progress_round_trips_through_toml() {
+        let mut progress = HintProgress::from_toml("").unwrap();
+        assert!(Hint::ALL.iter().all(|hint| !progress.is_done(*hint)));
+        progress.finish(Hint::Zoom);
+        progress.finish(Hint::Zoom);
+        let text = progress.to_toml().unwrap();
+        assert_eq!(text.trim(), r#"done = ["zoom"]"#);
+        assert_eq!(HintProgress::from_toml(&text).unwrap(), progress);
+    }
+}
@@ -0,0 +1,197 @@
+// A tiny blocking HTTP/1.1 server for remote control and monitoring. Each
+// connection is read on its own thread and handed to the app as a
+// `PendingRequest`; systems pick out the requests they handle with
+// `HttpRequests::take` and answer them, and anything left over gets a 404.
+// Outside an app, `listen` hands over the requests to answer directly.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use async_channel::{Receiver, Sender};
+use bevy::prelude::*;
+
+// Requests with larger bodies are refused.
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+pub struct HttpPlugin {
+    pub address: String,
+}
+
+pub struct HttpResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn text(status: u16, body: impl Into<String>) -> Self {
+        Self { status, content_type: "text/plain; charset=utf-8", body: body.into().into_bytes() }
+    }
+
+    pub fn json(body: impl Into<String>) -> Self {
+        Self { status: 200, content_type: "application/json", body: body.into().into_bytes() }
+    }
+}
+
+pub struct PendingRequest {
+    pub method: String,
+    pub path: String,
+    // Everything after the `?`, if anything.
+    pub query: String,
+    pub body: Vec<u8>,
+    reply: Sender<HttpResponse>,
+}
+
+impl PendingRequest {
+    pub fn respond(self, response: HttpResponse) {
+        let _ = self.reply.try_send(response);
+    }
+
+    /// Answers with a 404, for a request nothing handles.
+    pub fn reject(self) {
+        let message = format!("no route for {} {}", self.method, self.path);
+        self.respond(HttpResponse::text(404, message));
+    }
+
+    /// The value of `name` in the query string, if given.
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// Requests received this frame that haven't been answered yet.
+#[derive(Resource)]
+pub struct HttpRequests {
+    incoming: Receiver<PendingRequest>,
+    pending: Vec<PendingRequest>,
+}
+
+impl HttpRequests {
+    /// Removes and returns the pending requests for `method` and `path`.
+    pub fn take(&mut self, method: &str, path: &str) -> Vec<PendingRequest> {
+        let (taken, rest) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|request| request.method == method && request.path == path);
+        self.pending = rest;
+        taken
+    }
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        413 => "Payload Too Large",
+        501 => "Not Implemented",
+        _ => "Unknown",
+    }
+}
+
+fn write_response(stream: &mut TcpStream, response: &HttpResponse) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status, status_text(response.status), response.content_type, response.body.len(),
+    )?;
+    stream.write_all(&response.body)
+}
+
+fn handle_connection(mut stream: TcpStream, requests: Sender<PendingRequest>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return write_response(&mut stream, &HttpResponse::text(400, "malformed request line"));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return write_response(&mut stream, &HttpResponse::text(413, "body too large"));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let (reply, response) = async_channel::bounded(1);
+    let request = PendingRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        body,
+        reply,
+    };
+    if requests.send_blocking(request).is_err() {
+        return Ok(());
+    }
+    if let Ok(response) = response.recv_blocking() {
+        write_response(&mut stream, &response)?;
+    }
+    Ok(())
+}
+
+fn receive_requests(mut requests: ResMut<HttpRequests>) {
+    while let Ok(request) = requests.incoming.try_recv() {
+        requests.pending.push(request);
+    }
+}
+
+fn reject_unhandled_requests(mut requests: ResMut<HttpRequests>) {
+    for request in requests.pending.drain(..) {
+        request.reject();
+    }
+}
+
+/// Listens for HTTP on `address`, on a thread of its own, and passes each
+/// request on as it's read. Every one waits for an answer.
+pub fn listen(address: &str) -> std::io::Result<Receiver<PendingRequest>> {
+    let listener = TcpListener::bind(address)?;
+    println!("listening for HTTP on {}", address);
+    let (sender, incoming) = async_channel::unbounded();
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                if let Err(err) = handle_connection(stream, sender) {
+                    println!("HTTP connection failed: {}", err);
+                }
+            });
+        }
+    });
+    Ok(incoming)
+}
+
+impl Plugin for HttpPlugin {
+    fn build(&self, app: &mut App) {
+        let incoming = match listen(&self.address) {
+            Ok(incoming) => incoming,
+            Err(err) => {
+                println!("failed to listen for HTTP on {}: {}", self.address, err);
+                return;
+            }
+        };
+
+        app
+        .insert_resource(HttpRequests { incoming, pending: vec![] })
+        .add_systems(PreUpdate, receive_requests)
+        .add_systems(Last, reject_unhandled_requests)
+        ;
+    }
+}
@@ -0,0 +1,166 @@
+// Seeding the board from a picture (`import FILE` in the console, or
+// `--import FILE`): a PNG or JPEG is scaled to fit the board, letterboxed and
+// centered, and its bright parts come alive. Each cell averages the pixels it
+// covers, then is either thresholded or dithered, which keeps shading in
+// photos. Whatever was on the board is cleared.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+use bevy::render::texture::{CompressedImageFormats, ImageSampler, ImageType};
+
+use crate::conway::{BoardSnapshot, ConwayCommand, Generation};
+use crate::event_log::LogEvent;
+
+pub struct ImageImportPlugin;
+
+pub const DEFAULT_THRESHOLD: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImportMode {
+    // Alive where brighter than this, from 0 to 1.
+    Threshold(f32),
+    // Floyd-Steinberg dithering, so mid grays come out as a mix.
+    Dither,
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct ImportImage {
+    pub path: String,
+    pub mode: ImportMode,
+}
+
+/// An image's brightness, from 0 to 1, row by row. Transparent pixels are dark.
+pub struct Luminance {
+    pub size: UVec2,
+    pub values: Vec<f32>,
+}
+
+impl Luminance {
+    pub fn from_rgba(size: UVec2, rgba: &[u8]) -> Self {
+        let values = rgba
+            .chunks_exact(4)
+            .map(|pixel| {
+                let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]].map(|c| c as f32 / 255.0);
+                (0.2126 * r + 0.7152 * g + 0.0722 * b) * a
+            })
+            .collect();
+        Self { size, values }
+    }
+
+    /// Loads a PNG or JPEG.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+        let extension = std::path::Path::new(path).extension().and_then(|extension| extension.to_str());
+        let extension = extension.unwrap_or_default();
+        let image = Image::from_buffer(
+            &bytes,
+            ImageType::Extension(extension),
+            CompressedImageFormats::NONE,
+            true,
+            ImageSampler::Default,
+        )
+            .map_err(|err| err.to_string())?;
+        let image = image.convert(TextureFormat::Rgba8UnormSrgb).ok_or("unsupported pixel format")?;
+        Ok(Self::from_rgba(image.size(), &image.data))
+    }
+
+    /// The image scaled to fit `board` without stretching and centered on it,
+    /// one value per cell. The letterbox around it is dark.
+    pub fn fitted(&self, board: UVec2) -> Vec<f32> {
+        let mut fitted = vec![0.0; (board.x * board.y) as usize];
+        if self.size.x == 0 || self.size.y == 0 {
+            return fitted;
+        }
+        let scale = (board.x as f32 / self.size.x as f32).min(board.y as f32 / self.size.y as f32);
+        let fitted_size = (self.size.as_vec2() * scale).round().as_uvec2().max(UVec2::ONE).min(board);
+        let offset = (board - fitted_size) / 2;
+        for y in 0..fitted_size.y {
+            // The pixels under this cell, at least one of them.
+            let (top, bottom) = (y * self.size.y / fitted_size.y, ((y + 1) * self.size.y).div_ceil(fitted_size.y));
+            for x in 0..fitted_size.x {
+                let (left, right) = (x * self.size.x / fitted_size.x, ((x + 1) * self.size.x).div_ceil(fitted_size.x));
+                let sum = (top..bottom)
+                    .flat_map(|sy| (left..right).map(move |sx| (sy * self.size.x + sx) as usize))
+                    .map(|index| self.values[index])
+                    .sum::<f32>();
+                let cell = (y + offset.y) * board.x + x + offset.x;
+                fitted[cell as usize] = sum / ((bottom - top) * (right - left)) as f32;
+            }
+        }
+        fitted
+    }
+}
+
+/// The live cells for an image fitted to a `board`-sized board.
+pub fn image_to_cells(image: &Luminance, board: UVec2, mode: ImportMode) -> Vec<IVec2> {
+    let mut values = image.fitted(board);
+    let mut cells = vec![];
+    let (width, height) = (board.x as usize, board.y as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let value = values[y * width + x];
+            let alive = match mode {
+                ImportMode::Threshold(threshold) => value > threshold,
+                ImportMode::Dither => value > 0.5,
+            };
+            if alive {
+                cells.push(IVec2::new(x as i32, y as i32));
+            }
+            if mode == ImportMode::Dither {
+                // Push what the cell got wrong onto the ones not done yet.
+                let error = value - alive as u8 as f32;
+                let mut spread = |dx: isize, dy: usize, weight: f32| {
+                    let (nx, ny) = (x as isize + dx, y + dy);
+                    if nx >= 0 && (nx as usize) < width && ny < height {
+                        values[ny * width + nx as usize] += error * weight;
+                    }
+                };
+                spread(1, 0, 7.0 / 16.0);
+                spread(-1, 1, 3.0 / 16.0);
+                spread(0, 1, 5.0 / 16.0);
+                spread(1, 1, 1.0 / 16.0);
+            }
+        }
+    }
+    cells
+}
+
+fn import_images(
+    mut imports: EventReader<ImportImage>,
+    generation: Res<Generation>,
+    board: Res<BoardSnapshot>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut log: EventWriter<LogEvent>,
+    // Imports from the command line come in before the board is ready for them.
+    mut waiting: Local<Vec<ImportImage>>,
+) {
+    waiting.extend(imports.read().cloned());
+    if waiting.is_empty() || !generation.is_seeded() {
+        return;
+    }
+    for import in waiting.drain(..) {
+        let image = match Luminance::load(&import.path) {
+            Ok(image) => image,
+            Err(err) => {
+                log.send(LogEvent(format!("Failed to import {}: {}", import.path, err)));
+                continue;
+            }
+        };
+        let cells = image_to_cells(&image, board.size(), import.mode);
+        // Disjoint, since the board sets cells before clearing them.
+        let is_imported = |cell: &IVec2| cells.binary_search_by_key(&(cell.y, cell.x), |cell| (cell.y, cell.x)).is_ok();
+        let cleared = board.alive_cells().into_iter().filter(|cell| !is_imported(cell)).collect();
+        commands.send(ConwayCommand::ClearCells(cleared));
+        log.send(LogEvent(format!("Imported {} ({} cells)", import.path, cells.len())));
+        commands.send(ConwayCommand::SetCells(cells));
+    }
+}
+
+impl Plugin for ImageImportPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<ImportImage>()
+        .add_systems(Update, import_images)
+        ;
+    }
+}
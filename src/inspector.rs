@@ -0,0 +1,87 @@
+// A panel in the top right corner where analysis tools show what they've
+// found. Each tool owns a titled section and the panel hides itself when
+// there's nothing to show.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+pub struct InspectorPlugin;
+
+const FONT_SIZE: f32 = 18.0;
+
+/// The sections shown in the inspector panel, by title.
+#[derive(Resource, Default)]
+pub struct Inspector {
+    sections: BTreeMap<String, String>,
+}
+
+impl Inspector {
+    pub fn set(&mut self, title: &str, text: impl Into<String>) {
+        self.sections.insert(title.to_string(), text.into());
+    }
+
+    pub fn remove(&mut self, title: &str) {
+        self.sections.remove(title);
+    }
+}
+
+#[derive(Component)]
+struct InspectorRoot;
+
+#[derive(Component)]
+struct InspectorText;
+
+fn setup(mut commands: Commands) {
+    commands
+        .spawn((
+            InspectorRoot,
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(8.0),
+                    right: Val::Px(8.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                z_index: ZIndex::Global(5),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((InspectorText, TextBundle::from_section("", TextStyle {
+                font_size: FONT_SIZE,
+                color: Color::WHITE,
+                ..default()
+            })));
+        });
+}
+
+fn render_inspector(
+    inspector: Res<Inspector>,
+    mut root: Query<&mut Style, With<InspectorRoot>>,
+    mut text: Query<&mut Text, With<InspectorText>>,
+) {
+    if !inspector.is_changed() {
+        return;
+    }
+    root.single_mut().display = if inspector.sections.is_empty() { Display::None } else { Display::Flex };
+    text.single_mut().sections[0].value = inspector
+        .sections
+        .iter()
+        .map(|(title, text)| format!("{}\n{}", title, text))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+}
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Inspector>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, render_inspector)
+        ;
+    }
+}
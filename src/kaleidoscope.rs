@@ -0,0 +1,138 @@
+// A display-only kaleidoscope: K cycles through folding the board into 6, 8
+// and 12-fold radial symmetry and back to the plain board. The simulation
+// carries on unchanged underneath, and the board's tint (see `BoardSprite`)
+// carries over.
+
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef, ShaderType};
+use bevy::sprite::{Material2d, Material2dPlugin, MaterialMesh2dBundle};
+
+use crate::conway::{BoardSprite, ConwayConfig, ConwayWorld};
+use crate::event_log::LogEvent;
+
+pub struct KaleidoscopePlugin;
+
+const FOLDS: &[u32] = &[6, 8, 12];
+
+#[derive(ShaderType, Debug, Clone)]
+struct KaleidoscopeSettings {
+    background: Vec4,
+    tint: Vec4,
+    folds: f32,
+    aspect: f32,
+}
+
+#[derive(AsBindGroup, Asset, TypePath, Debug, Clone)]
+struct KaleidoscopeMaterial {
+    #[uniform(0)]
+    settings: KaleidoscopeSettings,
+    #[texture(1)]
+    #[sampler(2)]
+    board: Handle<Image>,
+}
+
+impl Material2d for KaleidoscopeMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/kaleidoscope.wgsl".into()
+    }
+}
+
+// The quad drawn over the board, spawned the first time it's needed.
+#[derive(Resource)]
+struct Kaleidoscope {
+    entity: Entity,
+    material: Handle<KaleidoscopeMaterial>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cycle_folds(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    board: Option<Res<ConwayWorld>>,
+    config: Res<ConwayConfig>,
+    kaleidoscope: Option<Res<Kaleidoscope>>,
+    clear_color: Res<ClearColor>,
+    sprites: Query<&Sprite, With<BoardSprite>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<KaleidoscopeMaterial>>,
+    mut visibilities: Query<&mut Visibility>,
+    mut log: EventWriter<LogEvent>,
+    // The index into `FOLDS` being shown, if any.
+    mut current: Local<Option<usize>>,
+) {
+    let Some(board) = board else {
+        return;
+    };
+    if !keys.just_pressed(KeyCode::K) {
+        return;
+    }
+    *current = match *current {
+        None => Some(0),
+        Some(index) if index + 1 < FOLDS.len() => Some(index + 1),
+        Some(_) => None,
+    };
+    let Some(index) = *current else {
+        if let Some(kaleidoscope) = kaleidoscope {
+            if let Ok(mut visibility) = visibilities.get_mut(kaleidoscope.entity) {
+                *visibility = Visibility::Hidden;
+            }
+        }
+        log.send(LogEvent("Kaleidoscope off".to_string()));
+        return;
+    };
+
+    let extents = config.extents();
+    let settings = KaleidoscopeSettings {
+        background: clear_color.0.as_linear_rgba_f32().into(),
+        tint: sprites.get_single().map_or(Color::WHITE, |sprite| sprite.color).as_linear_rgba_f32().into(),
+        folds: FOLDS[index] as f32,
+        aspect: extents.x / extents.y,
+    };
+    match kaleidoscope {
+        Some(kaleidoscope) => {
+            if let Some(material) = materials.get_mut(&kaleidoscope.material) {
+                material.settings = settings;
+            }
+            if let Ok(mut visibility) = visibilities.get_mut(kaleidoscope.entity) {
+                *visibility = Visibility::Visible;
+            }
+        }
+        None => {
+            let material = materials.add(KaleidoscopeMaterial { settings, board: board.0.clone() });
+            let entity = commands
+                .spawn(MaterialMesh2dBundle {
+                    mesh: meshes.add(shape::Quad::new(extents).into()).into(),
+                    material: material.clone(),
+                    // Over the board and its overlays.
+                    transform: Transform::from_xyz(0.0, 0.0, 2.0),
+                    ..default()
+                })
+                .id();
+            commands.insert_resource(Kaleidoscope { entity, material });
+        }
+    }
+    log.send(LogEvent(format!("Kaleidoscope: {}-fold", FOLDS[index])));
+}
+
+fn follow_tint(
+    kaleidoscope: Option<Res<Kaleidoscope>>,
+    sprites: Query<&Sprite, (With<BoardSprite>, Changed<Sprite>)>,
+    mut materials: ResMut<Assets<KaleidoscopeMaterial>>,
+) {
+    let (Some(kaleidoscope), Ok(sprite)) = (kaleidoscope, sprites.get_single()) else {
+        return;
+    };
+    if let Some(material) = materials.get_mut(&kaleidoscope.material) {
+        material.settings.tint = sprite.color.as_linear_rgba_f32().into();
+    }
+}
+
+impl Plugin for KaleidoscopePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_plugins(Material2dPlugin::<KaleidoscopeMaterial>::default())
+        .add_systems(Update, (cycle_folds, follow_tint))
+        ;
+    }
+}
@@ -0,0 +1,87 @@
+// High scores for arcade mode, persisted to disk as tab-separated lines.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+
+pub const LEADERBOARD_PATH: &str = "leaderboard.tsv";
+const MAX_ENTRIES: usize = 10;
+pub const MAX_NAME_LENGTH: usize = 12;
+
+#[derive(Clone, Debug)]
+pub struct HighScore {
+    pub name: String,
+    pub score: u64,
+    // One-based level reached.
+    pub level: usize,
+    // Formatted as YYYY-MM-DD.
+    pub date: String,
+}
+
+/// The best arcade scores, highest first.
+#[derive(Resource, Default)]
+pub struct Leaderboard {
+    pub entries: Vec<HighScore>,
+}
+
+impl Leaderboard {
+    /// Loads the leaderboard, skipping any malformed lines. A missing file is an empty leaderboard.
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let contents = fs::read_to_string(path).unwrap_or_default();
+        let mut entries = contents
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                Some(HighScore {
+                    name: fields.next()?.to_string(),
+                    score: fields.next()?.parse().ok()?,
+                    level: fields.next()?.parse().ok()?,
+                    date: fields.next()?.to_string(),
+                })
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        entries.truncate(MAX_ENTRIES);
+        Self { entries }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let contents = self.entries
+            .iter()
+            .map(|entry| format!("{}\t{}\t{}\t{}\n", entry.name, entry.score, entry.level, entry.date))
+            .collect::<String>();
+        fs::write(path, contents)
+    }
+
+    /// Whether `score` would make it onto the leaderboard.
+    pub fn qualifies(&self, score: u64) -> bool {
+        score > 0 && (self.entries.len() < MAX_ENTRIES || self.entries.iter().any(|entry| score > entry.score))
+    }
+
+    pub fn insert(&mut self, mut entry: HighScore) {
+        // Tabs and newlines would break the file format.
+        entry.name.retain(|c| !c.is_control());
+        let index = self.entries.iter().position(|other| entry.score > other.score).unwrap_or(self.entries.len());
+        self.entries.insert(index, entry);
+        self.entries.truncate(MAX_ENTRIES);
+    }
+}
+
+/// Today's (UTC) date as YYYY-MM-DD.
+pub fn today() -> String {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    // Converts days since the epoch to a civil date; see http://howardhinnant.github.io/date_algorithms.html
+    let z = (seconds / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
@@ -0,0 +1,93 @@
+// The game's plugins and simulation, shared by the binary and the tests.
+//
+// Modules with `ShaderType` uniforms allow dead code: encase 0.6's derive
+// leaves an uncalled check behind for each field, which newer compilers warn
+// about.
+
+pub mod ab_test;
+pub mod achievements;
+pub mod annotations;
+pub mod ants;
+pub mod arcade;
+pub mod attract;
+pub mod cell_inspector;
+pub mod census;
+pub mod challenge;
+pub mod checkpoint;
+pub mod cli;
+pub mod compare;
+pub mod console;
+pub mod control_window;
+#[allow(dead_code)]
+pub mod conway;
+pub mod cpu;
+pub mod cursors;
+pub mod diff;
+#[allow(dead_code)]
+pub mod display;
+pub mod event_log;
+pub mod evolve;
+pub mod experiments;
+pub mod fast_forward;
+pub mod figure;
+pub mod flow;
+pub mod fog;
+pub mod formats;
+pub mod frame_capture;
+pub mod gardener;
+pub mod ghost;
+pub mod harness;
+pub mod heat_map;
+pub mod hints;
+pub mod http;
+pub mod image_import;
+pub mod inspector;
+#[allow(dead_code)]
+pub mod kaleidoscope;
+pub mod leaderboard;
+pub mod life_rule;
+pub mod lod;
+pub mod masks;
+pub mod metrics;
+pub mod mixer;
+pub mod morph;
+pub mod net;
+pub mod npy;
+pub mod osc;
+pub mod paddle;
+pub mod palette;
+pub mod patterns;
+pub mod picture;
+pub mod pipeline_stats;
+pub mod pnm;
+pub mod portals;
+pub mod power_saving;
+pub mod query;
+pub mod remote;
+pub mod rng;
+pub mod ruler;
+pub mod rule_table;
+pub mod save_file;
+pub mod schedule;
+pub mod scripting;
+pub mod server;
+pub mod snapshot;
+pub mod sonification;
+pub mod soup;
+pub mod soup_stamp;
+pub mod spectate;
+pub mod stabilization;
+pub mod tape;
+pub mod terrain;
+pub mod text_stamp;
+pub mod thresholds;
+pub mod timelapse;
+pub mod title;
+pub mod toml_file;
+pub mod tools;
+pub mod twitch;
+pub mod velocity;
+pub mod views;
+pub mod workspaces;
+#[allow(dead_code)]
+pub mod wrap_edges;
@@ -0,0 +1,243 @@
+// Outer totalistic "B/S" rules like B3/S23 (Conway's Life) or B36/S23
+// (HighLife): a dead cell is born with any of the B neighbour counts, and a
+// live cell survives with any of the S ones.
+//
+// Generations rules like B2/S/C3 (Brian's Brain) or B2/S345/C4 (Star Wars)
+// add decay states: a live cell that doesn't survive spends a generation in
+// each before it's dead, neither counting as a live neighbour nor being born
+// into in the meantime. C counts every state, dead and live included.
+//
+// Larger than Life rules, in Golly's notation, like R5,C0,M1,S34..58,B34..45,NM
+// (Bosco's Rule), count every cell within a radius R, in a square (NM) or a
+// diamond (NN), the cell itself too with M1, and give birth and survival as
+// ranges of counts. C is the state count as above, with C0 the same as C2.
+
+use std::fmt;
+
+use crate::rule_table::Neighborhood;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct LifeRule {
+    // Bit n is set when a dead cell with n live neighbours is born.
+    pub birth: u16,
+    // Bit n is set when a live cell with n live neighbours survives.
+    pub survival: u16,
+    // Every state a cell can be in, 2 without decay states.
+    pub states: u32,
+    // A wider neighbourhood, in place of the counts above.
+    pub larger: Option<LargerThanLife>,
+}
+
+/// A Larger than Life neighbourhood and the ranges of live cells in it, both
+/// ends included, that a cell is born or survives with.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct LargerThanLife {
+    pub radius: u32,
+    pub neighborhood: Neighborhood,
+    // Whether a live cell counts itself.
+    pub middle: bool,
+    pub birth: (u32, u32),
+    pub survival: (u32, u32),
+}
+
+impl LargerThanLife {
+    /// The widest neighbourhood the update shader will loop over.
+    pub const MAX_RADIUS: u32 = 10;
+
+    /// Whether the cell `dx`, `dy` away is a neighbour.
+    pub fn contains(&self, dx: i32, dy: i32) -> bool {
+        let radius = self.radius as i32;
+        let within = match self.neighborhood {
+            Neighborhood::Moore => dx.abs().max(dy.abs()) <= radius,
+            Neighborhood::VonNeumann => dx.abs() + dy.abs() <= radius,
+        };
+        within && (dx, dy) != (0, 0)
+    }
+
+    /// How many neighbours there are, not counting the cell itself.
+    pub fn neighbors(&self) -> u32 {
+        let side = 2 * self.radius + 1;
+        match self.neighborhood {
+            Neighborhood::Moore => side * side - 1,
+            Neighborhood::VonNeumann => 2 * self.radius * (self.radius + 1),
+        }
+    }
+
+    fn parse(text: &str) -> Result<(Self, u32), String> {
+        let error = || format!("expected a rule like R5,C0,M1,S34..58,B34..45,NM, got {:?}", text);
+        let number = |digits: &str| digits.parse::<u32>().map_err(|_| error());
+        let range = |text: &str| match text.split_once("..") {
+            Some((min, max)) => Ok((number(min)?, number(max)?)),
+            None => number(text).map(|count| (count, count)),
+        };
+        let (mut radius, mut states, mut middle, mut birth, mut survival) = (None, None, None, None, None);
+        let mut neighborhood = None;
+        for part in text.trim().split(',') {
+            let mut chars = part.chars();
+            let letter = chars.next().map(|c| c.to_ascii_uppercase());
+            let rest = chars.as_str();
+            let replaced = match letter {
+                Some('R') => radius.replace(number(rest)?).is_some(),
+                Some('C') => states.replace(number(rest)?).is_some(),
+                Some('M') => middle.replace(number(rest)? != 0).is_some(),
+                Some('S') => survival.replace(range(rest)?).is_some(),
+                Some('B') => birth.replace(range(rest)?).is_some(),
+                Some('N') => {
+                    let shape = match rest.to_ascii_uppercase().as_str() {
+                        "M" => Neighborhood::Moore,
+                        "N" => Neighborhood::VonNeumann,
+                        _ => return Err(error()),
+                    };
+                    neighborhood.replace(shape).is_some()
+                }
+                _ => return Err(error()),
+            };
+            if replaced {
+                return Err(error());
+            }
+        }
+        let larger = Self {
+            radius: radius.filter(|radius| (1..=Self::MAX_RADIUS).contains(radius)).ok_or_else(error)?,
+            neighborhood: neighborhood.unwrap_or(Neighborhood::Moore),
+            middle: middle.unwrap_or(false),
+            birth: birth.ok_or_else(error)?,
+            survival: survival.ok_or_else(error)?,
+        };
+        let most = larger.neighbors() + larger.middle as u32;
+        if [larger.birth, larger.survival].iter().any(|(min, max)| min > max || *max > most) {
+            return Err(format!("{} has ranges past the {} cells in its neighbourhood", text, most));
+        }
+        let states = match states.unwrap_or(0) {
+            0 => 2,
+            states if (2..=LifeRule::MAX_STATES).contains(&states) => states,
+            _ => return Err(error()),
+        };
+        Ok((larger, states))
+    }
+}
+
+impl LifeRule {
+    pub const CONWAY: Self = Self { birth: 1 << 3, survival: 1 << 2 | 1 << 3, states: 2, larger: None };
+    /// The most states there's room for in a cell's 8-bit gray level.
+    pub const MAX_STATES: u32 = 256;
+
+    /// Parses "B3/S23"-style rules, in either order and any case, with an
+    /// optional "/C3" (or "/G3") state count for generations rules. The
+    /// "23/3" and "23/3/3" S/B/C notations are accepted too, and so are
+    /// Larger than Life rules.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        if text.contains(',') {
+            let (larger, states) = LargerThanLife::parse(text)?;
+            return Ok(Self { birth: 0, survival: 0, states, larger: Some(larger) });
+        }
+        let error = || format!("expected a rule like B3/S23 or B2/S/C3, got {:?}", text);
+        let counts = |digits: &str| {
+            digits.chars().try_fold(0u16, |mask, c| match c.to_digit(10) {
+                Some(n) if n <= 8 => Ok(mask | 1 << n),
+                _ => Err(error()),
+            })
+        };
+        let state_count = |digits: &str| {
+            digits.parse().ok().filter(|states| (2..=Self::MAX_STATES).contains(states)).ok_or_else(error)
+        };
+        let parts = text.trim().split('/').collect::<Vec<_>>();
+        if !(2..=3).contains(&parts.len()) {
+            return Err(error());
+        }
+        if !parts.iter().any(|part| part.starts_with(|c: char| c.is_ascii_alphabetic())) {
+            let states = parts.get(2).map_or(Ok(2), |digits| state_count(digits))?;
+            return Ok(Self { birth: counts(parts[1])?, survival: counts(parts[0])?, states, larger: None });
+        }
+        let (mut birth, mut survival, mut states) = (None, None, None);
+        for part in parts {
+            let mut chars = part.chars();
+            let letter = chars.next().map(|c| c.to_ascii_uppercase());
+            let rest = chars.as_str();
+            let replaced = match letter {
+                Some('B') => birth.replace(counts(rest)?).is_some(),
+                Some('S') => survival.replace(counts(rest)?).is_some(),
+                Some('C' | 'G') => states.replace(state_count(rest)?).is_some(),
+                _ => return Err(error()),
+            };
+            if replaced {
+                return Err(error());
+            }
+        }
+        let (birth, survival) = (birth.ok_or_else(error)?, survival.ok_or_else(error)?);
+        Ok(Self { birth, survival, states: states.unwrap_or(2), larger: None })
+    }
+
+    /// Whether live cells pass through decay states on their way to dying.
+    pub fn decays(&self) -> bool {
+        self.states > 2
+    }
+
+    /// The rule, unless it has decay states or a Larger than Life
+    /// neighbourhood, which the CPU board can't run.
+    pub fn for_cpu(self) -> Result<Self, String> {
+        match (self.decays(), self.larger.is_some()) {
+            (true, _) => Err(format!("{} has decay states, so it can only run on the GPU", self)),
+            (_, true) => Err(format!("{} looks past the eight cells around, so it can only run on the GPU", self)),
+            (false, false) => Ok(self),
+        }
+    }
+
+    /// Whether a cell is alive next generation, leaving decay states aside.
+    pub fn next(&self, alive: bool, neighbors: u32) -> bool {
+        if let Some(larger) = &self.larger {
+            let count = neighbors + (alive && larger.middle) as u32;
+            let (min, max) = if alive { larger.survival } else { larger.birth };
+            return (min..=max).contains(&count);
+        }
+        let mask = if alive { self.survival } else { self.birth };
+        mask & 1 << neighbors != 0
+    }
+
+    /// The state a cell moves on to, with states numbered like a
+    /// `RuleTable`'s: 0 is dead and `states - 1` alive, with the decay states
+    /// counting down in between. That's how the board stores them, as gray
+    /// levels fading out.
+    pub fn next_state(&self, state: u32, neighbors: u32) -> u32 {
+        let alive = self.states - 1;
+        match state {
+            0 => self.next(false, neighbors) as u32 * alive,
+            state if state == alive && self.next(true, neighbors) => alive,
+            state => state - 1,
+        }
+    }
+}
+
+impl Default for LifeRule {
+    fn default() -> Self {
+        Self::CONWAY
+    }
+}
+
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(larger) = &self.larger {
+            let states = if self.decays() { self.states } else { 0 };
+            let range = |(min, max): (u32, u32)| format!("{}..{}", min, max);
+            let shape = match larger.neighborhood {
+                Neighborhood::Moore => 'M',
+                Neighborhood::VonNeumann => 'N',
+            };
+            return write!(
+                f,
+                "R{},C{},M{},S{},B{},N{}",
+                larger.radius,
+                states,
+                larger.middle as u32,
+                range(larger.survival),
+                range(larger.birth),
+                shape,
+            );
+        }
+        let counts = |mask: u16| (0..=8).filter(|n| mask & 1 << n != 0).map(|n| n.to_string()).collect::<String>();
+        write!(f, "B{}/S{}", counts(self.birth), counts(self.survival))?;
+        if self.decays() {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+// A mip chain of the board for drawing it zoomed out. Every `every` frames
+// the board is copied into the top level of `BoardLod` and each level below
+// is built from the one above, averaging 2 x 2 cells. Dead cells are left out
+// of the colors, so each level's alpha is how densely populated it is. The
+// display (see `display.rs`) reads the level that matches a pixel's footprint
+// instead of picking out single cells, which alias into noise once a pixel
+// covers many of them.
+
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
+use bevy::render::render_asset::RenderAssets;
+use bevy::render::render_graph::{self, Node as RenderNode, RenderGraph};
+use bevy::render::render_resource::*;
+use bevy::render::renderer::{RenderContext, RenderDevice};
+use bevy::render::{Render, RenderApp, RenderSet};
+
+use crate::conway::{self, BoardResized, ConwayConfig, ConwayWorld, PipelineStats};
+
+pub struct LodPlugin;
+
+const WORKGROUP_SIZE: u32 = 8;
+
+/// Whether the mip chain is built, and how many frames apart.
+#[derive(Resource, Clone, Copy, Debug, ExtractResource)]
+pub struct LodSettings {
+    pub enabled: bool,
+    pub every: u32,
+}
+
+impl Default for LodSettings {
+    fn default() -> Self {
+        Self { enabled: false, every: 1 }
+    }
+}
+
+/// The board's mip chain, level 0 being the board itself.
+#[derive(Resource, Clone, ExtractResource)]
+pub struct BoardLod(pub Handle<Image>);
+
+/// Levels down to a single texel for a board of `size`.
+pub fn level_count(size: UVec2) -> u32 {
+    32 - size.x.max(size.y).leading_zeros()
+}
+
+/// The size of a level, rounding down and never below one texel.
+pub fn level_size(size: UVec2, level: u32) -> UVec2 {
+    (size >> level).max(UVec2::ONE)
+}
+
+fn lod_image(size: UVec2) -> Image {
+    let levels = level_count(size);
+    let bytes = (0..levels).map(|level| level_size(size, level)).map(|size| (size.x * size.y * 4) as usize).sum();
+    Image {
+        data: vec![0; bytes],
+        texture_descriptor: TextureDescriptor {
+            label: Some("board_lod"),
+            size: Extent3d { width: size.x, height: size.y, depth_or_array_layers: 1 },
+            mip_level_count: levels,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::COPY_DST | TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        },
+        ..default()
+    }
+}
+
+fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>, config: Res<ConwayConfig>) {
+    commands.insert_resource(BoardLod(images.add(lod_image(config.size))));
+}
+
+// A new chain for a resized board, under the same handle.
+fn fit_lod(mut resized: EventReader<BoardResized>, lod: Res<BoardLod>, mut images: ResMut<Assets<Image>>) {
+    if let Some(resized) = resized.read().last() {
+        images.insert(lod.0.id(), lod_image(resized.size));
+    }
+}
+
+#[derive(Resource)]
+struct LodPipeline {
+    layout: BindGroupLayout,
+    pipeline: CachedComputePipelineId,
+}
+
+impl FromWorld for LodPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let layout = world.resource::<RenderDevice>().create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("board_lod_bind_group_layout"),
+            entries: &[
+                // The level above.
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The level being built.
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::StorageTexture {
+                        access: StorageTextureAccess::WriteOnly,
+                        format: TextureFormat::Rgba8Unorm,
+                        view_dimension: TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+            ],
+        });
+        let shader = world.resource::<AssetServer>().load("shaders/lod.wgsl");
+        let pipeline = world.resource::<PipelineCache>().queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some(Cow::from("board_lod_pipeline")),
+            layout: vec![layout.clone()],
+            push_constant_ranges: vec![],
+            shader,
+            shader_defs: vec![],
+            entry_point: Cow::from("downsample"),
+        });
+        Self { layout, pipeline }
+    }
+}
+
+// One bind group per level below the top, which only need making again when
+// the board's resized.
+#[derive(Resource)]
+struct LodBindGroups {
+    size: UVec2,
+    levels: Vec<BindGroup>,
+}
+
+fn prepare_bind_groups(
+    mut commands: Commands,
+    pipeline: Res<LodPipeline>,
+    lod: Option<Res<BoardLod>>,
+    gpu_images: Res<RenderAssets<Image>>,
+    render_device: Res<RenderDevice>,
+    bind_groups: Option<Res<LodBindGroups>>,
+) {
+    let Some(image) = lod.and_then(|lod| gpu_images.get(&lod.0)) else {
+        return;
+    };
+    let size = image.size.as_uvec2();
+    if bind_groups.is_some_and(|bind_groups| bind_groups.size == size) {
+        return;
+    }
+    let view = |level| image.texture.create_view(&TextureViewDescriptor {
+        base_mip_level: level,
+        mip_level_count: Some(1),
+        ..default()
+    });
+    let levels = (1..level_count(size))
+        .map(|level| render_device.create_bind_group(
+            Some("board_lod_bind_group"),
+            &pipeline.layout,
+            &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&view(level - 1)),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(&view(level)),
+                },
+            ],
+        ))
+        .collect();
+    commands.insert_resource(LodBindGroups { size, levels });
+}
+
+fn report_pipeline(pipeline: Res<LodPipeline>, pipeline_cache: Res<PipelineCache>, stats: Res<PipelineStats>) {
+    stats.set_state("board_lod", pipeline_cache.get_compute_pipeline_state(pipeline.pipeline));
+}
+
+#[derive(Default)]
+struct LodNode {
+    frames: u32,
+    due: bool,
+}
+
+impl RenderNode for LodNode {
+    fn update(&mut self, world: &mut World) {
+        let settings = world.resource::<LodSettings>();
+        self.due = settings.enabled && self.frames.is_multiple_of(settings.every.max(1));
+        self.frames = self.frames.wrapping_add(1);
+    }
+
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        if !self.due {
+            return Ok(());
+        }
+        let Some(bind_groups) = world.get_resource::<LodBindGroups>() else {
+            return Ok(());
+        };
+        let pipeline_id = world.resource::<LodPipeline>().pipeline;
+        let Some(pipeline) = world.resource::<PipelineCache>().get_compute_pipeline(pipeline_id) else {
+            return Ok(());
+        };
+        let gpu_images = world.resource::<RenderAssets<Image>>();
+        let board = gpu_images.get(&world.resource::<ConwayWorld>().0);
+        let lod = world.get_resource::<BoardLod>().and_then(|lod| gpu_images.get(&lod.0));
+        let (Some(board), Some(lod)) = (board, lod) else {
+            return Ok(());
+        };
+        // Just after a resize, until everything's been made again at the new size.
+        let size = board.size.as_uvec2();
+        if lod.size.as_uvec2() != size || bind_groups.size != size {
+            return Ok(());
+        }
+        world.resource::<PipelineStats>().dispatched("board_lod", bind_groups.levels.len() as u32);
+        let encoder = render_context.command_encoder();
+        encoder.copy_texture_to_texture(
+            board.texture.as_image_copy(),
+            lod.texture.as_image_copy(),
+            Extent3d { width: size.x, height: size.y, depth_or_array_layers: 1 },
+        );
+        // A pass per level, so each reads the one above once it's written.
+        for (level, bind_group) in (1..).zip(&bind_groups.levels) {
+            let size = level_size(size, level);
+            let mut pass = conway::compute_pass(encoder, "board_lod");
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.dispatch_workgroups(size.x.div_ceil(WORKGROUP_SIZE), size.y.div_ceil(WORKGROUP_SIZE), 1);
+        }
+        Ok(())
+    }
+}
+
+impl Plugin for LodPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<LodSettings>()
+        .add_plugins(ExtractResourcePlugin::<LodSettings>::default())
+        .add_plugins(ExtractResourcePlugin::<BoardLod>::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, fit_lod)
+        ;
+
+        let render_app = app.sub_app_mut(RenderApp);
+        render_app.init_resource::<LodSettings>();
+        render_app.add_systems(Render, (
+            prepare_bind_groups.in_set(RenderSet::PrepareBindGroups),
+            report_pipeline.in_set(RenderSet::Cleanup),
+        ));
+        let mut render_graph = render_app.world.resource_mut::<RenderGraph>();
+        render_graph.add_node("board_lod", LodNode::default());
+        // After the board updates, and before it's drawn.
+        render_graph.add_node_edge(conway::BOARD_NODE, "board_lod");
+        render_graph.add_node_edge("board_lod", bevy::render::main_graph::node::CAMERA_DRIVER);
+    }
+
+    fn finish(&self, app: &mut App) {
+        app.sub_app_mut(RenderApp).init_resource::<LodPipeline>();
+    }
+}
@@ -1,21 +1,208 @@
 use bevy::prelude::*;
 use bevy::diagnostic::FrameTimeDiagnosticsPlugin;
 use bevy::diagnostic::LogDiagnosticsPlugin;
-// mod hellow;
-// mod paddle;
-mod conway;
+use rand::Rng;
+use std::sync::Arc;
 
-fn setup(mut commands: Commands) -> () {
-    commands.spawn(Camera2dBundle::default());
+use my_bevy_game::{
+    ab_test, achievements, annotations, ants, arcade, attract, cell_inspector, challenge,
+    checkpoint, cli, compare, console, control_window, conway, cursors, diff, display, event_log,
+    evolve, experiments, fast_forward, figure, flow, fog, frame_capture, gardener, ghost, heat_map,
+    hints, http, image_import, inspector, kaleidoscope, lod, masks, metrics, mixer, morph, net, npy,
+    osc, paddle, palette, picture, pipeline_stats, pnm, portals, power_saving, remote, rng,
+    rule_table, ruler, schedule, scripting, server, sonification, soup, soup_stamp, spectate,
+    stabilization, tape, terrain, text_stamp, thresholds, timelapse, title, tools, twitch, velocity,
+    views, workspaces, wrap_edges,
+};
+
+fn setup(mut commands: Commands) {
+    commands.spawn((Camera2dBundle::default(), views::BoardCamera));
 }
  
 fn main() {
-    App::new()
+    let cli = cli::CliArgs::parse();
+    let mut sim_rng = cli.seed.map(rng::SimRng::from_seed).unwrap_or_default();
+    println!("seed: {}", sim_rng.seed);
+
+    if let Some(spec) = &cli.experiments {
+        if let Err(err) = experiments::run_experiments(spec) {
+            println!("experiments failed: {}", err);
+        }
+        return;
+    }
+    if let Some(spec) = &cli.evolve {
+        if let Err(err) = evolve::run_evolution(spec, sim_rng.seed) {
+            println!("evolution failed: {}", err);
+        }
+        return;
+    }
+    if let Some(soups) = cli.soup_search {
+        soup::run_soup_search(soups, sim_rng.seed);
+        return;
+    }
+    let config = match cli.board_config() {
+        Ok(config) => config,
+        Err(err) => {
+            println!("can't make a board that size: {}", err);
+            return;
+        }
+    };
+    if let Some(address) = &cli.relay {
+        if let Err(err) = net::run_relay(address, sim_rng.rng.gen(), config) {
+            println!("relay failed: {}", err);
+        }
+        return;
+    }
+    if let Some(address) = &cli.server {
+        if let Err(err) = server::run_server(address, cli.http.as_deref(), sim_rng.rng.gen(), config) {
+            println!("server failed: {}", err);
+        }
+        return;
+    }
+
+    let mut app = App::new();
+    app
+    .insert_resource(sim_rng)
+    .insert_resource(cli.clone())
     .add_plugins(DefaultPlugins)
-    .add_plugins(FrameTimeDiagnosticsPlugin::default())
+    .add_plugins(FrameTimeDiagnosticsPlugin)
     .add_plugins(LogDiagnosticsPlugin::default())
-    .add_plugins(conway::ConwayPlugin)
+    .add_plugins(conway::ConwayPlugin::new(config))
+    .add_plugins(ab_test::AbTestPlugin)
+    .add_plugins(achievements::AchievementsPlugin)
+    .add_plugins(annotations::AnnotationsPlugin)
+    .add_plugins(ants::AntsPlugin)
+    .add_plugins(cell_inspector::CellInspectorPlugin)
+    .add_plugins(challenge::ChallengePlugin)
+    .add_plugins(compare::ComparePlugin)
+    .add_plugins(console::ConsolePlugin)
+    .add_plugins(cursors::CursorsPlugin)
+    .add_plugins(diff::DiffPlugin)
+    .add_plugins(display::DisplayPlugin)
+    .add_plugins(event_log::EventLogPlugin)
+    .add_plugins(fast_forward::FastForwardPlugin)
+    .add_plugins(figure::FigurePlugin)
+    .add_plugins(flow::FlowPlugin)
+    .add_plugins(fog::FogPlugin)
+    .add_plugins(gardener::GardenerPlugin)
+    .add_plugins(ghost::GhostPlugin)
+    .add_plugins(heat_map::HeatMapPlugin)
+    .add_plugins(hints::HintsPlugin)
+    .add_plugins(image_import::ImageImportPlugin)
+    .add_plugins(inspector::InspectorPlugin)
+    .add_plugins(kaleidoscope::KaleidoscopePlugin)
+    .add_plugins(lod::LodPlugin)
+    .add_plugins(masks::MasksPlugin)
+    .add_plugins(mixer::MixerPlugin)
+    .add_plugins(morph::MorphPlugin)
+    .add_plugins(npy::NpyExportPlugin)
+    .add_plugins(palette::PalettePlugin)
+    .add_plugins(picture::PicturePlugin)
+    .add_plugins(pipeline_stats::PipelineStatsPlugin)
+    .add_plugins(pnm::PnmExportPlugin)
+    .add_plugins(portals::PortalsPlugin)
+    .add_plugins(power_saving::PowerSavingPlugin)
+    .add_plugins(ruler::RulerPlugin)
+    .add_plugins(schedule::SchedulePlugin)
+    .add_plugins(sonification::SonificationPlugin)
+    .add_plugins(soup_stamp::SoupStampPlugin)
+    .add_plugins(stabilization::StabilizationPlugin {
+        generations: cli.stable_for.unwrap_or(stabilization::DEFAULT_GENERATIONS),
+        auto_pause: cli.auto_stop,
+    })
+    .add_plugins(tape::TapePlugin)
+    .add_plugins(terrain::TerrainPlugin)
+    .add_plugins(text_stamp::TextStampPlugin)
+    .add_plugins(thresholds::ThresholdsPlugin)
+    .add_plugins(timelapse::TimeLapsePlugin)
+    .add_plugins(title::TitlePlugin)
+    .add_plugins(tools::ToolsPlugin)
+    .add_plugins(velocity::VelocityPlugin)
+    .add_plugins(views::ViewsPlugin)
+    .add_plugins(workspaces::WorkspacesPlugin)
+    .add_plugins(wrap_edges::WrapEdgesPlugin)
     .add_systems(Startup, setup)
     .add_systems(Update, bevy::window::close_on_esc)
-    .run();
+    ;
+    if cli.control_window {
+        app.add_plugins(control_window::ControlWindowPlugin);
+    }
+    if cli.arcade {
+        app.add_plugins((paddle::PaddlePlugin, arcade::ArcadePlugin));
+    }
+    if let Some(url) = &cli.connect {
+        app.add_plugins(net::NetPlugin { url: url.clone() });
+    }
+    if let Some(address) = &cli.http {
+        app.add_plugins((
+            http::HttpPlugin { address: address.clone() },
+            remote::RemoteControlPlugin,
+            metrics::MetricsPlugin,
+        ));
+        if cli.spectate {
+            app.add_plugins(spectate::SpectatePlugin);
+        }
+    }
+    if let Some(target) = &cli.osc {
+        app.add_plugins(osc::OscPlugin { target: target.clone() });
+    }
+    if let Some(channel) = &cli.twitch {
+        app.add_plugins(twitch::TwitchPlugin { channel: channel.clone() });
+    }
+    if let Some(path) = &cli.rule_table {
+        match rule_table::RuleTable::load(path) {
+            Ok(table) => {
+                app.insert_resource(conway::ActiveRuleTable(Some(Arc::new(table))));
+            }
+            Err(err) => println!("failed to load rule table {}: {}", path, err),
+        }
+    }
+    if cli.energy {
+        app.insert_resource(conway::EnergySettings { enabled: true, ..default() });
+    }
+    if let Some(edges) = cli.edges {
+        app.insert_resource(edges);
+    }
+    if let Some(samples) = cli.supersample {
+        app.insert_resource(display::Supersampling(samples));
+    }
+    if cli.lod {
+        app.insert_resource(lod::LodSettings { enabled: true, ..default() });
+    }
+    if cli.srgb {
+        app.insert_resource(display::StateEncoding::Srgb);
+    }
+    if let Some(path) = &cli.challenge {
+        match challenge::Challenge::load(path) {
+            Ok(challenge) => {
+                app.world.send_event(challenge::ChallengeCommand::Start(challenge));
+            }
+            Err(err) => println!("failed to load challenge: {}", err),
+        }
+    }
+    if let Some(path) = &cli.import {
+        // Start from an empty board, so there's nothing to clear around the image.
+        app.world.resource_mut::<conway::SeedSettings>().density = 0.0;
+        app.world.send_event(image_import::ImportImage {
+            path: path.clone(),
+            mode: image_import::ImportMode::Dither,
+        });
+    }
+    if let Some(path) = &cli.picture {
+        app.world.send_event(picture::PictureCommand::Start { path: path.clone(), bias: picture::DEFAULT_BIAS });
+    }
+    if cli.checkpoint.is_some() || cli.resume {
+        app.add_plugins(checkpoint::CheckpointPlugin { every: cli.checkpoint, resume: cli.resume });
+    }
+    if cli.gpu_debug {
+        app.add_plugins(frame_capture::FrameCapturePlugin);
+    }
+    if let Some(idle_seconds) = cli.attract {
+        app.add_plugins(attract::AttractPlugin { idle_seconds });
+    }
+    let script = cli.script.clone().or_else(|| cli.tutorial.then(|| scripting::TUTORIAL_SCRIPT.to_string()));
+    if let Some(path) = script {
+        app.add_plugins(scripting::ScriptingPlugin { path });
+    }
+    app.run();
 }
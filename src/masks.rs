@@ -0,0 +1,138 @@
+// Painting `BoardMasks` with the walls and freeze tools, and an overlay
+// showing them on top of the board.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::texture::ImageSampler;
+
+use crate::conway::{BoardMasks, BoardResized, ConwayCommand, ConwayConfig};
+use crate::tools::{self, BoardCursor, Tool};
+
+pub struct MasksPlugin;
+
+const WALL_COLOR: [u8; 4] = [110, 110, 130, 255];
+// A translucent tint, so frozen cells still show through.
+const FROZEN_COLOR: [u8; 4] = [60, 120, 255, 70];
+
+#[derive(Resource)]
+struct MaskOverlay(Handle<Image>);
+
+#[derive(Component)]
+struct MaskSprite;
+
+fn tool_mask(tool: Tool) -> Option<u8> {
+    match tool {
+        Tool::Walls => Some(BoardMasks::WALL),
+        Tool::Freeze => Some(BoardMasks::FROZEN),
+        Tool::Cells
+        | Tool::Portal
+        | Tool::Inspect
+        | Tool::Soup
+        | Tool::Text
+        | Tool::Flow
+        | Tool::Ruler
+        | Tool::Ant => None,
+    }
+}
+
+fn overlay_image(size: UVec2) -> Image {
+    let mut image = Image::new_fill(
+        Extent3d { width: size.x, height: size.y, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        &[0, 0, 0, 0],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
+
+fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>, config: Res<ConwayConfig>) {
+    let handle = images.add(overlay_image(config.size));
+    commands.spawn((
+        MaskSprite,
+        SpriteBundle {
+            texture: handle.clone(),
+            transform: Transform {
+                translation: Vec3::new(0.0, 0.0, 1.0),
+                scale: Vec3::new(config.display_scale, config.display_scale, 1.0),
+                ..default()
+            },
+            ..default()
+        },
+    ));
+    commands.insert_resource(MaskOverlay(handle));
+}
+
+// A fresh overlay as big as the new board, which `update_overlay` then fills
+// in from the resized masks.
+fn fit_overlay(
+    mut resized: EventReader<BoardResized>,
+    overlay: Res<MaskOverlay>,
+    config: Res<ConwayConfig>,
+    mut images: ResMut<Assets<Image>>,
+    mut sprites: Query<&mut Transform, With<MaskSprite>>,
+) {
+    if resized.read().count() == 0 {
+        return;
+    }
+    images.insert(overlay.0.id(), overlay_image(config.size));
+    for mut transform in &mut sprites {
+        transform.scale = Vec3::new(config.display_scale, config.display_scale, 1.0);
+    }
+}
+
+fn paint_masks(
+    tool: Res<Tool>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    cursor: BoardCursor,
+    mut commands: EventWriter<ConwayCommand>,
+    // Where the stroke was last frame.
+    mut last_cell: Local<Option<IVec2>>,
+) {
+    let Some(mask) = tool_mask(*tool) else {
+        return;
+    };
+    if !mouse_button_input.pressed(MouseButton::Left) {
+        *last_cell = None;
+        return;
+    }
+    let Some(cell) = cursor.cell() else {
+        return;
+    };
+    let erase = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) || tools::erasing(&keys);
+    commands.send(ConwayCommand::SetMask {
+        cells: tools::line_cells(last_cell.unwrap_or(cell), cell),
+        mask,
+        enabled: !erase,
+    });
+    *last_cell = Some(cell);
+}
+
+fn update_overlay(masks: Res<BoardMasks>, overlay: Res<MaskOverlay>, mut images: ResMut<Assets<Image>>) {
+    if !masks.is_changed() {
+        return;
+    }
+    let Some(image) = images.get_mut(&overlay.0) else {
+        return;
+    };
+    for (pixel, flags) in image.data.chunks_exact_mut(4).zip(&masks.cells) {
+        let color = if flags & BoardMasks::WALL != 0 {
+            WALL_COLOR
+        } else if flags & BoardMasks::FROZEN != 0 {
+            FROZEN_COLOR
+        } else {
+            [0; 4]
+        };
+        pixel.copy_from_slice(&color);
+    }
+}
+
+impl Plugin for MasksPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Startup, setup)
+        .add_systems(Update, (paint_masks, (fit_overlay, update_overlay).chain()))
+        ;
+    }
+}
@@ -0,0 +1,108 @@
+// Board statistics for monitoring long-running installations with standard
+// tooling, served at `GET /metrics` in Prometheus' text format:
+//
+//   gol_population              live cells
+//   gol_generation              generations since the board was last seeded
+//   gol_generations_per_second  over the last second
+//   gol_frame_time_seconds      smoothed over the last few frames
+//
+// They're all gauges, as the generation goes back to zero on a reseed. Needs
+// an `HttpPlugin` to receive requests from. The headless server serves the
+// same ones for its own board, see `server::run_server`.
+
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+
+use crate::conway::{Generation, LivingCells};
+use crate::http::{HttpRequests, HttpResponse, PendingRequest};
+
+pub struct MetricsPlugin;
+
+// How long the generation rate is averaged over.
+const RATE_SECONDS: f32 = 1.0;
+
+/// Generations run per second, averaged over `RATE_SECONDS`.
+#[derive(Resource, Default)]
+pub struct GenerationRate {
+    // The generation and time the current window started at.
+    since: Option<(u64, f32)>,
+    per_second: f32,
+}
+
+impl GenerationRate {
+    /// Notes the board being at `current` as of `now`, in seconds, updating
+    /// the rate once the window's up.
+    pub fn measure(&mut self, current: u64, now: f32) {
+        let Some((started_at, started)) = self.since else {
+            self.since = Some((current, now));
+            return;
+        };
+        if now - started < RATE_SECONDS {
+            return;
+        }
+        // Going backwards means it was reseeded, which counts as nothing run.
+        self.per_second = current.saturating_sub(started_at) as f32 / (now - started);
+        self.since = Some((current, now));
+    }
+}
+
+fn measure_generation_rate(time: Res<Time>, generation: Res<Generation>, mut rate: ResMut<GenerationRate>) {
+    rate.measure(generation.get(), time.elapsed_seconds());
+}
+
+fn gauge(text: &mut String, name: &str, help: &str, value: f64) {
+    text.push_str(&format!("# HELP {} {}\n# TYPE {} gauge\n{} {}\n", name, help, name, name, value));
+}
+
+/// Answers `requests` with the metrics, given the frame time in seconds.
+pub fn respond_with_metrics(
+    requests: Vec<PendingRequest>,
+    population: u64,
+    generation: u64,
+    rate: &GenerationRate,
+    frame_time: f64,
+) {
+    let mut text = String::new();
+    gauge(&mut text, "gol_population", "Live cells on the board.", population as f64);
+    gauge(&mut text, "gol_generation", "Generations since the board was last seeded.", generation as f64);
+    gauge(&mut text, "gol_generations_per_second", "Generations run per second.", rate.per_second as f64);
+    gauge(&mut text, "gol_frame_time_seconds", "Smoothed time per frame.", frame_time);
+    for request in requests {
+        request.respond(HttpResponse {
+            status: 200,
+            content_type: "text/plain; version=0.0.4",
+            body: text.clone().into_bytes(),
+        });
+    }
+}
+
+fn serve_metrics(
+    mut requests: ResMut<HttpRequests>,
+    living_cells: Res<LivingCells>,
+    generation: Res<Generation>,
+    rate: Res<GenerationRate>,
+    diagnostics: Res<DiagnosticsStore>,
+) {
+    let requests = requests.take("GET", "/metrics");
+    if requests.is_empty() {
+        return;
+    }
+    // In milliseconds, and missing without `FrameTimeDiagnosticsPlugin`.
+    let frame_time = diagnostics
+        .get(FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .and_then(|diagnostic| diagnostic.smoothed())
+        .unwrap_or(0.0);
+    respond_with_metrics(requests, living_cells.0, generation.get(), &rate, frame_time / 1000.0);
+}
+
+impl Plugin for MetricsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<GenerationRate>()
+        .add_systems(Update, (
+            measure_generation_rate,
+            serve_metrics.run_if(resource_exists::<HttpRequests>()),
+        ))
+        ;
+    }
+}
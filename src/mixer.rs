@@ -0,0 +1,146 @@
+// A plugin that plays looping background music with an on-screen volume slider.
+// The music is ducked while other sounds (sonification notes, collisions) play;
+// those call `AudioMixer::duck` whenever they start a sound.
+
+use std::path::Path;
+
+use bevy::audio::AudioSinkPlayback;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::event_log::LogEvent;
+
+pub struct MixerPlugin;
+
+// Relative to the assets folder. The music is optional and skipped if missing.
+const MUSIC_PATH: &str = "sounds/music.ogg";
+const DEFAULT_MUSIC_VOLUME: f32 = 0.5;
+// Gain applied to the music while ducked.
+const DUCKED_GAIN: f32 = 0.3;
+// How long the music stays ducked after the last sound started.
+const DUCK_HOLD_SECONDS: f32 = 0.5;
+// How long it takes to fade between ducked and full volume.
+const DUCK_FADE_SECONDS: f32 = 0.2;
+
+#[derive(Resource)]
+pub struct AudioMixer {
+    pub music_volume: f32,
+    duck_remaining: f32,
+    // Eased towards `DUCKED_GAIN` while ducked, and back to 1 after.
+    duck_gain: f32,
+}
+
+impl Default for AudioMixer {
+    fn default() -> Self {
+        Self {
+            music_volume: DEFAULT_MUSIC_VOLUME,
+            duck_remaining: 0.0,
+            duck_gain: 1.0,
+        }
+    }
+}
+
+impl AudioMixer {
+    /// Lowers the music for a moment so that another sound can be heard over it.
+    pub fn duck(&mut self) {
+        self.duck_remaining = DUCK_HOLD_SECONDS;
+    }
+}
+
+#[derive(Component)]
+struct Music;
+
+#[derive(Component)]
+struct VolumeSlider;
+
+#[derive(Component)]
+struct VolumeSliderFill;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut log: EventWriter<LogEvent>) {
+    if Path::new("assets").join(MUSIC_PATH).exists() {
+        commands.spawn((
+            Music,
+            AudioBundle {
+                source: asset_server.load(MUSIC_PATH),
+                settings: PlaybackSettings::LOOP,
+            },
+        ));
+    } else {
+        log.send(LogEvent(format!("No background music at assets/{}", MUSIC_PATH)));
+    }
+
+    commands.spawn((
+        VolumeSlider,
+        ButtonBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(10.0),
+                right: Val::Px(10.0),
+                width: Val::Px(150.0),
+                height: Val::Px(12.0),
+                ..default()
+            },
+            background_color: Color::DARK_GRAY.into(),
+            ..default()
+        },
+    )).with_children(|parent| {
+        parent.spawn((
+            VolumeSliderFill,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0 * DEFAULT_MUSIC_VOLUME),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                background_color: Color::WHITE.into(),
+                ..default()
+            },
+        ));
+    });
+}
+
+fn drag_volume_slider(
+    slider_query: Query<(&Interaction, &Node, &GlobalTransform), With<VolumeSlider>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut mixer: ResMut<AudioMixer>,
+) {
+    let (interaction, node, transform) = slider_query.single();
+    if *interaction != Interaction::Pressed {
+        return;
+    }
+    if let Some(cursor_pos) = window.single().cursor_position() {
+        // UI transforms are centered on the node, in window coordinates.
+        let left = transform.translation().x - node.size().x / 2.0;
+        mixer.music_volume = ((cursor_pos.x - left) / node.size().x).clamp(0.0, 1.0);
+    }
+}
+
+fn apply_mixer(
+    time: Res<Time>,
+    mut mixer: ResMut<AudioMixer>,
+    music_query: Query<&AudioSink, With<Music>>,
+    mut fill_query: Query<&mut Style, With<VolumeSliderFill>>,
+) {
+    mixer.duck_remaining = (mixer.duck_remaining - time.delta_seconds()).max(0.0);
+    let target_gain = if mixer.duck_remaining > 0.0 { DUCKED_GAIN } else { 1.0 };
+    let max_step = time.delta_seconds() / DUCK_FADE_SECONDS;
+    let duck_gain = mixer.duck_gain + (target_gain - mixer.duck_gain).clamp(-max_step, max_step);
+    mixer.duck_gain = duck_gain;
+
+    for sink in music_query.iter() {
+        sink.set_volume(mixer.music_volume * mixer.duck_gain);
+    }
+    for mut style in fill_query.iter_mut() {
+        style.width = Val::Percent(100.0 * mixer.music_volume);
+    }
+}
+
+impl Plugin for MixerPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<AudioMixer>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, (drag_volume_slider, apply_mixer).chain())
+        ;
+    }
+}
@@ -0,0 +1,93 @@
+// Morphing between two rules on a timer, e.g. Conway by day and Seeds by
+// night. The board follows rule A for `period` seconds, then rule B for
+// `period` seconds, and so on, crossfading over the last `fade` seconds of
+// each half by having a growing share of cells follow the next rule (see
+// `RuleBlend`).
+
+use std::sync::Arc;
+
+use bevy::prelude::*;
+
+use crate::conway::ConwayCommand;
+use crate::event_log::LogEvent;
+use crate::rule_table::RuleTable;
+
+pub struct MorphPlugin;
+
+#[derive(Event, Clone)]
+pub enum MorphCommand {
+    Start { a: Arc<RuleTable>, b: Arc<RuleTable>, period: f32, fade: f32 },
+    Stop,
+}
+
+struct Morph {
+    b: Arc<RuleTable>,
+    period: f32,
+    fade: f32,
+    elapsed: f32,
+}
+
+impl Morph {
+    // How much of the board follows rule B.
+    fn amount(&self) -> f32 {
+        let time = self.elapsed % (2.0 * self.period);
+        let ramp = |start: f32| ((time - start) / self.fade.max(f32::EPSILON)).clamp(0.0, 1.0);
+        if time < self.period {
+            ramp(self.period - self.fade)
+        } else {
+            1.0 - ramp(2.0 * self.period - self.fade)
+        }
+    }
+}
+
+// The morph that's running, if any.
+#[derive(Resource, Default)]
+struct RuleMorph(Option<Morph>);
+
+fn handle_commands(
+    mut morph: ResMut<RuleMorph>,
+    mut morph_commands: EventReader<MorphCommand>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut log: EventWriter<LogEvent>,
+) {
+    for command in morph_commands.read() {
+        match command {
+            MorphCommand::Start { a, b, period, fade } => {
+                if a.states != b.states || a.neighborhood != b.neighborhood {
+                    log.send(LogEvent(format!(
+                        "Can't morph between {} and {}: they need the same states and neighbourhood",
+                        a.name, b.name,
+                    )));
+                    continue;
+                }
+                let period = period.max(f32::EPSILON);
+                commands.send(ConwayCommand::SetRuleTable(Some(a.clone())));
+                log.send(LogEvent(format!("Morphing between {} and {} every {}s", a.name, b.name, period)));
+                morph.0 = Some(Morph { b: b.clone(), period, fade: fade.clamp(0.0, period), elapsed: 0.0 });
+            }
+            MorphCommand::Stop => {
+                if morph.0.take().is_some() {
+                    commands.send(ConwayCommand::SetRuleBlend { table: None, amount: 0.0 });
+                }
+            }
+        }
+    }
+}
+
+fn advance_morph(time: Res<Time>, mut morph: ResMut<RuleMorph>, mut commands: EventWriter<ConwayCommand>) {
+    let Some(morph) = &mut morph.0 else {
+        return;
+    };
+    morph.elapsed += time.delta_seconds();
+    commands.send(ConwayCommand::SetRuleBlend { table: Some(morph.b.clone()), amount: morph.amount() });
+}
+
+impl Plugin for MorphPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<RuleMorph>()
+        .add_event::<MorphCommand>()
+        .add_systems(Update, (handle_commands, advance_morph).chain())
+        ;
+    }
+}
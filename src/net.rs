@@ -0,0 +1,536 @@
+// Shared multiplayer boards. A relay server (`--relay ADDR`) hands every client
+// the same seed and keeps them in lockstep: it ticks `TICKS_PER_SECOND` times a
+// second, and rebroadcasts each client's cell edits to everyone, the sender
+// included, in the tick they arrived in. Clients (`--connect ws://ADDR`) send
+// their local edits rather than making them, and run their boards from tick to
+// tick, putting each tick's edits in at the same generation everywhere. The
+// relay runs a copy of the board on the CPU too, under Life, and every
+// `SNAPSHOT_TICKS` it keeps a snapshot of it and forgets the edits before.
+// Late joiners get the latest snapshot and the edits since, and catch up at
+// warp speed. The relay drives the simulation, so pausing and the speed are
+// left to it, and the boards have to be as big as `--size` makes the relay's
+// to stay in step.
+//
+// Clients can also connect to an authoritative server (see `server.rs`), which
+// streams the board as a keyframe followed by per-generation diffs instead.
+
+use std::collections::{HashMap, VecDeque};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use async_channel::{Receiver, Sender};
+use bevy::prelude::*;
+use tungstenite::{Message, WebSocket};
+use tungstenite::stream::MaybeTlsStream;
+
+use crate::conway::{
+    CellRect, CellRects, CellRuns, ClearCells, ConwayConfig, Generation, SeedSettings, SetCells, SimulationState,
+    MAX_SPEED,
+};
+use crate::cpu::CpuBoard;
+
+// How long socket threads sleep when there is nothing to read or write.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+// How fast the relay's boards run.
+const TICKS_PER_SECOND: u32 = 30;
+// How often the relay snapshots its board, a minute's worth of ticks.
+const SNAPSHOT_TICKS: u64 = 60 * TICKS_PER_SECOND as u64;
+
+// Tags for the binary messages.
+const KEYFRAME_TAG: u8 = 0;
+const DIFF_TAG: u8 = 1;
+const SNAPSHOT_TAG: u8 = 2;
+
+pub struct NetPlugin {
+    pub url: String,
+}
+
+pub(crate) enum NetMessage {
+    // Sent by the relay on connect so every board starts from the same soup.
+    Seed(u32),
+    // Sent by the relay as each tick closes. The edits since the tick before
+    // go in at generation `tick - 1`, just before the board steps to `tick`.
+    Tick(u64),
+    // Clip-space points, as used by `SetCells` and `ClearCells`.
+    SetCells(Vec<Vec2>),
+    ClearCells(Vec<Vec2>),
+    // Indices of every living cell, sent by the server when a viewer connects.
+    Keyframe(Vec<usize>),
+    // Indices of the cells that flipped since the last keyframe or diff.
+    Diff(Vec<usize>),
+    // Sent by the relay after the seed: the tick it last snapshotted its
+    // board at, and the indices of the living cells on it then.
+    Snapshot(u64, Vec<usize>),
+}
+
+// Cell indices are sent in increasing order as LEB128-encoded gaps, which keeps
+// sparse changes on a large board to a byte or two each.
+fn encode_indices(tag: u8, indices: &[usize]) -> Vec<u8> {
+    let mut bytes = vec![tag];
+    let mut previous = 0;
+    for &index in indices {
+        let mut gap = index - previous;
+        previous = index;
+        loop {
+            let byte = (gap & 0x7f) as u8;
+            gap >>= 7;
+            if gap == 0 {
+                bytes.push(byte);
+                break;
+            }
+            bytes.push(byte | 0x80);
+        }
+    }
+    bytes
+}
+
+fn decode_indices(bytes: &[u8]) -> Option<Vec<usize>> {
+    let mut indices = vec![];
+    let mut previous = 0;
+    let mut gap = 0;
+    let mut shift = 0;
+    for &byte in bytes {
+        gap |= ((byte & 0x7f) as usize).checked_shl(shift)?;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            previous += gap;
+            indices.push(previous);
+            gap = 0;
+            shift = 0;
+        }
+    }
+    (shift == 0).then_some(indices)
+}
+
+impl NetMessage {
+    pub(crate) fn encode(&self) -> Message {
+        let points = |points: &[Vec2]| points.iter().map(|p| format!(" {} {}", p.x, p.y)).collect::<String>();
+        match self {
+            NetMessage::Seed(seed) => Message::Text(format!("seed {}", seed)),
+            NetMessage::Tick(tick) => Message::Text(format!("tick {}", tick)),
+            NetMessage::SetCells(cells) => Message::Text(format!("set{}", points(cells))),
+            NetMessage::ClearCells(cells) => Message::Text(format!("clear{}", points(cells))),
+            NetMessage::Keyframe(indices) => Message::Binary(encode_indices(KEYFRAME_TAG, indices)),
+            NetMessage::Diff(indices) => Message::Binary(encode_indices(DIFF_TAG, indices)),
+            NetMessage::Snapshot(tick, indices) => {
+                let mut bytes = encode_indices(SNAPSHOT_TAG, indices);
+                bytes.splice(1..1, tick.to_le_bytes());
+                Message::Binary(bytes)
+            }
+        }
+    }
+
+    pub(crate) fn decode(message: &Message) -> Option<Self> {
+        match message {
+            Message::Text(text) => {
+                let (kind, rest) = text.split_once(' ').unwrap_or((text, ""));
+                let points = || -> Option<Vec<Vec2>> {
+                    let numbers = rest.split_whitespace().map(|word| word.parse::<f32>().ok()).collect::<Option<Vec<_>>>()?;
+                    Some(numbers.chunks_exact(2).map(|xy| Vec2::new(xy[0], xy[1])).collect())
+                };
+                match kind {
+                    "seed" => Some(NetMessage::Seed(rest.trim().parse().ok()?)),
+                    "tick" => Some(NetMessage::Tick(rest.trim().parse().ok()?)),
+                    "set" => Some(NetMessage::SetCells(points()?)),
+                    "clear" => Some(NetMessage::ClearCells(points()?)),
+                    _ => None,
+                }
+            }
+            Message::Binary(bytes) => match bytes.split_first()? {
+                (&KEYFRAME_TAG, rest) => Some(NetMessage::Keyframe(decode_indices(rest)?)),
+                (&DIFF_TAG, rest) => Some(NetMessage::Diff(decode_indices(rest)?)),
+                (&SNAPSHOT_TAG, rest) if rest.len() >= 8 => {
+                    let (tick, rest) = rest.split_at(8);
+                    Some(NetMessage::Snapshot(u64::from_le_bytes(tick.try_into().ok()?), decode_indices(rest)?))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+// Shuttles text and binary messages between a websocket and a pair of channels
+// until either side closes.
+fn pump_socket<S: std::io::Read + std::io::Write>(
+    mut socket: WebSocket<S>,
+    outgoing: Receiver<Message>,
+    incoming: Sender<Message>,
+) {
+    loop {
+        let mut idle = true;
+        match socket.read() {
+            Ok(message @ (Message::Text(_) | Message::Binary(_))) => {
+                idle = false;
+                if incoming.send_blocking(message).is_err() {
+                    return;
+                }
+            }
+            Ok(Message::Close(_)) => return,
+            Ok(_) => idle = false,
+            Err(tungstenite::Error::Io(err)) if err.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(err) => {
+                println!("websocket closed: {}", err);
+                return;
+            }
+        }
+        while let Ok(message) = outgoing.try_recv() {
+            idle = false;
+            match socket.send(message) {
+                // The message is queued and goes out on a later flush.
+                Ok(()) | Err(tungstenite::Error::Io(_)) => {}
+                Err(_) => return,
+            }
+        }
+        if idle {
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// The channels for talking to a websocket peer, whose socket is pumped on its own thread.
+pub(crate) struct Peer {
+    pub(crate) outgoing: Sender<Message>,
+    pub(crate) incoming: Receiver<Message>,
+}
+
+impl Peer {
+    fn spawn<S: std::io::Read + std::io::Write + Send + 'static>(socket: WebSocket<S>) -> Self {
+        let (outgoing, to_peer) = async_channel::unbounded();
+        let (from_peer, incoming) = async_channel::unbounded();
+        thread::spawn(move || pump_socket(socket, to_peer, from_peer));
+        Self { outgoing, incoming }
+    }
+
+    /// Completes the websocket handshake on an accepted connection.
+    pub(crate) fn accept(stream: TcpStream) -> Option<Self> {
+        // Accepted streams may inherit non-blocking mode from the listener.
+        stream.set_nonblocking(false).ok()?;
+        let socket = match tungstenite::accept(stream) {
+            Ok(socket) => socket,
+            Err(err) => {
+                println!("websocket handshake failed: {}", err);
+                return None;
+            }
+        };
+        socket.get_ref().set_nonblocking(true).ok()?;
+        Some(Self::spawn(socket))
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn connect(url: &str) -> tungstenite::Result<Self> {
+        let (socket, _) = tungstenite::connect(url)?;
+        if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+            stream.set_nonblocking(true)?;
+        }
+        Ok(Self::spawn(socket))
+    }
+}
+
+// A tick's edits in the order they came, as batches of points to set or clear.
+type Edits = Vec<(Vec<Vec2>, bool)>;
+
+struct Relay {
+    // Every connected client, by id.
+    clients: Vec<(usize, Sender<Message>)>,
+    // The relay's own copy of the board, as of `tick`.
+    board: CpuBoard,
+    // This tick's edits, for putting on `board` as it closes.
+    edits: Edits,
+    // The board's living cells as of the tick it was last snapshotted at.
+    snapshot: (u64, Vec<usize>),
+    // Every edit since the snapshot, each tick's followed by the tick, for
+    // late joiners.
+    history: Vec<Message>,
+    tick: u64,
+    // Whether this tick has had any edits.
+    edited: bool,
+}
+
+impl Relay {
+    fn broadcast(&self, message: &Message) {
+        for (_, client) in &self.clients {
+            let _ = client.try_send(message.clone());
+        }
+    }
+}
+
+/// Runs the relay server for boards made with `config` until the process is
+/// killed.
+pub fn run_relay(address: &str, seed: u32, config: ConwayConfig) -> std::io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    println!("relaying on ws://{} with seed {}", address, seed);
+    // The soup the clients' boards start from.
+    let mut board = CpuBoard::new(config.size.x, config.size.y);
+    board.seed_like_gpu(SeedSettings::default().density, seed);
+    let snapshot = (0, board.alive_indices());
+    let relay = Relay { clients: vec![], board, edits: vec![], snapshot, history: vec![], tick: 0, edited: false };
+    // Everything's sent with this held, so every client hears it all in the same order.
+    let relay = Arc::new(Mutex::new(relay));
+
+    let ticker = relay.clone();
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_secs(1) / TICKS_PER_SECOND);
+        let mut relay = ticker.lock().unwrap();
+        relay.tick += 1;
+        let message = NetMessage::Tick(relay.tick).encode();
+        relay.broadcast(&message);
+        // The tick's edits go in just before the step, as they do on the clients.
+        for (points, alive) in std::mem::take(&mut relay.edits) {
+            points.into_iter().for_each(|point| relay.board.set(config.clip_to_cell(point), alive));
+        }
+        relay.board.step();
+        // Ticks without edits are implied by the next one along.
+        if relay.edited {
+            relay.history.push(message);
+            relay.edited = false;
+        }
+        if relay.tick.is_multiple_of(SNAPSHOT_TICKS) {
+            relay.snapshot = (relay.tick, relay.board.alive_indices());
+            relay.history.clear();
+        }
+    });
+
+    for (id, stream) in listener.incoming().enumerate() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                println!("failed to accept client: {}", err);
+                continue;
+            }
+        };
+        let relay = relay.clone();
+        thread::spawn(move || {
+            let Some(peer) = Peer::accept(stream) else {
+                return;
+            };
+            {
+                let mut relay = relay.lock().unwrap();
+                let _ = peer.outgoing.send_blocking(NetMessage::Seed(seed).encode());
+                let (tick, alive) = relay.snapshot.clone();
+                let _ = peer.outgoing.send_blocking(NetMessage::Snapshot(tick, alive).encode());
+                for message in &relay.history {
+                    let _ = peer.outgoing.send_blocking(message.clone());
+                }
+                // From here on it hears everything live, and the next tick brings it up to date.
+                relay.clients.push((id, peer.outgoing.clone()));
+            }
+            println!("client {} connected", id);
+
+            // Rebroadcast everything this client says to everyone, so it goes in at the same tick.
+            while let Ok(message) = peer.incoming.recv_blocking() {
+                let edit = match NetMessage::decode(&message) {
+                    Some(NetMessage::SetCells(points)) => (points, true),
+                    Some(NetMessage::ClearCells(points)) => (points, false),
+                    _ => continue,
+                };
+                let mut relay = relay.lock().unwrap();
+                relay.broadcast(&message);
+                relay.history.push(message);
+                relay.edits.push(edit);
+                relay.edited = true;
+            }
+            relay.lock().unwrap().clients.retain(|(other_id, _)| *other_id != id);
+            println!("client {} disconnected", id);
+        });
+    }
+    Ok(())
+}
+
+#[derive(Resource)]
+struct NetConnection(Peer);
+
+// Where the board is in the relay's ticks.
+#[derive(Resource, Default)]
+struct Lockstep {
+    // Set once the relay's sent its seed.
+    running: bool,
+    // The edits since the last tick, in the order the relay sent them.
+    edits: Edits,
+    // The ticks with edits the board hasn't reached, oldest first.
+    ticks: VecDeque<(u64, Edits)>,
+    // The relay's latest tick, which is as far as the board can go.
+    latest: u64,
+    // The generation the board will be on once the frames in flight are done.
+    generation: u64,
+    // The relay's snapshot and its tick, until it's on the board.
+    snapshot: Option<(u64, Vec<usize>)>,
+}
+
+// Runs after local input but before remote edits are added, so only local edits
+// get sent. They're taken off the board until they come back from the other end.
+fn send_local_edits(
+    connection: Res<NetConnection>,
+    config: Res<ConwayConfig>,
+    mut set_cells: ResMut<SetCells>,
+    mut clear_cells: ResMut<ClearCells>,
+    mut cell_runs: ResMut<CellRuns>,
+    mut cell_rects: ResMut<CellRects>,
+) {
+    // In the order the board writes them: rects, then points, then runs. Rects
+    // and runs go out as points, since that's all the relay knows.
+    let to_clip = |cell: IVec2| config.cell_to_clip(cell);
+    let rects = cell_rects.0.drain(..).map(|rect| (rect.cells().map(to_clip).collect(), rect.alive));
+    let points = [(std::mem::take(&mut set_cells.0), true), (std::mem::take(&mut clear_cells.0), false)];
+    let runs = cell_runs.0.drain(..).map(|run| (run.cells().map(to_clip).collect(), run.alive));
+    let edits = rects.chain(points).chain(runs).collect::<Vec<_>>();
+    for (cells, alive) in edits.into_iter().filter(|(cells, _)| !cells.is_empty()) {
+        let message = if alive { NetMessage::SetCells(cells) } else { NetMessage::ClearCells(cells) };
+        let _ = connection.0.outgoing.try_send(message.encode());
+    }
+}
+
+// Puts a tick's edits on the board. They're resolved here, since the board
+// draws all its sets before its clears.
+fn apply_edits(
+    edits: Edits,
+    config: &ConwayConfig,
+    set_cells: &mut SetCells,
+    clear_cells: &mut ClearCells,
+) {
+    let mut cells = HashMap::new();
+    for (points, alive) in edits {
+        cells.extend(points.into_iter().map(|point| (config.clip_to_cell(point), alive)));
+    }
+    for (cell, alive) in cells {
+        let point = config.cell_to_clip(cell);
+        if alive {
+            set_cells.0.push(point);
+        } else {
+            clear_cells.0.push(point);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn apply_remote_edits(
+    connection: Res<NetConnection>,
+    generation: Res<Generation>,
+    config: Res<ConwayConfig>,
+    mut set_cells: ResMut<SetCells>,
+    mut clear_cells: ResMut<ClearCells>,
+    mut cell_rects: ResMut<CellRects>,
+    mut seed_settings: ResMut<SeedSettings>,
+    mut simulation_state: ResMut<SimulationState>,
+    mut lockstep: ResMut<Lockstep>,
+    // The server's board, kept in sync from keyframes and diffs.
+    mut remote_board: Local<Vec<bool>>,
+    // Whether the whole of it has to go on the board once the board's seeded.
+    mut redraw: Local<bool>,
+) {
+    let size = config.size;
+    let index_to_clip = |index: usize| config.cell_to_clip(IVec2::new(
+        (index % size.x as usize) as i32,
+        (index / size.x as usize) as i32,
+    ));
+
+    while let Ok(message) = connection.0.incoming.try_recv() {
+        match NetMessage::decode(&message) {
+            Some(NetMessage::Seed(seed)) => {
+                seed_settings.seed = seed;
+                seed_settings.reseed();
+                *lockstep = Lockstep { running: true, ..default() };
+            }
+            Some(NetMessage::Tick(tick)) => {
+                let edits = std::mem::take(&mut lockstep.edits);
+                if !edits.is_empty() {
+                    lockstep.ticks.push_back((tick, edits));
+                }
+                lockstep.latest = tick;
+            }
+            Some(NetMessage::SetCells(cells)) => lockstep.edits.push((cells, true)),
+            Some(NetMessage::ClearCells(cells)) => lockstep.edits.push((cells, false)),
+            Some(NetMessage::Snapshot(tick, alive)) => lockstep.snapshot = Some((tick, alive)),
+            Some(NetMessage::Keyframe(alive)) => {
+                // The server owns the simulation, so only mirror it.
+                simulation_state.paused = true;
+                *remote_board = vec![false; (size.x * size.y) as usize];
+                for index in alive {
+                    if let Some(cell) = remote_board.get_mut(index) {
+                        *cell = true;
+                    }
+                }
+                // It's usually the first thing the server sends, while the
+                // init shader is yet to run and would wipe it.
+                *redraw = true;
+            }
+            Some(NetMessage::Diff(flipped)) => {
+                for index in flipped {
+                    if let Some(cell) = remote_board.get_mut(index) {
+                        *cell = !*cell;
+                        if *redraw {
+                            continue;
+                        }
+                        if *cell {
+                            set_cells.0.push(index_to_clip(index));
+                        } else {
+                            clear_cells.0.push(index_to_clip(index));
+                        }
+                    }
+                }
+            }
+            None => println!("ignoring malformed message {:?}", message),
+        }
+    }
+
+    if *redraw && generation.is_seeded() {
+        for (index, alive) in remote_board.iter().enumerate() {
+            if *alive {
+                set_cells.0.push(index_to_clip(index));
+            } else {
+                clear_cells.0.push(index_to_clip(index));
+            }
+        }
+        *redraw = false;
+    }
+
+    if !lockstep.running {
+        return;
+    }
+    // Held between ticks, and until the frames in flight have caught up.
+    simulation_state.paused = true;
+    // The snapshot goes over the soup once it's down, and the board carries on
+    // from its tick.
+    if generation.is_seeded_for(&seed_settings) {
+        if let Some((tick, alive)) = lockstep.snapshot.take() {
+            cell_rects.0.extend(CellRect::clipped(IVec2::ZERO, size, false, size));
+            set_cells.0.extend(alive.into_iter().map(index_to_clip));
+            generation.set(tick);
+            lockstep.generation = tick;
+            lockstep.latest = lockstep.latest.max(tick);
+            return;
+        }
+    }
+    let at = lockstep.generation;
+    if !generation.is_seeded_for(&seed_settings) || generation.get() != at || at >= lockstep.latest {
+        return;
+    }
+    if lockstep.ticks.front().is_some_and(|(tick, _)| *tick == at + 1) {
+        let (_, edits) = lockstep.ticks.pop_front().unwrap();
+        apply_edits(edits, &config, &mut set_cells, &mut clear_cells);
+    }
+    // Up to, but not past, the next tick with edits to put in.
+    let next_edits = lockstep.ticks.front().map_or(u64::MAX, |(tick, _)| *tick);
+    let generations = (lockstep.latest.min(next_edits - 1) - at).min(MAX_SPEED as u64);
+    simulation_state.paused = false;
+    simulation_state.speed = generations as u32;
+    lockstep.generation += generations;
+}
+
+impl Plugin for NetPlugin {
+    fn build(&self, app: &mut App) {
+        match Peer::connect(&self.url) {
+            Ok(peer) => {
+                app
+                .insert_resource(NetConnection(peer))
+                .init_resource::<Lockstep>()
+                .add_systems(PostUpdate, send_local_edits)
+                .add_systems(Last, apply_remote_edits)
+                ;
+            }
+            Err(err) => println!("failed to connect to {}: {}", self.url, err),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+// Exports the board as NumPy `.npy` arrays of 0s and 1s, either the current
+// board (shape `(height, width)`) or a recording of the next few generations
+// stacked along the first axis (shape `(generations, height, width)`):
+//
+//   board = numpy.load("board.npy")
+//
+// Paths ending in `.npz` get the array zipped up as `board`, the way
+// `numpy.savez` does it:
+//
+//   board = numpy.load("board.npz")["board"]
+//
+// Recordings take one board per frame the simulation advanced, so at speeds
+// above 1 the generations in between are skipped.
+
+use std::io::Write;
+
+use bevy::prelude::*;
+
+use crate::conway::{BoardSnapshot, GenerationAdvanced};
+use crate::event_log::LogEvent;
+
+pub struct NpyExportPlugin;
+
+/// Send this to export the current board, or to record the next
+/// `generations` boards into one array if it's more than 1.
+#[derive(Event, Clone, Debug)]
+pub struct ExportNpy {
+    pub path: String,
+    pub generations: u32,
+}
+
+struct Recording {
+    path: String,
+    generations: u32,
+    // One byte per cell, board after board.
+    cells: Vec<u8>,
+    recorded: u32,
+}
+
+#[derive(Resource, Default)]
+struct Recordings(Vec<Recording>);
+
+/// Writes an array of bytes in the `.npy` format.
+pub fn write_npy(mut writer: impl Write, shape: &[u32], data: &[u8]) -> std::io::Result<()> {
+    let dimensions = shape.iter().map(|n| format!("{}, ", n)).collect::<String>();
+    let mut header = format!("{{'descr': '|u1', 'fortran_order': False, 'shape': ({}), }}", dimensions.trim_end());
+    // The magic string, version and header length take 10 bytes, and the
+    // header is padded with spaces so the data starts 64-byte aligned.
+    let padding = 63 - (10 + header.len()) % 64;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+
+    writer.write_all(b"\x93NUMPY\x01\x00")?;
+    writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    writer.write_all(header.as_bytes())?;
+    writer.write_all(data)
+}
+
+// The CRC-32 zip files check their entries with.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Writes named arrays of bytes as an uncompressed `.npz`, a zip of `.npy`
+/// files, one per array.
+pub fn write_npz(mut writer: impl Write, arrays: &[(&str, &[u32], &[u8])]) -> std::io::Result<()> {
+    let too_big = || std::io::Error::new(std::io::ErrorKind::InvalidInput, "too big for a .npz");
+    let (mut offset, mut directory) = (0u32, vec![]);
+    for (name, shape, data) in arrays {
+        let mut npy = vec![];
+        write_npy(&mut npy, shape, data)?;
+        let name = format!("{}.npy", name);
+        let size = u32::try_from(npy.len()).map_err(|_| too_big())?;
+        // What the local and central headers share: version 2.0, no flags,
+        // stored, 1980-01-01, then the checksum, sizes and name length.
+        let mut fields = vec![];
+        for field in [20u16, 0, 0, 0, 0x21] {
+            fields.extend(field.to_le_bytes());
+        }
+        for field in [crc32(&npy), size, size] {
+            fields.extend(field.to_le_bytes());
+        }
+        fields.extend((name.len() as u16).to_le_bytes());
+
+        writer.write_all(b"PK\x03\x04")?;
+        writer.write_all(&fields)?;
+        writer.write_all(&0u16.to_le_bytes())?;
+        writer.write_all(name.as_bytes())?;
+        writer.write_all(&npy)?;
+
+        directory.extend(b"PK\x01\x02");
+        directory.extend(20u16.to_le_bytes());
+        directory.extend(&fields);
+        // No extra field, comment, disk number or attributes.
+        directory.extend([0; 12]);
+        directory.extend(offset.to_le_bytes());
+        directory.extend(name.as_bytes());
+        let entry = (30 + name.len() as u32).checked_add(size).ok_or_else(too_big)?;
+        offset = offset.checked_add(entry).ok_or_else(too_big)?;
+    }
+    writer.write_all(&directory)?;
+    writer.write_all(b"PK\x05\x06")?;
+    writer.write_all(&[0; 4])?;
+    writer.write_all(&(arrays.len() as u16).to_le_bytes())?;
+    writer.write_all(&(arrays.len() as u16).to_le_bytes())?;
+    writer.write_all(&(directory.len() as u32).to_le_bytes())?;
+    writer.write_all(&offset.to_le_bytes())?;
+    writer.write_all(&0u16.to_le_bytes())
+}
+
+fn save(path: &str, shape: &[u32], cells: &[u8]) -> std::io::Result<()> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    if path.ends_with(".npz") {
+        write_npz(file, &[("board", shape, cells)])
+    } else {
+        write_npy(file, shape, cells)
+    }
+}
+
+fn alive_bits(board: &BoardSnapshot) -> impl Iterator<Item = u8> + '_ {
+    board.cells.iter().map(|cell| (*cell == 255) as u8)
+}
+
+fn start_exports(
+    mut exports: EventReader<ExportNpy>,
+    mut recordings: ResMut<Recordings>,
+    board: Res<BoardSnapshot>,
+    mut log: EventWriter<LogEvent>,
+) {
+    let size = board.size();
+    for export in exports.read() {
+        if export.generations > 1 {
+            recordings.0.push(Recording {
+                path: export.path.clone(),
+                generations: export.generations,
+                cells: Vec::with_capacity((export.generations * size.x * size.y) as usize),
+                recorded: 0,
+            });
+            log.send(LogEvent(format!("Recording {} generations to {}", export.generations, export.path)));
+            continue;
+        }
+        let cells = alive_bits(&board).collect::<Vec<_>>();
+        match save(&export.path, &[size.y, size.x], &cells) {
+            Ok(()) => log.send(LogEvent(format!("Exported the board to {}", export.path))),
+            Err(err) => log.send(LogEvent(format!("Failed to export {}: {}", export.path, err))),
+        }
+    }
+}
+
+fn record_generations(
+    mut generations: EventReader<GenerationAdvanced>,
+    mut recordings: ResMut<Recordings>,
+    board: Res<BoardSnapshot>,
+    mut log: EventWriter<LogEvent>,
+) {
+    if generations.read().count() == 0 {
+        return;
+    }
+    let size = board.size();
+    for recording in recordings.0.iter_mut() {
+        recording.cells.extend(alive_bits(&board));
+        recording.recorded += 1;
+    }
+    recordings.0.retain(|recording| {
+        if recording.recorded < recording.generations {
+            return true;
+        }
+        match save(&recording.path, &[recording.generations, size.y, size.x], &recording.cells) {
+            Ok(()) => log.send(LogEvent(format!("Saved {} generations to {}", recording.generations, recording.path))),
+            Err(err) => log.send(LogEvent(format!("Failed to export {}: {}", recording.path, err))),
+        }
+        false
+    });
+}
+
+impl Plugin for NpyExportPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<ExportNpy>()
+        .init_resource::<Recordings>()
+        .add_systems(Update, (start_exports, record_generations).chain())
+        ;
+    }
+}
@@ -0,0 +1,146 @@
+// Streams statistics over OSC (Open Sound Control) on UDP, so VJ and audio
+// tools like TouchDesigner or SuperCollider can follow the board. Each time
+// the board advances it sends:
+//
+//   /gol/generation      i  generations since seeding
+//   /gol/population      i  live cells
+//   /gol/births          i  cells born since the last message
+//   /gol/deaths          i  cells that died since the last message
+//   /gol/activity        ff the centroid of those births and deaths, from 0
+//                           to 1 across and down the board; skipped when
+//                           nothing changed
+//
+// Counts are clamped to the OSC 32-bit int range.
+
+use std::net::UdpSocket;
+
+use bevy::prelude::*;
+
+use crate::conway::{BoardSnapshot, GenerationAdvanced};
+use crate::event_log::LogEvent;
+
+pub struct OscPlugin {
+    // Where to send the messages, like "127.0.0.1:9000".
+    pub target: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OscArgument {
+    Int(i32),
+    Float(f32),
+}
+
+#[derive(Resource)]
+struct OscOutput {
+    socket: Option<UdpSocket>,
+    target: String,
+    // The board as of the last message, to find what changed since.
+    previous: Vec<u8>,
+}
+
+// OSC strings are null terminated and padded out to four bytes.
+fn push_padded(bytes: &mut Vec<u8>, text: &str) {
+    bytes.extend_from_slice(text.as_bytes());
+    bytes.push(0);
+    while !bytes.len().is_multiple_of(4) {
+        bytes.push(0);
+    }
+}
+
+/// Encodes an OSC message.
+pub fn encode_message(address: &str, arguments: &[OscArgument]) -> Vec<u8> {
+    let mut bytes = vec![];
+    push_padded(&mut bytes, address);
+    let tags = arguments
+        .iter()
+        .map(|argument| match argument {
+            OscArgument::Int(_) => 'i',
+            OscArgument::Float(_) => 'f',
+        })
+        .collect::<String>();
+    push_padded(&mut bytes, &format!(",{}", tags));
+    for argument in arguments {
+        match argument {
+            OscArgument::Int(value) => bytes.extend_from_slice(&value.to_be_bytes()),
+            OscArgument::Float(value) => bytes.extend_from_slice(&value.to_be_bytes()),
+        }
+    }
+    bytes
+}
+
+fn clamped(count: u64) -> OscArgument {
+    OscArgument::Int(count.min(i32::MAX as u64) as i32)
+}
+
+fn setup(mut output: ResMut<OscOutput>, mut log: EventWriter<LogEvent>) {
+    match UdpSocket::bind("0.0.0.0:0").and_then(|socket| socket.set_nonblocking(true).map(|_| socket)) {
+        Ok(socket) => {
+            log.send(LogEvent(format!("Sending OSC statistics to {}", output.target)));
+            output.socket = Some(socket);
+        }
+        Err(err) => log.send(LogEvent(format!("Failed to open a socket for OSC: {}", err))),
+    }
+}
+
+fn send_statistics(
+    mut generations: EventReader<GenerationAdvanced>,
+    board: Res<BoardSnapshot>,
+    mut output: ResMut<OscOutput>,
+    mut log: EventWriter<LogEvent>,
+) {
+    let Some(advanced) = generations.read().last() else {
+        return;
+    };
+    let output = &mut *output;
+    let Some(socket) = &output.socket else {
+        return;
+    };
+
+    // Births and deaths both count as activity.
+    let size = board.size();
+    let mut changed = 0u64;
+    let mut sum = Vec2::ZERO;
+    if output.previous.len() == board.cells.len() {
+        for (i, (old, new)) in output.previous.iter().zip(&board.cells).enumerate() {
+            if (*old == 255) != (*new == 255) {
+                changed += 1;
+                sum += Vec2::new((i as u32 % size.x) as f32 + 0.5, (i as u32 / size.x) as f32 + 0.5);
+            }
+        }
+    }
+    output.previous.clone_from(&board.cells);
+
+    let mut messages = vec![
+        encode_message("/gol/generation", &[clamped(advanced.generation)]),
+        encode_message("/gol/population", &[clamped(advanced.population)]),
+        encode_message("/gol/births", &[clamped(advanced.births)]),
+        encode_message("/gol/deaths", &[clamped(advanced.deaths)]),
+    ];
+    if changed > 0 {
+        let centroid = sum / changed as f32 / size.as_vec2();
+        messages.push(encode_message("/gol/activity", &[OscArgument::Float(centroid.x), OscArgument::Float(centroid.y)]));
+    }
+    for message in messages {
+        match socket.send_to(&message, &output.target) {
+            Ok(_) => {}
+            // Dropping a message now and then is fine for live statistics.
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(err) => {
+                // Don't keep failing every frame.
+                log.send(LogEvent(format!("Stopped sending OSC to {}: {}", output.target, err)));
+                output.socket = None;
+                return;
+            }
+        }
+    }
+}
+
+impl Plugin for OscPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(OscOutput { socket: None, target: self.target.clone(), previous: vec![] })
+        .add_systems(Startup, setup)
+        .add_systems(Update, send_statistics)
+        ;
+    }
+}
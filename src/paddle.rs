@@ -11,16 +11,21 @@ use bevy::render::render_resource::*;
 pub struct PaddlePlugin;
 
 #[derive(Component)]
-struct Paddle;
+pub(crate) struct Paddle;
 
 #[derive(Component)]
-struct Ball;
+pub(crate) struct Ball;
 
 #[derive(Component)]
-struct Velocity(Vec3);
-
-#[derive(Event, Default)]
-struct CollisionEvent;
+pub(crate) struct Velocity(pub(crate) Vec3);
+
+#[derive(Event)]
+pub(crate) struct CollisionEvent {
+    // Where the ball was when it hit the paddle.
+    pub(crate) position: Vec2,
+    // Points from the paddle towards the ball.
+    pub(crate) normal: Vec2,
+}
 
 const PADDLE_COLOR: Color = Color::rgb(0.3, 0.3, 0.7);
 const PADDLE_SIZE: Vec3 = Vec3::new(120.0, 20.0, 0.0);
@@ -51,7 +56,7 @@ fn setup(
             Velocity(Vec3::new(0.0, 0.0, 0.0)),
             SpriteBundle {
                 transform: Transform::
-                    from_translation(Vec3::new(0.0, -250.0, 0.0))
+                    from_translation(Vec3::new(0.0, -250.0, 1.0))
                     .with_scale(PADDLE_SIZE),
                 sprite: Sprite {
                     color: PADDLE_COLOR,
@@ -69,7 +74,7 @@ fn setup(
                 mesh: meshes.add(shape::Circle::default().into()).into(),
                 material: materials.add(CustomMaterial {color: Color::WHITE.into()}),
                 transform: Transform {
-                    translation: Vec3::new(0.0, 0.0, 0.0),
+                    translation: Vec3::new(0.0, 0.0, 1.0),
                     scale: BALL_SIZE,
                     ..Default::default()
                 },
@@ -109,7 +114,10 @@ fn check_collisions(
             paddle_transform.scale.truncate(),
         );
         if let Some(collision) = collision {
-            collision_events.send_default();
+            collision_events.send(CollisionEvent {
+                position: ball_transform.translation.truncate(),
+                normal: (ball_transform.translation - paddle_transform.translation).truncate().normalize_or_zero(),
+            });
             match collision {
                 Collision::Left | Collision::Right => {
                     ball_velocity.0.x = -ball_velocity.0.x;
@@ -0,0 +1,66 @@
+// Slowly cycles the hue the board is drawn in, independent of the simulation.
+// P turns it on and off, and the console's `palette` command sets the speed.
+
+use bevy::prelude::*;
+
+use crate::conway::BoardSprite;
+use crate::event_log::LogEvent;
+
+pub struct PalettePlugin;
+
+pub const DEFAULT_DEGREES_PER_SECOND: f32 = 20.0;
+// Keep some white in the tint so dim multi-state cells stay visible.
+const SATURATION: f32 = 0.6;
+const LIGHTNESS: f32 = 0.65;
+
+#[derive(Resource)]
+pub struct PaletteCycle {
+    pub enabled: bool,
+    // Hue change in degrees per second.
+    pub speed: f32,
+    hue: f32,
+}
+
+impl Default for PaletteCycle {
+    fn default() -> Self {
+        Self { enabled: false, speed: DEFAULT_DEGREES_PER_SECOND, hue: 0.0 }
+    }
+}
+
+fn toggle_cycle(keys: Res<Input<KeyCode>>, mut palette: ResMut<PaletteCycle>, mut log: EventWriter<LogEvent>) {
+    if keys.just_pressed(KeyCode::P) {
+        palette.enabled = !palette.enabled;
+        log.send(LogEvent(format!("Palette cycling {}", if palette.enabled { "on" } else { "off" })));
+    }
+}
+
+fn cycle_palette(
+    time: Res<Time>,
+    mut palette: ResMut<PaletteCycle>,
+    mut sprites: Query<&mut Sprite, With<BoardSprite>>,
+    mut was_enabled: Local<bool>,
+) {
+    if !palette.enabled {
+        // Go back to the plain board once.
+        if std::mem::take(&mut *was_enabled) {
+            for mut sprite in &mut sprites {
+                sprite.color = Color::WHITE;
+            }
+        }
+        return;
+    }
+    *was_enabled = true;
+    palette.hue = (palette.hue + palette.speed * time.delta_seconds()).rem_euclid(360.0);
+    for mut sprite in &mut sprites {
+        sprite.color = Color::hsl(palette.hue, SATURATION, LIGHTNESS);
+    }
+}
+
+impl Plugin for PalettePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<PaletteCycle>()
+        .add_systems(Update, (toggle_cycle, cycle_palette).chain())
+        ;
+    }
+}
@@ -0,0 +1,101 @@
+// Small built-in patterns, stored as offsets of their live cells (x right, y down),
+// and the library of saved ones in `assets/patterns`.
+
+use bevy::prelude::*;
+
+// Travels one cell down and to the right every four generations.
+pub const GLIDER: &[(i32, i32)] = &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+
+/// Offsets a pattern so its top-left corner is at `origin`.
+pub fn placed(cells: &[(i32, i32)], origin: IVec2) -> Vec<IVec2> {
+    cells.iter().map(|&(x, y)| origin + IVec2::new(x, y)).collect()
+}
+
+/// Mirrors a pattern within its bounding box.
+pub fn oriented(cells: &[(i32, i32)], flip_x: bool, flip_y: bool) -> Vec<IVec2> {
+    let width = cells.iter().map(|(x, _)| *x).max().unwrap_or(0);
+    let height = cells.iter().map(|(_, y)| *y).max().unwrap_or(0);
+    cells
+        .iter()
+        .map(|&(x, y)| IVec2::new(
+            if flip_x { width - x } else { x },
+            if flip_y { height - y } else { y },
+        ))
+        .collect()
+}
+
+// Period 2 oscillator.
+pub const BLINKER: &[(i32, i32)] = &[(0, 0), (1, 0), (2, 0)];
+// Still life.
+pub const BLOCK: &[(i32, i32)] = &[(0, 0), (1, 0), (0, 1), (1, 1)];
+// The lightweight spaceship, travelling left.
+pub const LWSS: &[(i32, i32)] = &[
+    (1, 0), (4, 0), (0, 1), (0, 2), (4, 2), (0, 3), (1, 3), (2, 3), (3, 3),
+];
+// A methuselah that takes 1103 generations to settle.
+pub const R_PENTOMINO: &[(i32, i32)] = &[(1, 0), (2, 0), (0, 1), (1, 1), (1, 2)];
+// Gosper's glider gun, firing a glider down and to the right every 30 generations.
+pub const GOSPER_GLIDER_GUN: &[(i32, i32)] = &[
+    (24, 0),
+    (22, 1), (24, 1),
+    (12, 2), (13, 2), (20, 2), (21, 2), (34, 2), (35, 2),
+    (11, 3), (15, 3), (20, 3), (21, 3), (34, 3), (35, 3),
+    (0, 4), (1, 4), (10, 4), (16, 4), (20, 4), (21, 4),
+    (0, 5), (1, 5), (10, 5), (14, 5), (16, 5), (17, 5), (22, 5), (24, 5),
+    (10, 6), (16, 6), (24, 6),
+    (11, 7), (15, 7),
+    (12, 8), (13, 8),
+];
+
+/// The names `by_name` knows about.
+pub const NAMES: &[&str] = &["glider", "blinker", "block", "lwss", "r-pentomino", "gosper"];
+
+/// Looks up a built-in pattern by (case-insensitive) name.
+pub fn by_name(name: &str) -> Option<&'static [(i32, i32)]> {
+    match name.to_ascii_lowercase().as_str() {
+        "glider" => Some(GLIDER),
+        "blinker" => Some(BLINKER),
+        "block" => Some(BLOCK),
+        "lwss" => Some(LWSS),
+        "r-pentomino" | "rpentomino" => Some(R_PENTOMINO),
+        "gosper" | "gosper-glider-gun" => Some(GOSPER_GLIDER_GUN),
+        _ => None,
+    }
+}
+
+/// Where saved patterns live, as `.rle` files named after the pattern.
+pub const LIBRARY_DIRECTORY: &str = "assets/patterns";
+
+pub fn library_path(name: &str) -> String {
+    format!("{}/{}.rle", LIBRARY_DIRECTORY, name)
+}
+
+/// The names of the patterns saved in the library, sorted.
+pub fn library_names() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(LIBRARY_DIRECTORY) else {
+        return vec![];
+    };
+    let mut names = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == "rle").then(|| path.file_stem()?.to_str().map(str::to_string))?
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Looks up a pattern by name, first among the built-in ones and then in the
+/// library, with `origin` as its top-left corner.
+pub fn lookup(name: &str, origin: IVec2) -> Option<Vec<IVec2>> {
+    if let Some(cells) = by_name(name) {
+        return Some(placed(cells, origin));
+    }
+    // Names become file names, so keep them to something safe.
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return None;
+    }
+    let text = std::fs::read_to_string(library_path(name)).ok()?;
+    let cells = crate::formats::parse_rle(&text).ok()?;
+    Some(cells.into_iter().map(|cell| origin + cell).collect())
+}
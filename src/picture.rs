@@ -0,0 +1,102 @@
+// Picture mode (`picture FILE [BIAS]` in the console, or `--picture FILE`):
+// the board is pulled toward a target image while the rule keeps running.
+// The image is fitted to the board like an import, and each update every cell
+// takes its state in the picture instead of the rule's with probability BIAS,
+// so the picture surfaces out of the churn and is smudged by it at once. The
+// Inspector shows how much of the board matches as it goes.
+
+use bevy::prelude::*;
+
+use crate::conway::{BoardMasks, BoardSnapshot, ConwayCommand, GenerationAdvanced};
+use crate::event_log::LogEvent;
+use crate::image_import::{self, ImportMode, Luminance, DEFAULT_THRESHOLD};
+use crate::inspector::Inspector;
+
+pub struct PicturePlugin;
+
+pub const DEFAULT_BIAS: f32 = 0.02;
+const SECTION: &str = "Picture";
+
+#[derive(Event, Clone, Debug)]
+pub enum PictureCommand {
+    Start { path: String, bias: f32 },
+    Stop,
+}
+
+// The picture being followed.
+#[derive(Resource, Default)]
+struct ActivePicture(Option<(String, f32)>);
+
+/// How well the board matches the target picture: the living cells in both,
+/// over the living cells in either, from 0 to 1. An empty picture on an empty
+/// board matches.
+pub fn similarity(masks: &BoardMasks, board: &BoardSnapshot) -> f32 {
+    let (mut both, mut either) = (0u64, 0u64);
+    for (flags, cell) in masks.cells.iter().zip(&board.cells) {
+        let (wanted, alive) = (flags & BoardMasks::TARGET != 0, *cell == 255);
+        both += (wanted && alive) as u64;
+        either += (wanted || alive) as u64;
+    }
+    if either == 0 { 1.0 } else { both as f32 / either as f32 }
+}
+
+fn handle_commands(
+    mut picture_commands: EventReader<PictureCommand>,
+    board: Res<BoardSnapshot>,
+    mut active: ResMut<ActivePicture>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut inspector: ResMut<Inspector>,
+    mut log: EventWriter<LogEvent>,
+) {
+    for command in picture_commands.read() {
+        match command {
+            PictureCommand::Start { path, bias } => {
+                let image = match Luminance::load(path) {
+                    Ok(image) => image,
+                    Err(err) => {
+                        log.send(LogEvent(format!("Failed to load picture {}: {}", path, err)));
+                        continue;
+                    }
+                };
+                let mode = ImportMode::Threshold(DEFAULT_THRESHOLD);
+                let cells = image_import::image_to_cells(&image, board.size(), mode);
+                commands.send(ConwayCommand::SetTarget { cells, bias: *bias });
+                active.0 = Some((path.clone(), *bias));
+            }
+            PictureCommand::Stop => {
+                commands.send(ConwayCommand::SetTarget { cells: vec![], bias: 0.0 });
+                active.0 = None;
+                inspector.remove(SECTION);
+            }
+        }
+    }
+}
+
+fn show_similarity(
+    mut generations: EventReader<GenerationAdvanced>,
+    active: Res<ActivePicture>,
+    masks: Res<BoardMasks>,
+    board: Res<BoardSnapshot>,
+    mut inspector: ResMut<Inspector>,
+) {
+    if generations.read().last().is_none() {
+        return;
+    }
+    let Some((path, bias)) = &active.0 else {
+        return;
+    };
+    inspector.set(SECTION, format!(
+        "{}\nbias: {:.3}\nsimilarity: {:.1}%",
+        path, bias, similarity(&masks, &board) * 100.0,
+    ));
+}
+
+impl Plugin for PicturePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<PictureCommand>()
+        .init_resource::<ActivePicture>()
+        .add_systems(Update, (handle_commands, show_similarity).chain())
+        ;
+    }
+}
@@ -0,0 +1,49 @@
+// A debug overlay for the render world (`pipelines` in the console, and
+// `pipelines off`). The Inspector lists each of the board's pipelines with
+// its state in the `PipelineCache`, how many dispatches or draws it made last
+// frame, and how many cells were sent to be set and cleared, which beats
+// printing from inside the render graph.
+
+use bevy::prelude::*;
+
+use crate::conway::PipelineStats;
+use crate::inspector::Inspector;
+
+pub struct PipelineStatsPlugin;
+
+const SECTION: &str = "Pipelines";
+
+/// Whether the pipeline statistics are shown.
+#[derive(Resource, Default)]
+pub struct PipelineOverlay {
+    pub enabled: bool,
+}
+
+fn show_pipelines(overlay: Res<PipelineOverlay>, stats: Res<PipelineStats>, mut inspector: ResMut<Inspector>) {
+    if !overlay.enabled {
+        if overlay.is_changed() {
+            inspector.remove(SECTION);
+        }
+        return;
+    }
+    let report = stats.report();
+    let mut lines = report
+        .states
+        .iter()
+        .map(|(label, state)| {
+            let dispatches = report.dispatches.get(label).copied().unwrap_or(0);
+            format!("{}: {}, {} dispatches", label, state, dispatches)
+        })
+        .collect::<Vec<_>>();
+    lines.push(format!("set cells: {}, clear cells: {}", report.set_cells, report.clear_cells));
+    inspector.set(SECTION, lines.join("\n"));
+}
+
+impl Plugin for PipelineStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<PipelineOverlay>()
+        .add_systems(Update, show_pipelines)
+        ;
+    }
+}
@@ -0,0 +1,134 @@
+// Exports the board as a binary portable bitmap (`.pbm`, 1 for alive) or
+// graymap (`.pgm`, each live cell's age in generations, up to 255, and 0 for
+// dead ones), for piping into other tools:
+//
+//   convert board.pgm -scale 400% board.png
+//
+// Ages are counted from the boards the simulation reads back, one per frame, so
+// at speeds above 1 cells born in between count from the frame they're seen.
+
+use std::io::Write;
+
+use bevy::prelude::*;
+
+use crate::conway::{BoardSnapshot, GenerationAdvanced};
+use crate::event_log::LogEvent;
+
+pub struct PnmExportPlugin;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PnmFormat {
+    Pbm,
+    Pgm,
+}
+
+/// Send this to export the current board.
+#[derive(Event, Clone, Debug)]
+pub struct ExportPnm {
+    pub path: String,
+    pub format: PnmFormat,
+}
+
+/// How many generations each live cell has been alive for, as seen from the
+/// boards read back.
+#[derive(Resource, Default)]
+pub struct CellAges {
+    ages: Vec<u32>,
+    size: UVec2,
+    generation: u64,
+}
+
+impl CellAges {
+    pub fn get(&self, cell: IVec2) -> u32 {
+        let size = self.size;
+        if cell.x < 0 || cell.y < 0 || cell.x >= size.x as i32 || cell.y >= size.y as i32 {
+            return 0;
+        }
+        self.ages.get((cell.y as u32 * size.x + cell.x as u32) as usize).copied().unwrap_or(0)
+    }
+}
+
+/// Writes a binary PBM with a comment line. `alive` has one entry per cell, row by row.
+pub fn write_pbm(mut writer: impl Write, size: UVec2, comment: &str, alive: &[bool]) -> std::io::Result<()> {
+    write!(writer, "P4\n# {}\n{} {}\n", comment, size.x, size.y)?;
+    for row in alive.chunks(size.x as usize) {
+        // Eight cells to a byte, most significant bit first, with each row padded out.
+        let bytes = row
+            .chunks(8)
+            .map(|cells| cells.iter().enumerate().fold(0u8, |byte, (i, alive)| byte | ((*alive as u8) << (7 - i))))
+            .collect::<Vec<_>>();
+        writer.write_all(&bytes)?;
+    }
+    Ok(())
+}
+
+/// Writes a binary 8-bit PGM with a comment line. `levels` has one entry per cell, row by row.
+pub fn write_pgm(mut writer: impl Write, size: UVec2, comment: &str, levels: &[u8]) -> std::io::Result<()> {
+    write!(writer, "P5\n# {}\n{} {}\n255\n", comment, size.x, size.y)?;
+    writer.write_all(levels)
+}
+
+fn track_ages(mut generations: EventReader<GenerationAdvanced>, board: Res<BoardSnapshot>, mut ages: ResMut<CellAges>) {
+    let Some(advanced) = generations.read().last() else {
+        return;
+    };
+    // Restart when the board is reseeded.
+    let elapsed = advanced.generation.saturating_sub(ages.generation);
+    if ages.ages.len() != board.cells.len() || ages.size != board.size() || elapsed == 0 {
+        ages.ages = vec![0; board.cells.len()];
+        ages.size = board.size();
+    }
+    let elapsed = elapsed.max(1) as u32;
+    for (age, cell) in ages.ages.iter_mut().zip(&board.cells) {
+        *age = if *cell == 255 { age.saturating_add(elapsed) } else { 0 };
+    }
+    ages.generation = advanced.generation;
+}
+
+fn save(export: &ExportPnm, board: &BoardSnapshot, ages: &CellAges) -> std::io::Result<()> {
+    let mut file = std::io::BufWriter::new(std::fs::File::create(&export.path)?);
+    let comment = format!("generation {}", ages.generation);
+    match export.format {
+        PnmFormat::Pbm => {
+            let alive = board.cells.iter().map(|cell| *cell == 255).collect::<Vec<_>>();
+            write_pbm(&mut file, board.size(), &comment, &alive)?;
+        }
+        PnmFormat::Pgm => {
+            let levels = board
+                .cells
+                .iter()
+                .enumerate()
+                .map(|(i, cell)| {
+                    // Cells set since the last generation haven't been aged yet.
+                    if *cell == 255 { ages.ages.get(i).copied().unwrap_or(0).clamp(1, 255) as u8 } else { 0 }
+                })
+                .collect::<Vec<_>>();
+            write_pgm(&mut file, board.size(), &comment, &levels)?;
+        }
+    }
+    file.flush()
+}
+
+fn run_exports(
+    mut exports: EventReader<ExportPnm>,
+    board: Res<BoardSnapshot>,
+    ages: Res<CellAges>,
+    mut log: EventWriter<LogEvent>,
+) {
+    for export in exports.read() {
+        match save(export, &board, &ages) {
+            Ok(()) => log.send(LogEvent(format!("Exported the board to {}", export.path))),
+            Err(err) => log.send(LogEvent(format!("Failed to export {}: {}", export.path, err))),
+        }
+    }
+}
+
+impl Plugin for PnmExportPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<ExportPnm>()
+        .init_resource::<CellAges>()
+        .add_systems(Update, (track_ages, run_exports).chain())
+        ;
+    }
+}
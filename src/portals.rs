@@ -0,0 +1,102 @@
+// The portal tool, and outlines showing where the portals are. The portals
+// themselves are in `conway`, since the update shaders do the work.
+
+use bevy::prelude::*;
+
+use crate::conway::{ConwayCommand, ConwayConfig, Portal, Portals};
+use crate::event_log::LogEvent;
+use crate::tools::{self, BoardCursor, Tool};
+
+pub struct PortalsPlugin;
+
+// Each portal's two ends are drawn in the same color.
+const COLORS: &[Color] = &[Color::CYAN, Color::ORANGE, Color::LIME_GREEN, Color::FUCHSIA];
+
+#[derive(Resource, Default)]
+struct PortalDraft {
+    // Where the drag for the first end started.
+    dragging_from: Option<IVec2>,
+    // The first end, as its top-left corner and size, waiting for the second.
+    first: Option<(IVec2, IVec2)>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn use_portal_tool(
+    tool: Res<Tool>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    cursor: BoardCursor,
+    config: Res<ConwayConfig>,
+    mut draft: ResMut<PortalDraft>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut log: EventWriter<LogEvent>,
+) {
+    if *tool != Tool::Portal {
+        *draft = PortalDraft::default();
+        return;
+    }
+    let Some(cell) = cursor.cell() else {
+        return;
+    };
+    if mouse_button_input.just_pressed(MouseButton::Left) {
+        if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) || tools::erasing(&keys) {
+            *draft = PortalDraft::default();
+            commands.send(ConwayCommand::ClearPortals);
+            log.send(LogEvent("Removed every portal".to_string()));
+        } else if let Some((a, size)) = draft.first.take() {
+            let portal = Portal { a, b: cell, size };
+            if portal.is_valid(config.size) {
+                commands.send(ConwayCommand::AddPortal(portal));
+            } else {
+                log.send(LogEvent("Portal ends have to be on the board and can't overlap".to_string()));
+            }
+        } else {
+            draft.dragging_from = Some(cell);
+        }
+    }
+    if mouse_button_input.just_released(MouseButton::Left) {
+        if let Some(from) = draft.dragging_from.take() {
+            draft.first = Some((from.min(cell), (from - cell).abs() + IVec2::ONE));
+            log.send(LogEvent("Click where the other end of the portal goes".to_string()));
+        }
+    }
+}
+
+fn draw_portals(
+    mut gizmos: Gizmos,
+    portals: Res<Portals>,
+    draft: Res<PortalDraft>,
+    cursor: BoardCursor,
+    config: Res<ConwayConfig>,
+) {
+    let mut outline = |corner: IVec2, size: IVec2, color: Color| {
+        let top_left = config.cell_to_world(corner);
+        let bottom_right = config.cell_to_world(corner + size);
+        gizmos.rect_2d((top_left + bottom_right) / 2.0, 0.0, (bottom_right - top_left).abs(), color);
+    };
+    for (i, portal) in portals.0.iter().enumerate() {
+        let color = COLORS[i % COLORS.len()];
+        outline(portal.a, portal.size, color);
+        outline(portal.b, portal.size, color);
+    }
+
+    let cursor = cursor.cell();
+    if let (Some(from), Some(cursor)) = (draft.dragging_from, cursor) {
+        outline(from.min(cursor), (from - cursor).abs() + IVec2::ONE, Color::WHITE);
+    }
+    if let Some((corner, size)) = draft.first {
+        outline(corner, size, Color::WHITE);
+        if let Some(cursor) = cursor {
+            outline(cursor, size, Color::GRAY);
+        }
+    }
+}
+
+impl Plugin for PortalsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<PortalDraft>()
+        .add_systems(Update, (use_portal_tool, draw_portals).chain())
+        ;
+    }
+}
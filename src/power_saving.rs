@@ -0,0 +1,95 @@
+// Keeping a paused board from running the GPU flat out in the background.
+// Once it's been paused for `IDLE_SECONDS` with no keys, mouse, commands or
+// edits to the board or its seed, however they were made, the window only
+// redraws on input, or every `IDLE_WAIT` for anything ticking along, and the
+// board's ghost and readback are left alone until something changes.
+// Resuming, or any input, puts it back to full speed.
+
+use std::time::Duration;
+
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::mouse::{MouseButtonInput, MouseWheel};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::CursorMoved;
+use bevy::winit::{UpdateMode, WinitSettings};
+
+use crate::conway::{
+    CellRects, CellRuns, ClearCells, ConwayCommand, Idle, PendingRuns, SeedSettings, SetCells, SimulationState,
+    StepRequest,
+};
+
+pub struct PowerSavingPlugin;
+
+const IDLE_SECONDS: f32 = 2.0;
+const IDLE_WAIT: Duration = Duration::from_secs(1);
+
+// Edits that reach the board without a `ConwayCommand`, from the server,
+// scripts or tools writing the queues directly, and reseeds.
+#[derive(SystemParam)]
+struct BoardEdits<'w> {
+    set_cells: Res<'w, SetCells>,
+    clear_cells: Res<'w, ClearCells>,
+    cell_runs: Res<'w, CellRuns>,
+    cell_rects: Res<'w, CellRects>,
+    pending_runs: Res<'w, PendingRuns>,
+    seed_settings: Res<'w, SeedSettings>,
+}
+
+impl BoardEdits<'_> {
+    fn any(&self) -> bool {
+        !self.set_cells.0.is_empty()
+            || !self.clear_cells.0.is_empty()
+            || !self.cell_runs.0.is_empty()
+            || !self.cell_rects.0.is_empty()
+            || !self.pending_runs.is_empty()
+            || self.seed_settings.is_changed()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn watch_activity(
+    time: Res<Time>,
+    simulation_state: Res<SimulationState>,
+    step_request: Res<StepRequest>,
+    mut keys: EventReader<KeyboardInput>,
+    mut buttons: EventReader<MouseButtonInput>,
+    mut cursor_moves: EventReader<CursorMoved>,
+    mut wheels: EventReader<MouseWheel>,
+    mut commands: EventReader<ConwayCommand>,
+    edits: BoardEdits,
+    // When something last happened.
+    mut last_active: Local<f32>,
+    mut idle: ResMut<Idle>,
+    mut winit_settings: ResMut<WinitSettings>,
+) {
+    // Read them all so none of the queues is left behind.
+    let inputs = [keys.read().count(), buttons.read().count(), cursor_moves.read().count(), wheels.read().count()];
+    let active = inputs.iter().sum::<usize>() > 0 || commands.read().count() > 0 || step_request.0 || edits.any();
+    if active || !simulation_state.paused {
+        *last_active = time.elapsed_seconds();
+    }
+    let now_idle = time.elapsed_seconds() - *last_active > IDLE_SECONDS;
+    if now_idle == idle.0 {
+        return;
+    }
+    idle.0 = now_idle;
+    *winit_settings = if now_idle {
+        WinitSettings {
+            focused_mode: UpdateMode::Reactive { wait: IDLE_WAIT },
+            unfocused_mode: UpdateMode::ReactiveLowPower { wait: IDLE_WAIT },
+            ..default()
+        }
+    } else {
+        WinitSettings::default()
+    };
+}
+
+impl Plugin for PowerSavingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        // After anything that might send a command this frame.
+        .add_systems(PostUpdate, watch_activity)
+        ;
+    }
+}
@@ -0,0 +1,69 @@
+// Reading parts of the board from outside the simulation. `ConwayQuery` is a
+// system parameter that hands out futures for regions of the board, which
+// resolve from a readback on a later frame:
+//
+//   fn sample(mut query: ConwayQuery) {
+//       let region = query.region(URect::new(0, 0, 16, 16));
+//       AsyncComputeTaskPool::get().spawn(async move { println!("{:?}", region.await) }).detach();
+//   }
+//
+// `ConwayPlugin` sets this up.
+
+use std::future::Future;
+
+use async_channel::Sender;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::conway::BoardSnapshot;
+
+struct RegionRequest {
+    rect: URect,
+    reply: Sender<Vec<u8>>,
+}
+
+// Requests wait a frame, so they're answered from a readback that started
+// after they were made.
+#[derive(Resource, Default)]
+pub(crate) struct RegionRequests {
+    pending: Vec<RegionRequest>,
+    waiting: Vec<RegionRequest>,
+}
+
+#[derive(SystemParam)]
+pub struct ConwayQuery<'w> {
+    requests: ResMut<'w, RegionRequests>,
+}
+
+impl ConwayQuery<'_> {
+    /// Reads a region of the board, in cells, clipped to the board. Resolves
+    /// on a later frame to the cells' gray levels, row by row, 255 for alive.
+    /// If the app exits first it resolves to nothing.
+    pub fn region(&mut self, rect: URect) -> impl Future<Output = Vec<u8>> {
+        let (reply, response) = async_channel::bounded(1);
+        self.requests.pending.push(RegionRequest { rect, reply });
+        async move { response.recv().await.unwrap_or_default() }
+    }
+}
+
+fn read_region(board: &BoardSnapshot, rect: URect) -> Vec<u8> {
+    let size = board.size();
+    let min = rect.min.min(size);
+    let max = rect.max.min(size);
+    // Nothing's been read back yet for the first few frames.
+    (min.y..max.y)
+        .flat_map(|y| {
+            let row = board.row(y);
+            (min.x..max.x).map(move |x| row.get(x as usize).copied().unwrap_or(0))
+        })
+        .collect()
+}
+
+pub(crate) fn answer_region_requests(mut requests: ResMut<RegionRequests>, board: Res<BoardSnapshot>) {
+    for request in requests.waiting.drain(..) {
+        // Nobody's waiting if the future was dropped.
+        let _ = request.reply.try_send(read_region(&board, request.rect));
+    }
+    let requests = &mut *requests;
+    std::mem::swap(&mut requests.pending, &mut requests.waiting);
+}
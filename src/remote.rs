@@ -0,0 +1,90 @@
+// A plugin exposing the board over HTTP, mapped onto `ConwayCommand`s:
+//
+//   POST   /cells    set the cells listed in the body, as "x y" pairs
+//   DELETE /cells    clear the cells listed in the body
+//   POST   /reseed   reseed the board (`?density=0.1&seed=42`)
+//   POST   /pause    pause the simulation
+//   POST   /resume   resume the simulation
+//   POST   /rule     run the B/S rule in the body, like B36/S23
+//   GET    /stats    population, births and deaths as JSON
+//
+// Needs an `HttpPlugin` to receive requests from.
+
+use bevy::prelude::*;
+
+use crate::conway::{BoardStats, ConwayCommand, LivingCells, SimulationState};
+use crate::http::{HttpRequests, HttpResponse, PendingRequest};
+use crate::life_rule::LifeRule;
+
+pub struct RemoteControlPlugin;
+
+fn parse_cells(request: &PendingRequest) -> Option<Vec<IVec2>> {
+    let body = std::str::from_utf8(&request.body).ok()?;
+    let numbers = body
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.parse::<i32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    if numbers.len() % 2 != 0 {
+        return None;
+    }
+    Some(numbers.chunks_exact(2).map(|xy| IVec2::new(xy[0], xy[1])).collect())
+}
+
+fn handle_requests(
+    mut requests: ResMut<HttpRequests>,
+    mut commands: EventWriter<ConwayCommand>,
+    living_cells: Res<LivingCells>,
+    stats: Res<BoardStats>,
+    simulation_state: Res<SimulationState>,
+) {
+    for (method, make_command) in [
+        ("POST", ConwayCommand::SetCells as fn(Vec<IVec2>) -> ConwayCommand),
+        ("DELETE", ConwayCommand::ClearCells),
+    ] {
+        for request in requests.take(method, "/cells") {
+            match parse_cells(&request) {
+                Some(cells) => {
+                    commands.send(make_command(cells));
+                    request.respond(HttpResponse::text(200, "ok"));
+                }
+                None => request.respond(HttpResponse::text(400, "expected whitespace separated x y pairs")),
+            }
+        }
+    }
+    for request in requests.take("POST", "/reseed") {
+        let density = request.query_param("density").and_then(|value| value.parse().ok()).unwrap_or(0.1);
+        let seed = request.query_param("seed").and_then(|value| value.parse().ok()).unwrap_or(0);
+        commands.send(ConwayCommand::Reseed { density, seed });
+        request.respond(HttpResponse::text(200, "ok"));
+    }
+    for request in requests.take("POST", "/pause") {
+        commands.send(ConwayCommand::Pause);
+        request.respond(HttpResponse::text(200, "ok"));
+    }
+    for request in requests.take("POST", "/resume") {
+        commands.send(ConwayCommand::Resume);
+        request.respond(HttpResponse::text(200, "ok"));
+    }
+    for request in requests.take("POST", "/rule") {
+        match LifeRule::parse(&String::from_utf8_lossy(&request.body)) {
+            Ok(rule) => {
+                commands.send(ConwayCommand::SetRule(rule));
+                request.respond(HttpResponse::text(200, "ok"));
+            }
+            Err(err) => request.respond(HttpResponse::text(400, err)),
+        }
+    }
+    for request in requests.take("GET", "/stats") {
+        request.respond(HttpResponse::json(format!(
+            "{{\"population\": {}, \"births\": {}, \"deaths\": {}, \"paused\": {}}}",
+            living_cells.0, stats.births, stats.deaths, simulation_state.paused,
+        )));
+    }
+}
+
+impl Plugin for RemoteControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, handle_requests.run_if(resource_exists::<HttpRequests>()));
+    }
+}
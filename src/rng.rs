@@ -0,0 +1,39 @@
+// The crate-wide random number generator. Anything random should draw from
+// `SimRng` so that a whole run can be reproduced from a single seed.
+
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+#[derive(Resource)]
+pub struct SimRng {
+    // The seed the generator started from, so a run can be repeated.
+    pub seed: u64,
+    // What `StdRng` wraps, but able to say how far along it is.
+    pub rng: ChaCha12Rng,
+}
+
+impl SimRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self { seed, rng: ChaCha12Rng::seed_from_u64(seed) }
+    }
+
+    /// The generator from `seed`, moved on to where `position` says, as a
+    /// checkpoint saves it.
+    pub fn resume(seed: u64, position: u64) -> Self {
+        let mut sim_rng = Self::from_seed(seed);
+        sim_rng.rng.set_word_pos(position as u128);
+        sim_rng
+    }
+
+    /// How many words have been drawn since it was seeded.
+    pub fn position(&self) -> u64 {
+        self.rng.get_word_pos() as u64
+    }
+}
+
+impl Default for SimRng {
+    fn default() -> Self {
+        Self::from_seed(rand::thread_rng().gen())
+    }
+}
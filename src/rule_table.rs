@@ -0,0 +1,409 @@
+// Golly-style transition tables (`.table` files, or the `@TABLE` section of a
+// `.rule` file), compiled into a lookup table the update shader can index
+// directly. A table looks like:
+//
+//   n_states:3
+//   neighborhood:Moore
+//   symmetries:rotate8
+//   var a={0,1,2}
+//   var b=a
+//   # C,N,NE,E,SE,S,SW,W,NW,C'
+//   0,1,1,1,0,0,0,0,0,1
+//   1,a,b,0,0,0,0,0,0,2
+//
+// Each transition lists the centre cell, its neighbours clockwise from north
+// and the centre's next state. Variables match any of their values, and a
+// variable used twice in a transition has to match the same value both times.
+// The first matching transition wins, and cells no transition matches keep
+// their state.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::life_rule::LifeRule;
+
+// Tables with more entries than this are refused, since the whole table is
+// uploaded to the GPU. That's up to 5 states for Moore neighbourhoods.
+const MAX_LOOKUP_ENTRIES: usize = 1 << 22;
+// Marks lookup entries no transition has matched yet.
+const UNSET: u8 = u8::MAX;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Neighborhood {
+    // The eight surrounding cells.
+    Moore,
+    // The four orthogonally adjacent cells.
+    VonNeumann,
+}
+
+impl Neighborhood {
+    // The number of neighbours, not counting the centre.
+    pub fn neighbors(&self) -> usize {
+        match self {
+            Neighborhood::Moore => 8,
+            Neighborhood::VonNeumann => 4,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RuleTable {
+    pub name: String,
+    pub states: u32,
+    pub neighborhood: Neighborhood,
+    // The next state for every centre and neighbourhood, indexed by reading
+    // the states in transition order (centre first) as the digits of a
+    // little-endian base `states` number.
+    pub lookup: Vec<u8>,
+}
+
+impl RuleTable {
+    /// Loads a `.table` file, or the `@TABLE` section of a `.rule` file.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let name = std::path::Path::new(path)
+            .file_stem()
+            .map_or_else(|| path.to_string(), |stem| stem.to_string_lossy().into_owned());
+        if path.ends_with(".rule") {
+            Self::parse_rule_file(&text)
+        } else {
+            Self::parse_table(&name, &text)
+        }
+    }
+
+    /// Writes out a B/S rule as a two-state Moore table, leaving any decay
+    /// states aside.
+    pub fn from_life_rule(rule: &LifeRule) -> Self {
+        let lookup = (0..1u32 << 9)
+            .map(|index| rule.next(index & 1 == 1, (index >> 1).count_ones()) as u8)
+            .collect();
+        Self { name: rule.to_string(), states: 2, neighborhood: Neighborhood::Moore, lookup }
+    }
+
+    /// Parses a B/S rule like "B36/S23", or else loads a table file.
+    /// Generations and Larger than Life rules only run as a `RuleString`.
+    pub fn parse_or_load(rule: &str) -> Result<Self, String> {
+        match LifeRule::parse(rule) {
+            Ok(rule) if rule.decays() => Err(format!("{} has decay states, so it can't be a table", rule)),
+            Ok(rule) if rule.larger.is_some() => Err(format!("{} has too many neighbours to be a table", rule)),
+            Ok(rule) => Ok(Self::from_life_rule(&rule)),
+            Err(_) => Self::load(rule),
+        }
+    }
+
+    /// Parses a `.rule` file, which must have a `@TABLE` section.
+    pub fn parse_rule_file(text: &str) -> Result<Self, String> {
+        let mut name = String::new();
+        let mut table = None;
+        let mut section = "";
+        for line in text.lines() {
+            if let Some(header) = line.trim().strip_prefix('@') {
+                let mut words = header.split_whitespace();
+                section = words.next().unwrap_or("");
+                if section == "RULE" {
+                    name = words.next().unwrap_or("").to_string();
+                }
+                if section == "TABLE" {
+                    table = Some(String::new());
+                }
+                continue;
+            }
+            if section == "TABLE" {
+                let table = table.as_mut().unwrap();
+                table.push_str(line);
+                table.push('\n');
+            }
+        }
+        let table = table.ok_or("only rules with a @TABLE section are supported")?;
+        Self::parse_table(&name, &table)
+    }
+
+    /// Parses the contents of a `.table` file.
+    pub fn parse_table(name: &str, text: &str) -> Result<Self, String> {
+        let mut states = None;
+        let mut neighborhood = Neighborhood::Moore;
+        let mut symmetries = "none".to_string();
+        let mut variables: HashMap<String, Vec<u8>> = HashMap::new();
+        let mut transitions = vec![];
+
+        for (number, line) in text.lines().enumerate() {
+            let error = |message: String| format!("line {}: {}", number + 1, message);
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                let value = value.trim();
+                match key.trim() {
+                    "n_states" => {
+                        let count = value.parse::<u32>().ok().filter(|count| (2..UNSET as u32).contains(count));
+                        states = Some(count.ok_or_else(|| error(format!("bad state count {:?}", value)))?);
+                    }
+                    "neighborhood" => {
+                        neighborhood = match value {
+                            "Moore" => Neighborhood::Moore,
+                            "vonNeumann" => Neighborhood::VonNeumann,
+                            _ => return Err(error(format!("unsupported neighborhood {:?}", value))),
+                        };
+                    }
+                    "symmetries" => symmetries = value.to_string(),
+                    _ => return Err(error(format!("unknown setting {:?}", key))),
+                }
+                continue;
+            }
+            let states = states.ok_or_else(|| error("n_states has to come first".to_string()))?;
+            if let Some(definition) = line.strip_prefix("var ") {
+                let (variable, values) = definition.split_once('=').ok_or_else(|| error("expected var NAME={...}".to_string()))?;
+                let values = parse_values(values.trim(), states, &variables).map_err(error)?;
+                variables.insert(variable.trim().to_string(), values);
+                continue;
+            }
+            transitions.push(parse_transition(line, states, neighborhood, &variables).map_err(error)?);
+        }
+
+        let states = states.ok_or("missing n_states")?;
+        let lookup_entries = (states as usize).checked_pow(neighborhood.neighbors() as u32 + 1)
+            .filter(|entries| *entries <= MAX_LOOKUP_ENTRIES)
+            .ok_or_else(|| format!("{} states is too many for this neighborhood", states))?;
+        let permutations = symmetry_permutations(&symmetries, neighborhood)?;
+
+        let mut lookup = vec![UNSET; lookup_entries];
+        for transition in &transitions {
+            transition.expand(&mut |inputs, output| {
+                for permutation in &permutations {
+                    let entry = &mut lookup[index(inputs, permutation, states)];
+                    if *entry == UNSET {
+                        *entry = output;
+                    }
+                }
+            }, symmetries == "permute");
+        }
+        // Unmatched cells keep their state, which is the first digit of the index.
+        for (i, entry) in lookup.iter_mut().enumerate() {
+            if *entry == UNSET {
+                *entry = (i % states as usize) as u8;
+            }
+        }
+        Ok(Self { name: name.to_string(), states, neighborhood, lookup })
+    }
+
+    /// The next state for a cell, given its state followed by its neighbours'
+    /// in transition order. Missing neighbours count as state 0.
+    pub fn next_state(&self, states: &[u32]) -> u32 {
+        let index = (0..=self.neighborhood.neighbors())
+            .rev()
+            .fold(0, |index, i| index * self.states as usize + states.get(i).copied().unwrap_or(0) as usize);
+        self.lookup.get(index).copied().unwrap_or(0) as u32
+    }
+
+    /// The lookup table packed four entries to a word, for uploading to the GPU.
+    pub fn packed(&self) -> Vec<u32> {
+        self.lookup
+            .chunks(4)
+            .map(|chunk| chunk.iter().rev().fold(0, |word, entry| word << 8 | *entry as u32))
+            .collect()
+    }
+}
+
+// One field of a transition: either a fixed state or one of a set of states.
+#[derive(Clone, Debug)]
+enum Field {
+    State(u8),
+    // Fields that share a `binding` have to match the same state.
+    Variable { binding: usize, values: Vec<u8> },
+}
+
+#[derive(Debug)]
+struct Transition {
+    // The centre followed by the neighbours.
+    inputs: Vec<Field>,
+    output: Field,
+}
+
+impl Transition {
+    // Calls `visit` with every combination of input states this transition
+    // matches and the state it leads to. With `permute`, every ordering of the
+    // neighbours is visited too, but each distinct set of them only once.
+    fn expand(&self, visit: &mut dyn FnMut(&[u8], u8), permute: bool) {
+        let mut bound = HashMap::new();
+        let mut inputs = vec![0; self.inputs.len()];
+        if !permute {
+            self.expand_from(0, &mut inputs, &mut bound, visit);
+            return;
+        }
+        let mut seen = HashSet::new();
+        self.expand_from(0, &mut inputs, &mut bound, &mut |inputs, output| {
+            let mut sorted = inputs.to_vec();
+            sorted[1..].sort();
+            if !seen.insert(sorted.clone()) {
+                return;
+            }
+            for permutation in distinct_permutations(&sorted[1..]) {
+                let mut permuted = vec![sorted[0]];
+                permuted.extend(permutation);
+                visit(&permuted, output);
+            }
+        });
+    }
+
+    fn expand_from(
+        &self,
+        field: usize,
+        inputs: &mut Vec<u8>,
+        bound: &mut HashMap<usize, u8>,
+        visit: &mut dyn FnMut(&[u8], u8),
+    ) {
+        if field == self.inputs.len() {
+            let output = match &self.output {
+                Field::State(state) => *state,
+                Field::Variable { binding, .. } => bound[binding],
+            };
+            visit(inputs, output);
+            return;
+        }
+        match &self.inputs[field] {
+            Field::State(state) => {
+                inputs[field] = *state;
+                self.expand_from(field + 1, inputs, bound, visit);
+            }
+            Field::Variable { binding, values } => {
+                if let Some(state) = bound.get(binding).copied() {
+                    inputs[field] = state;
+                    self.expand_from(field + 1, inputs, bound, visit);
+                    return;
+                }
+                for state in values {
+                    inputs[field] = *state;
+                    bound.insert(*binding, *state);
+                    self.expand_from(field + 1, inputs, bound, visit);
+                }
+                bound.remove(binding);
+            }
+        }
+    }
+}
+
+// Every distinct ordering of `states`, which must be sorted.
+fn distinct_permutations(states: &[u8]) -> Vec<Vec<u8>> {
+    let mut current = states.to_vec();
+    let mut permutations = vec![current.clone()];
+    // The standard next-permutation algorithm, which skips duplicates.
+    loop {
+        let Some(i) = (1..current.len()).rev().find(|&i| current[i - 1] < current[i]) else {
+            return permutations;
+        };
+        let j = (i..current.len()).rev().find(|&j| current[j] > current[i - 1]).unwrap();
+        current.swap(i - 1, j);
+        current[i..].reverse();
+        permutations.push(current.clone());
+    }
+}
+
+// Parses a state, a `{...}` set, or the name of a variable into its states.
+fn parse_values(text: &str, states: u32, variables: &HashMap<String, Vec<u8>>) -> Result<Vec<u8>, String> {
+    if let Some(inner) = text.strip_prefix('{').and_then(|rest| rest.strip_suffix('}')) {
+        let mut values = vec![];
+        for item in inner.split(',') {
+            values.extend(parse_values(item.trim(), states, variables)?);
+        }
+        return Ok(values);
+    }
+    if let Some(values) = variables.get(text) {
+        return Ok(values.clone());
+    }
+    match text.parse::<u32>() {
+        Ok(state) if state < states => Ok(vec![state as u8]),
+        _ => Err(format!("expected a state or variable, got {:?}", text)),
+    }
+}
+
+// Splits a transition into its fields, which are separated by commas or
+// spaces, or are single characters when there are no separators at all.
+fn split_fields(line: &str) -> Vec<String> {
+    if !line.contains(|c: char| c == ',' || c.is_whitespace()) {
+        return line.chars().map(String::from).collect();
+    }
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut depth = 0;
+    for c in line.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+        if depth == 0 && (c == ',' || c.is_whitespace()) {
+            if !field.is_empty() {
+                fields.push(std::mem::take(&mut field));
+            }
+        } else {
+            field.push(c);
+        }
+    }
+    if !field.is_empty() {
+        fields.push(field);
+    }
+    fields
+}
+
+fn parse_transition(
+    line: &str,
+    states: u32,
+    neighborhood: Neighborhood,
+    variables: &HashMap<String, Vec<u8>>,
+) -> Result<Transition, String> {
+    let fields = split_fields(line);
+    let expected = neighborhood.neighbors() + 2;
+    if fields.len() != expected {
+        return Err(format!("expected {} fields, got {}", expected, fields.len()));
+    }
+    // Named variables are bound where they first appear; inline sets aren't shared.
+    let mut bindings: HashMap<&str, usize> = HashMap::new();
+    let mut parsed = vec![];
+    for (i, field) in fields.iter().enumerate() {
+        let values = parse_values(field, states, variables)?;
+        parsed.push(match (values.as_slice(), variables.contains_key(field.as_str())) {
+            ([state], false) => Field::State(*state),
+            (_, true) => Field::Variable { binding: *bindings.entry(field).or_insert(i), values },
+            (_, false) => Field::Variable { binding: i, values },
+        });
+    }
+    let output = parsed.pop().unwrap();
+    if let Field::Variable { binding, .. } = &output {
+        if *binding == fields.len() - 1 {
+            return Err("the output can only be a state or a variable used in the inputs".to_string());
+        }
+    }
+    Ok(Transition { inputs: parsed, output })
+}
+
+// The ways of reordering the neighbours that a symmetry treats as equivalent,
+// as index maps into the neighbours (which go clockwise from north).
+fn symmetry_permutations(symmetries: &str, neighborhood: Neighborhood) -> Result<Vec<Vec<usize>>, String> {
+    let n = neighborhood.neighbors();
+    let rotate = |steps: usize| (0..n).map(|i| (i + steps) % n).collect::<Vec<_>>();
+    // Mirroring left to right keeps north and south where they are.
+    let reflect = |permutation: &Vec<usize>| permutation.iter().map(|i| (n - i) % n).collect::<Vec<_>>();
+    // A quarter turn is two steps around a Moore neighbourhood but one around a von Neumann one.
+    let quarter = n / 4;
+    let rotations = |step: usize| (0..n / step).map(|i| rotate(i * step)).collect::<Vec<_>>();
+    let with_reflections = |permutations: Vec<Vec<usize>>| {
+        let reflected = permutations.iter().map(reflect).collect::<Vec<_>>();
+        permutations.into_iter().chain(reflected).collect::<Vec<_>>()
+    };
+    Ok(match symmetries {
+        // `permute` is handled while expanding transitions.
+        "none" | "permute" => vec![rotate(0)],
+        "rotate4" => rotations(quarter),
+        "rotate4reflect" => with_reflections(rotations(quarter)),
+        "rotate8" if neighborhood == Neighborhood::Moore => rotations(1),
+        "rotate8reflect" if neighborhood == Neighborhood::Moore => with_reflections(rotations(1)),
+        "reflect_horizontal" => with_reflections(vec![rotate(0)]),
+        _ => return Err(format!("unsupported symmetries {:?}", symmetries)),
+    })
+}
+
+// The lookup index for a centre and neighbours, with the neighbours reordered.
+fn index(inputs: &[u8], permutation: &[usize], states: u32) -> usize {
+    let digits = std::iter::once(inputs[0]).chain(permutation.iter().map(|i| inputs[1 + i]));
+    digits.rev().fold(0, |index, digit| index * states as usize + digit as usize)
+}
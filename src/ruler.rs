@@ -0,0 +1,141 @@
+// The ruler tool (9): drag across the board to measure. The box the drag
+// spans is outlined with the line between its ends across it, and a readout
+// by the cursor gives the box's size and the line's length, all in cells.
+// The last measurement stays up until the next drag, and its box is what
+// `figure` exports (see `figure.rs`). Insert fills the box with live cells and
+// Delete clears it, as do `fill` and `clear` in the console.
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::conway::{ConwayCommand, ConwayConfig};
+use crate::tools::{BoardCursor, Tool};
+
+pub struct RulerPlugin;
+
+const COLOR: Color = Color::rgb(0.4, 1.0, 0.9);
+const FONT_SIZE: f32 = 18.0;
+// Where the readout sits from the cursor, in pixels.
+const READOUT_OFFSET: Vec2 = Vec2::new(16.0, 16.0);
+
+/// The ends of the last measurement, and whether it's still being dragged.
+#[derive(Resource, Default)]
+pub struct Measurement {
+    ends: Option<(IVec2, IVec2)>,
+    dragging: bool,
+}
+
+impl Measurement {
+    /// The top left corner and size of the box measured, both ends included.
+    pub fn region(&self) -> Option<(IVec2, UVec2)> {
+        let (from, to) = self.ends?;
+        Some((from.min(to), ((from - to).abs() + IVec2::ONE).as_uvec2()))
+    }
+}
+
+#[derive(Component)]
+struct Readout;
+
+/// What the readout says for a measurement from `from` to `to`, both cells
+/// included: the box's width and height, and how far apart the ends are.
+pub fn describe(from: IVec2, to: IVec2) -> String {
+    let delta = to - from;
+    let size = delta.abs() + IVec2::ONE;
+    format!("{} x {} cells, {:.1} across", size.x, size.y, delta.as_vec2().length())
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Readout,
+        TextBundle::from_section("", TextStyle { font_size: FONT_SIZE, color: COLOR, ..default() })
+            .with_style(Style { position_type: PositionType::Absolute, ..default() }),
+    ));
+}
+
+fn measure(
+    tool: Res<Tool>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    cursor: BoardCursor,
+    mut measurement: ResMut<Measurement>,
+) {
+    // Kept for exporting after switching away.
+    if *tool != Tool::Ruler {
+        measurement.dragging = false;
+        return;
+    }
+    let Some(cell) = cursor.cell() else {
+        return;
+    };
+    if mouse_button_input.just_pressed(MouseButton::Left) {
+        *measurement = Measurement { ends: Some((cell, cell)), dragging: true };
+    }
+    if measurement.dragging {
+        if let Some((_, to)) = &mut measurement.ends {
+            *to = cell;
+        }
+    }
+    if mouse_button_input.just_released(MouseButton::Left) {
+        measurement.dragging = false;
+    }
+}
+
+fn show_measurement(
+    tool: Res<Tool>,
+    measurement: Res<Measurement>,
+    config: Res<ConwayConfig>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut readouts: Query<(&mut Text, &mut Style, &mut Visibility), With<Readout>>,
+    mut gizmos: Gizmos,
+) {
+    let Ok((mut text, mut style, mut visibility)) = readouts.get_single_mut() else {
+        return;
+    };
+    let (Some((from, to)), Tool::Ruler) = (measurement.ends, *tool) else {
+        *visibility = Visibility::Hidden;
+        return;
+    };
+    let Some((corner, size)) = measurement.region() else {
+        return;
+    };
+    let top_left = config.cell_to_world(corner);
+    let bottom_right = config.cell_to_world(corner + size.as_ivec2());
+    gizmos.rect_2d((top_left + bottom_right) / 2.0, 0.0, (bottom_right - top_left).abs(), COLOR);
+    // From the middle of one end's cell to the other's.
+    let middle = |cell| (config.cell_to_world(cell) + config.cell_to_world(cell + IVec2::ONE)) / 2.0;
+    gizmos.line_2d(middle(from), middle(to), COLOR);
+
+    text.sections[0].value = describe(from, to);
+    *visibility = Visibility::Visible;
+    // Follows the cursor while dragging, and stays put after.
+    let cursor = window.get_single().ok().and_then(Window::cursor_position);
+    if let (Some(cursor), true) = (cursor, measurement.dragging) {
+        style.left = Val::Px(cursor.x + READOUT_OFFSET.x);
+        style.top = Val::Px(cursor.y + READOUT_OFFSET.y);
+    }
+}
+
+fn fill_measurement(
+    tool: Res<Tool>,
+    keys: Res<Input<KeyCode>>,
+    measurement: Res<Measurement>,
+    mut commands: EventWriter<ConwayCommand>,
+) {
+    let (Some((corner, size)), Tool::Ruler, false) = (measurement.region(), *tool, measurement.dragging) else {
+        return;
+    };
+    for (key, alive) in [(KeyCode::Insert, true), (KeyCode::Delete, false)] {
+        if keys.just_pressed(key) {
+            commands.send(ConwayCommand::FillRect { corner, size, alive });
+        }
+    }
+}
+
+impl Plugin for RulerPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Measurement>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, ((measure, show_measurement).chain(), fill_measurement))
+        ;
+    }
+}
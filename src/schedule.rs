@@ -0,0 +1,74 @@
+// Scheduled pattern injections: "at generation G, stamp pattern P at (x, y)".
+// Entries are queued from the console (`at G NAME X Y`) or scripts
+// (`schedule(G, NAME, X, Y)`) and stamped through `ConwayCommand::SetCells`
+// once the board gets there.
+
+use bevy::prelude::*;
+
+use crate::conway::{ConwayCommand, GenerationAdvanced};
+use crate::event_log::LogEvent;
+use crate::patterns;
+
+pub struct SchedulePlugin;
+
+#[derive(Clone, Debug)]
+pub struct Injection {
+    pub generation: u64,
+    pub pattern: String,
+    pub position: IVec2,
+}
+
+/// Injections waiting to happen, earliest first.
+#[derive(Resource, Default)]
+pub struct InjectionSchedule {
+    injections: Vec<Injection>,
+}
+
+impl InjectionSchedule {
+    pub fn add(&mut self, injection: Injection) {
+        let index = self.injections.partition_point(|queued| queued.generation <= injection.generation);
+        self.injections.insert(index, injection);
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Injection> {
+        (index < self.injections.len()).then(|| self.injections.remove(index))
+    }
+
+    pub fn clear(&mut self) {
+        self.injections.clear();
+    }
+
+    pub fn injections(&self) -> &[Injection] {
+        &self.injections
+    }
+}
+
+fn run_injections(
+    mut schedule: ResMut<InjectionSchedule>,
+    mut generations: EventReader<GenerationAdvanced>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut log: EventWriter<LogEvent>,
+) {
+    let Some(advanced) = generations.read().last() else {
+        return;
+    };
+    let due = schedule.injections.partition_point(|injection| injection.generation <= advanced.generation);
+    if due == 0 {
+        return;
+    }
+    for injection in schedule.injections.drain(..due) {
+        match patterns::lookup(&injection.pattern, injection.position) {
+            Some(cells) => commands.send(ConwayCommand::SetCells(cells)),
+            None => log.send(LogEvent(format!("Schedule: unknown pattern {:?}", injection.pattern))),
+        }
+    }
+}
+
+impl Plugin for SchedulePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<InjectionSchedule>()
+        .add_systems(Update, run_injections)
+        ;
+    }
+}
@@ -0,0 +1,285 @@
+// Scenario scripting with Rhai (`--script FILE`). The script's top level runs
+// once at startup, and can queue up stamps for later with
+// `schedule(generation, name, x, y)`. An `on_generation(generation, population,
+// births, deaths)` function, if it defines one, runs whenever the board advances:
+//
+//   fn on_generation(generation, population, births, deaths) {
+//       if generation == 200 { stamp("glider", 10, 10); }
+//       if population < 100 { reseed(0.2, generation); }
+//   }
+//
+// An `on_frame(stats)` function runs every frame, paused or not, with a map of
+// `paused`, `speed`, `generation` and `population`. Both can keep state between
+// calls in `this`, which starts out as an empty map, and `prompt(text)` shows
+// a line of instructions at the bottom of the screen (an empty one hides it).
+// The tutorial (`--tutorial`) is a script like this.
+//
+// `fill_rect(x, y, width, height)` and `clear_rect(x, y, width, height)` write a
+// whole rectangle at once, much faster than cell by cell.
+//
+// Scripts only get the functions registered below, which queue up
+// `ConwayCommand`s and log events, and are cut off if they run for too long.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use rhai::{CallFnOptions, Dynamic, Engine, FuncArgs, Map, Scope, AST};
+
+use crate::conway::{ConwayCommand, Generation, GenerationAdvanced, LivingCells, SimulationState};
+use crate::event_log::LogEvent;
+use crate::life_rule::LifeRule;
+use crate::patterns;
+use crate::rule_table::RuleTable;
+use crate::schedule::{Injection, InjectionSchedule};
+
+// Limits that keep a runaway script from freezing the game.
+const MAX_OPERATIONS: u64 = 1_000_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_STRING_SIZE: usize = 64 * 1024;
+const MAX_ARRAY_SIZE: usize = 64 * 1024;
+const PROMPT_FONT_SIZE: f32 = 28.0;
+
+/// The script run by `--tutorial`.
+pub const TUTORIAL_SCRIPT: &str = "assets/scripts/tutorial.rhai";
+
+pub struct ScriptingPlugin {
+    pub path: String,
+}
+
+enum ScriptAction {
+    Command(ConwayCommand),
+    Schedule(Injection),
+    Log(String),
+    Prompt(String),
+}
+
+// Shared between the engine's registered functions and the systems that drain them.
+type ScriptActions = Rc<RefCell<Vec<ScriptAction>>>;
+
+// Rhai engines aren't `Send`, so this lives in a non-send resource.
+struct Script {
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    actions: ScriptActions,
+    // `this` for the callbacks.
+    state: Dynamic,
+    has_on_generation: bool,
+    has_on_frame: bool,
+}
+
+impl Script {
+    fn call(&mut self, name: &str, args: impl FuncArgs) -> Result<(), String> {
+        // Without `eval_ast(false)` the top level would run again on every call.
+        let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(&mut self.state);
+        self.engine
+            .call_fn_with_options::<Dynamic>(options, &mut self.scope, &self.ast, name, args)
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// The script's current instructions, if it's showing any.
+#[derive(Resource, Default)]
+pub struct ScriptPrompt(pub String);
+
+#[derive(Component)]
+struct PromptText;
+
+fn sandboxed_engine(actions: &ScriptActions) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_string_size(MAX_STRING_SIZE)
+        .set_max_array_size(MAX_ARRAY_SIZE);
+
+    let queue = actions.clone();
+    engine.register_fn("set_cell", move |x: i64, y: i64| {
+        queue.borrow_mut().push(ScriptAction::Command(ConwayCommand::SetCells(vec![IVec2::new(x as i32, y as i32)])));
+    });
+    let queue = actions.clone();
+    engine.register_fn("clear_cell", move |x: i64, y: i64| {
+        queue.borrow_mut().push(ScriptAction::Command(ConwayCommand::ClearCells(vec![IVec2::new(x as i32, y as i32)])));
+    });
+    for (name, alive) in [("fill_rect", true), ("clear_rect", false)] {
+        let queue = actions.clone();
+        engine.register_fn(name, move |x: i64, y: i64, width: i64, height: i64| {
+            let corner = IVec2::new(x as i32, y as i32);
+            let size = UVec2::new(width.max(0) as u32, height.max(0) as u32);
+            queue.borrow_mut().push(ScriptAction::Command(ConwayCommand::FillRect { corner, size, alive }));
+        });
+    }
+    let queue = actions.clone();
+    engine.register_fn("stamp", move |name: &str, x: i64, y: i64| {
+        let mut queue = queue.borrow_mut();
+        match patterns::lookup(name, IVec2::new(x as i32, y as i32)) {
+            Some(cells) => queue.push(ScriptAction::Command(ConwayCommand::SetCells(cells))),
+            None => queue.push(ScriptAction::Log(format!("Script: unknown pattern {:?}", name))),
+        }
+    });
+    let queue = actions.clone();
+    engine.register_fn("schedule", move |generation: i64, name: &str, x: i64, y: i64| {
+        queue.borrow_mut().push(ScriptAction::Schedule(Injection {
+            generation: generation.max(0) as u64,
+            pattern: name.to_string(),
+            position: IVec2::new(x as i32, y as i32),
+        }));
+    });
+    let queue = actions.clone();
+    engine.register_fn("reseed", move |density: f64, seed: i64| {
+        queue.borrow_mut().push(ScriptAction::Command(ConwayCommand::Reseed { density: density as f32, seed: seed as u32 }));
+    });
+    let queue = actions.clone();
+    engine.register_fn("pause", move || queue.borrow_mut().push(ScriptAction::Command(ConwayCommand::Pause)));
+    let queue = actions.clone();
+    engine.register_fn("resume", move || queue.borrow_mut().push(ScriptAction::Command(ConwayCommand::Resume)));
+    let queue = actions.clone();
+    engine.register_fn("speed", move |speed: i64| {
+        queue.borrow_mut().push(ScriptAction::Command(ConwayCommand::SetSpeed(speed.max(1) as u32)));
+    });
+    let queue = actions.clone();
+    engine.register_fn("rule", move |rule: &str| {
+        let mut queue = queue.borrow_mut();
+        if let Ok(rule) = LifeRule::parse(rule) {
+            queue.push(ScriptAction::Command(ConwayCommand::SetRule(rule)));
+            return;
+        }
+        match RuleTable::load(rule) {
+            Ok(table) => queue.push(ScriptAction::Command(ConwayCommand::SetRuleTable(Some(Arc::new(table))))),
+            Err(err) => queue.push(ScriptAction::Log(format!("Script: failed to load rule {}: {}", rule, err))),
+        }
+    });
+    let queue = actions.clone();
+    engine.register_fn("log", move |text: &str| queue.borrow_mut().push(ScriptAction::Log(text.to_string())));
+    let queue = actions.clone();
+    engine.register_fn("prompt", move |text: &str| queue.borrow_mut().push(ScriptAction::Prompt(text.to_string())));
+    let queue = actions.clone();
+    engine.on_print(move |text| queue.borrow_mut().push(ScriptAction::Log(text.to_string())));
+    engine
+}
+
+fn load_script(path: &str) -> Result<Script, String> {
+    let source = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let actions = ScriptActions::default();
+    let engine = sandboxed_engine(&actions);
+    let ast = engine.compile(&source).map_err(|err| err.to_string())?;
+    let has_function = |name: &str| ast.iter_functions().any(|function| function.name == name);
+    let (has_on_generation, has_on_frame) = (has_function("on_generation"), has_function("on_frame"));
+    let state = Map::new().into();
+    Ok(Script { engine, ast, scope: Scope::new(), actions, state, has_on_generation, has_on_frame })
+}
+
+fn run_top_level(mut script: NonSendMut<Script>, mut log: EventWriter<LogEvent>) {
+    let Script { engine, ast, scope, .. } = &mut *script;
+    if let Err(err) = engine.run_ast_with_scope(scope, ast) {
+        log.send(LogEvent(format!("Script error: {}", err)));
+    }
+}
+
+fn run_on_generation(
+    mut script: NonSendMut<Script>,
+    mut generations: EventReader<GenerationAdvanced>,
+    mut log: EventWriter<LogEvent>,
+) {
+    for advanced in generations.read() {
+        if !script.has_on_generation {
+            continue;
+        }
+        let args = (
+            advanced.generation as i64,
+            advanced.population as i64,
+            advanced.births as i64,
+            advanced.deaths as i64,
+        );
+        if let Err(err) = script.call("on_generation", args) {
+            log.send(LogEvent(format!("Script error: {}", err)));
+        }
+    }
+}
+
+fn run_on_frame(
+    mut script: NonSendMut<Script>,
+    simulation_state: Res<SimulationState>,
+    generation: Res<Generation>,
+    living_cells: Res<LivingCells>,
+    mut log: EventWriter<LogEvent>,
+) {
+    if !script.has_on_frame {
+        return;
+    }
+    let mut stats = Map::new();
+    stats.insert("paused".into(), simulation_state.paused.into());
+    stats.insert("speed".into(), (simulation_state.speed as i64).into());
+    stats.insert("generation".into(), (generation.get() as i64).into());
+    stats.insert("population".into(), (living_cells.0 as i64).into());
+    if let Err(err) = script.call("on_frame", (stats,)) {
+        log.send(LogEvent(format!("Script error: {}", err)));
+    }
+}
+
+fn apply_script_actions(
+    script: NonSend<Script>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut schedule: ResMut<InjectionSchedule>,
+    mut prompt: ResMut<ScriptPrompt>,
+    mut log: EventWriter<LogEvent>,
+) {
+    for action in script.actions.borrow_mut().drain(..) {
+        match action {
+            ScriptAction::Command(command) => commands.send(command),
+            ScriptAction::Schedule(injection) => schedule.add(injection),
+            ScriptAction::Log(text) => log.send(LogEvent(text)),
+            ScriptAction::Prompt(text) => prompt.0 = text,
+        }
+    }
+}
+
+fn setup_prompt(mut commands: Commands) {
+    commands.spawn((
+        PromptText,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: PROMPT_FONT_SIZE,
+                color: Color::WHITE,
+                ..Default::default()
+            }
+        ).with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(40.0),
+            left: Val::Percent(20.0),
+            ..default()
+        }),
+    ));
+}
+
+fn render_prompt(prompt: Res<ScriptPrompt>, mut query: Query<&mut Text, With<PromptText>>) {
+    if !prompt.is_changed() {
+        return;
+    }
+    if let Ok(mut text) = query.get_single_mut() {
+        text.sections[0].value.clone_from(&prompt.0);
+    }
+}
+
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut App) {
+        let script = match load_script(&self.path) {
+            Ok(script) => script,
+            Err(err) => {
+                println!("failed to load script {}: {}", self.path, err);
+                return;
+            }
+        };
+        app
+        .insert_non_send_resource(script)
+        .init_resource::<ScriptPrompt>()
+        .add_systems(Startup, (setup_prompt, run_top_level))
+        .add_systems(Update, (run_on_generation, run_on_frame, apply_script_actions).chain())
+        .add_systems(Update, render_prompt.after(apply_script_actions))
+        ;
+    }
+}
@@ -0,0 +1,105 @@
+// Headless authoritative server mode (`--server ADDR`). The board lives on the
+// CPU here, as big as `--size` makes it; viewers connect with
+// `--connect ws://ADDR`, get a keyframe of the whole board and then a diff per
+// generation, and their cell edits are applied before the next step. With
+// `--http`, the board's `/metrics` are served too.
+
+use std::net::TcpListener;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::conway::ConwayConfig;
+use crate::cpu::CpuBoard;
+use crate::http::{self, PendingRequest};
+use crate::metrics::{self, GenerationRate};
+use crate::net::{NetMessage, Peer};
+
+const GENERATIONS_PER_SECOND: u32 = 30;
+const SEED_DENSITY: f32 = 0.1;
+
+/// Runs the server on a board made with `config` until the process is killed,
+/// serving its metrics over HTTP on `http_address` if given.
+pub fn run_server(address: &str, http_address: Option<&str>, seed: u64, config: ConwayConfig) -> std::io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    listener.set_nonblocking(true)?;
+    println!("serving on ws://{} with seed {}", address, seed);
+    let http_requests = http_address.map(http::listen).transpose()?;
+
+    let size = config.size;
+    let mut board = CpuBoard::new(size.x, size.y);
+    board.seed(SEED_DENSITY, &mut StdRng::seed_from_u64(seed));
+
+    // Handshakes happen on their own threads so a slow client can't stall the simulation.
+    let (new_viewers, accepted_viewers) = async_channel::unbounded::<Peer>();
+    let mut viewers: Vec<Peer> = vec![];
+    let tick = Duration::from_secs(1) / GENERATIONS_PER_SECOND;
+    let running_since = Instant::now();
+    let mut generation = 0;
+    let mut rate = GenerationRate::default();
+    // How long the last generation took, sleep and all, as there are no frames.
+    let mut frame_time = 0.0;
+
+    loop {
+        let started = Instant::now();
+
+        // Only the metrics are served here; the rest of the API needs the app.
+        if let Some(http_requests) = &http_requests {
+            let (requests, others): (Vec<PendingRequest>, Vec<_>) = std::iter::from_fn(|| http_requests.try_recv().ok())
+                .partition(|request| request.method == "GET" && request.path == "/metrics");
+            others.into_iter().for_each(PendingRequest::reject);
+            if !requests.is_empty() {
+                let population = board.alive_indices().len() as u64;
+                metrics::respond_with_metrics(requests, population, generation, &rate, frame_time);
+            }
+        }
+
+        while let Ok((stream, _)) = listener.accept() {
+            let new_viewers = new_viewers.clone();
+            thread::spawn(move || {
+                if let Some(peer) = Peer::accept(stream) {
+                    let _ = new_viewers.send_blocking(peer);
+                }
+            });
+        }
+        while let Ok(viewer) = accepted_viewers.try_recv() {
+            let _ = viewer.outgoing.try_send(NetMessage::Keyframe(board.alive_indices()).encode());
+            viewers.push(viewer);
+            println!("viewer connected ({} total)", viewers.len());
+        }
+
+        // Edits are applied straight away, and reach viewers with the next diff.
+        let previous = board.cells.clone();
+        for viewer in viewers.iter() {
+            while let Ok(message) = viewer.incoming.try_recv() {
+                match NetMessage::decode(&message) {
+                    Some(NetMessage::SetCells(points)) => {
+                        points.into_iter().for_each(|point| board.set(config.clip_to_cell(point), true));
+                    }
+                    Some(NetMessage::ClearCells(points)) => {
+                        points.into_iter().for_each(|point| board.set(config.clip_to_cell(point), false));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        board.step();
+        generation += 1;
+        rate.measure(generation, running_since.elapsed().as_secs_f32());
+        let flipped = previous
+            .iter()
+            .zip(board.cells.iter())
+            .enumerate()
+            .filter(|(_, (old, new))| old != new)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        let diff = NetMessage::Diff(flipped).encode();
+        viewers.retain(|viewer| viewer.outgoing.try_send(diff.clone()).is_ok());
+
+        thread::sleep(tick.saturating_sub(started.elapsed()));
+        frame_time = started.elapsed().as_secs_f64();
+    }
+}
@@ -0,0 +1,133 @@
+// Boards saved as PNGs, one pixel per cell, white for alive. The rule, seed,
+// density and generation go along in PNG text chunks, with a hash of the
+// board so a shared image can be checked and its run reproduced. Checkpoints
+// also save where `SimRng` was up to, to pick the run up from where it left off.
+
+use bevy::prelude::*;
+
+// Snapshots bigger than this are refused rather than risking a huge
+// allocation on a malformed file.
+const MAX_SIDE: u32 = 1 << 14;
+
+const RULE: &str = "Rule";
+const SEED: &str = "Seed";
+const DENSITY: &str = "Density";
+const GENERATION: &str = "Generation";
+const HASH: &str = "Board hash";
+const RNG_SEED: &str = "RNG seed";
+const RNG_POSITION: &str = "RNG position";
+
+/// What's known about how a board came about. Any of it may be missing from
+/// images saved elsewhere.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct SnapshotMetadata {
+    pub rule: Option<String>,
+    pub seed: Option<u32>,
+    pub density: Option<f32>,
+    pub generation: Option<u64>,
+    // The seed of `SimRng` when a checkpoint was taken, and how far along it was.
+    pub rng_seed: Option<u64>,
+    pub rng_position: Option<u64>,
+    // Filled in when the snapshot is encoded, see `board_hash`.
+    pub hash: Option<u64>,
+}
+
+/// A board read back from a PNG, or a save file (see `save_file.rs`).
+pub struct Snapshot {
+    pub size: UVec2,
+    pub cells: Vec<IVec2>,
+    pub metadata: SnapshotMetadata,
+}
+
+impl Snapshot {
+    /// Whether the cells still match the saved hash.
+    pub fn hash_matches(&self) -> bool {
+        self.metadata.hash == Some(board_hash(self.size, &self.cells))
+    }
+}
+
+/// A 64-bit FNV-1a hash of the board size and its live cells, which are
+/// expected row by row. Unlike `DefaultHasher` it's stable across builds.
+pub fn board_hash(size: UVec2, cells: &[IVec2]) -> u64 {
+    let words = [size.x as i32, size.y as i32].into_iter().chain(cells.iter().flat_map(|cell| [cell.x, cell.y]));
+    words.flat_map(i32::to_le_bytes).fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Encodes a `size` board with the given live cells, which should be row by
+/// row, as a grayscale PNG. The hash in `metadata` is replaced with the board's.
+pub fn encode(size: UVec2, cells: &[IVec2], metadata: &SnapshotMetadata) -> Result<Vec<u8>, String> {
+    let mut pixels = vec![0u8; (size.x * size.y) as usize];
+    for cell in cells {
+        if cell.x >= 0 && cell.y >= 0 && (cell.x as u32) < size.x && (cell.y as u32) < size.y {
+            pixels[(cell.y as u32 * size.x + cell.x as u32) as usize] = 255;
+        }
+    }
+
+    let mut bytes = vec![];
+    let mut encoder = png::Encoder::new(&mut bytes, size.x, size.y);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let chunks = [
+        (RULE, metadata.rule.clone()),
+        (SEED, metadata.seed.map(|seed| seed.to_string())),
+        (DENSITY, metadata.density.map(|density| density.to_string())),
+        (GENERATION, metadata.generation.map(|generation| generation.to_string())),
+        (RNG_SEED, metadata.rng_seed.map(|seed| seed.to_string())),
+        (RNG_POSITION, metadata.rng_position.map(|position| position.to_string())),
+        (HASH, Some(format!("{:016x}", board_hash(size, cells)))),
+    ];
+    for (keyword, text) in chunks {
+        if let Some(text) = text {
+            encoder.add_text_chunk(keyword.to_string(), text).map_err(|err| err.to_string())?;
+        }
+    }
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(|err| err.to_string())?;
+    Ok(bytes)
+}
+
+/// Decodes a PNG into a board, counting any pixel brighter than half as alive.
+pub fn decode(bytes: &[u8]) -> Result<Snapshot, String> {
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|err| err.to_string())?;
+    let (width, height) = reader.info().size();
+    if width > MAX_SIDE || height > MAX_SIDE {
+        return Err(format!("image is too big ({}x{})", width, height));
+    }
+
+    let mut metadata = SnapshotMetadata::default();
+    for chunk in &reader.info().uncompressed_latin1_text {
+        let text = chunk.text.trim();
+        match chunk.keyword.as_str() {
+            RULE => metadata.rule = Some(text.to_string()),
+            SEED => metadata.seed = text.parse().ok(),
+            DENSITY => metadata.density = text.parse().ok(),
+            GENERATION => metadata.generation = text.parse().ok(),
+            RNG_SEED => metadata.rng_seed = text.parse().ok(),
+            RNG_POSITION => metadata.rng_position = text.parse().ok(),
+            HASH => metadata.hash = u64::from_str_radix(text, 16).ok(),
+            _ => {}
+        }
+    }
+
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut pixels).map_err(|err| err.to_string())?;
+    let samples = frame.color_type.samples();
+    let mut cells = vec![];
+    for (y, row) in pixels[..frame.buffer_size()].chunks_exact(frame.line_size).enumerate() {
+        for (x, pixel) in row.chunks_exact(samples).enumerate().take(width as usize) {
+            // Average the color channels, leaving out alpha.
+            let colors = if samples == 2 || samples == 4 { &pixel[..samples - 1] } else { pixel };
+            let brightness = colors.iter().map(|c| *c as u32).sum::<u32>() / colors.len() as u32;
+            if brightness >= 128 {
+                cells.push(IVec2::new(x as i32, y as i32));
+            }
+        }
+    }
+    Ok(Snapshot { size: UVec2::new(width, height), cells, metadata })
+}
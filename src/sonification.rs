@@ -0,0 +1,136 @@
+// A plugin that turns the simulation into a generative instrument. Either the
+// births and deaths since the last beat set the pitch and volume of a tone, or
+// cells appearing in a handful of "tracker rows" each trigger their own note.
+
+use std::time::Duration;
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::conway::{BoardSnapshot, BoardStats, LivingCells};
+use crate::event_log::LogEvent;
+use crate::mixer::AudioMixer;
+
+pub struct SonificationPlugin;
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SonificationMode {
+    #[default]
+    Off,
+    // Births versus deaths pick the pitch and overall activity the volume.
+    Stats,
+    // Cells appearing in evenly spaced rows trigger that row's note.
+    TrackerRows,
+}
+
+const BEAT_SECONDS: f32 = 0.25;
+const NOTE_SECONDS: f32 = 0.2;
+// Two octaves of the A major pentatonic scale.
+const SCALE: &[f32] = &[220.0, 247.5, 277.2, 330.0, 370.0, 440.0, 495.0, 554.4, 660.0, 740.0];
+const TRACKER_ROWS: u32 = 8;
+
+#[derive(Resource)]
+struct BeatTimer(Timer);
+
+// Births and deaths accumulated since the last beat.
+#[derive(Resource, Default)]
+struct BeatStats {
+    births: u64,
+    deaths: u64,
+}
+
+fn toggle_mode(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut mode: ResMut<SonificationMode>,
+    mut log: EventWriter<LogEvent>,
+) {
+    if keyboard_input.just_pressed(KeyCode::M) {
+        *mode = match *mode {
+            SonificationMode::Off => SonificationMode::Stats,
+            SonificationMode::Stats => SonificationMode::TrackerRows,
+            SonificationMode::TrackerRows => SonificationMode::Off,
+        };
+        log.send(LogEvent(format!("Sonification: {:?}", *mode)));
+    }
+}
+
+fn accumulate_stats(stats: Res<BoardStats>, mut beat_stats: ResMut<BeatStats>) {
+    beat_stats.births += stats.births;
+    beat_stats.deaths += stats.deaths;
+}
+
+fn play_note(
+    commands: &mut Commands,
+    pitches: &mut Assets<Pitch>,
+    mixer: &mut AudioMixer,
+    frequency: f32,
+    volume: f32,
+) {
+    mixer.duck();
+    commands.spawn(PitchBundle {
+        source: pitches.add(Pitch::new(frequency, Duration::from_secs_f32(NOTE_SECONDS))),
+        settings: PlaybackSettings::DESPAWN.with_volume(Volume::new_relative(volume)),
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn play_beat(
+    mut commands: Commands,
+    time: Res<Time>,
+    mode: Res<SonificationMode>,
+    living_cells: Res<LivingCells>,
+    board: Res<BoardSnapshot>,
+    mut timer: ResMut<BeatTimer>,
+    mut beat_stats: ResMut<BeatStats>,
+    mut pitches: ResMut<Assets<Pitch>>,
+    mut mixer: ResMut<AudioMixer>,
+    // Live cells per tracker row at the last beat.
+    mut row_counts: Local<Vec<usize>>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let BeatStats { births, deaths } = std::mem::take(&mut *beat_stats);
+
+    match *mode {
+        SonificationMode::Off => {}
+        SonificationMode::Stats => {
+            let activity = births + deaths;
+            if activity > 0 {
+                let note = (births as f32 / activity as f32 * (SCALE.len() - 1) as f32).round() as usize;
+                let volume = (activity as f32 / living_cells.0.max(1) as f32).sqrt().clamp(0.05, 1.0);
+                play_note(&mut commands, &mut pitches, &mut mixer, SCALE[note], volume);
+            }
+        }
+        SonificationMode::TrackerRows => {
+            let size = board.size();
+            let counts = (0..TRACKER_ROWS)
+                .map(|i| {
+                    let y = (2 * i + 1) * size.y / (2 * TRACKER_ROWS);
+                    board.row(y).iter().filter(|cell| **cell == 255).count()
+                })
+                .collect::<Vec<_>>();
+            for (i, count) in counts.iter().enumerate() {
+                let previous = row_counts.get(i).copied().unwrap_or(0);
+                if *count > previous {
+                    // Rows further down the board play lower notes.
+                    let note = SCALE[(TRACKER_ROWS as usize - 1 - i) % SCALE.len()];
+                    let volume = ((count - previous) as f32 / 16.0).clamp(0.1, 1.0);
+                    play_note(&mut commands, &mut pitches, &mut mixer, note, volume);
+                }
+            }
+            *row_counts = counts;
+        }
+    }
+}
+
+impl Plugin for SonificationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<SonificationMode>()
+        .init_resource::<BeatStats>()
+        .insert_resource(BeatTimer(Timer::from_seconds(BEAT_SECONDS, TimerMode::Repeating)))
+        .add_systems(Update, (toggle_mode, accumulate_stats, play_beat).chain())
+        ;
+    }
+}
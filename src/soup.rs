@@ -0,0 +1,148 @@
+// Soup search (`soup-search COUNT`): seeds COUNT random 16x16 soups in the
+// middle of an empty board, runs each on the CPU until it stabilizes and takes
+// a census of the ash with the pattern recognizer. Spaceships are counted as
+// they escape. At the end it prints the totals and the rare finds, with the
+// seed of the soup they came from and their RLE so they can be looked at.
+//
+// Soup seeds count up from `--seed`, so a search can be picked up where
+// another left off.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::census::{self, Object};
+use crate::cpu::{self, CpuBoard};
+
+const BOARD_SIDE: u32 = 256;
+const SOUP_SIDE: i32 = 16;
+const SOUP_DENSITY: f32 = 0.5;
+const MAX_GENERATIONS: u32 = 20_000;
+// Generations between checks for stabilization. Everything the recognizer
+// knows has a period dividing this, so a settled board looks the same at
+// every check.
+const CHECK_INTERVAL: u32 = 60;
+// Spaceships at least this far from everything else have escaped.
+const ESCAPE_DISTANCE: i32 = 24;
+// Objects turning up in fewer than this share of soups are rare finds.
+const RARE_SHARE: f64 = 0.01;
+
+struct SoupResult {
+    seed: u64,
+    stabilized_at: Option<u32>,
+    objects: Vec<Object>,
+}
+
+fn alive_cells(board: &CpuBoard) -> Vec<IVec2> {
+    (0..board.height as i32)
+        .flat_map(|y| (0..board.width as i32).map(move |x| IVec2::new(x, y)))
+        .filter(|cell| board.is_alive(*cell))
+        .collect()
+}
+
+fn is_isolated(object: &Object, objects: &[Object]) -> bool {
+    let min = object.min() - IVec2::splat(ESCAPE_DISTANCE);
+    let max = object.max() + IVec2::splat(ESCAPE_DISTANCE);
+    objects
+        .iter()
+        .filter(|other| other.cells != object.cells)
+        .flat_map(|other| other.cells.iter())
+        .all(|cell| cell.cmplt(min).any() || cell.cmpgt(max).any())
+}
+
+fn run_soup(seed: u64) -> SoupResult {
+    let mut board = CpuBoard::new(BOARD_SIDE, BOARD_SIDE);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let corner = IVec2::splat((BOARD_SIDE as i32 - SOUP_SIDE) / 2);
+    for y in 0..SOUP_SIDE {
+        for x in 0..SOUP_SIDE {
+            board.set(corner + IVec2::new(x, y), rng.gen::<f32>() < SOUP_DENSITY);
+        }
+    }
+
+    let mut escaped = vec![];
+    let mut last_hash = None;
+    let mut generation = 0;
+    while generation < MAX_GENERATIONS {
+        for _ in 0..CHECK_INTERVAL {
+            board.step();
+        }
+        generation += CHECK_INTERVAL;
+
+        // Take escaping spaceships off the board before they hit the edge.
+        let objects = census::recognize(&alive_cells(&board));
+        for object in &objects {
+            if object.is_spaceship() && is_isolated(object, &objects) {
+                for cell in &object.cells {
+                    board.set(*cell, false);
+                }
+                escaped.push(object.clone());
+            }
+        }
+
+        let hash = cpu::cells_hash(&board.cells);
+        if last_hash == Some(hash) {
+            let mut objects = census::recognize(&alive_cells(&board));
+            objects.extend(escaped);
+            return SoupResult { seed, stabilized_at: Some(generation - CHECK_INTERVAL), objects };
+        }
+        last_hash = Some(hash);
+    }
+    SoupResult { seed, stabilized_at: None, objects: escaped }
+}
+
+fn describe(counts: &BTreeMap<String, usize>) -> String {
+    if counts.is_empty() {
+        return "nothing".to_string();
+    }
+    counts.iter().map(|(label, count)| format!("{} {}", count, label)).collect::<Vec<_>>().join(", ")
+}
+
+pub fn run_soup_search(soups: u64, seed: u64) {
+    // Soups are independent, so spread them over every core.
+    let results = (0..soups)
+        .into_par_iter()
+        .map(|i| {
+            let result = run_soup(seed.wrapping_add(i));
+            let counts = census::census(&result.objects);
+            match result.stabilized_at {
+                Some(generation) => println!("soup {}: stabilized by {}: {}", result.seed, generation, describe(&counts)),
+                None => println!("soup {}: didn't stabilize in {} generations", result.seed, MAX_GENERATIONS),
+            }
+            result
+        })
+        .collect::<Vec<_>>();
+
+    let mut totals = BTreeMap::<String, usize>::new();
+    // How many soups each label turned up in.
+    let mut soups_with = BTreeMap::<String, u64>::new();
+    for result in &results {
+        let counts = census::census(&result.objects);
+        for (label, count) in counts {
+            *totals.entry(label.clone()).or_default() += count;
+            *soups_with.entry(label).or_default() += 1;
+        }
+    }
+    println!("census of {} soups:", soups);
+    let mut by_count = totals.iter().collect::<Vec<_>>();
+    by_count.sort_by_key(|(label, count)| (std::cmp::Reverse(**count), label.to_string()));
+    for (label, count) in by_count {
+        println!("  {:>8} {}", count, label);
+    }
+
+    println!("rare finds:");
+    for result in &results {
+        if result.stabilized_at.is_none() {
+            println!("  soup {} didn't stabilize", result.seed);
+        }
+        for object in &result.objects {
+            let share = soups_with[&object.label()] as f64 / soups as f64;
+            if object.name.is_none() || share < RARE_SHARE {
+                println!("  soup {}: {}\n{}", result.seed, object.label(), object.to_rle());
+            }
+        }
+    }
+}
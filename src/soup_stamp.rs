@@ -0,0 +1,91 @@
+// The soup tool (6): drag out a rectangle and it's filled with a fresh random
+// soup at the `SoupStamp` density, leaving the rest of the board alone. The
+// console's `soup` command does the same for a rectangle given in cells.
+// Holding Alt clears the rectangle instead.
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::conway::{CellRect, ConwayCommand, ConwayConfig};
+use crate::rng::SimRng;
+use crate::tools::{self, BoardCursor, Tool};
+
+pub struct SoupStampPlugin;
+
+pub const DEFAULT_DENSITY: f32 = 0.35;
+
+/// How dense stamped soups are.
+#[derive(Resource)]
+pub struct SoupStamp {
+    pub density: f32,
+}
+
+impl Default for SoupStamp {
+    fn default() -> Self {
+        Self { density: DEFAULT_DENSITY }
+    }
+}
+
+/// Fills the rectangle from `corner` spanning `size` cells with soup: each
+/// cell of it on a board of `board` cells is set with probability `density`
+/// and cleared otherwise.
+pub fn stamp(corner: IVec2, size: IVec2, density: f32, board: UVec2, rng: &mut SimRng) -> Vec<ConwayCommand> {
+    // Clipped first, so a huge rectangle doesn't roll for cells that aren't there.
+    let Some(rect) = CellRect::clipped(corner, size.max(IVec2::ZERO).as_uvec2(), false, board) else {
+        return vec![];
+    };
+    let set = rect.cells().filter(|_| rng.rng.gen::<f32>() < density).collect();
+    // The rectangle's cleared in one go, then the soup set on it.
+    vec![
+        ConwayCommand::FillRect { corner: rect.corner.as_ivec2(), size: rect.size, alive: false },
+        ConwayCommand::SetCells(set),
+    ]
+}
+
+#[allow(clippy::too_many_arguments)]
+fn use_soup_tool(
+    tool: Res<Tool>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    cursor: BoardCursor,
+    config: Res<ConwayConfig>,
+    soup: Res<SoupStamp>,
+    mut rng: ResMut<SimRng>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut gizmos: Gizmos,
+    // Where the drag started.
+    mut dragging_from: Local<Option<IVec2>>,
+) {
+    if *tool != Tool::Soup {
+        *dragging_from = None;
+        return;
+    }
+    let Some(cell) = cursor.cell() else {
+        return;
+    };
+    if mouse_button_input.just_pressed(MouseButton::Left) {
+        *dragging_from = Some(cell);
+    }
+    let Some(from) = *dragging_from else {
+        return;
+    };
+    let (corner, size) = (from.min(cell), (from - cell).abs() + IVec2::ONE);
+    if mouse_button_input.just_released(MouseButton::Left) {
+        *dragging_from = None;
+        let density = if tools::erasing(&keys) { 0.0 } else { soup.density };
+        commands.send_batch(stamp(corner, size, density, config.size, &mut rng));
+        return;
+    }
+    let top_left = config.cell_to_world(corner);
+    let bottom_right = config.cell_to_world(corner + size);
+    gizmos.rect_2d((top_left + bottom_right) / 2.0, 0.0, (bottom_right - top_left).abs(), Color::WHITE);
+}
+
+impl Plugin for SoupStampPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<SoupStamp>()
+        .add_systems(Update, use_soup_tool)
+        ;
+    }
+}
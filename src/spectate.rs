@@ -0,0 +1,106 @@
+// A plugin that lets the board be watched remotely without a full client. It
+// periodically renders a downscaled grayscale PNG of the board and serves it
+// over HTTP, along with a page that keeps refreshing it:
+//
+//   GET /spectate        an HTML page showing the live thumbnail
+//   GET /thumbnail.png   the latest thumbnail
+//
+// Needs an `HttpPlugin` to receive requests from.
+
+use bevy::prelude::*;
+
+use crate::conway::BoardSnapshot;
+use crate::http::{HttpRequests, HttpResponse};
+
+pub struct SpectatePlugin;
+
+// Each thumbnail pixel covers a square of this many cells, shaded by how many are alive.
+const CELLS_PER_PIXEL: u32 = 4;
+const THUMBNAIL_SECONDS: f32 = 1.0;
+
+const SPECTATE_PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head><title>Conway spectator</title></head>
+<body style="background: black; margin: 0">
+<img id="board" src="/thumbnail.png" style="width: 100%; image-rendering: pixelated">
+<script>
+setInterval(() => { document.getElementById("board").src = "/thumbnail.png?" + Date.now(); }, 1000);
+</script>
+</body>
+</html>
+"#;
+
+#[derive(Resource)]
+struct ThumbnailTimer(Timer);
+
+// The latest encoded PNG.
+#[derive(Resource, Default)]
+struct Thumbnail(Vec<u8>);
+
+fn encode_thumbnail(board: &BoardSnapshot) -> Result<Vec<u8>, png::EncodingError> {
+    let size = board.size() / CELLS_PER_PIXEL;
+    let mut pixels = Vec::with_capacity((size.x * size.y) as usize);
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let alive = (0..CELLS_PER_PIXEL * CELLS_PER_PIXEL)
+                .filter(|i| {
+                    let cell = UVec2::new(x, y) * CELLS_PER_PIXEL + UVec2::new(i % CELLS_PER_PIXEL, i / CELLS_PER_PIXEL);
+                    board.is_alive(cell.as_ivec2())
+                })
+                .count() as u32;
+            pixels.push((alive * 255 / (CELLS_PER_PIXEL * CELLS_PER_PIXEL)) as u8);
+        }
+    }
+
+    let mut bytes = vec![];
+    let mut encoder = png::Encoder::new(&mut bytes, size.x, size.y);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&pixels)?;
+    Ok(bytes)
+}
+
+fn update_thumbnail(
+    time: Res<Time>,
+    board: Res<BoardSnapshot>,
+    mut timer: ResMut<ThumbnailTimer>,
+    mut thumbnail: ResMut<Thumbnail>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() || board.cells.is_empty() {
+        return;
+    }
+    match encode_thumbnail(&board) {
+        Ok(bytes) => thumbnail.0 = bytes,
+        Err(err) => println!("failed to encode thumbnail: {}", err),
+    }
+}
+
+fn handle_requests(mut requests: ResMut<HttpRequests>, thumbnail: Res<Thumbnail>) {
+    for request in requests.take("GET", "/spectate") {
+        request.respond(HttpResponse {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: SPECTATE_PAGE.as_bytes().to_vec(),
+        });
+    }
+    for request in requests.take("GET", "/thumbnail.png") {
+        request.respond(HttpResponse {
+            status: 200,
+            content_type: "image/png",
+            body: thumbnail.0.clone(),
+        });
+    }
+}
+
+impl Plugin for SpectatePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Thumbnail>()
+        .insert_resource(ThumbnailTimer(Timer::from_seconds(THUMBNAIL_SECONDS, TimerMode::Repeating)))
+        .add_systems(Update, (
+            update_thumbnail,
+            handle_requests.run_if(resource_exists::<HttpRequests>()),
+        ).chain())
+        ;
+    }
+}
@@ -0,0 +1,115 @@
+// Notices when the board has settled down: once it has cycled through the
+// same states (a period of 1 for a board that's stopped changing) for a while,
+// it sends `Stabilized` and logs "stabilized at generation G". With
+// `--auto-stop` it also pauses the simulation there.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::conway::{BoardSnapshot, ConwayCommand, GenerationAdvanced};
+use crate::cpu;
+use crate::event_log::LogEvent;
+
+pub struct StabilizationPlugin {
+    // Generations the board has to keep repeating itself before it counts as stable.
+    pub generations: u64,
+    // Pause the simulation once it's stable.
+    pub auto_pause: bool,
+}
+
+pub const DEFAULT_GENERATIONS: u64 = 100;
+// Sightings of the board kept to compare against, so the longest period
+// that's detected.
+const MAX_HISTORY: usize = 64;
+
+/// Sent when the board has been repeating itself for long enough.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Stabilized {
+    // The first generation of the repeating cycle.
+    pub generation: u64,
+    pub period: u64,
+    pub population: u64,
+}
+
+#[derive(Resource)]
+struct StabilizationSettings {
+    generations: u64,
+    auto_pause: bool,
+}
+
+#[derive(Resource, Default)]
+struct Detector {
+    // Recent (generation, board hash) pairs, oldest first.
+    history: VecDeque<(u64, u64)>,
+    // The period and the generation the board started repeating with it.
+    cycle: Option<(u64, u64)>,
+    // Whether `Stabilized` has been sent for the current cycle.
+    reported: bool,
+}
+
+fn detect_stabilization(
+    settings: Res<StabilizationSettings>,
+    mut detector: ResMut<Detector>,
+    mut generations: EventReader<GenerationAdvanced>,
+    board: Res<BoardSnapshot>,
+    mut stabilized: EventWriter<Stabilized>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut log: EventWriter<LogEvent>,
+) {
+    let Some(advanced) = generations.read().last() else {
+        return;
+    };
+    // The board was reseeded.
+    if detector.history.back().is_some_and(|(generation, _)| advanced.generation < *generation) {
+        *detector = Detector::default();
+    }
+
+    let hash = cpu::cells_hash(&board.cells);
+    let period = detector
+        .history
+        .iter()
+        .rev()
+        .find(|(_, previous)| *previous == hash)
+        .map(|(generation, _)| advanced.generation - generation);
+    match (period, detector.cycle) {
+        (Some(period), Some((cycle_period, _))) if period == cycle_period => {}
+        (Some(period), _) => {
+            detector.cycle = Some((period, advanced.generation - period));
+            detector.reported = false;
+        }
+        (None, _) => {
+            detector.cycle = None;
+            detector.reported = false;
+        }
+    }
+    detector.history.push_back((advanced.generation, hash));
+    if detector.history.len() > MAX_HISTORY {
+        detector.history.pop_front();
+    }
+
+    let Some((period, since)) = detector.cycle else {
+        return;
+    };
+    if detector.reported || advanced.generation - since < settings.generations {
+        return;
+    }
+    detector.reported = true;
+    stabilized.send(Stabilized { generation: since, period, population: advanced.population });
+    let cycle = if period == 1 { String::new() } else { format!(" with period {}", period) };
+    log.send(LogEvent(format!("Stabilized at generation {}{}", since, cycle)));
+    if settings.auto_pause {
+        commands.send(ConwayCommand::Pause);
+    }
+}
+
+impl Plugin for StabilizationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(StabilizationSettings { generations: self.generations, auto_pause: self.auto_pause })
+        .init_resource::<Detector>()
+        .add_event::<Stabilized>()
+        .add_systems(Update, detect_stabilization)
+        ;
+    }
+}
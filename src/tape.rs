@@ -0,0 +1,319 @@
+// Recording the board to a tape that can be scrubbed back and forth. Each
+// generation read back from the GPU is stored as the cells born and the cells
+// that died since the last one, with the whole board kept every so many
+// frames so any frame can be rebuilt from the keyframe before it without
+// storing every board. The board only reads back once a frame, so recording
+// runs it a generation a frame, putting the speed back after; past
+// `MAX_FRAMES` the oldest frames are dropped.
+//
+//   tape record [K]   start recording, with a keyframe every K frames (100)
+//   tape stop         stop recording, keeping the tape
+//   tape seek G       pause and show the board as it was at generation G
+//   tape clear        throw the tape away
+//
+// While paused, comma steps back a frame and period forward, and holding
+// either plays the tape that way. Resuming from an earlier frame records over
+// the rest of the tape.
+
+use std::cmp::Ordering;
+
+use bevy::prelude::*;
+
+use crate::conway::{BoardSnapshot, ConwayCommand, Generation, GenerationAdvanced, SimulationState};
+use crate::event_log::LogEvent;
+use crate::inspector::Inspector;
+
+pub struct TapePlugin;
+
+pub const DEFAULT_KEYFRAME_EVERY: usize = 100;
+/// How many frames a tape keeps, give or take a keyframe's worth.
+pub const MAX_FRAMES: usize = 10_000;
+const SECTION: &str = "Tape";
+// How long a step key has to be held before it plays.
+const HOLD_SECONDS: f32 = 0.3;
+
+fn row_major(cell: &IVec2) -> (i32, i32) {
+    (cell.y, cell.x)
+}
+
+/// The cells born going from `from` to `to`, and the cells that died. Both
+/// boards' cells have to be row by row, and so are the results.
+pub fn diff(from: &[IVec2], to: &[IVec2]) -> (Vec<IVec2>, Vec<IVec2>) {
+    let (mut births, mut deaths) = (vec![], vec![]);
+    let (mut from, mut to) = (from.iter().peekable(), to.iter().peekable());
+    loop {
+        match (from.peek(), to.peek()) {
+            (None, None) => break,
+            (Some(_), None) => deaths.extend(from.by_ref().copied()),
+            (None, Some(_)) => births.extend(to.by_ref().copied()),
+            (Some(old), Some(new)) => match row_major(old).cmp(&row_major(new)) {
+                Ordering::Less => deaths.push(*from.next().unwrap()),
+                Ordering::Greater => births.push(*to.next().unwrap()),
+                Ordering::Equal => {
+                    from.next();
+                    to.next();
+                }
+            },
+        }
+    }
+    (births, deaths)
+}
+
+// The board after a frame's births and deaths, still row by row.
+fn apply(state: &[IVec2], births: &[IVec2], deaths: &[IVec2]) -> Vec<IVec2> {
+    let mut next = state
+        .iter()
+        .filter(|cell| deaths.binary_search_by_key(&row_major(cell), row_major).is_err())
+        .copied()
+        .chain(births.iter().copied())
+        .collect::<Vec<_>>();
+    next.sort_by_key(row_major);
+    next
+}
+
+pub struct TapeFrame {
+    pub generation: u64,
+    // The whole board, on keyframes.
+    pub keyframe: Option<Vec<IVec2>>,
+    pub births: Vec<IVec2>,
+    pub deaths: Vec<IVec2>,
+}
+
+/// A recording of the board, one frame per generation recorded.
+pub struct Tape {
+    pub frames: Vec<TapeFrame>,
+    keyframe_every: usize,
+    max_frames: usize,
+    // The board at the last frame.
+    last: Vec<IVec2>,
+}
+
+impl Tape {
+    pub fn new(keyframe_every: usize) -> Self {
+        Self::with_max_frames(keyframe_every, MAX_FRAMES)
+    }
+
+    /// A tape dropping its oldest keyframe's worth of frames once it's
+    /// recorded more than `max_frames`.
+    pub fn with_max_frames(keyframe_every: usize, max_frames: usize) -> Self {
+        let max_frames = max_frames.max(1);
+        Self { frames: vec![], keyframe_every: keyframe_every.clamp(1, max_frames), max_frames, last: vec![] }
+    }
+
+    /// Adds the board at `generation`, with its cells row by row. A
+    /// generation at or before the last one recorded, after rewinding or a
+    /// reseed, replaces the frames from there on.
+    pub fn record(&mut self, generation: u64, cells: Vec<IVec2>) {
+        if self.frames.last().is_some_and(|frame| frame.generation >= generation) {
+            self.frames.retain(|frame| frame.generation < generation);
+            self.last = self.frames.len().checked_sub(1).map_or_else(Vec::new, |index| self.state_at(index));
+        }
+        let (births, deaths) = diff(&self.last, &cells);
+        let keyframe = self.frames.len().is_multiple_of(self.keyframe_every).then(|| cells.clone());
+        self.frames.push(TapeFrame { generation, keyframe, births, deaths });
+        self.last = cells;
+        // Whole keyframes at a time, so the first frame is still one.
+        if self.frames.len() > self.max_frames {
+            self.frames.drain(..self.keyframe_every);
+        }
+    }
+
+    /// The board at a frame, row by row.
+    pub fn state_at(&self, index: usize) -> Vec<IVec2> {
+        let start = index - index % self.keyframe_every;
+        let mut state = self.frames[start].keyframe.clone().unwrap_or_default();
+        for frame in &self.frames[start + 1..=index] {
+            state = apply(&state, &frame.births, &frame.deaths);
+        }
+        state
+    }
+
+    /// The last frame at or before `generation`.
+    pub fn index_at(&self, generation: u64) -> Option<usize> {
+        self.frames.partition_point(|frame| frame.generation <= generation).checked_sub(1)
+    }
+
+    /// Cells stored across every frame, as a rough size.
+    pub fn stored_cells(&self) -> usize {
+        self.frames
+            .iter()
+            .map(|frame| frame.keyframe.as_ref().map_or(0, Vec::len) + frame.births.len() + frame.deaths.len())
+            .sum()
+    }
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub enum TapeCommand {
+    Record { keyframe_every: usize },
+    Stop,
+    Seek(u64),
+    Clear,
+}
+
+#[derive(Resource, Default)]
+struct TapeDeck {
+    tape: Option<Tape>,
+    recording: bool,
+    // The frame on the board while scrubbing, and what it put there.
+    shown: Option<(usize, Vec<IVec2>)>,
+    // The speed from before recording, put back once it stops.
+    speed: Option<u32>,
+}
+
+// Pauses and puts a frame on the board.
+fn show_frame(
+    deck: &mut TapeDeck,
+    index: usize,
+    board: &BoardSnapshot,
+    generation: &Generation,
+    commands: &mut EventWriter<ConwayCommand>,
+) {
+    let Some(tape) = &deck.tape else {
+        return;
+    };
+    let state = tape.state_at(index);
+    // While scrubbing the readback lags behind, so diff against what was last shown.
+    let (set, cleared) = match &deck.shown {
+        Some((_, shown)) => diff(shown, &state),
+        None => diff(&board.alive_cells(), &state),
+    };
+    commands.send(ConwayCommand::Pause);
+    // Disjoint, since the board sets cells before clearing them.
+    commands.send(ConwayCommand::ClearCells(cleared));
+    commands.send(ConwayCommand::SetCells(set));
+    generation.set(tape.frames[index].generation);
+    deck.shown = Some((index, state));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_commands(
+    mut tape_commands: EventReader<TapeCommand>,
+    mut deck: ResMut<TapeDeck>,
+    board: Res<BoardSnapshot>,
+    generation: Res<Generation>,
+    simulation_state: Res<SimulationState>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut inspector: ResMut<Inspector>,
+    mut log: EventWriter<LogEvent>,
+) {
+    for command in tape_commands.read() {
+        match *command {
+            TapeCommand::Record { keyframe_every } => {
+                deck.tape = Some(Tape::new(keyframe_every));
+                deck.recording = true;
+                deck.shown = None;
+                // Every generation gets a readback of its own.
+                deck.speed.get_or_insert(simulation_state.speed);
+                commands.send(ConwayCommand::SetSpeed(1));
+            }
+            TapeCommand::Stop => {
+                deck.recording = false;
+                if let Some(speed) = deck.speed.take() {
+                    commands.send(ConwayCommand::SetSpeed(speed));
+                }
+            }
+            TapeCommand::Seek(target) => {
+                let index = deck.tape.as_ref().and_then(|tape| tape.index_at(target));
+                match index {
+                    Some(index) => show_frame(&mut deck, index, &board, &generation, &mut commands),
+                    None => log.send(LogEvent(format!("Nothing on the tape at generation {}", target))),
+                }
+            }
+            TapeCommand::Clear => {
+                if let Some(speed) = deck.speed {
+                    commands.send(ConwayCommand::SetSpeed(speed));
+                }
+                *deck = TapeDeck::default();
+                inspector.remove(SECTION);
+            }
+        }
+    }
+}
+
+fn record(
+    mut generations: EventReader<GenerationAdvanced>,
+    simulation_state: Res<SimulationState>,
+    board: Res<BoardSnapshot>,
+    mut deck: ResMut<TapeDeck>,
+) {
+    let Some(advanced) = generations.read().last() else {
+        return;
+    };
+    // Scrubbing moves the generation too, but only running adds frames.
+    if !deck.recording || simulation_state.paused {
+        return;
+    }
+    deck.shown = None;
+    if let Some(tape) = &mut deck.tape {
+        tape.record(advanced.generation, board.alive_cells());
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn scrub(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    simulation_state: Res<SimulationState>,
+    board: Res<BoardSnapshot>,
+    generation: Res<Generation>,
+    mut deck: ResMut<TapeDeck>,
+    mut commands: EventWriter<ConwayCommand>,
+    // How long the step key has been held.
+    mut held: Local<f32>,
+) {
+    let frames = deck.tape.as_ref().map_or(0, |tape| tape.frames.len());
+    let step = match (keys.pressed(KeyCode::Comma), keys.pressed(KeyCode::Period)) {
+        (true, false) => -1,
+        (false, true) => 1,
+        _ => 0,
+    };
+    if !simulation_state.paused || frames == 0 || step == 0 {
+        *held = 0.0;
+        return;
+    }
+    let just_pressed = keys.any_just_pressed([KeyCode::Comma, KeyCode::Period]);
+    *held += time.delta_seconds();
+    if !just_pressed && *held < HOLD_SECONDS {
+        return;
+    }
+    let current = match &deck.shown {
+        Some((index, _)) => *index as i64,
+        None => frames as i64,
+    };
+    let index = (current + step).clamp(0, frames as i64 - 1) as usize;
+    if deck.shown.as_ref().map(|(shown, _)| *shown) != Some(index) {
+        show_frame(&mut deck, index, &board, &generation, &mut commands);
+    }
+}
+
+fn show_tape(deck: Res<TapeDeck>, mut inspector: ResMut<Inspector>) {
+    if !deck.is_changed() {
+        return;
+    }
+    let Some(tape) = &deck.tape else {
+        return;
+    };
+    let (first, last) = match (tape.frames.first(), tape.frames.last()) {
+        (Some(first), Some(last)) => (first.generation, last.generation),
+        _ => (0, 0),
+    };
+    let mut lines = vec![
+        if deck.recording { "recording" } else { "stopped" }.to_string(),
+        format!("{} frames, generations {} to {}", tape.frames.len(), first, last),
+        format!("{} cells stored", tape.stored_cells()),
+    ];
+    if let Some((index, _)) = &deck.shown {
+        lines.push(format!("showing generation {}", tape.frames[*index].generation));
+    }
+    inspector.set(SECTION, lines.join("\n"));
+}
+
+impl Plugin for TapePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<TapeCommand>()
+        .init_resource::<TapeDeck>()
+        .add_systems(Update, (handle_commands, record, scrub, show_tape).chain())
+        ;
+    }
+}
@@ -0,0 +1,171 @@
+// A 3D view of the board as terrain (T): a plane with a vertex every couple
+// of cells, each raised by the cell under it, so live cells stand up as hills
+// and, with lifespans on, older cells stand taller. The heights are read
+// straight from the board texture in the vertex shader (see `terrain.wgsl`),
+// so the terrain moves with the simulation without anything being copied
+// back. Drag with the middle mouse button to orbit and scroll to zoom.
+//
+// The terrain has a camera of its own drawn over the 2D one, which keeps
+// going underneath, along with the tools.
+
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+
+use crate::conway::{ConwayConfig, ConwayWorld};
+use crate::event_log::LogEvent;
+
+pub struct TerrainPlugin;
+
+// Vertices across the plane, a little over one every two cells.
+const SUBDIVISIONS: u32 = 640;
+// How high a live cell stands, in cells.
+const HEIGHT_SCALE: f32 = 24.0;
+// Radians turned per pixel dragged, and the share of the distance a notch
+// of the wheel zooms by.
+const ORBIT_SPEED: f32 = 0.005;
+const ZOOM_SPEED: f32 = 0.1;
+const MIN_PITCH: f32 = 0.1;
+const MAX_PITCH: f32 = 1.5;
+const MIN_DISTANCE: f32 = 100.0;
+const MAX_DISTANCE: f32 = 4000.0;
+
+#[derive(AsBindGroup, Asset, TypePath, Debug, Clone)]
+struct TerrainMaterial {
+    #[uniform(0)]
+    height_scale: f32,
+    // Read with `textureLoad`, so no sampler.
+    #[texture(1)]
+    board: Handle<Image>,
+}
+
+impl Material for TerrainMaterial {
+    fn vertex_shader() -> ShaderRef {
+        "shaders/terrain.wgsl".into()
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        "shaders/terrain.wgsl".into()
+    }
+}
+
+// Where the camera orbits, around the middle of the board.
+#[derive(Resource)]
+struct Orbit {
+    yaw: f32,
+    pitch: f32,
+    distance: f32,
+}
+
+impl Default for Orbit {
+    fn default() -> Self {
+        Self { yaw: 0.0, pitch: 0.8, distance: 1200.0 }
+    }
+}
+
+impl Orbit {
+    fn transform(&self) -> Transform {
+        let (yaw_sin, yaw_cos) = self.yaw.sin_cos();
+        let (pitch_sin, pitch_cos) = self.pitch.sin_cos();
+        let eye = Vec3::new(pitch_cos * yaw_sin, pitch_sin, pitch_cos * yaw_cos) * self.distance;
+        Transform::from_translation(eye).looking_at(Vec3::ZERO, Vec3::Y)
+    }
+}
+
+// The terrain and its camera, spawned the first time it's shown.
+#[derive(Resource)]
+struct Terrain {
+    mesh: Entity,
+    camera: Entity,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn toggle_terrain(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    board: Option<Res<ConwayWorld>>,
+    config: Res<ConwayConfig>,
+    terrain: Option<Res<Terrain>>,
+    orbit: Res<Orbit>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<TerrainMaterial>>,
+    mut cameras: Query<&mut Camera>,
+    mut visibilities: Query<&mut Visibility>,
+    mut log: EventWriter<LogEvent>,
+) {
+    if !keys.just_pressed(KeyCode::T) {
+        return;
+    }
+    let Some(terrain) = terrain else {
+        let Some(board) = board else {
+            return;
+        };
+        let extents = config.extents();
+        let mesh = commands
+            .spawn(MaterialMeshBundle {
+                mesh: meshes.add(shape::Plane { size: extents.x, subdivisions: SUBDIVISIONS }.into()),
+                material: materials.add(TerrainMaterial {
+                    height_scale: HEIGHT_SCALE * config.display_scale,
+                    board: board.0.clone(),
+                }),
+                // The plane is square, so squash it to the board's shape.
+                transform: Transform::from_scale(Vec3::new(1.0, 1.0, extents.y / extents.x)),
+                ..default()
+            })
+            .id();
+        let camera = commands
+            .spawn(Camera3dBundle {
+                // Over the 2D camera.
+                camera: Camera { order: 1, ..default() },
+                transform: orbit.transform(),
+                ..default()
+            })
+            .id();
+        commands.insert_resource(Terrain { mesh, camera });
+        log.send(LogEvent("Terrain shown".to_string()));
+        return;
+    };
+    let Ok(mut camera) = cameras.get_mut(terrain.camera) else {
+        return;
+    };
+    camera.is_active = !camera.is_active;
+    if let Ok(mut visibility) = visibilities.get_mut(terrain.mesh) {
+        *visibility = if camera.is_active { Visibility::Visible } else { Visibility::Hidden };
+    }
+    log.send(LogEvent(format!("Terrain {}", if camera.is_active { "shown" } else { "hidden" })));
+}
+
+fn orbit_camera(
+    mouse_button_input: Res<Input<MouseButton>>,
+    mut motions: EventReader<MouseMotion>,
+    mut wheels: EventReader<MouseWheel>,
+    terrain: Option<Res<Terrain>>,
+    mut orbit: ResMut<Orbit>,
+    mut cameras: Query<(&Camera, &mut Transform)>,
+) {
+    let dragged = motions.read().map(|motion| motion.delta).sum::<Vec2>();
+    let scrolled = wheels.read().map(|wheel| wheel.y).sum::<f32>();
+    let Some((camera, mut transform)) = terrain.and_then(|terrain| cameras.get_mut(terrain.camera).ok()) else {
+        return;
+    };
+    if !camera.is_active {
+        return;
+    }
+    if mouse_button_input.pressed(MouseButton::Middle) {
+        orbit.yaw -= dragged.x * ORBIT_SPEED;
+        orbit.pitch = (orbit.pitch + dragged.y * ORBIT_SPEED).clamp(MIN_PITCH, MAX_PITCH);
+    }
+    orbit.distance = (orbit.distance * (1.0 - scrolled * ZOOM_SPEED)).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    *transform = orbit.transform();
+}
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Orbit>()
+        .add_plugins(MaterialPlugin::<TerrainMaterial>::default())
+        .add_systems(Update, (toggle_terrain, orbit_camera).chain())
+        ;
+    }
+}
@@ -0,0 +1,210 @@
+// The text tool (7): click on the board and type, and the text is previewed
+// there in a built-in 5x7 bitmap font. Enter stamps it as live cells, to
+// dissolve under the rules, and Escape drops it. Up and Down make the letters
+// bigger or smaller. Holding Alt on Enter clears the text's cells instead.
+//
+// Typing swallows the keyboard, like the console does, so letters don't
+// trigger their shortcuts.
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+
+use crate::conway::{ConwayCommand, ConwayConfig};
+use crate::event_log::LogEvent;
+use crate::tools::{self, BoardCursor, Tool};
+
+pub struct TextStampPlugin;
+
+pub const GLYPH_WIDTH: i32 = 5;
+pub const GLYPH_HEIGHT: i32 = 7;
+// A column between letters.
+const ADVANCE: i32 = GLYPH_WIDTH + 1;
+const DEFAULT_SCALE: u32 = 2;
+pub const MAX_SCALE: u32 = 16;
+const MAX_LENGTH: usize = 80;
+const PREVIEW_COLOR: Color = Color::rgb(1.0, 0.8, 0.1);
+
+// Each row is five bits, the leftmost column in the highest.
+const FONT: &[(char, [u8; 7])] = &[
+    ('A', [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+    ('B', [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110]),
+    ('C', [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110]),
+    ('D', [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100]),
+    ('E', [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111]),
+    ('F', [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000]),
+    ('G', [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111]),
+    ('H', [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001]),
+    ('I', [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('J', [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100]),
+    ('K', [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001]),
+    ('L', [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111]),
+    ('M', [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001]),
+    ('N', [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001]),
+    ('O', [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('P', [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000]),
+    ('Q', [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101]),
+    ('R', [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001]),
+    ('S', [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110]),
+    ('T', [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100]),
+    ('U', [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110]),
+    ('V', [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100]),
+    ('W', [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010]),
+    ('X', [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001]),
+    ('Y', [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100]),
+    ('Z', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111]),
+    ('0', [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110]),
+    ('1', [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110]),
+    ('2', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111]),
+    ('3', [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110]),
+    ('4', [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010]),
+    ('5', [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110]),
+    ('6', [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110]),
+    ('7', [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000]),
+    ('8', [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110]),
+    ('9', [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100]),
+    (' ', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000]),
+    ('.', [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100]),
+    (',', [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000]),
+    ('!', [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100]),
+    ('?', [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100]),
+    ('\'', [0b01100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000]),
+    ('-', [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000]),
+    (':', [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000]),
+    ('/', [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000]),
+    ('(', [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010]),
+    (')', [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000]),
+    ('+', [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000]),
+    ('=', [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000]),
+];
+
+/// The rows of a character's glyph. Letters are all capitals, and anything
+/// the font doesn't have comes out as a question mark.
+pub fn glyph(character: char) -> [u8; 7] {
+    let character = character.to_ascii_uppercase();
+    let lookup = |character| FONT.iter().find(|(c, _)| *c == character).map(|(_, rows)| *rows);
+    lookup(character).or_else(|| lookup('?')).unwrap()
+}
+
+/// The live cells for `text` written on one line from `origin`, its top-left
+/// corner, with each pixel of the font `scale` cells across.
+pub fn rasterize(text: &str, origin: IVec2, scale: u32) -> Vec<IVec2> {
+    let scale = scale.clamp(1, MAX_SCALE) as i32;
+    let mut cells = vec![];
+    for (index, character) in text.chars().enumerate() {
+        let left = origin.x + index as i32 * ADVANCE * scale;
+        for (row, bits) in glyph(character).into_iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - column)) == 0 {
+                    continue;
+                }
+                let corner = IVec2::new(left + column * scale, origin.y + row as i32 * scale);
+                cells.extend((0..scale).flat_map(|y| (0..scale).map(move |x| corner + IVec2::new(x, y))));
+            }
+        }
+    }
+    cells
+}
+
+/// How big stamped text is.
+#[derive(Resource)]
+pub struct TextStamp {
+    pub scale: u32,
+}
+
+impl Default for TextStamp {
+    fn default() -> Self {
+        Self { scale: DEFAULT_SCALE }
+    }
+}
+
+// The text being typed, and where it goes.
+#[derive(Resource, Default)]
+struct TextEntry(Option<(IVec2, String)>);
+
+#[allow(clippy::too_many_arguments)]
+fn type_text(
+    tool: Res<Tool>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut characters: EventReader<ReceivedCharacter>,
+    cursor: BoardCursor,
+    mut stamp: ResMut<TextStamp>,
+    mut entry: ResMut<TextEntry>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut log: EventWriter<LogEvent>,
+) {
+    if *tool != Tool::Text {
+        entry.0 = None;
+        characters.clear();
+        return;
+    }
+    if mouse_button_input.just_pressed(MouseButton::Left) {
+        if let Some(cell) = cursor.cell() {
+            if entry.0.is_none() {
+                log.send(LogEvent("Type, then Enter to stamp or Escape to drop".to_string()));
+            }
+            // Whatever's been typed moves with the click.
+            let text = entry.0.take().map(|(_, text)| text).unwrap_or_default();
+            entry.0 = Some((cell, text));
+        }
+    }
+    let Some((origin, text)) = &mut entry.0 else {
+        characters.clear();
+        return;
+    };
+    for character in characters.read() {
+        if !character.char.is_control() && text.chars().count() < MAX_LENGTH {
+            text.push(character.char);
+        }
+    }
+    if keys.just_pressed(KeyCode::Back) {
+        text.pop();
+    }
+    if keys.just_pressed(KeyCode::Up) {
+        stamp.scale = (stamp.scale + 1).min(MAX_SCALE);
+    }
+    if keys.just_pressed(KeyCode::Down) {
+        stamp.scale = stamp.scale.saturating_sub(1).max(1);
+    }
+    if keys.just_pressed(KeyCode::Return) {
+        let cells = rasterize(text, *origin, stamp.scale);
+        commands.send(if tools::erasing(&keys) {
+            ConwayCommand::ClearCells(cells)
+        } else {
+            ConwayCommand::SetCells(cells)
+        });
+        entry.0 = None;
+    } else if keys.just_pressed(KeyCode::Escape) {
+        entry.0 = None;
+    }
+    keys.reset_all();
+}
+
+fn draw_preview(entry: Res<TextEntry>, stamp: Res<TextStamp>, config: Res<ConwayConfig>, mut gizmos: Gizmos) {
+    let Some((origin, text)) = &entry.0 else {
+        return;
+    };
+    let scale = stamp.scale as i32;
+    // One rect per pixel of the font, not per cell.
+    for pixel in rasterize(text, IVec2::ZERO, 1) {
+        let corner = *origin + pixel * scale;
+        let top_left = config.cell_to_world(corner);
+        let bottom_right = config.cell_to_world(corner + IVec2::splat(scale));
+        gizmos.rect_2d((top_left + bottom_right) / 2.0, 0.0, (bottom_right - top_left).abs(), PREVIEW_COLOR);
+    }
+    // The caret, after the last letter.
+    let caret = *origin + IVec2::new(text.chars().count() as i32 * ADVANCE * scale, 0);
+    let bottom = caret + IVec2::new(0, GLYPH_HEIGHT * scale);
+    gizmos.line_2d(config.cell_to_world(caret), config.cell_to_world(bottom), Color::WHITE);
+}
+
+impl Plugin for TextStampPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<TextStamp>()
+        .init_resource::<TextEntry>()
+        .add_systems(PreUpdate, type_text.after(InputSystem))
+        .add_systems(Update, draw_preview)
+        ;
+    }
+}
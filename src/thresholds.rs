@@ -0,0 +1,109 @@
+// Population alerts for long unattended runs: when the population crosses
+// one of the `PopulationThresholds`, this logs it, plays a note and sends
+// `ThresholdCrossed` for anything else that cares, like a game's win or lose
+// condition. Each threshold fires once per crossing, and again only after the
+// population has gone back across it.
+
+use std::time::Duration;
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+
+use crate::conway::GenerationAdvanced;
+use crate::event_log::LogEvent;
+use crate::mixer::AudioMixer;
+
+pub struct ThresholdsPlugin;
+
+// A high note for going above a threshold and a low one otherwise.
+const HIGH_NOTE: f32 = 880.0;
+const LOW_NOTE: f32 = 330.0;
+const NOTE_SECONDS: f32 = 0.15;
+const NOTE_VOLUME: f32 = 0.6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Threshold {
+    Below(u64),
+    Above(u64),
+    // Everything died.
+    Extinct,
+}
+
+impl Threshold {
+    pub fn is_met(self, population: u64) -> bool {
+        match self {
+            Threshold::Below(limit) => population < limit,
+            Threshold::Above(limit) => population > limit,
+            Threshold::Extinct => population == 0,
+        }
+    }
+}
+
+impl std::fmt::Display for Threshold {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Threshold::Below(limit) => write!(f, "below {}", limit),
+            Threshold::Above(limit) => write!(f, "above {}", limit),
+            Threshold::Extinct => write!(f, "zero"),
+        }
+    }
+}
+
+/// The thresholds to watch the population for.
+#[derive(Resource, Default)]
+pub struct PopulationThresholds(pub Vec<Threshold>);
+
+/// Sent when the population crosses one of the `PopulationThresholds`.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ThresholdCrossed {
+    pub threshold: Threshold,
+    pub generation: u64,
+    pub population: u64,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn check_thresholds(
+    mut commands: Commands,
+    mut generations: EventReader<GenerationAdvanced>,
+    thresholds: Res<PopulationThresholds>,
+    mut pitches: ResMut<Assets<Pitch>>,
+    mut mixer: ResMut<AudioMixer>,
+    mut crossed: EventWriter<ThresholdCrossed>,
+    mut log: EventWriter<LogEvent>,
+    // The thresholds that were met last generation.
+    mut met: Local<Vec<Threshold>>,
+) {
+    let Some(advanced) = generations.read().last() else {
+        return;
+    };
+    let now = thresholds.0.iter().copied().filter(|threshold| threshold.is_met(advanced.population));
+    let now = now.collect::<Vec<_>>();
+    for threshold in now.iter().filter(|threshold| !met.contains(threshold)) {
+        crossed.send(ThresholdCrossed {
+            threshold: *threshold,
+            generation: advanced.generation,
+            population: advanced.population,
+        });
+        log.send(LogEvent(format!(
+            "Population {} at generation {}: {} alive",
+            threshold, advanced.generation, advanced.population,
+        )));
+        let frequency = if let Threshold::Above(_) = threshold { HIGH_NOTE } else { LOW_NOTE };
+        mixer.duck();
+        commands.spawn(PitchBundle {
+            source: pitches.add(Pitch::new(frequency, Duration::from_secs_f32(NOTE_SECONDS))),
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new_relative(NOTE_VOLUME)),
+        });
+    }
+    *met = now;
+}
+
+impl Plugin for ThresholdsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<PopulationThresholds>()
+        .add_event::<ThresholdCrossed>()
+        .add_systems(Update, check_thresholds)
+        ;
+    }
+}
@@ -0,0 +1,116 @@
+// Time-lapse capture: saves the board as a numbered PNG snapshot (see
+// `snapshot.rs`) every Nth generation rather than every frame, so hours of
+// evolution make a short video:
+//
+//   ffmpeg -framerate 30 -i frames/%06d.png -vf scale=iw*2:ih*2:flags=neighbor run.mp4
+//
+// Frames come from the boards the simulation reads back, one per frame, so at
+// speeds above 1 each is the first board on or past its generation.
+
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+use crate::conway::{ActiveRuleTable, BoardSnapshot, GenerationAdvanced, RuleString, SeedSettings};
+use crate::event_log::LogEvent;
+use crate::snapshot::{self, SnapshotMetadata};
+
+pub struct TimeLapsePlugin;
+
+#[derive(Event, Clone, Debug)]
+pub enum TimeLapseCommand {
+    // Save a frame into `directory` every `every` generations.
+    Start { directory: String, every: u64 },
+    Stop,
+}
+
+struct Capture {
+    directory: PathBuf,
+    every: u64,
+    // The generation the next frame is due at.
+    next: u64,
+    frames: u64,
+}
+
+#[derive(Resource, Default)]
+struct TimeLapse(Option<Capture>);
+
+fn handle_commands(
+    mut commands: EventReader<TimeLapseCommand>,
+    mut time_lapse: ResMut<TimeLapse>,
+    mut log: EventWriter<LogEvent>,
+) {
+    for command in commands.read() {
+        match command {
+            TimeLapseCommand::Start { directory, every } => {
+                if let Err(err) = fs::create_dir_all(directory) {
+                    log.send(LogEvent(format!("Failed to create {}: {}", directory, err)));
+                    continue;
+                }
+                let every = (*every).max(1);
+                time_lapse.0 = Some(Capture { directory: directory.into(), every, next: 0, frames: 0 });
+                log.send(LogEvent(format!("Capturing a frame every {} generations into {}", every, directory)));
+            }
+            TimeLapseCommand::Stop => {
+                if let Some(capture) = time_lapse.0.take() {
+                    log.send(LogEvent(format!("Captured {} frames into {}", capture.frames, capture.directory.display())));
+                }
+            }
+        }
+    }
+}
+
+fn capture_frames(
+    mut generations: EventReader<GenerationAdvanced>,
+    mut time_lapse: ResMut<TimeLapse>,
+    board: Res<BoardSnapshot>,
+    rule_table: Res<ActiveRuleTable>,
+    rule_string: Res<RuleString>,
+    seed_settings: Res<SeedSettings>,
+    mut log: EventWriter<LogEvent>,
+) {
+    let Some(advanced) = generations.read().last() else {
+        return;
+    };
+    let Some(capture) = &mut time_lapse.0 else {
+        return;
+    };
+    // The board was reseeded, so start counting again.
+    if advanced.generation + capture.every < capture.next {
+        capture.next = 0;
+    }
+    if advanced.generation < capture.next {
+        return;
+    }
+    let metadata = SnapshotMetadata {
+        rule: Some(rule_table.name(&rule_string).into_owned()),
+        seed: Some(seed_settings.seed),
+        density: Some(seed_settings.density),
+        generation: Some(advanced.generation),
+        rng_seed: None,
+        rng_position: None,
+        hash: None,
+    };
+    let path = capture.directory.join(format!("{:06}.png", capture.frames));
+    let saved = snapshot::encode(board.size(), &board.alive_cells(), &metadata)
+        .and_then(|bytes| fs::write(&path, bytes).map_err(|err| err.to_string()));
+    if let Err(err) = saved {
+        // Don't keep failing every frame.
+        log.send(LogEvent(format!("Stopped the time-lapse, failed to save {}: {}", path.display(), err)));
+        time_lapse.0 = None;
+        return;
+    }
+    capture.frames += 1;
+    capture.next = (advanced.generation / capture.every + 1) * capture.every;
+}
+
+impl Plugin for TimeLapsePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<TimeLapseCommand>()
+        .init_resource::<TimeLapse>()
+        .add_systems(Update, (handle_commands, capture_frames).chain())
+        ;
+    }
+}
@@ -0,0 +1,53 @@
+// Keeps the window title showing the board's state, for when the HUD is
+// hidden or the window is only a taskbar thumbnail.
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::conway::{ActiveRuleTable, Generation, LivingCells, RuleString, SimulationState};
+
+pub struct TitlePlugin;
+
+const TITLE: &str = "Conway";
+// Retitling every frame would flood some window managers.
+const UPDATE_SECONDS: f32 = 0.25;
+
+#[derive(Resource)]
+struct TitleTimer(Timer);
+
+#[allow(clippy::too_many_arguments)]
+fn update_title(
+    time: Res<Time>,
+    mut timer: ResMut<TitleTimer>,
+    generation: Res<Generation>,
+    living_cells: Res<LivingCells>,
+    rule_table: Res<ActiveRuleTable>,
+    rule_string: Res<RuleString>,
+    simulation_state: Res<SimulationState>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    let Ok(mut window) = window.get_single_mut() else {
+        return;
+    };
+    let speed = if simulation_state.paused { "paused".to_string() } else { format!("{}x", simulation_state.speed) };
+    let title = format!(
+        "{} - generation {} - {} alive - {} - {}",
+        TITLE, generation.get(), living_cells.0, rule_table.name(&rule_string), speed,
+    );
+    // Only touch the window when something changed.
+    if window.title != title {
+        window.title = title;
+    }
+}
+
+impl Plugin for TitlePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(TitleTimer(Timer::from_seconds(UPDATE_SECONDS, TimerMode::Repeating)))
+        .add_systems(Update, update_title)
+        ;
+    }
+}
@@ -0,0 +1,33 @@
+// Files kept as TOML: view bookmarks, annotations, hint progress and
+// workspace settings all read and write themselves the same way, through
+// `TomlFile`.
+
+use std::fs;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Something saved as a TOML file of its serialized self.
+pub trait TomlFile: Serialize + DeserializeOwned {
+    fn to_toml(&self) -> Result<String, String> {
+        toml::to_string(self).map_err(|err| err.to_string())
+    }
+
+    fn from_toml(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|err| err.to_string())
+    }
+
+    /// What's saved at `path`, or the default if nothing's been saved there
+    /// yet or it can't be read.
+    fn load(path: impl AsRef<Path>) -> Self
+    where
+        Self: Default,
+    {
+        fs::read_to_string(path).ok().and_then(|text| Self::from_toml(&text).ok()).unwrap_or_default()
+    }
+
+    fn save(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        fs::write(path, self.to_toml()?).map_err(|err| err.to_string())
+    }
+}
@@ -0,0 +1,175 @@
+// The tool the left mouse button uses, picked with the number keys:
+//
+//   1  cells   paint cells with the `Brush`
+//   2  walls   paint walls, or erase them while holding Shift
+//   3  freeze  paint frozen regions, which don't change, or thaw them with Shift
+//   4  portal  drag out one end of a portal, then click where the other goes;
+//              Shift-click removes every portal
+//   5  inspect hover over a cell to see its neighbours and what happens to
+//              it next generation
+//   6  soup    drag out a rectangle to fill with a random soup
+//   7  text    click and type, then Enter writes the text in live cells
+//   8  flow    drag to lay down a current that carries cells along
+//   9  ruler   drag to measure distances and boxes, in cells; Insert fills
+//              the box and Delete clears it
+//   0  ant     put down an ant, or take away the ants on a cell with Alt
+//
+// Holding Alt turns any tool into an eraser for what it makes: cells, walls,
+// frozen regions, soups, text, currents, portals or ants.
+//
+// Each tool shows its own cursor (see `cursors.rs`).
+//
+// Along with helpers for turning the cursor into board cells.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::conway::ConwayConfig;
+use crate::event_log::LogEvent;
+use crate::views::BoardCamera;
+
+pub struct ToolsPlugin;
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Tool {
+    #[default]
+    Cells,
+    Walls,
+    Freeze,
+    Portal,
+    Inspect,
+    Soup,
+    Text,
+    Flow,
+    Ruler,
+    Ant,
+}
+
+impl Tool {
+    const ALL: &'static [(Tool, KeyCode)] = &[
+        (Tool::Cells, KeyCode::Key1),
+        (Tool::Walls, KeyCode::Key2),
+        (Tool::Freeze, KeyCode::Key3),
+        (Tool::Portal, KeyCode::Key4),
+        (Tool::Inspect, KeyCode::Key5),
+        (Tool::Soup, KeyCode::Key6),
+        (Tool::Text, KeyCode::Key7),
+        (Tool::Flow, KeyCode::Key8),
+        (Tool::Ruler, KeyCode::Key9),
+        (Tool::Ant, KeyCode::Key0),
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::Cells => "cells",
+            Tool::Walls => "walls",
+            Tool::Freeze => "freeze",
+            Tool::Portal => "portal",
+            Tool::Inspect => "inspect",
+            Tool::Soup => "soup",
+            Tool::Text => "text",
+            Tool::Flow => "flow",
+            Tool::Ruler => "ruler",
+            Tool::Ant => "ant",
+        }
+    }
+
+    /// The tool `name` gives the name of, if any.
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.iter().map(|(tool, _)| *tool).find(|tool| tool.name() == name)
+    }
+}
+
+/// What the cells tool paints with: a disc `radius` cells out from the
+/// cursor, where each cell is only set with probability `density` for soft,
+/// airbrushed strokes. The default sets just the cell under the cursor.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct Brush {
+    pub radius: u32,
+    pub density: f32,
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Self { radius: 0, density: 1.0 }
+    }
+}
+
+pub const MAX_BRUSH_RADIUS: u32 = 32;
+
+impl Brush {
+    /// The cells the brush covers around `center`.
+    pub fn cells(&self, center: IVec2) -> Vec<IVec2> {
+        let radius = self.radius.min(MAX_BRUSH_RADIUS) as i32;
+        (-radius..=radius)
+            .flat_map(|y| (-radius..=radius).map(move |x| IVec2::new(x, y)))
+            .filter(|offset| offset.length_squared() <= radius * radius)
+            .map(|offset| center + offset)
+            .collect()
+    }
+}
+
+/// Whether the erase modifier is held.
+pub fn erasing(keys: &Input<KeyCode>) -> bool {
+    keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight])
+}
+
+/// The board cell under the cursor, wherever the board camera is panned and
+/// zoomed to.
+#[derive(SystemParam)]
+pub struct BoardCursor<'w, 's> {
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    cameras: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<BoardCamera>>,
+    config: Res<'w, ConwayConfig>,
+}
+
+impl BoardCursor<'_, '_> {
+    /// The cell, if the cursor's in the window.
+    pub fn cell(&self) -> Option<IVec2> {
+        self.world().map(|position| self.config.world_to_cell(position))
+    }
+
+    /// Where the cursor is in the world, if it's in the window.
+    pub fn world(&self) -> Option<Vec2> {
+        let cursor = self.windows.get_single().ok()?.cursor_position()?;
+        let (camera, transform) = self.cameras.get_single().ok()?;
+        camera.viewport_to_world_2d(transform, cursor)
+    }
+}
+
+/// The cells on a line from `from` to `to`, both included, so fast strokes
+/// don't leave gaps.
+pub fn line_cells(from: IVec2, to: IVec2) -> Vec<IVec2> {
+    let delta = to - from;
+    let steps = delta.x.abs().max(delta.y.abs());
+    if steps == 0 {
+        return vec![from];
+    }
+    (0..=steps)
+        .map(|step| from + (delta.as_vec2() * step as f32 / steps as f32).round().as_ivec2())
+        .collect()
+}
+
+fn select_tool(keys: Res<Input<KeyCode>>, mut tool: ResMut<Tool>, mut log: EventWriter<LogEvent>) {
+    // Shift with a number is for view bookmarks.
+    if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        return;
+    }
+    for (candidate, key) in Tool::ALL {
+        if keys.just_pressed(*key) && *tool != *candidate {
+            *tool = *candidate;
+            log.send(LogEvent(format!("Tool: {}", candidate.name())));
+        }
+    }
+}
+
+impl Plugin for ToolsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Tool>()
+        .init_resource::<Brush>()
+        .add_systems(Update, select_tool)
+        ;
+    }
+}
@@ -0,0 +1,116 @@
+// A plugin that lets Twitch chat place patterns with commands like
+// `!glider 40 20`. It joins the channel's IRC chat anonymously (read only),
+// rate limits each viewer, and credits placements in the event log.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::thread;
+
+use async_channel::{Receiver, Sender};
+use bevy::prelude::*;
+
+use crate::conway::{ConwayCommand, ConwayConfig};
+use crate::event_log::LogEvent;
+use crate::patterns;
+
+const TWITCH_IRC_ADDRESS: &str = "irc.chat.twitch.tv:6667";
+// Anonymous logins use a "justinfan" nick and no password.
+const ANONYMOUS_NICK: &str = "justinfan31337";
+// Seconds each viewer has to wait between placements.
+const PLACEMENT_COOLDOWN_SECONDS: f32 = 10.0;
+
+pub struct TwitchPlugin {
+    pub channel: String,
+}
+
+struct ChatMessage {
+    user: String,
+    text: String,
+}
+
+#[derive(Resource)]
+struct TwitchChat(Receiver<ChatMessage>);
+
+// Parses `:user!user@user.tmi.twitch.tv PRIVMSG #channel :text`.
+fn parse_privmsg(line: &str) -> Option<ChatMessage> {
+    let rest = line.strip_prefix(':')?;
+    let (prefix, rest) = rest.split_once(' ')?;
+    let (command, rest) = rest.split_once(' ')?;
+    if command != "PRIVMSG" {
+        return None;
+    }
+    let (_, text) = rest.split_once(" :")?;
+    Some(ChatMessage {
+        user: prefix.split('!').next()?.to_string(),
+        text: text.trim_end().to_string(),
+    })
+}
+
+fn read_chat(channel: &str, messages: Sender<ChatMessage>) -> std::io::Result<()> {
+    let mut stream = TcpStream::connect(TWITCH_IRC_ADDRESS)?;
+    write!(stream, "NICK {}\r\nJOIN #{}\r\n", ANONYMOUS_NICK, channel.to_ascii_lowercase())?;
+    let reader = BufReader::new(stream.try_clone()?);
+    for line in reader.lines() {
+        let line = line?;
+        if let Some(server) = line.strip_prefix("PING ") {
+            write!(stream, "PONG {}\r\n", server)?;
+        } else if let Some(message) = parse_privmsg(&line) {
+            if messages.send_blocking(message).is_err() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn place_patterns(
+    time: Res<Time>,
+    chat: Res<TwitchChat>,
+    config: Res<ConwayConfig>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut log: EventWriter<LogEvent>,
+    // When each viewer last placed a pattern.
+    mut last_placed: Local<HashMap<String, f32>>,
+) {
+    let size = config.size.as_ivec2();
+    while let Ok(message) = chat.0.try_recv() {
+        let mut words = message.text.split_whitespace();
+        let Some(name) = words.next().and_then(|word| word.strip_prefix('!')) else {
+            continue;
+        };
+        let Some(pattern) = patterns::by_name(name) else {
+            continue;
+        };
+        let (Some(Ok(x)), Some(Ok(y))) = (words.next().map(str::parse::<i32>), words.next().map(str::parse::<i32>)) else {
+            continue;
+        };
+        if x < 0 || y < 0 || x >= size.x || y >= size.y {
+            continue;
+        }
+        let now = time.elapsed_seconds();
+        if last_placed.get(&message.user).is_some_and(|last| now - last < PLACEMENT_COOLDOWN_SECONDS) {
+            continue;
+        }
+        last_placed.insert(message.user.clone(), now);
+
+        commands.send(ConwayCommand::SetCells(patterns::placed(pattern, IVec2::new(x, y))));
+        log.send(LogEvent(format!("{} placed a {} at ({}, {})", message.user, name.to_ascii_lowercase(), x, y)));
+    }
+}
+
+impl Plugin for TwitchPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = async_channel::unbounded();
+        let channel = self.channel.clone();
+        thread::spawn(move || {
+            if let Err(err) = read_chat(&channel, sender) {
+                println!("twitch chat disconnected: {}", err);
+            }
+        });
+        app
+        .insert_resource(TwitchChat(receiver))
+        .add_systems(Update, place_patterns)
+        ;
+    }
+}
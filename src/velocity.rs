@@ -0,0 +1,199 @@
+// Measures how fast a pattern moves. Drag out a box around it with the right
+// mouse button and the tracker follows its live cells from generation to
+// generation, waiting for the same shape to turn up again. The displacement
+// between the two sightings and the generations in between give its speed,
+// like "c/4 diagonal" for a glider. A right click without dragging stops it.
+//
+// The results show up in the inspector panel.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::conway::{BoardSnapshot, ConwayConfig, GenerationAdvanced, MAX_SPEED};
+use crate::inspector::Inspector;
+use crate::tools::BoardCursor;
+
+pub struct VelocityPlugin;
+
+const SECTION: &str = "Velocity";
+// Sightings kept while looking for a repeat, so this is also the longest
+// period that can be measured.
+const MAX_HISTORY: usize = 512;
+
+// The shape of the tracked cells at one generation.
+struct Sighting {
+    generation: u64,
+    origin: IVec2,
+    // Live cells relative to `origin`, row by row.
+    shape: Vec<IVec2>,
+}
+
+struct Tracked {
+    // The bounding box of the tracked cells, inclusive.
+    min: IVec2,
+    max: IVec2,
+    history: VecDeque<Sighting>,
+    // The displacement and period, once the shape has repeated.
+    measurement: Option<(IVec2, u64)>,
+}
+
+#[derive(Resource, Default)]
+struct Tracker {
+    // Where the right mouse button went down, while selecting.
+    selecting_from: Option<IVec2>,
+    tracked: Option<Tracked>,
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Describes a pattern that moves by `displacement` cells every `period`
+/// generations, e.g. "c/4 diagonal" or "oscillator, period 2".
+pub fn describe_velocity(displacement: IVec2, period: u64) -> String {
+    let (dx, dy) = (displacement.x.unsigned_abs() as u64, displacement.y.unsigned_abs() as u64);
+    if dx == 0 && dy == 0 {
+        return if period <= 1 { "still life".to_string() } else { format!("oscillator, period {}", period) };
+    }
+    if dx != 0 && dy != 0 && dx != dy {
+        return format!("({}, {})c/{} oblique", dx, dy, period);
+    }
+    let distance = dx.max(dy);
+    let divisor = gcd(distance, period);
+    let speed = match (distance / divisor, period / divisor) {
+        (1, 1) => "c".to_string(),
+        (distance, 1) => format!("{}c", distance),
+        (1, period) => format!("c/{}", period),
+        (distance, period) => format!("{}c/{}", distance, period),
+    };
+    let direction = if dx == dy { "diagonal" } else { "orthogonal" };
+    format!("{} {}", speed, direction)
+}
+
+fn handle_selection(
+    mut tracker: ResMut<Tracker>,
+    mut inspector: ResMut<Inspector>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    cursor: BoardCursor,
+) {
+    if mouse_button_input.just_pressed(MouseButton::Right) {
+        tracker.selecting_from = cursor.cell();
+    }
+    if mouse_button_input.just_released(MouseButton::Right) {
+        let (Some(from), Some(to)) = (tracker.selecting_from.take(), cursor.cell()) else {
+            return;
+        };
+        if from == to {
+            tracker.tracked = None;
+            inspector.remove(SECTION);
+            return;
+        }
+        tracker.tracked = Some(Tracked {
+            min: from.min(to),
+            max: from.max(to),
+            history: VecDeque::new(),
+            measurement: None,
+        });
+        inspector.set(SECTION, "waiting for the next generation");
+    }
+}
+
+fn track_pattern(
+    mut tracker: ResMut<Tracker>,
+    mut inspector: ResMut<Inspector>,
+    mut generations: EventReader<GenerationAdvanced>,
+    board: Res<BoardSnapshot>,
+) {
+    let Some(advanced) = generations.read().last() else {
+        return;
+    };
+    let Some(tracked) = &mut tracker.tracked else {
+        return;
+    };
+
+    // The board was reseeded, so earlier sightings don't mean anything.
+    let last_generation = tracked.history.back().map_or(0, |sighting| sighting.generation);
+    if advanced.generation < last_generation {
+        tracked.history.clear();
+        tracked.measurement = None;
+    }
+
+    // Nothing moves faster than one cell a generation, so look that far
+    // beyond where the pattern was last seen.
+    let margin = (advanced.generation.saturating_sub(last_generation).max(1) as i32).min(MAX_SPEED as i32);
+    let mut cells = vec![];
+    for y in tracked.min.y - margin..=tracked.max.y + margin {
+        for x in tracked.min.x - margin..=tracked.max.x + margin {
+            if board.is_alive(IVec2::new(x, y)) {
+                cells.push(IVec2::new(x, y));
+            }
+        }
+    }
+    if cells.is_empty() {
+        tracker.tracked = None;
+        inspector.set(SECTION, "the pattern died out");
+        return;
+    }
+
+    let min = cells.iter().copied().fold(IVec2::MAX, IVec2::min);
+    let max = cells.iter().copied().fold(IVec2::MIN, IVec2::max);
+    let shape = cells.iter().map(|cell| *cell - min).collect::<Vec<_>>();
+    let centroid = cells.iter().map(|cell| cell.as_vec2()).sum::<Vec2>() / cells.len() as f32;
+    if let Some(previous) = tracked.history.iter().rev().find(|sighting| sighting.shape == shape) {
+        tracked.measurement = Some((min - previous.origin, advanced.generation - previous.generation));
+    }
+    tracked.min = min;
+    tracked.max = max;
+    tracked.history.push_back(Sighting { generation: advanced.generation, origin: min, shape });
+    if tracked.history.len() > MAX_HISTORY {
+        tracked.history.pop_front();
+    }
+
+    let size = max - min + IVec2::ONE;
+    let mut text = format!(
+        "{} cells, {}x{}\ncentroid ({:.1}, {:.1})",
+        cells.len(), size.x, size.y, centroid.x, centroid.y,
+    );
+    match tracked.measurement {
+        Some((displacement, period)) => text.push_str(&format!(
+            "\nmoved ({}, {}) in {} generations\n{}",
+            displacement.x, displacement.y, period, describe_velocity(displacement, period),
+        )),
+        None => text.push_str("\nwaiting for the shape to repeat"),
+    }
+    inspector.set(SECTION, text);
+}
+
+// Outline the selection being dragged out and the pattern being tracked.
+fn draw_outlines(
+    mut gizmos: Gizmos,
+    tracker: Res<Tracker>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    cursor: BoardCursor,
+    config: Res<ConwayConfig>,
+) {
+    let mut outline = |min: IVec2, max: IVec2, color: Color| {
+        let top_left = config.cell_to_world(min);
+        let bottom_right = config.cell_to_world(max + IVec2::ONE);
+        gizmos.rect_2d((top_left + bottom_right) / 2.0, 0.0, (bottom_right - top_left).abs(), color);
+    };
+    if let Some(tracked) = &tracker.tracked {
+        outline(tracked.min, tracked.max, Color::YELLOW);
+    }
+    let cursor = cursor.cell();
+    if let (Some(from), Some(to)) = (tracker.selecting_from, cursor) {
+        if mouse_button_input.pressed(MouseButton::Right) {
+            outline(from.min(to), from.max(to), Color::WHITE);
+        }
+    }
+}
+
+impl Plugin for VelocityPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Tracker>()
+        .add_systems(Update, (handle_selection, track_pattern, draw_outlines).chain())
+        ;
+    }
+}
@@ -0,0 +1,179 @@
+// Getting around a big board. Drag with the middle mouse button to pan and
+// scroll to zoom in, unless the terrain's up, which takes them for orbiting.
+// Ctrl+Shift+1..9 bookmarks the view, and Shift+1..9 jumps back to it, for
+// going between work sites. From the console:
+//
+//   view N NAME             bookmark the view as NAME, under Shift+N
+//   view N                  go to bookmark N
+//   view home               back to the whole board
+//   views                   list the bookmarks
+//
+// Bookmarks are kept in `views.toml`, read at startup and written whenever
+// one's set, so they're still there next session.
+
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::conway::ConwayConfig;
+use crate::event_log::LogEvent;
+use crate::toml_file::TomlFile;
+
+pub struct ViewsPlugin;
+
+pub const VIEWS_PATH: &str = "views.toml";
+pub const SLOTS: u8 = 9;
+// The share of the view a notch of the wheel zooms by, and how far in it
+// goes, as the camera's scale; 1 shows the whole board.
+const ZOOM_SPEED: f32 = 0.1;
+const MIN_SCALE: f32 = 0.05;
+const MAX_SCALE: f32 = 1.0;
+const SLOT_KEYS: [KeyCode; SLOTS as usize] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+];
+
+/// Marks the camera looking at the board, as opposed to the control window's
+/// or the terrain's.
+#[derive(Component)]
+pub struct BoardCamera;
+
+/// Where the board camera is looking: the world point at the middle of the
+/// window, and the camera's scale.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Pose {
+    pub center: (f32, f32),
+    pub scale: f32,
+}
+
+impl Pose {
+    pub const HOME: Self = Self { center: (0.0, 0.0), scale: 1.0 };
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Bookmark {
+    /// 1 to `SLOTS`, for Shift and that number.
+    pub slot: u8,
+    pub name: String,
+    pub pose: Pose,
+}
+
+/// The bookmarked views, in slot order.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Bookmarks {
+    #[serde(default)]
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    pub fn get(&self, slot: u8) -> Option<&Bookmark> {
+        self.bookmarks.iter().find(|bookmark| bookmark.slot == slot)
+    }
+
+    /// Bookmarks `pose` under `slot`, replacing whatever was there.
+    pub fn set(&mut self, slot: u8, name: String, pose: Pose) {
+        self.bookmarks.retain(|bookmark| bookmark.slot != slot);
+        self.bookmarks.push(Bookmark { slot, name, pose });
+        self.bookmarks.sort_by_key(|bookmark| bookmark.slot);
+    }
+}
+
+impl TomlFile for Bookmarks {}
+
+#[derive(Event, Clone, Debug)]
+pub enum ViewCommand {
+    Bookmark { slot: u8, name: String },
+    Recall(u8),
+    Home,
+}
+
+fn pan_and_zoom(
+    mouse_button_input: Res<Input<MouseButton>>,
+    mut motions: EventReader<MouseMotion>,
+    mut wheels: EventReader<MouseWheel>,
+    config: Res<ConwayConfig>,
+    terrain_cameras: Query<&Camera, With<Camera3d>>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<BoardCamera>>,
+) {
+    let dragged = motions.read().map(|motion| motion.delta).sum::<Vec2>();
+    let scrolled = wheels.read().map(|wheel| wheel.y).sum::<f32>();
+    if terrain_cameras.iter().any(|camera| camera.is_active) {
+        return;
+    }
+    let Ok((mut transform, mut projection)) = cameras.get_single_mut() else {
+        return;
+    };
+    if mouse_button_input.pressed(MouseButton::Middle) {
+        // Screen y runs down and world y up.
+        transform.translation += Vec3::new(-dragged.x, dragged.y, 0.0) * projection.scale;
+    }
+    projection.scale = (projection.scale * (1.0 - scrolled * ZOOM_SPEED)).clamp(MIN_SCALE, MAX_SCALE);
+    // Keep the middle of the window on the board.
+    let reach = config.extents() / 2.0;
+    let center = transform.translation.truncate().clamp(-reach, reach);
+    transform.translation = center.extend(transform.translation.z);
+}
+
+fn handle_commands(
+    keys: Res<Input<KeyCode>>,
+    mut view_commands: EventReader<ViewCommand>,
+    mut bookmarks: ResMut<Bookmarks>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<BoardCamera>>,
+    mut log: EventWriter<LogEvent>,
+) {
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let control = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let pressed = (1..=SLOTS)
+        .zip(SLOT_KEYS)
+        .filter(|(_, key)| shift && keys.just_pressed(*key))
+        .map(|(slot, _)| {
+            if control {
+                ViewCommand::Bookmark { slot, name: format!("view {}", slot) }
+            } else {
+                ViewCommand::Recall(slot)
+            }
+        });
+    let Ok((mut transform, mut projection)) = cameras.get_single_mut() else {
+        return;
+    };
+    for command in view_commands.read().cloned().chain(pressed) {
+        let pose = match command {
+            ViewCommand::Bookmark { slot, name } => {
+                let center = transform.translation.truncate().into();
+                bookmarks.set(slot, name.clone(), Pose { center, scale: projection.scale });
+                match bookmarks.save(VIEWS_PATH) {
+                    Ok(()) => log.send(LogEvent(format!("Bookmarked {:?} under Shift+{}", name, slot))),
+                    Err(err) => log.send(LogEvent(format!("Failed to save {}: {}", VIEWS_PATH, err))),
+                }
+                continue;
+            }
+            ViewCommand::Recall(slot) => match bookmarks.get(slot) {
+                Some(bookmark) => bookmark.pose,
+                None => {
+                    log.send(LogEvent(format!("Nothing bookmarked under Shift+{}", slot)));
+                    continue;
+                }
+            },
+            ViewCommand::Home => Pose::HOME,
+        };
+        transform.translation = Vec2::from(pose.center).extend(transform.translation.z);
+        projection.scale = pose.scale.clamp(MIN_SCALE, MAX_SCALE);
+    }
+}
+
+impl Plugin for ViewsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<ViewCommand>()
+        .insert_resource(Bookmarks::load(VIEWS_PATH))
+        .add_systems(Update, (pan_and_zoom, handle_commands).chain())
+        ;
+    }
+}
@@ -0,0 +1,392 @@
+// Named workspaces, for keeping several experiments going at once. Each is
+// a board, its rule and seed, the generation it's up to, where the camera was
+// and which tool was in hand, kept in `workspaces/NAME.gol` and
+// `workspaces/NAME.toml`. Switching saves the one that's open and loads the
+// other in its place, or starts an empty board under the new name, at
+// generation 0; quitting saves the open one too. A session
+// starts on a scratch board in none of them, which is dropped on switching.
+//
+// F2 opens a menu of them, walked with Up and Down and picked with Enter.
+// From the console:
+//
+//   workspace NAME          switch to workspace NAME, making it if it's new
+//   workspaces              list them
+
+use std::fs;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::conway::{
+    ActiveRuleTable, BoardResized, BoardSnapshot, ConwayCommand, Generation, RuleString, SeedSettings,
+};
+use crate::event_log::LogEvent;
+use crate::save_file;
+use crate::snapshot::SnapshotMetadata;
+use crate::toml_file::TomlFile;
+use crate::tools::{Brush, Tool};
+use crate::views::{BoardCamera, Pose};
+
+pub struct WorkspacesPlugin;
+
+pub const WORKSPACE_DIRECTORY: &str = "workspaces";
+const FONT_SIZE: f32 = 22.0;
+const COLOR: Color = Color::rgb(0.8, 0.8, 0.8);
+const SELECTED_COLOR: Color = Color::rgb(1.0, 0.85, 0.3);
+
+/// Everything in a workspace but its board.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WorkspaceSettings {
+    pub pose: Pose,
+    pub tool: String,
+    pub brush_radius: u32,
+    pub brush_density: f32,
+}
+
+impl TomlFile for WorkspaceSettings {}
+
+/// The open workspace, if it's not the scratch board, and all the ones there
+/// are, sorted.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Workspaces {
+    pub current: Option<String>,
+    pub names: Vec<String>,
+}
+
+impl Workspaces {
+    // The ones saved from earlier sessions.
+    fn scan() -> Self {
+        let mut names = fs::read_dir(WORKSPACE_DIRECTORY)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                (path.extension()? == save_file::EXTENSION).then_some(path.file_stem()?.to_str()?.to_string())
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+        Self { current: None, names }
+    }
+}
+
+#[derive(Event, Clone, Debug)]
+pub enum WorkspaceCommand {
+    Switch(String),
+}
+
+// Workspace names become file names, so keep them to something safe.
+pub fn workspace_path(name: &str, extension: &str) -> Result<String, String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("bad workspace name {:?}", name));
+    }
+    Ok(format!("{}/{}.{}", WORKSPACE_DIRECTORY, name, extension))
+}
+
+#[derive(Resource, Default)]
+struct WorkspaceMenu {
+    open: bool,
+    selected: usize,
+}
+
+#[derive(Component)]
+struct WorkspaceMenuText;
+
+// A loaded board bigger or smaller than the one on screen, waiting for the
+// board to be remade at its size.
+#[derive(Resource, Default)]
+struct PendingCells(Option<Vec<IVec2>>);
+
+fn metadata(
+    rule_table: &ActiveRuleTable,
+    rule_string: &RuleString,
+    seed_settings: &SeedSettings,
+    generation: &Generation,
+) -> SnapshotMetadata {
+    SnapshotMetadata {
+        rule: Some(rule_table.name(rule_string).into_owned()),
+        seed: Some(seed_settings.seed),
+        density: Some(seed_settings.density),
+        generation: Some(generation.get()),
+        rng_seed: None,
+        rng_position: None,
+        hash: None,
+    }
+}
+
+fn settings(
+    transform: &Transform,
+    projection: &OrthographicProjection,
+    tool: Tool,
+    brush: &Brush,
+) -> WorkspaceSettings {
+    WorkspaceSettings {
+        pose: Pose { center: transform.translation.truncate().into(), scale: projection.scale },
+        tool: tool.name().to_string(),
+        brush_radius: brush.radius,
+        brush_density: brush.density,
+    }
+}
+
+fn save_workspace(
+    name: &str,
+    board: &BoardSnapshot,
+    metadata: &SnapshotMetadata,
+    settings: &WorkspaceSettings,
+) -> Result<(), String> {
+    let board_path = workspace_path(name, save_file::EXTENSION)?;
+    let settings_path = workspace_path(name, "toml")?;
+    let bytes = save_file::encode(board.size(), &board.alive_cells(), metadata)?;
+    fs::create_dir_all(WORKSPACE_DIRECTORY)
+        .and_then(|_| fs::write(&board_path, bytes))
+        .map_err(|err| format!("failed to save {}: {}", board_path, err))?;
+    settings.save(&settings_path).map_err(|err| format!("failed to save {}: {}", settings_path, err))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn switch_workspace(
+    mut workspace_commands: EventReader<WorkspaceCommand>,
+    mut workspaces: ResMut<Workspaces>,
+    board: Res<BoardSnapshot>,
+    rule_table: Res<ActiveRuleTable>,
+    rule_string: Res<RuleString>,
+    mut seed_settings: ResMut<SeedSettings>,
+    generation: Res<Generation>,
+    mut tool: ResMut<Tool>,
+    mut brush: ResMut<Brush>,
+    mut cameras: Query<(&mut Transform, &mut OrthographicProjection), With<BoardCamera>>,
+    mut pending: ResMut<PendingCells>,
+    mut commands: EventWriter<ConwayCommand>,
+    mut log: EventWriter<LogEvent>,
+) {
+    let Ok((mut transform, mut projection)) = cameras.get_single_mut() else {
+        return;
+    };
+    for WorkspaceCommand::Switch(name) in workspace_commands.read() {
+        if let Err(err) = workspace_path(name, save_file::EXTENSION) {
+            log.send(LogEvent(err));
+            continue;
+        }
+        if workspaces.current.as_ref() == Some(name) {
+            log.send(LogEvent(format!("Already in workspace {}", name)));
+            continue;
+        }
+        if let Some(current) = &workspaces.current {
+            let metadata = metadata(&rule_table, &rule_string, &seed_settings, &generation);
+            let settings = settings(&transform, &projection, *tool, &brush);
+            if let Err(err) = save_workspace(current, &board, &metadata, &settings) {
+                log.send(LogEvent(format!("Couldn't save workspace {}: {}", current, err)));
+                continue;
+            }
+        }
+
+        let mut notes = vec![];
+        let board_path = workspace_path(name, save_file::EXTENSION).unwrap_or_default();
+        let cells = match fs::read(&board_path) {
+            Ok(bytes) => match save_file::decode(&bytes) {
+                Ok(save) => {
+                    let metadata = &save.metadata;
+                    if let Some(rule) = &metadata.rule {
+                        match ConwayCommand::set_rule_named(rule) {
+                            Ok(command) => commands.send(command),
+                            Err(_) => notes.push(format!("couldn't find its rule {}", rule)),
+                        }
+                    }
+                    if let (Some(seed), Some(density)) = (metadata.seed, metadata.density) {
+                        seed_settings.seed = seed;
+                        seed_settings.density = density;
+                    }
+                    // It carries on counting from where it was left.
+                    let saved = metadata.generation.unwrap_or(0);
+                    generation.set(saved);
+                    notes.push(format!("back at generation {}", saved));
+                    if save.size != board.size() {
+                        commands.send(ConwayCommand::Resize { size: save.size, resample: false });
+                        notes.push(format!("remade the board at {}x{}", save.size.x, save.size.y));
+                        pending.0 = Some(save.cells);
+                        None
+                    } else {
+                        Some(save.cells)
+                    }
+                }
+                Err(err) => {
+                    log.send(LogEvent(format!("Couldn't load {}: {}", board_path, err)));
+                    continue;
+                }
+            },
+            Err(_) => {
+                generation.set(0);
+                notes.push("a new workspace".to_string());
+                Some(vec![])
+            }
+        };
+        if let Some(cells) = cells {
+            commands.send(ConwayCommand::FillRect { corner: IVec2::ZERO, size: board.size(), alive: false });
+            commands.send(ConwayCommand::SetCells(cells));
+        }
+
+        let settings_path = workspace_path(name, "toml").unwrap_or_default();
+        if let Ok(text) = fs::read_to_string(&settings_path) {
+            match WorkspaceSettings::from_toml(&text) {
+                Ok(settings) => {
+                    transform.translation = Vec2::from(settings.pose.center).extend(transform.translation.z);
+                    projection.scale = settings.pose.scale;
+                    *tool = Tool::parse(&settings.tool).unwrap_or_default();
+                    *brush = Brush { radius: settings.brush_radius, density: settings.brush_density };
+                }
+                Err(err) => notes.push(format!("couldn't read {}: {}", settings_path, err)),
+            }
+        }
+
+        workspaces.current = Some(name.clone());
+        if let Err(index) = workspaces.names.binary_search(name) {
+            workspaces.names.insert(index, name.clone());
+        }
+        log.send(LogEvent(format!("Switched to workspace {}: {}", name, notes.join(", "))));
+    }
+}
+
+// Lay the cells of a workspace that needed the board remade onto it, once it
+// has been.
+fn place_pending_cells(
+    mut resized: EventReader<BoardResized>,
+    mut pending: ResMut<PendingCells>,
+    mut commands: EventWriter<ConwayCommand>,
+) {
+    let Some(resized) = resized.read().last() else {
+        return;
+    };
+    let Some(cells) = pending.0.take() else {
+        return;
+    };
+    commands.send(ConwayCommand::FillRect { corner: IVec2::ZERO, size: resized.size, alive: false });
+    commands.send(ConwayCommand::SetCells(cells));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn save_on_exit(
+    mut exits: EventReader<AppExit>,
+    workspaces: Res<Workspaces>,
+    board: Res<BoardSnapshot>,
+    rule_table: Res<ActiveRuleTable>,
+    rule_string: Res<RuleString>,
+    seed_settings: Res<SeedSettings>,
+    generation: Res<Generation>,
+    tool: Res<Tool>,
+    brush: Res<Brush>,
+    cameras: Query<(&Transform, &OrthographicProjection), With<BoardCamera>>,
+) {
+    if exits.read().count() == 0 {
+        return;
+    }
+    let (Some(current), Ok((transform, projection))) = (&workspaces.current, cameras.get_single()) else {
+        return;
+    };
+    let metadata = metadata(&rule_table, &rule_string, &seed_settings, &generation);
+    let settings = settings(transform, projection, *tool, &brush);
+    // The log's gone with the window by now.
+    if let Err(err) = save_workspace(current, &board, &metadata, &settings) {
+        println!("couldn't save workspace {}: {}", current, err);
+    }
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        WorkspaceMenuText,
+        TextBundle::default().with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(60.0),
+            left: Val::Px(20.0),
+            display: Display::None,
+            ..default()
+        }),
+    ));
+}
+
+fn handle_menu(
+    mut keys: ResMut<Input<KeyCode>>,
+    workspaces: Res<Workspaces>,
+    mut menu: ResMut<WorkspaceMenu>,
+    mut workspace_commands: EventWriter<WorkspaceCommand>,
+) {
+    if keys.just_pressed(KeyCode::F2) {
+        menu.open = !menu.open;
+        menu.selected = workspaces.names.iter().position(|name| workspaces.current.as_ref() == Some(name)).unwrap_or(0);
+    }
+    let count = workspaces.names.len();
+    if !menu.open || count == 0 {
+        return;
+    }
+    if keys.clear_just_pressed(KeyCode::Down) {
+        menu.selected = (menu.selected + 1) % count;
+    }
+    if keys.clear_just_pressed(KeyCode::Up) {
+        menu.selected = (menu.selected + count - 1) % count;
+    }
+    if keys.clear_just_pressed(KeyCode::Return) {
+        menu.open = false;
+        workspace_commands.send(WorkspaceCommand::Switch(workspaces.names[menu.selected].clone()));
+    }
+}
+
+fn draw_menu(
+    workspaces: Res<Workspaces>,
+    menu: Res<WorkspaceMenu>,
+    mut texts: Query<(&mut Text, &mut Style), With<WorkspaceMenuText>>,
+) {
+    if !menu.is_changed() && !workspaces.is_changed() {
+        return;
+    }
+    let Ok((mut text, mut style)) = texts.get_single_mut() else {
+        return;
+    };
+    style.display = if menu.open { Display::Flex } else { Display::None };
+    let style = TextStyle { font_size: FONT_SIZE, color: COLOR, ..default() };
+    let title = match workspaces.names.is_empty() {
+        true => "no workspaces yet; make one with `workspace NAME`\n",
+        false => "workspaces\n",
+    };
+    let mut sections = vec![TextSection::new(title, style.clone())];
+    for (index, name) in workspaces.names.iter().enumerate() {
+        let marker = if workspaces.current.as_ref() == Some(name) { "*" } else { " " };
+        let color = if index == menu.selected { SELECTED_COLOR } else { COLOR };
+        sections.push(TextSection::new(
+            format!("{} {}\n", marker, name),
+            TextStyle { color, ..style.clone() },
+        ));
+    }
+    text.sections = sections;
+}
+
+impl Plugin for WorkspacesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_event::<WorkspaceCommand>()
+        .insert_resource(Workspaces::scan())
+        .init_resource::<WorkspaceMenu>()
+        .init_resource::<PendingCells>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, (handle_menu, switch_workspace, place_pending_cells, draw_menu).chain())
+        .add_systems(Last, save_on_exit)
+        ;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspace_settings_round_trip_through_toml() {
+        let settings = WorkspaceSettings {
+            pose: Pose { center: (-64.0, 32.5), scale: 0.2 },
+            tool: Tool::Freeze.name().to_string(),
+            brush_radius: 4,
+            brush_density: 0.5,
+        };
+        let text = settings.to_toml().unwrap();
+        assert_eq!(WorkspaceSettings::from_toml(&text).unwrap(), settings);
+        assert_eq!(Tool::parse(&settings.tool), Some(Tool::Freeze));
+        assert!(workspace_path("../escape", "toml").is_err());
+    }
+}
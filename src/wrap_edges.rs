@@ -0,0 +1,147 @@
+// Faint copies of the opposite edges drawn just past the board's borders
+// while it wraps around (see `Edges`), so gliders about to come back on
+// the other side show up before they do. W turns them on and off.
+
+use bevy::prelude::*;
+use bevy::reflect::TypePath;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef, ShaderType};
+use bevy::sprite::{Material2d, Material2dPlugin, MaterialMesh2dBundle};
+
+use crate::conway::{BoardResized, BoardSprite, ConwayConfig, ConwayWorld, Edges};
+use crate::event_log::LogEvent;
+
+pub struct WrapEdgesPlugin;
+
+// How far past each edge the copies reach, in cells.
+const MARGIN_CELLS: f32 = 64.0;
+// How opaque the copies are right at the edge. They fade out from there.
+const STRENGTH: f32 = 0.35;
+
+/// Whether to show the wrapped edges. They only show while the board wraps.
+#[derive(Resource, Default)]
+pub struct WrapGhosts(pub bool);
+
+#[derive(ShaderType, Debug, Clone)]
+struct WrapEdgesSettings {
+    tint: Vec4,
+    // The margin as a fraction of the board's width and height.
+    margin: Vec2,
+    strength: f32,
+}
+
+#[derive(AsBindGroup, Asset, TypePath, Debug, Clone)]
+struct WrapEdgesMaterial {
+    #[uniform(0)]
+    settings: WrapEdgesSettings,
+    #[texture(1)]
+    #[sampler(2)]
+    board: Handle<Image>,
+}
+
+impl Material2d for WrapEdgesMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/wrap_edges.wgsl".into()
+    }
+}
+
+// The frame drawn around the board, spawned the first time it's needed.
+#[derive(Resource)]
+struct WrapEdges {
+    entity: Entity,
+    material: Handle<WrapEdgesMaterial>,
+}
+
+fn toggle_ghosts(keys: Res<Input<KeyCode>>, mut ghosts: ResMut<WrapGhosts>, mut log: EventWriter<LogEvent>) {
+    if keys.just_pressed(KeyCode::W) {
+        ghosts.0 = !ghosts.0;
+        log.send(LogEvent(format!("Wrapped edges {}", if ghosts.0 { "shown" } else { "hidden" })));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn show_edges(
+    mut commands: Commands,
+    board: Option<Res<ConwayWorld>>,
+    config: Res<ConwayConfig>,
+    wrap_edges: Option<Res<WrapEdges>>,
+    ghosts: Res<WrapGhosts>,
+    edges: Res<Edges>,
+    sprites: Query<&Sprite, With<BoardSprite>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<WrapEdgesMaterial>>,
+    mut visibilities: Query<&mut Visibility>,
+) {
+    let Some(board) = board else {
+        return;
+    };
+    if !ghosts.is_changed() && !edges.is_changed() {
+        return;
+    }
+    let shown = ghosts.0 && *edges == Edges::Wrap;
+    match wrap_edges {
+        Some(wrap_edges) => {
+            if let Ok(mut visibility) = visibilities.get_mut(wrap_edges.entity) {
+                *visibility = if shown { Visibility::Visible } else { Visibility::Hidden };
+            }
+        }
+        None if shown => {
+            let extents = config.extents();
+            let margin = Vec2::splat(MARGIN_CELLS) / config.size.as_vec2();
+            let tint = sprites.get_single().map_or(Color::WHITE, |sprite| sprite.color).as_linear_rgba_f32().into();
+            let material = materials.add(WrapEdgesMaterial {
+                settings: WrapEdgesSettings { tint, margin, strength: STRENGTH },
+                board: board.0.clone(),
+            });
+            let entity = commands
+                .spawn(MaterialMesh2dBundle {
+                    mesh: meshes.add(shape::Quad::new(extents * (Vec2::ONE + 2.0 * margin)).into()).into(),
+                    material: material.clone(),
+                    // The middle is left to the board, so this can go in front of it.
+                    transform: Transform::from_xyz(0.0, 0.0, 0.5),
+                    ..default()
+                })
+                .id();
+            commands.insert_resource(WrapEdges { entity, material });
+        }
+        None => {}
+    }
+}
+
+// The copies are sized from the board, so a resize makes them over again,
+// the next time they show.
+fn fit_edges(
+    mut commands: Commands,
+    mut resized: EventReader<BoardResized>,
+    wrap_edges: Option<Res<WrapEdges>>,
+    mut ghosts: ResMut<WrapGhosts>,
+) {
+    let (true, Some(wrap_edges)) = (resized.read().count() > 0, wrap_edges) else {
+        return;
+    };
+    commands.entity(wrap_edges.entity).despawn();
+    commands.remove_resource::<WrapEdges>();
+    ghosts.set_changed();
+}
+
+fn follow_tint(
+    wrap_edges: Option<Res<WrapEdges>>,
+    sprites: Query<&Sprite, (With<BoardSprite>, Changed<Sprite>)>,
+    mut materials: ResMut<Assets<WrapEdgesMaterial>>,
+) {
+    let (Some(wrap_edges), Ok(sprite)) = (wrap_edges, sprites.get_single()) else {
+        return;
+    };
+    if let Some(material) = materials.get_mut(&wrap_edges.material) {
+        material.settings.tint = sprite.color.as_linear_rgba_f32().into();
+    }
+}
+
+impl Plugin for WrapEdgesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<WrapGhosts>()
+        .add_plugins(Material2dPlugin::<WrapEdgesMaterial>::default())
+        .add_systems(Update, (fit_edges, toggle_ghosts, show_edges, follow_tint).chain())
+        ;
+    }
+}
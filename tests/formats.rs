@@ -0,0 +1,239 @@
+// Round trips and fuzzing for the pattern formats: whatever gets written
+// should parse back to the same cells, and no input should make a parser panic.
+
+use bevy::prelude::*;
+use proptest::prelude::*;
+
+use my_bevy_game::annotations::{Annotations, Arrow, Label};
+use my_bevy_game::cpu::CpuBoard;
+use my_bevy_game::formats;
+use my_bevy_game::image_import::{self, ImportMode, Luminance};
+use my_bevy_game::npy;
+use my_bevy_game::pnm;
+use my_bevy_game::save_file;
+use my_bevy_game::snapshot::{self, SnapshotMetadata};
+use my_bevy_game::tape::Tape;
+use my_bevy_game::toml_file::TomlFile;
+use my_bevy_game::views::{Bookmarks, Pose};
+
+const BOARD_SIZE: u32 = 64;
+
+/// Distinct cells on a small board, in row-major order like the parsers return them.
+fn cells() -> impl Strategy<Value = Vec<IVec2>> {
+    prop::collection::btree_set((0..BOARD_SIZE as i32, 0..BOARD_SIZE as i32), 0..300).prop_map(|cells| {
+        let mut cells = cells.into_iter().map(|(x, y)| IVec2::new(x, y)).collect::<Vec<_>>();
+        cells.sort_by_key(|cell| (cell.y, cell.x));
+        cells
+    })
+}
+
+// Puts the cells on a board and reads them back off it.
+fn through_board(cells: &[IVec2]) -> Vec<IVec2> {
+    let mut board = CpuBoard::new(BOARD_SIZE, BOARD_SIZE);
+    for cell in cells {
+        board.set(*cell, true);
+    }
+    (0..BOARD_SIZE as i32)
+        .flat_map(|y| (0..BOARD_SIZE as i32).map(move |x| IVec2::new(x, y)))
+        .filter(|cell| board.is_alive(*cell))
+        .collect()
+}
+
+proptest! {
+    #[test]
+    fn rle_round_trips(cells in cells()) {
+        let text = formats::write_rle("test", Some("B3/S23"), &cells);
+        let parsed = formats::parse_rle(&text).unwrap();
+        let exported = formats::write_rle("test", Some("B3/S23"), &through_board(&parsed));
+        prop_assert_eq!(&exported, &text);
+        prop_assert_eq!(formats::parse_rle(&exported).unwrap(), cells);
+    }
+
+    #[test]
+    fn rle_lines_are_wrapped(cells in cells()) {
+        let text = formats::write_rle("test", None, &cells);
+        prop_assert!(text.lines().all(|line| line.len() <= 70), "{}", text);
+    }
+
+    #[test]
+    fn plaintext_round_trips(cells in cells()) {
+        let text = formats::write_plaintext("test", &cells);
+        let parsed = formats::parse_plaintext(&text).unwrap();
+        let exported = formats::write_plaintext("test", &through_board(&parsed));
+        prop_assert_eq!(&exported, &text);
+        prop_assert_eq!(formats::parse_plaintext(&exported).unwrap(), cells);
+    }
+
+    #[test]
+    fn png_snapshots_round_trip(
+        cells in cells(), seed in any::<u32>(), generation in any::<u64>(), rng_seed in any::<Option<u64>>(),
+        rng_position in any::<Option<u64>>(),
+    ) {
+        let size = UVec2::splat(BOARD_SIZE);
+        let metadata = SnapshotMetadata {
+            rule: Some("B36/S23".to_string()),
+            seed: Some(seed),
+            density: Some(0.25),
+            generation: Some(generation),
+            rng_seed,
+            rng_position,
+            hash: None,
+        };
+        let decoded = snapshot::decode(&snapshot::encode(size, &cells, &metadata).unwrap()).unwrap();
+        prop_assert_eq!(decoded.size, size);
+        prop_assert_eq!(&decoded.cells, &cells);
+        prop_assert_eq!(decoded.metadata, SnapshotMetadata { hash: Some(snapshot::board_hash(size, &cells)), ..metadata });
+    }
+
+    #[test]
+    fn png_decoder_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..512)) {
+        let _ = snapshot::decode(&bytes);
+    }
+
+    #[test]
+    fn save_files_round_trip(
+        cells in cells(), seed in any::<Option<u32>>(), generation in any::<Option<u64>>(), rng_seed in any::<Option<u64>>(),
+        rng_position in any::<Option<u64>>(),
+    ) {
+        let size = UVec2::splat(BOARD_SIZE);
+        let metadata = SnapshotMetadata {
+            rule: Some("B36/S23".to_string()),
+            seed,
+            density: None,
+            generation,
+            rng_seed,
+            rng_position,
+            hash: None,
+        };
+        let decoded = save_file::decode(&save_file::encode(size, &cells, &metadata).unwrap()).unwrap();
+        prop_assert_eq!(decoded.size, size);
+        prop_assert_eq!(&decoded.cells, &cells);
+        prop_assert_eq!(decoded.metadata, SnapshotMetadata { hash: Some(snapshot::board_hash(size, &cells)), ..metadata });
+    }
+
+    #[test]
+    fn save_file_decoder_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..512)) {
+        let _ = save_file::decode(&bytes);
+    }
+
+    #[test]
+    fn tapes_rebuild_every_frame(boards in prop::collection::vec(cells(), 1..12), keyframe_every in 1..5usize) {
+        let mut tape = Tape::new(keyframe_every);
+        for (generation, board) in boards.iter().enumerate() {
+            tape.record(generation as u64, board.clone());
+        }
+        for (index, board) in boards.iter().enumerate() {
+            prop_assert_eq!(&tape.state_at(index), board);
+        }
+    }
+
+    #[test]
+    fn full_tapes_drop_old_frames(boards in prop::collection::vec(cells(), 1..24), keyframe_every in 1..5usize) {
+        let mut tape = Tape::with_max_frames(keyframe_every, 8);
+        for (generation, board) in boards.iter().enumerate() {
+            tape.record(generation as u64, board.clone());
+        }
+        prop_assert!(tape.frames.len() <= 8);
+        let dropped = boards.len() - tape.frames.len();
+        for (index, board) in boards[dropped..].iter().enumerate() {
+            prop_assert_eq!(tape.frames[index].generation, (dropped + index) as u64);
+            prop_assert_eq!(&tape.state_at(index), board);
+        }
+    }
+
+    #[test]
+    fn rle_parser_never_panics(text in "\\PC*") {
+        let _ = formats::parse_rle(&text);
+    }
+
+    // Mostly valid RLE, which gets much further into the parser than random text.
+    #[test]
+    fn rle_parser_never_panics_on_near_misses(text in "(#N [a-z]*\n)?(x = [0-9]{1,6}, y = [0-9]{1,6}\n)?[0-9bo.$!A-Z \n]{0,200}") {
+        let _ = formats::parse_rle(&text);
+    }
+
+    #[test]
+    fn plaintext_parser_never_panics(text in "\\PC*") {
+        let _ = formats::parse_plaintext(&text);
+    }
+
+    #[test]
+    fn plaintext_parser_never_panics_on_near_misses(text in "(![a-z ]*\n)?[.Oo*\n]{0,300}") {
+        let _ = formats::parse_plaintext(&text);
+    }
+}
+
+#[test]
+fn rle_headers_name_the_rule_only_when_its_known() {
+    let cells = [IVec2::new(0, 0), IVec2::new(1, 1)];
+    let highlife = formats::write_rle("test", Some("B36/S23"), &cells);
+    assert_eq!(highlife.lines().nth(1), Some("x = 2, y = 2, rule = B36/S23"));
+    let unknown = formats::write_rle("test", None, &cells);
+    assert_eq!(unknown.lines().nth(1), Some("x = 2, y = 2"));
+    assert_eq!(formats::parse_rle(&unknown).unwrap(), cells);
+}
+
+#[test]
+fn pbm_rows_are_packed_and_padded() {
+    let alive = [
+        true, false, false, false, false, false, false, true, true, true,
+        false, true, false, false, false, false, false, false, false, false,
+    ];
+    let mut bytes = vec![];
+    pnm::write_pbm(&mut bytes, UVec2::new(10, 2), "test", &alive).unwrap();
+    assert_eq!(bytes, b"P4\n# test\n10 2\n\x81\xc0\x40\x00");
+}
+
+#[test]
+fn npz_stores_each_array_as_an_npy_entry() {
+    let mut npy = vec![];
+    npy::write_npy(&mut npy, &[2, 3], &[0, 1, 0, 1, 1, 0]).unwrap();
+    let mut npz = vec![];
+    npy::write_npz(&mut npz, &[("board", &[2, 3], &[0, 1, 0, 1, 1, 0])]).unwrap();
+
+    // A local header, the name and the array as it is, since it's stored.
+    assert_eq!(&npz[..4], b"PK\x03\x04");
+    assert_eq!(&npz[30..39], b"board.npy");
+    assert_eq!(&npz[39..39 + npy.len()], &npy[..]);
+    // The end record lists the one entry, with the directory after the array.
+    let end = &npz[npz.len() - 22..];
+    assert_eq!(&end[..4], b"PK\x05\x06");
+    assert_eq!(&end[10..12], &1u16.to_le_bytes());
+    assert_eq!(&end[16..20], &(39 + npy.len() as u32).to_le_bytes());
+}
+
+#[test]
+fn imported_images_are_letterboxed() {
+    let white = Luminance::from_rgba(UVec2::new(2, 1), &[255; 8]);
+    let cells = image_import::image_to_cells(&white, UVec2::splat(8), ImportMode::Threshold(0.5));
+    let expected = (2..6).flat_map(|y| (0..8).map(move |x| IVec2::new(x, y))).collect::<Vec<_>>();
+    assert_eq!(cells, expected);
+
+    // Dithered mid gray comes out about half alive.
+    let gray = Luminance::from_rgba(UVec2::new(1, 1), &[128, 128, 128, 255]);
+    let alive = image_import::image_to_cells(&gray, UVec2::splat(8), ImportMode::Dither).len();
+    assert!((28..=36).contains(&alive), "{} of 64 alive", alive);
+}
+
+#[test]
+fn annotations_round_trip_through_toml() {
+    let annotations = Annotations {
+        labels: vec![Label { x: 10, y: 20, text: "glider gun \"output\"".to_string() }],
+        arrows: vec![Arrow { from: (10, 20), to: (40, 50) }],
+    };
+    let text = annotations.to_toml().unwrap();
+    assert_eq!(Annotations::from_toml(&text).unwrap(), annotations);
+    assert_eq!(Annotations::from_toml("").unwrap(), Annotations::default());
+}
+
+#[test]
+fn bookmarks_replace_their_slot_and_round_trip_through_toml() {
+    let mut bookmarks = Bookmarks::default();
+    bookmarks.set(3, "reactor".to_string(), Pose { center: (120.5, -40.0), scale: 0.25 });
+    bookmarks.set(1, "gun".to_string(), Pose::HOME);
+    bookmarks.set(3, "eater".to_string(), Pose { center: (0.0, 8.0), scale: 0.5 });
+    let slots = bookmarks.bookmarks.iter().map(|bookmark| (bookmark.slot, bookmark.name.as_str())).collect::<Vec<_>>();
+    assert_eq!(slots, [(1, "gun"), (3, "eater")]);
+    let text = bookmarks.to_toml().unwrap();
+    assert_eq!(Bookmarks::from_toml(&text).unwrap(), bookmarks);
+}
@@ -0,0 +1,344 @@
+// Golden tests for the simulation: known patterns on the CPU reference
+// implementation, and bit-exact agreement between it and the compute shader.
+//
+// The GPU tests need an adapter, so they're ignored by default. Run them with
+// `cargo test -- --ignored` on a machine with a GPU.
+
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use my_bevy_game::ants::{Ant, Turmite};
+use my_bevy_game::conway;
+use my_bevy_game::cpu::CpuBoard;
+use my_bevy_game::harness::ConwayTestHarness;
+use my_bevy_game::life_rule::LifeRule;
+use my_bevy_game::patterns;
+use my_bevy_game::rule_table::RuleTable;
+
+fn cpu_board_with(width: u32, height: u32, pattern: &[(i32, i32)], origin: IVec2) -> CpuBoard {
+    let mut board = CpuBoard::new(width, height);
+    for cell in patterns::placed(pattern, origin) {
+        board.set(cell, true);
+    }
+    board
+}
+
+fn alive_cells(board: &CpuBoard) -> Vec<IVec2> {
+    let mut cells = (0..board.height as i32)
+        .flat_map(|y| (0..board.width as i32).map(move |x| IVec2::new(x, y)))
+        .filter(|cell| board.is_alive(*cell))
+        .collect::<Vec<_>>();
+    cells.sort_by_key(|cell| (cell.y, cell.x));
+    cells
+}
+
+fn population(board: &CpuBoard) -> usize {
+    board.cells.iter().filter(|cell| **cell == 255).count()
+}
+
+#[test]
+fn glider_moves_one_cell_diagonally_every_four_generations() {
+    let mut board = cpu_board_with(32, 32, patterns::GLIDER, IVec2::new(5, 5));
+    for _ in 0..4 {
+        board.step();
+    }
+    let expected = cpu_board_with(32, 32, patterns::GLIDER, IVec2::new(6, 6));
+    assert_eq!(alive_cells(&board), alive_cells(&expected));
+}
+
+#[test]
+fn blinker_has_period_two() {
+    let start = cpu_board_with(8, 8, patterns::BLINKER, IVec2::new(2, 3));
+    let mut board = start.clone();
+    board.step();
+    assert_eq!(alive_cells(&board), vec![IVec2::new(3, 2), IVec2::new(3, 3), IVec2::new(3, 4)]);
+    board.step();
+    assert_eq!(alive_cells(&board), alive_cells(&start));
+}
+
+#[test]
+fn block_is_still() {
+    let start = cpu_board_with(8, 8, patterns::BLOCK, IVec2::new(3, 3));
+    let mut board = start.clone();
+    board.step();
+    assert_eq!(alive_cells(&board), alive_cells(&start));
+}
+
+#[test]
+fn r_pentomino_populations() {
+    let mut board = cpu_board_with(256, 256, patterns::R_PENTOMINO, IVec2::new(127, 127));
+    let mut populations = vec![];
+    for _ in 0..12 {
+        populations.push(population(&board));
+        board.step();
+    }
+    assert_eq!(populations, vec![5, 6, 7, 9, 8, 9, 12, 11, 18, 11, 11, 10]);
+    for _ in 12..200 {
+        board.step();
+    }
+    assert_eq!(population(&board), 120);
+}
+
+#[test]
+fn cells_beyond_the_edges_are_dead() {
+    // A blinker along the top edge loses the cell that would go off the board.
+    let mut board = cpu_board_with(8, 8, patterns::BLINKER, IVec2::new(2, 0));
+    board.step();
+    assert_eq!(alive_cells(&board), vec![IVec2::new(3, 0), IVec2::new(3, 1)]);
+}
+
+#[test]
+fn packed_steps_match_counting_neighbours_cell_by_cell() {
+    // Widths either side of a word boundary, and rules that are born from nothing.
+    let mut rng = StdRng::seed_from_u64(7);
+    for rule in ["B3/S23", "B36/S23", "B2/S", "B0/S8", "B1357/S1357"] {
+        let rule = LifeRule::parse(rule).unwrap();
+        for (width, height) in [(130, 37), (64, 3), (1, 5), (63, 18)] {
+            let mut board = CpuBoard::new(width, height);
+            board.seed(0.4, &mut rng);
+            let before = board.clone();
+            board.step_with(&rule);
+            for y in 0..height as i32 {
+                for x in 0..width as i32 {
+                    let cell = IVec2::new(x, y);
+                    let neighbors = (-1..=1)
+                        .flat_map(|dy| (-1..=1).map(move |dx| IVec2::new(dx, dy)))
+                        .filter(|offset| *offset != IVec2::ZERO && before.is_alive(cell + *offset))
+                        .count();
+                    let expected = rule.next(before.is_alive(cell), neighbors as u32);
+                    assert_eq!(board.is_alive(cell), expected, "{} at {} on {}x{}", rule, cell, width, height);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn packed_steps_read_past_the_edges_as_they_fold() {
+    use conway::Edges;
+    let mut rng = StdRng::seed_from_u64(11);
+    let rule = LifeRule::CONWAY;
+    for edges in Edges::ALL {
+        for (width, height) in [(130, 9), (64, 4), (1, 3), (63, 7)] {
+            let size = IVec2::new(width as i32, height as i32);
+            let mut board = CpuBoard::new(width, height);
+            board.seed(0.4, &mut rng);
+            let before = board.clone();
+            board.step_with_edges(&rule, edges);
+            for y in 0..size.y {
+                for x in 0..size.x {
+                    let cell = IVec2::new(x, y);
+                    let neighbors = (-1..=1)
+                        .flat_map(|dy| (-1..=1).map(move |dx| IVec2::new(dx, dy)))
+                        .filter(|offset| *offset != IVec2::ZERO && before.is_alive(edges.fold(cell + *offset, size)))
+                        .count();
+                    let expected = rule.next(before.is_alive(cell), neighbors as u32);
+                    assert_eq!(board.is_alive(cell), expected, "{} at {} on {}x{}", edges.name(), cell, width, height);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn generations_rules_decay_a_state_a_generation() {
+    let brain = LifeRule::parse("B2/S/C3").unwrap();
+    assert_eq!(LifeRule::parse("/2/3"), Ok(brain));
+    assert_eq!(brain.to_string(), "B2/S/C3");
+    assert_eq!(LifeRule::parse("B3/S23").unwrap().to_string(), "B3/S23");
+    assert!(LifeRule::parse("B2/S/C1").is_err());
+    // Alive is the highest state, and dying ones count down to dead.
+    assert_eq!((brain.next_state(0, 2), brain.next_state(0, 3)), (2, 0));
+    assert_eq!((brain.next_state(2, 2), brain.next_state(1, 2)), (1, 0));
+    let star_wars = LifeRule::parse("B2/S345/C4").unwrap();
+    assert_eq!((star_wars.next_state(3, 4), star_wars.next_state(3, 2)), (3, 2));
+    assert!(star_wars.for_cpu().is_err());
+}
+
+#[test]
+fn larger_than_life_rules_count_ranges_over_a_wider_neighbourhood() {
+    let bosco = LifeRule::parse("R5,C0,M1,S34..58,B34..45,NM").unwrap();
+    assert_eq!(bosco.to_string(), "R5,C0,M1,S34..58,B34..45,NM");
+    let larger = bosco.larger.unwrap();
+    assert_eq!(larger.neighbors(), 120);
+    assert!(larger.contains(5, -5) && !larger.contains(6, 0) && !larger.contains(0, 0));
+    // The middle cell counts towards survival, but there's none to count at birth.
+    assert!(bosco.next(true, 33) && !bosco.next(true, 32));
+    assert!(bosco.next(false, 34) && !bosco.next(false, 46));
+    let diamond = LifeRule::parse("R2,C0,M0,S1..3,B2,NN").unwrap();
+    assert_eq!(diamond.larger.unwrap().neighbors(), 12);
+    assert!(!diamond.larger.unwrap().contains(2, 1));
+    assert!(LifeRule::parse("R2,C0,M0,S1..30,B2,NN").is_err());
+    assert!(bosco.for_cpu().is_err());
+}
+
+#[test]
+fn lenia_kernel_is_a_ring_weighing_one_in_all() {
+    let settings = conway::LeniaSettings::default();
+    let kernel = settings.kernel();
+    let radius = settings.radius as usize;
+    let side = 2 * radius + 1;
+    assert_eq!(kernel.len(), side * side);
+    assert!((kernel.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+    // Nothing at the middle or out at the corners, and the most about halfway out.
+    let middle = side * side / 2;
+    assert_eq!((kernel[middle], kernel[0]), (0.0, 0.0));
+    let halfway = kernel[middle + radius / 2];
+    assert!(halfway > kernel[middle + 2] && halfway > kernel[middle + radius - 1]);
+    assert_eq!(settings.growth(settings.mu), 1.0);
+    assert!(settings.growth(0.0) < -0.99);
+}
+
+#[test]
+fn langtons_ant_turns_right_on_dead_cells_and_left_on_living_ones() {
+    let langton = Turmite::parse("RL").unwrap();
+    assert_eq!(langton.to_string(), "1R0,0L0");
+    assert_eq!(Turmite::parse("1r0,0l0"), Ok(langton.clone()));
+    assert!(Turmite::parse("1R1,0L0").is_err());
+    assert!(Turmite::parse("1R0").is_err());
+    let mut living = HashSet::new();
+    let mut ant = Ant::new(IVec2::ZERO);
+    let mut path = vec![];
+    for _ in 0..5 {
+        let cell = ant.cell;
+        if langton.step(&mut ant, living.contains(&cell)) {
+            living.insert(cell);
+        } else {
+            living.remove(&cell);
+        }
+        path.push(ant.cell);
+    }
+    // Round a square clockwise, then off to the left over the cell it started on.
+    assert_eq!(path, [IVec2::new(1, 0), IVec2::new(1, 1), IVec2::new(0, 1), IVec2::ZERO, IVec2::new(-1, 0)]);
+    assert_eq!(living.len(), 3);
+}
+
+#[test]
+fn elementary_rules_read_the_three_cells_above_as_a_bit_of_the_rule_number() {
+    let patterns = (0..8u8).rev().map(|bits| (bits & 4 != 0, bits & 2 != 0, bits & 1 != 0)).collect::<Vec<_>>();
+    let rule_30 = patterns.iter().map(|(l, m, r)| conway::ElementaryRule(30).next(*l, *m, *r)).collect::<Vec<_>>();
+    assert_eq!(rule_30, [false, false, false, true, true, true, true, false]);
+    // Rule 90 is the left cell or the right one, but not both: Sierpinski's triangle.
+    assert!(patterns.iter().all(|(l, m, r)| conway::ElementaryRule(90).next(*l, *m, *r) == (l != r)));
+}
+
+#[test]
+fn rule_table_next_state_follows_the_life_rule() {
+    let rule = LifeRule::parse("B36/S23").unwrap();
+    let table = RuleTable::from_life_rule(&rule);
+    for alive in [false, true] {
+        for neighbors in 0..=8u32 {
+            // The centre, then the live neighbours first.
+            let states = std::iter::once(alive as u32).chain((0..8).map(|i| (i < neighbors) as u32)).collect::<Vec<_>>();
+            assert_eq!(table.next_state(&states) == 1, rule.next(alive, neighbors), "{} {}", alive, neighbors);
+        }
+    }
+}
+
+fn assert_gpu_matches_cpu(pattern: &[(i32, i32)], origin: IVec2, generations: u32) {
+    assert_gpu_matches_cpu_under(LifeRule::CONWAY, pattern, origin, generations);
+}
+
+fn assert_gpu_matches_cpu_under(rule: LifeRule, pattern: &[(i32, i32)], origin: IVec2, generations: u32) {
+    let mut harness = ConwayTestHarness::new();
+    let size = harness.snapshot().size();
+    harness.app_mut().insert_resource(conway::RuleString(rule));
+    harness.set_cells(&patterns::placed(pattern, origin));
+
+    let mut board = cpu_board_with(size.x, size.y, pattern, origin);
+    assert_eq!(harness.snapshot().alive_cells(), alive_cells(&board), "the pattern wasn't placed");
+    for (generation, snapshot) in harness.run(generations).iter().enumerate() {
+        board.step_with(&rule);
+        assert_eq!(snapshot.alive_cells(), alive_cells(&board), "boards differ at generation {}", generation + 1);
+    }
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn cpu_seeds_the_same_soup_as_the_gpu() {
+    let mut harness = ConwayTestHarness::new();
+    let size = harness.snapshot().size();
+    harness.reseed(0.3, 42);
+    let mut board = CpuBoard::new(size.x, size.y);
+    board.seed_like_gpu(0.3, 42);
+    assert_eq!(harness.snapshot().alive_cells(), alive_cells(&board));
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn gpu_matches_cpu_for_glider() {
+    assert_gpu_matches_cpu(patterns::GLIDER, IVec2::new(100, 100), 8);
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn gpu_matches_cpu_for_blinker() {
+    assert_gpu_matches_cpu(patterns::BLINKER, IVec2::new(100, 100), 4);
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn gpu_matches_cpu_for_r_pentomino() {
+    assert_gpu_matches_cpu(patterns::R_PENTOMINO, IVec2::new(640, 360), 50);
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn gpu_matches_cpu_at_the_edges() {
+    assert_gpu_matches_cpu(patterns::BLINKER, IVec2::new(0, 0), 2);
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn gpu_matches_cpu_under_other_rule_strings() {
+    for rule in ["B36/S23", "B3678/S34678", "B2/S"] {
+        let rule = LifeRule::parse(rule).unwrap();
+        assert_gpu_matches_cpu_under(rule, patterns::R_PENTOMINO, IVec2::new(640, 360), 20);
+    }
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn live_bounds_box_the_living_cells() {
+    let mut harness = ConwayTestHarness::new();
+    harness.set_cells(&patterns::placed(patterns::GLIDER, IVec2::new(100, 50)));
+    harness.step(8);
+    let cells = harness.snapshot().alive_cells();
+    let min = cells.iter().fold(IVec2::MAX, |min, cell| min.min(*cell));
+    let max = cells.iter().fold(IVec2::MIN, |max, cell| max.max(*cell));
+    assert_eq!(harness.app_mut().world.resource::<conway::LiveBounds>().0, Some((min, max)));
+
+    harness.clear_cells(&cells);
+    assert_eq!(harness.app_mut().world.resource::<conway::LiveBounds>().0, None);
+}
+
+#[test]
+#[ignore = "needs a GPU"]
+fn gpu_writes_an_elementary_rule_a_row_a_generation() {
+    let rule = conway::ElementaryRule(30);
+    let mut harness = ConwayTestHarness::new();
+    let width = harness.snapshot().size().x as i32;
+    harness.app_mut().insert_resource(rule);
+    harness.app_mut().insert_resource(conway::SimulationKind::Elementary);
+    // Its pipeline may still be compiling for the first few frames.
+    for _ in 0..64 {
+        if harness.generation() >= 16 {
+            break;
+        }
+        harness.step(1);
+    }
+    assert_eq!(harness.generation(), 16);
+
+    // The board restarts from a single cell in the middle of the top row.
+    let mut row = (0..width).map(|x| x == width / 2).collect::<Vec<_>>();
+    for y in 0..=16 {
+        let cells = harness.snapshot().row(y).iter().map(|cell| *cell == 255).collect::<Vec<_>>();
+        assert_eq!(cells, row, "rows differ at row {}", y);
+        let alive = |x: i32| x >= 0 && row.get(x as usize).copied().unwrap_or(false);
+        row = (0..width).map(|x| rule.next(alive(x - 1), alive(x), alive(x + 1))).collect();
+    }
+    assert!(harness.snapshot().row(17).iter().all(|cell| *cell == 0), "the rows to come aren't empty");
+}